@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use mina_p2p_messages::v2::StateHash;
+use node::account::AccountPublicKey;
+use openmina_node_native::archive::{ArchiveBlocksQuery, ArchiveChainStatus, ArchiveDb};
+use serde::Serialize;
+
+/// Query the archive database written by `openmina node --archive-path`.
+#[derive(Debug, clap::Args)]
+pub struct Archive {
+    /// Path to the archive database.
+    #[arg(long, env)]
+    pub archive_path: PathBuf,
+
+    #[command(subcommand)]
+    pub command: ArchiveCommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ArchiveCommand {
+    /// List archived blocks, starting from the highest one.
+    Blocks(Blocks),
+    /// Show a block with its commands and the accounts it changed.
+    Block(Block),
+    /// Show the state of the account after each block that changed it.
+    AccountHistory(AccountHistory),
+    /// List user commands sent or received by the account.
+    AccountCommands(AccountCommands),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Blocks {
+    #[arg(long)]
+    pub from_height: Option<u32>,
+
+    #[arg(long)]
+    pub to_height: Option<u32>,
+
+    /// Only list blocks with this status (pending, canonical or orphaned).
+    #[arg(long)]
+    pub chain_status: Option<ArchiveChainStatus>,
+
+    #[arg(long, default_value_t = 100)]
+    pub limit: u32,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct Block {
+    pub state_hash: StateHash,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct AccountHistory {
+    pub public_key: AccountPublicKey,
+
+    /// Only include blocks with this status (pending, canonical or orphaned).
+    #[arg(long)]
+    pub chain_status: Option<ArchiveChainStatus>,
+
+    #[arg(long, default_value_t = 100)]
+    pub limit: u32,
+}
+
+#[derive(Debug, clap::Args)]
+pub struct AccountCommands {
+    pub public_key: AccountPublicKey,
+
+    /// Only include blocks with this status (pending, canonical or orphaned).
+    #[arg(long)]
+    pub chain_status: Option<ArchiveChainStatus>,
+
+    #[arg(long, default_value_t = 100)]
+    pub limit: u32,
+}
+
+impl Archive {
+    pub fn run(self) -> Result<(), crate::CommandError> {
+        let db = ArchiveDb::open_read_only(&self.archive_path)?;
+
+        match self.command {
+            ArchiveCommand::Blocks(v) => print_json(&db.blocks(&ArchiveBlocksQuery {
+                from_height: v.from_height,
+                to_height: v.to_height,
+                chain_status: v.chain_status,
+                limit: v.limit,
+            })?),
+            ArchiveCommand::Block(v) => match db.block(&v.state_hash)? {
+                Some(block) => print_json(&block),
+                None => Err(format!("block {} is not archived", v.state_hash).into()),
+            },
+            ArchiveCommand::AccountHistory(v) => {
+                print_json(&db.account_history(&v.public_key, v.chain_status, v.limit)?)
+            }
+            ArchiveCommand::AccountCommands(v) => {
+                print_json(&db.account_user_commands(&v.public_key, v.chain_status, v.limit)?)
+            }
+        }
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), crate::CommandError> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
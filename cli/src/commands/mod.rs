@@ -1,8 +1,10 @@
+pub mod archive;
 pub mod build_info;
 pub mod misc;
 pub mod node;
 pub mod replay;
 pub mod snark;
+pub mod vrf_audit;
 
 pub type CommandError = Box<dyn std::error::Error>;
 
@@ -21,6 +23,10 @@ pub enum Command {
     /// Miscilaneous utilities.
     Misc(misc::Misc),
     Replay(replay::Replay),
+    /// Query the embedded archive database.
+    Archive(archive::Archive),
+    /// Audit slot wins of archived blocks against a staking ledger.
+    VrfAudit(vrf_audit::VrfAudit),
     BuildInfo(build_info::Command),
 }
 
@@ -31,6 +37,8 @@ impl Command {
             Self::Node(v) => v.run(),
             Self::Misc(v) => v.run(),
             Self::Replay(v) => v.run(),
+            Self::Archive(v) => v.run(),
+            Self::VrfAudit(v) => v.run(),
             Self::BuildInfo(v) => v.run(),
         }
     }
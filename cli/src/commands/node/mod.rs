@@ -1,4 +1,6 @@
 use std::ffi::OsString;
+use std::net::IpAddr;
+use std::num::NonZeroUsize;
 
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -11,7 +13,7 @@ use rand::prelude::*;
 
 use tokio::select;
 
-use node::account::AccountPublicKey;
+use node::account::{AccountLocalSigner, AccountPublicKey, AccountSecretKey, AccountSigner};
 use node::core::channels::mpsc;
 use node::core::log::inner::Level;
 use node::event_source::EventSourceAction;
@@ -26,12 +28,15 @@ use node::service::{Recorder, Service};
 use node::snark::{get_srs, get_verifier_index, VerifierKind};
 use node::stats::Stats;
 use node::{
-    BuildEnv, Config, GlobalConfig, LedgerConfig, SnarkConfig, SnarkerConfig, SnarkerStrategy,
-    State, TransitionFrontierConfig,
+    BlockProducerConfig, BuildEnv, Config, GlobalConfig, LedgerConfig, SnarkConfig, SnarkerConfig,
+    SnarkerFeePolicy, SnarkerStrategy, SnarkerWorkerKind, State, TransitionFrontierConfig,
 };
 
+use node::rpc::RpcRole;
+use openmina_node_native::http_server::{self, HttpServerConfig, RpcAuth};
+use openmina_node_native::remote_signer::{AccountRemoteSigner, RemoteSignerAddr};
 use openmina_node_native::rpc::RpcService;
-use openmina_node_native::{http_server, tracing, NodeService, P2pTaskSpawner, RpcSender};
+use openmina_node_native::{tracing, NodeService, P2pTaskSpawner, RpcSender};
 
 const CHAIN_ID: &'static str = "fd7d111973bf5a9e3e87384f560fdead2f272589ca00b6d9e357fca9839631da";
 
@@ -49,6 +54,34 @@ pub struct Node {
     #[arg(long, short, env, default_value = "3000")]
     pub port: u16,
 
+    /// Address for the http server to bind to
+    #[arg(long, env, default_value = "0.0.0.0")]
+    pub http_bind_address: IpAddr,
+
+    /// Serve admin rpc endpoints on this port instead of the http port.
+    ///
+    /// Without rpc auth, admin endpoints on the http port are only served
+    /// to clients on the loopback interface.
+    #[arg(long, env)]
+    pub admin_port: Option<u16>,
+
+    /// Address for the admin http server to bind to
+    #[arg(long, env, default_value = "127.0.0.1")]
+    pub admin_bind_address: IpAddr,
+
+    /// File with rpc auth tokens, one `<read-only|admin> <token>` per line.
+    ///
+    /// Enables authentication on the http server. Webrtc signaling and
+    /// health checks stay public.
+    #[arg(long, env)]
+    pub rpc_auth_file: Option<PathBuf>,
+
+    /// Token granting admin access to the http server.
+    ///
+    /// Enables authentication on the http server, same as `--rpc-auth-file`.
+    #[arg(long, env, hide_env_values = true)]
+    pub rpc_admin_token: Option<String>,
+
     /// LibP2P port to listen on
     #[arg(long, env, default_value = "8302")]
     pub libp2p_port: u16,
@@ -66,25 +99,89 @@ pub struct Node {
     #[arg(long, env)]
     pub run_snarker: Option<AccountPublicKey>,
 
-    // /// Enable block producer with this key
-    // #[arg(long, env)]
-    // pub producer_key: Option<String>,
+    /// Produce blocks with this key.
+    ///
+    /// Can be repeated, or given as a comma separated list in the env
+    /// variable, to produce blocks for several keys from a single node.
+    #[arg(
+        long,
+        env = "OPENMINA_PRODUCER_SEC_KEYS",
+        value_delimiter = ',',
+        hide_env_values = true
+    )]
+    pub producer_key: Vec<AccountSecretKey>,
+
+    /// Produce blocks with the keys held by the remote signer listening
+    /// on this socket, `unix:<path>`, instead of `--producer-key`.
+    #[arg(long, env, conflicts_with = "producer_key")]
+    pub producer_remote_signer: Option<RemoteSignerAddr>,
+
+    /// Go through block production for won slots, but never inject or
+    /// broadcast the produced blocks.
+    #[arg(long, env)]
+    pub block_producer_dry_run: bool,
+
     /// Snark fee, in Mina
     #[arg(long, env, default_value_t = 1_000_000)]
     pub snarker_fee: u64,
 
+    /// Lower bound of the snark fee, in nanomina. When set together with
+    /// `--snarker-fee-max`, the fee is adjusted to undercut most of the
+    /// recently winning fees of other snarkers, within these bounds.
+    #[arg(long, env, requires = "snarker_fee_max")]
+    pub snarker_fee_min: Option<u64>,
+
+    /// Upper bound of the snark fee, in nanomina.
+    #[arg(long, env, requires = "snarker_fee_min")]
+    pub snarker_fee_max: Option<u64>,
+
     #[arg(long, env, default_value = "seq")]
     pub snarker_strategy: SnarkerStrategy,
 
+    /// Minimum payout, in nanomina per second of estimated proving time,
+    /// for a job to be worth committing to with `profit` snarker strategy.
+    #[arg(long, env, default_value_t = 0)]
+    pub snarker_min_payout_per_sec: u64,
+
     /// Mina snark worker path
     #[arg(long, env, default_value = "cli/bin/snark-worker")]
     pub snarker_exe_path: OsString,
 
+    /// Number of snark workers, which prove jobs in parallel.
+    #[arg(long, env, default_value = "1")]
+    pub snarker_workers: NonZeroUsize,
+
+    /// Whether to spawn external snark worker processes at
+    /// `--snarker-exe-path`, or to prove in the node's process.
+    #[arg(long, env, default_value = "external")]
+    pub snarker_worker_kind: SnarkerWorkerKind,
+
     #[arg(long, default_value = "none")]
     pub record: String,
 
     #[arg(long, default_value = "none")]
     pub additional_ledgers_path: Option<PathBuf>,
+
+    /// Archive applied blocks into the SQLite database at this path.
+    ///
+    /// Use `openmina archive` to query it.
+    #[arg(long, env)]
+    pub archive_path: Option<PathBuf>,
+
+    /// Persist verified snarks and our own commitments in a log at this
+    /// path, so that they are restored after the node is restarted.
+    #[arg(long, env)]
+    pub snark_pool_path: Option<PathBuf>,
+
+    /// Persist history of won slots and produced blocks in a file at this
+    /// path, so that it is restored after the node is restarted.
+    #[arg(long, env)]
+    pub block_producer_history_path: Option<PathBuf>,
+
+    /// Correct slot timing by the estimated skew of the system clock,
+    /// detected from arrival times of blocks, by at most half a slot.
+    #[arg(long, env)]
+    pub clock_skew_correction: bool,
 }
 
 fn default_peers() -> Vec<P2pConnectionOutgoingInitOpts> {
@@ -109,6 +206,15 @@ impl Node {
     pub fn run(self) -> Result<(), crate::CommandError> {
         tracing::initialize(self.verbosity);
 
+        if let (Some(min), Some(max)) = (self.snarker_fee_min, self.snarker_fee_max) {
+            if min > max {
+                return Err(format!(
+                    "--snarker-fee-min ({min}) must not be greater than --snarker-fee-max ({max})"
+                )
+                .into());
+            }
+        }
+
         if let Err(ref e) = rayon::ThreadPoolBuilder::new()
             .num_threads(num_cpus::get().max(2) - 1)
             .thread_name(|i| format!("openmina_rayon_{i}"))
@@ -133,20 +239,21 @@ impl Node {
         });
         let pub_key = secret_key.public_key();
 
-        // let block_producer: Option<BlockProducerConfig> =
-        //     self.producer_key.clone().map(|producer_key| {
-        //         let compressed_pub_key = keypair_from_bs58_string(&producer_key)
-        //             .public
-        //             .into_compressed();
-        //         BlockProducerConfig {
-        //             pub_key: NonZeroCurvePoint::from(NonZeroCurvePointUncompressedStableV1 {
-        //                 x: compressed_pub_key.x.into(),
-        //                 is_odd: compressed_pub_key.is_odd,
-        //             }),
-        //             custom_coinbase_receiver: None,
-        //             proposed_protocol_version: None,
-        //         }
-        //     });
+        let producer_signer: Option<Arc<dyn AccountSigner>> = match self.producer_remote_signer {
+            Some(addr) => Some(Arc::new(AccountRemoteSigner::new(addr))),
+            None if !self.producer_key.is_empty() => {
+                Some(Arc::new(AccountLocalSigner::new(self.producer_key)))
+            }
+            None => None,
+        };
+        let block_producer = producer_signer
+            .as_ref()
+            .map(|signer| signer.public_keys())
+            .transpose()?
+            .map(|pub_keys| BlockProducerConfig {
+                dry_run: self.block_producer_dry_run,
+                ..BlockProducerConfig::new(pub_keys.into_iter().map(Into::into).collect())
+            });
 
         let work_dir = shellexpand::full(&self.work_dir).unwrap().into_owned();
         let rng_seed = rng.next_u64();
@@ -169,8 +276,23 @@ impl Node {
                     )),
                     strategy: self.snarker_strategy,
                     auto_commit: true,
+                    min_payout_per_sec: self.snarker_min_payout_per_sec,
                     path: self.snarker_exe_path,
+                    workers: self.snarker_workers.get(),
+                    worker_kind: self.snarker_worker_kind,
+                    fee_policy: self
+                        .snarker_fee_min
+                        .zip(self.snarker_fee_max)
+                        .map(|(min, max)| SnarkerFeePolicy {
+                            min: CurrencyFeeStableV1(
+                                UnsignedExtendedUInt64Int64ForVersionTagsStableV1(min.into()),
+                            ),
+                            max: CurrencyFeeStableV1(
+                                UnsignedExtendedUInt64Int64ForVersionTagsStableV1(max.into()),
+                            ),
+                        }),
                 }),
+                clock_skew_correction: self.clock_skew_correction,
             },
             p2p: P2pConfig {
                 libp2p_port: Some(self.libp2p_port),
@@ -182,7 +304,7 @@ impl Node {
                 enabled_channels: ChannelId::iter_all().collect(),
             },
             transition_frontier: TransitionFrontierConfig::default(),
-            block_producer: None,
+            block_producer,
         };
         let (event_sender, event_receiver) = mpsc::unbounded_channel();
 
@@ -210,7 +332,24 @@ impl Node {
 
         let mut rpc_service = RpcService::new();
 
-        let http_port = self.port;
+        let mut rpc_auth = self
+            .rpc_auth_file
+            .as_deref()
+            .map(RpcAuth::load)
+            .transpose()?;
+        if let Some(token) = self.rpc_admin_token.clone() {
+            rpc_auth
+                .get_or_insert_with(RpcAuth::default)
+                .add_token(token, RpcRole::Admin);
+        }
+        let http_config = HttpServerConfig {
+            bind_address: self.http_bind_address,
+            port: self.port,
+            admin_port: self.admin_port,
+            admin_bind_address: self.admin_bind_address,
+            auth: rpc_auth,
+            chain_id: CHAIN_ID.to_owned(),
+        };
         let rpc_sender = RpcSender::new(rpc_service.req_sender().clone());
 
         // spawn http-server
@@ -222,7 +361,7 @@ impl Node {
             .name("openmina_http_server".to_owned())
             .spawn(move || {
                 let local_set = tokio::task::LocalSet::new();
-                local_set.block_on(&runtime, http_server::run(http_port, rpc_sender))
+                local_set.block_on(&runtime, http_server::run(http_config, rpc_sender))
             })
             .unwrap();
 
@@ -247,7 +386,7 @@ impl Node {
 
                 let local_set = tokio::task::LocalSet::new();
                 local_set.block_on(&runtime, async move {
-                    let service = NodeService {
+                    let mut service = NodeService {
                         rng: StdRng::seed_from_u64(rng_seed),
                         event_sender,
                         p2p_event_sender,
@@ -257,7 +396,11 @@ impl Node {
                         peers,
                         libp2p,
                         block_producer: None,
-                        snark_worker_sender: None,
+                        block_producer_history_persist: None,
+                        snark_workers: Default::default(),
+                        archive: None,
+                        snark_pool_persist: None,
+                        snark_verifier: Default::default(),
                         rpc: rpc_service,
                         stats: Stats::new(),
                         recorder: match record.trim() {
@@ -268,9 +411,36 @@ impl Node {
                         replayer: None,
                         invariants_state: Default::default(),
                     };
-                    // if let Some(producer_key) = self.producer_key {
-                    //     service.block_producer_start(keypair_from_bs58_string(&producer_key));
-                    // }
+                    if let Some(path) = &self.archive_path {
+                        if let Err(e) = service.archive_start(path) {
+                            openmina_core::log::error!(openmina_core::log::system_time();
+                                    kind = "FatalError",
+                                    summary = "failed to open archive database",
+                                    error = e.to_string());
+                            panic!("FatalError: {:?}", e);
+                        }
+                    }
+                    if let Some(path) = &self.snark_pool_path {
+                        if let Err(e) = service.snark_pool_persist_start(path) {
+                            openmina_core::log::error!(openmina_core::log::system_time();
+                                    kind = "FatalError",
+                                    summary = "failed to open snark pool log",
+                                    error = e.to_string());
+                            panic!("FatalError: {:?}", e);
+                        }
+                    }
+                    if let Some(path) = &self.block_producer_history_path {
+                        if let Err(e) = service.block_producer_history_persist_start(path) {
+                            openmina_core::log::error!(openmina_core::log::system_time();
+                                    kind = "FatalError",
+                                    summary = "failed to read block producer history",
+                                    error = e.to_string());
+                            panic!("FatalError: {:?}", e);
+                        }
+                    }
+                    if let Some(signer) = producer_signer {
+                        service.block_producer_start(signer);
+                    }
 
                     let state = State::new(config);
                     let mut node = ::node::Node::new(state, service, None);
@@ -61,7 +61,10 @@ impl ReplayStateWithInputActions {
             peers: Default::default(),
             libp2p: Libp2pService::mocked().0,
             block_producer: None,
-            snark_worker_sender: None,
+            snark_workers: Default::default(),
+            archive: None,
+            snark_pool_persist: None,
+            snark_verifier: Default::default(),
             rpc: RpcService::new(),
             stats: Default::default(),
             recorder: Recorder::None,
@@ -0,0 +1,873 @@
+use std::collections::HashMap;
+use std::fs;
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use ledger::generators::user_command::sequence_zkapp_command_with_ledger;
+use ledger::generators::zkapp_command_builder::replace_authorizations;
+use ledger::proofs::block::BlockParams;
+use ledger::proofs::gates::{get_provers, Provers};
+use ledger::proofs::merge::MergeParams;
+use ledger::proofs::transaction::transaction_snark::CONSTRAINT_CONSTANTS;
+use ledger::proofs::transaction::{ProofError, TransactionParams};
+use ledger::proofs::zkapp::{LedgerProof, ZkappParams};
+use ledger::proofs::{
+    generate_block_proof, generate_merge_proof, generate_tx_proof, generate_zkapp_proof,
+};
+use ledger::scan_state::currency::{Fee, Magnitude, Nonce, Slot};
+use ledger::scan_state::pending_coinbase::Stack;
+use ledger::scan_state::scan_state::transaction_snark::{SokMessage, Statement};
+use ledger::scan_state::transaction_logic::for_tests::{
+    HashableKeypair, InitLedger, TransactionSpec,
+};
+use ledger::scan_state::transaction_logic::protocol_state::protocol_state_body_view;
+use ledger::scan_state::transaction_logic::signed_command::{
+    self, PaymentPayload, SignedCommand, SignedCommandPayload,
+};
+use ledger::scan_state::transaction_logic::transaction_union_payload::TransactionUnionPayload;
+use ledger::scan_state::transaction_logic::zkapp_command::Control;
+use ledger::scan_state::transaction_logic::{
+    apply_transactions, valid, Memo, Transaction, UserCommand,
+};
+use ledger::staged_ledger::staged_ledger::StagedLedger;
+use ledger::{gen_keypair, Mask};
+use mina_p2p_messages::binprot::BinProtRead;
+use mina_p2p_messages::hash::MinaHash;
+use mina_p2p_messages::v2::{
+    LedgerProofProdStableV2, MinaBaseUserCommandStableV2,
+    MinaStateBlockchainStateValueStableV2LedgerProofStatement,
+    MinaStateProtocolStateBodyValueStableV2,
+    MinaStateSnarkedLedgerStatePendingCoinbaseStackStateInitStackStableV1,
+    MinaTransactionTransactionStableV2, ProverExtendBlockchainInputStableV2,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single,
+    TransactionSnarkScanStateTransactionWithWitnessStableV2, TransactionWitnessStableV2,
+};
+use mina_signer::{NetworkId, Signer};
+use serde::Serialize;
+
+/// Snark work spec bundled with the binary, proved if no `--spec` is given.
+const DEFAULT_SPEC: &[u8] = include_bytes!("../../../../tests/files/snark_spec/spec1.bin");
+
+/// How many times to try generating a zkApp command without proofs.
+const ZKAPP_GENERATE_ATTEMPTS: usize = 64;
+
+/// Benchmark proving of transaction, merge, zkApp and block circuits.
+///
+/// Transaction, zkApp and merge jobs are taken from snark work specs,
+/// as received by the snark worker, and from payments and zkApp commands
+/// generated with `ledger::generators`. Generated commands are applied
+/// on top of the protocol state of the first transaction in the specs.
+/// Proofs of a pair of transactions from the same spec, and of the first
+/// two generated payments, are also merged, so that the merge circuit is
+/// measured even if no spec contains a merge job.
+///
+/// No block prover input is shipped, as it can't be generated without a
+/// chain. One can be dumped by a Mina block producer, by writing the
+/// `Prover.Extend_blockchain_input.t` it passes to its prover with
+/// `bin_write_t`, prefixed with its 64-bit little-endian length. That is
+/// the format of `block_input-*.bin` files read by the ledger proof tests.
+///
+/// Report is printed as json, to be used for sizing snarker hardware.
+#[derive(Debug, clap::Args)]
+pub struct Bench {
+    /// Snark work spec, binprot encoded `SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse`.
+    /// If none is given, the spec bundled with the binary is used.
+    #[arg(long)]
+    pub spec: Vec<PathBuf>,
+
+    /// Block prover input, binprot encoded `ProverExtendBlockchainInputStableV2`,
+    /// prefixed with its 64-bit little-endian length.
+    #[arg(long)]
+    pub block_input: Vec<PathBuf>,
+
+    /// How many payments to generate and prove.
+    #[arg(long, default_value_t = 2)]
+    pub generated_payments: usize,
+
+    /// How many zkApp commands to generate and prove.
+    #[arg(long, default_value_t = 1)]
+    pub generated_zkapps: usize,
+
+    /// How many times to prove each input.
+    #[arg(long, short, default_value_t = 1)]
+    pub iterations: usize,
+}
+
+#[derive(Serialize, Debug)]
+struct BenchReport {
+    cpus: usize,
+    /// Time to build provers of all circuits, which is spent once, when
+    /// the snarker starts.
+    provers_build_ms: u64,
+    provers_peak_memory: Option<u64>,
+    circuits: Vec<CircuitBench>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+enum Circuit {
+    Transaction,
+    Merge,
+    Zkapp,
+    Block,
+}
+
+#[derive(Serialize, Debug)]
+struct CircuitBench {
+    circuit: Circuit,
+    input: String,
+    /// Time to prepare the input the job comes from, spent once: decoding
+    /// it, or generating and applying the generated commands. Not
+    /// available for merges of proofs created by the benchmark.
+    input_ms: Option<u64>,
+    /// Witness generation and constraint check of the step circuit,
+    /// without creating proofs. Not available for zkApps.
+    witness_ms: Option<BenchTimes>,
+    /// Step and wrap proof creation, which is what is left of the whole
+    /// proof generation once the witness is done. Not available for zkApps.
+    proof_ms: Option<BenchTimes>,
+    /// Whole proof generation, including the witness.
+    prove_ms: BenchTimes,
+    /// Peak resident memory of the process while proving, in bytes.
+    /// Only available on linux.
+    peak_memory: Option<u64>,
+}
+
+#[derive(Serialize, Debug)]
+struct BenchTimes {
+    min: u64,
+    mean: u64,
+    max: u64,
+}
+
+impl BenchTimes {
+    fn new(times: &[Duration]) -> Option<Self> {
+        let millis = times.iter().map(|t| t.as_millis() as u64);
+        Some(Self {
+            min: millis.clone().min()?,
+            mean: millis.clone().sum::<u64>() / times.len() as u64,
+            max: millis.max()?,
+        })
+    }
+}
+
+/// Transaction snark jobs of one input, proved for the same message.
+struct JobsInput {
+    name: String,
+    message: SokMessage,
+    jobs: Vec<SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single>,
+    input_time: Duration,
+    /// Whether jobs are consecutive transactions of the same ledger, so
+    /// that proofs of the first two can be merged.
+    mergeable: bool,
+}
+
+/// Protocol state the generated commands are applied on top of.
+struct GenerateState {
+    body: MinaStateProtocolStateBodyValueStableV2,
+    global_slot: Slot,
+}
+
+impl GenerateState {
+    fn from_job(job: &SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single) -> Option<Self> {
+        match job {
+            SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Transition(_, tx_witness) => {
+                Some(Self {
+                    body: tx_witness.protocol_state_body.clone(),
+                    global_slot: (&tx_witness.block_global_slot).into(),
+                })
+            }
+            SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Merge(_) => None,
+        }
+    }
+}
+
+enum BenchJob<'a> {
+    Transaction {
+        statement: &'a MinaStateBlockchainStateValueStableV2LedgerProofStatement,
+        tx_witness: &'a TransactionWitnessStableV2,
+        message: &'a SokMessage,
+    },
+    Zkapp {
+        statement: &'a MinaStateBlockchainStateValueStableV2LedgerProofStatement,
+        tx_witness: &'a TransactionWitnessStableV2,
+        message: &'a SokMessage,
+    },
+    Merge {
+        statement: Statement<()>,
+        proofs: [LedgerProofProdStableV2; 2],
+        message: &'a SokMessage,
+    },
+    Block(&'a ProverExtendBlockchainInputStableV2),
+}
+
+impl<'a> BenchJob<'a> {
+    fn from_single(
+        single: &'a SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single,
+        message: &'a SokMessage,
+    ) -> Self {
+        match single {
+            SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Transition(
+                statement,
+                tx_witness,
+            ) => {
+                let is_zkapp = match &tx_witness.transaction {
+                    MinaTransactionTransactionStableV2::Command(cmd) => {
+                        matches!(&**cmd, MinaBaseUserCommandStableV2::ZkappCommand(_))
+                    }
+                    _ => false,
+                };
+                if is_zkapp {
+                    Self::Zkapp {
+                        statement,
+                        tx_witness,
+                        message,
+                    }
+                } else {
+                    Self::Transaction {
+                        statement,
+                        tx_witness,
+                        message,
+                    }
+                }
+            }
+            SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Merge(merge) => {
+                let (statement, proof1, proof2) = &**merge;
+                Self::Merge {
+                    statement: (&**statement).into(),
+                    proofs: [proof1.clone(), proof2.clone()],
+                    message,
+                }
+            }
+        }
+    }
+
+    fn circuit(&self) -> Circuit {
+        match self {
+            Self::Transaction { .. } => Circuit::Transaction,
+            Self::Merge { .. } => Circuit::Merge,
+            Self::Zkapp { .. } => Circuit::Zkapp,
+            Self::Block(_) => Circuit::Block,
+        }
+    }
+
+    /// Statement of the ledger proof produced by the job. Block proofs
+    /// can't be merged, so they have none.
+    fn statement(&self) -> Option<Statement<()>> {
+        match self {
+            Self::Transaction { statement, .. } | Self::Zkapp { statement, .. } => {
+                Some((*statement).into())
+            }
+            Self::Merge { statement, .. } => Some(statement.clone()),
+            Self::Block(_) => None,
+        }
+    }
+
+    /// Generates the proof, or if `only_witness` is set, only the step
+    /// witness, checking that it satisfies the constraints.
+    fn prove(
+        &self,
+        provers: &Provers,
+        only_witness: bool,
+    ) -> Result<Option<LedgerProofProdStableV2>, ProofError> {
+        let (proof, message) = match self {
+            Self::Transaction {
+                statement,
+                tx_witness,
+                message,
+            } => {
+                let proof = generate_tx_proof(TransactionParams {
+                    statement,
+                    tx_witness,
+                    message,
+                    tx_step_prover: &provers.tx_step_prover,
+                    tx_wrap_prover: &provers.tx_wrap_prover,
+                    only_verify_constraints: only_witness,
+                    expected_step_proof: None,
+                    ocaml_wrap_witness: None,
+                });
+                (witness_result(proof, only_witness)?, message)
+            }
+            Self::Zkapp {
+                statement,
+                tx_witness,
+                message,
+            } => {
+                let proof = generate_zkapp_proof(ZkappParams {
+                    statement,
+                    tx_witness,
+                    message,
+                    step_opt_signed_opt_signed_prover: &provers
+                        .zkapp_step_opt_signed_opt_signed_prover,
+                    step_opt_signed_prover: &provers.zkapp_step_opt_signed_prover,
+                    step_proof_prover: &provers.zkapp_step_proof_prover,
+                    merge_step_prover: &provers.merge_step_prover,
+                    tx_wrap_prover: &provers.tx_wrap_prover,
+                    opt_signed_path: None,
+                    proved_path: None,
+                })?;
+                return Ok(Some((&proof).into()));
+            }
+            Self::Merge {
+                statement,
+                proofs,
+                message,
+            } => {
+                let proof = generate_merge_proof(MergeParams {
+                    statement: statement.clone(),
+                    proofs,
+                    message,
+                    step_prover: &provers.merge_step_prover,
+                    wrap_prover: &provers.tx_wrap_prover,
+                    only_verify_constraints: only_witness,
+                    expected_step_proof: None,
+                    ocaml_wrap_witness: None,
+                });
+                (witness_result(proof, only_witness)?, message)
+            }
+            Self::Block(input) => {
+                let proof = generate_block_proof(BlockParams {
+                    input,
+                    block_step_prover: &provers.block_step_prover,
+                    block_wrap_prover: &provers.block_wrap_prover,
+                    tx_wrap_prover: &provers.tx_wrap_prover,
+                    only_verify_constraints: only_witness,
+                    expected_step_proof: None,
+                    ocaml_wrap_witness: None,
+                });
+                witness_result(proof, only_witness)?;
+                return Ok(None);
+            }
+        };
+        let (Some(proof), Some(statement)) = (proof, self.statement()) else {
+            return Ok(None);
+        };
+        let statement = statement.with_digest(message.digest());
+        Ok(Some((&LedgerProof { statement, proof }).into()))
+    }
+}
+
+/// Proof generation returns [`ProofError::ConstraintsOk`] once the
+/// witness is checked, if only the witness was requested.
+fn witness_result<T>(
+    result: Result<T, ProofError>,
+    only_witness: bool,
+) -> Result<Option<T>, ProofError> {
+    match result {
+        Ok(v) => Ok(Some(v)),
+        Err(ProofError::ConstraintsOk) if only_witness => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+impl Bench {
+    pub fn run(self) -> Result<(), crate::CommandError> {
+        let iterations = self.iterations.max(1);
+
+        let mut inputs = vec![];
+        for (name, data) in self.specs()? {
+            let (spec, input_time) = timed(|| read_spec(&data));
+            let (message, jobs) = spec.map_err(|err| format!("{name}: {err}"))?;
+            inputs.push(JobsInput {
+                name,
+                message,
+                jobs,
+                input_time,
+                mergeable: true,
+            });
+        }
+
+        if self.generated_payments > 0 || self.generated_zkapps > 0 {
+            eprintln!("generating commands...");
+            let state = inputs
+                .iter()
+                .flat_map(|input| &input.jobs)
+                .find_map(GenerateState::from_job)
+                .ok_or("generated commands need a transaction in the specs")?;
+            let message = SokMessage::create(Fee::zero(), gen_keypair().public.into_compressed());
+
+            if self.generated_payments > 0 {
+                let (jobs, input_time) =
+                    timed(|| generate_payments(self.generated_payments, &state));
+                inputs.push(JobsInput {
+                    name: "generated payments".to_owned(),
+                    message: message.clone(),
+                    jobs: jobs?,
+                    input_time,
+                    mergeable: true,
+                });
+            }
+            // Each zkApp command is generated with its own ledger, so
+            // their proofs can't be merged.
+            for i in 0..self.generated_zkapps {
+                let (jobs, input_time) = timed(|| generate_zkapp(&state));
+                inputs.push(JobsInput {
+                    name: format!("generated zkapp {i}"),
+                    message: message.clone(),
+                    jobs: jobs?,
+                    input_time,
+                    mergeable: false,
+                });
+            }
+        }
+
+        let mut block_inputs = vec![];
+        for path in &self.block_input {
+            let (input, input_time) = timed(|| read_block_input(path));
+            block_inputs.push((path.display().to_string(), input?, input_time));
+        }
+
+        eprintln!("building provers...");
+        peak_memory_reset();
+        let (provers, provers_build_time) = timed(get_provers);
+        let mut report = BenchReport {
+            cpus: num_cpus::get(),
+            provers_build_ms: provers_build_time.as_millis() as u64,
+            provers_peak_memory: peak_memory(),
+            circuits: vec![],
+        };
+
+        for input in &inputs {
+            report
+                .circuits
+                .extend(bench_jobs(input, &provers, iterations)?);
+        }
+
+        for (name, input, input_time) in block_inputs {
+            let job = BenchJob::Block(&input);
+            let (bench, _) = bench_job(&job, &provers, name, Some(input_time), iterations)?;
+            report.circuits.push(bench);
+        }
+
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        Ok(())
+    }
+
+    /// Names and contents of the specs to prove.
+    fn specs(&self) -> Result<Vec<(String, Vec<u8>)>, crate::CommandError> {
+        if self.spec.is_empty() {
+            return Ok(vec![("default spec".to_owned(), DEFAULT_SPEC.to_vec())]);
+        }
+        self.spec
+            .iter()
+            .map(|path| Ok((path.display().to_string(), fs::read(path)?)))
+            .collect()
+    }
+}
+
+/// Proves the jobs of the `input`, and merges proofs of the first two if
+/// they can be merged.
+fn bench_jobs(
+    input: &JobsInput,
+    provers: &Provers,
+    iterations: usize,
+) -> Result<Vec<CircuitBench>, crate::CommandError> {
+    let mut benches = vec![];
+    let mut proved = vec![];
+    for (i, single) in input.jobs.iter().enumerate() {
+        let job = BenchJob::from_single(single, &input.message);
+        let name = format!("{}[{i}]", input.name);
+        let (bench, proof) = bench_job(&job, provers, name, Some(input.input_time), iterations)?;
+        benches.push(bench);
+        proved.extend(job.statement().zip(proof));
+    }
+
+    if !input.mergeable {
+        return Ok(benches);
+    }
+    if let [(statement1, proof1), (statement2, proof2), ..] = &proved[..] {
+        match statement1.merge(statement2) {
+            Ok(statement) => {
+                let job = BenchJob::Merge {
+                    statement,
+                    proofs: [proof1.clone(), proof2.clone()],
+                    message: &input.message,
+                };
+                let name = format!("{}[0..2]", input.name);
+                let (bench, _) = bench_job(&job, provers, name, None, iterations)?;
+                benches.push(bench);
+            }
+            Err(_) => eprintln!("{}: statements can't be merged", input.name),
+        }
+    }
+    Ok(benches)
+}
+
+/// Proves the `job` `iterations` times. Returns the measurements and
+/// the last produced proof.
+fn bench_job(
+    job: &BenchJob,
+    provers: &Provers,
+    input: String,
+    input_time: Option<Duration>,
+    iterations: usize,
+) -> Result<(CircuitBench, Option<LedgerProofProdStableV2>), crate::CommandError> {
+    let circuit = job.circuit();
+    let err = |err: ProofError| format!("{input}: {circuit:?} proof failed: {err:?}");
+    eprintln!("proving {circuit:?} {input}...");
+
+    peak_memory_reset();
+    let mut witness_times = vec![];
+    let mut prove_times = vec![];
+    let mut proof = None;
+    for _ in 0..iterations {
+        if !matches!(job, BenchJob::Zkapp { .. }) {
+            let (res, time) = timed(|| job.prove(provers, true));
+            res.map_err(err)?;
+            witness_times.push(time);
+        }
+        let (res, time) = timed(|| job.prove(provers, false));
+        proof = res.map_err(err)?;
+        prove_times.push(time);
+    }
+
+    let bench = CircuitBench {
+        circuit,
+        input_ms: input_time.map(|t| t.as_millis() as u64),
+        witness_ms: BenchTimes::new(&witness_times),
+        proof_ms: BenchTimes::new(&proof_times(&prove_times, &witness_times)),
+        prove_ms: BenchTimes::new(&prove_times).expect("at least one iteration"),
+        peak_memory: peak_memory(),
+        input,
+    };
+    Ok((bench, proof))
+}
+
+/// Time left of each proof generation once its witness is done, measured
+/// as the difference with the witness generation of the same iteration.
+fn proof_times(prove_times: &[Duration], witness_times: &[Duration]) -> Vec<Duration> {
+    prove_times
+        .iter()
+        .zip(witness_times)
+        .map(|(prove, witness)| prove.saturating_sub(*witness))
+        .collect()
+}
+
+fn read_spec(
+    mut data: &[u8],
+) -> Result<
+    (
+        SokMessage,
+        Vec<SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single>,
+    ),
+    crate::CommandError,
+> {
+    let SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(Some((spec, public_key))) =
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse::binprot_read(&mut data)?
+    else {
+        return Err("spec without work".into());
+    };
+    let message = SokMessage::create((&spec.fee).into(), (&public_key).into());
+    let jobs = match spec.instances {
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances::One(single) => vec![single],
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances::Two((first, second)) => {
+            vec![first, second]
+        }
+    };
+    Ok((message, jobs))
+}
+
+fn read_block_input(
+    path: &Path,
+) -> Result<ProverExtendBlockchainInputStableV2, crate::CommandError> {
+    let data = fs::read(path)?;
+    let mut data = data
+        .get(size_of::<u64>()..)
+        .ok_or_else(|| format!("{}: block input too short", path.display()))?;
+    Ok(ProverExtendBlockchainInputStableV2::binprot_read(
+        &mut data,
+    )?)
+}
+
+/// Generates `count` payments between accounts of a random ledger.
+fn generate_payments(
+    count: usize,
+    state: &GenerateState,
+) -> Result<Vec<SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single>, crate::CommandError> {
+    let init_ledger = InitLedger::gen();
+    let mut ledger = Mask::create(CONSTRAINT_CONSTANTS.ledger_depth as usize);
+    init_ledger.init(Some(false), &mut ledger);
+
+    let mut nonces: HashMap<_, _> = init_ledger
+        .0
+        .iter()
+        .map(|(kp, _)| (HashableKeypair(kp.clone()), Nonce::zero()))
+        .collect();
+    let txns = (0..count)
+        .map(|_| {
+            let TransactionSpec {
+                fee,
+                sender: (sender, nonce),
+                receiver,
+                amount,
+            } = TransactionSpec::gen(&init_ledger, &mut nonces);
+            let sender_pk = sender.public.into_compressed();
+            let body = signed_command::Body::Payment(PaymentPayload {
+                receiver_pk: receiver,
+                amount,
+            });
+            let payload = SignedCommandPayload::create(
+                fee,
+                sender_pk.clone(),
+                nonce,
+                None,
+                Memo::dummy(),
+                body,
+            );
+
+            let payload_to_sign = TransactionUnionPayload::of_user_command_payload(&payload);
+            let mut signer = mina_signer::create_legacy(NetworkId::TESTNET);
+            let signature = signer.sign(&sender, &payload_to_sign);
+
+            let cmd = SignedCommand {
+                payload,
+                signer: sender_pk,
+                signature,
+            };
+            Transaction::Command(UserCommand::SignedCommand(Box::new(cmd)))
+        })
+        .collect();
+
+    apply_with_witness(ledger, txns, state)
+}
+
+/// Generates a zkApp command, along with its ledger. Account updates
+/// authorized by a proof would need a real side-loaded proof, so only
+/// commands authorized by signatures are kept.
+fn generate_zkapp(
+    state: &GenerateState,
+) -> Result<Vec<SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single>, crate::CommandError> {
+    let (zkapp, ledger) = (0..ZKAPP_GENERATE_ATTEMPTS)
+        .find_map(|_| {
+            let (mut commands, ledger) =
+                sequence_zkapp_command_with_ledger(None, Some(1), Some(1), None, None);
+            let (valid::UserCommand::ZkAppCommand(zkapp), _, keymap) = commands.pop()? else {
+                return None;
+            };
+            let mut zkapp = zkapp.forget();
+            let is_proved = zkapp.account_updates.fold(false, |is_proved, update| {
+                is_proved || matches!(update.authorization, Control::Proof(_))
+            });
+            if is_proved {
+                return None;
+            }
+            replace_authorizations(None, &keymap, &mut zkapp);
+            Some((zkapp, ledger))
+        })
+        .ok_or("failed to generate a zkApp command without proofs")?;
+
+    let txn = Transaction::Command(UserCommand::ZkAppCommand(Box::new(zkapp)));
+    apply_with_witness(ledger, vec![txn], state)
+}
+
+/// Applies `txns` to the `ledger` on top of the protocol `state`, the way
+/// the staged ledger does for a block, and returns their snark jobs.
+fn apply_with_witness(
+    ledger: Mask,
+    txns: Vec<Transaction>,
+    state: &GenerateState,
+) -> Result<Vec<SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single>, crate::CommandError> {
+    let state_view = protocol_state_body_view(&state.body);
+
+    // The staged ledger takes statuses of transactions from the block,
+    // here they are found by applying them to a child of the ledger.
+    let applied = apply_transactions(
+        &CONSTRAINT_CONSTANTS,
+        state.global_slot,
+        &state_view,
+        &mut ledger.make_child(),
+        &txns,
+    )?;
+    let txns = applied
+        .iter()
+        .map(|applied| applied.transaction())
+        .collect();
+
+    // State hash only identifies the block in the scan state, it isn't
+    // a part of the witness.
+    let state_and_body_hash = (Default::default(), MinaHash::hash(&state.body));
+    let (txns_with_witness, ..) = StagedLedger::update_ledger_and_get_statements(
+        &CONSTRAINT_CONSTANTS,
+        state.global_slot,
+        ledger,
+        &Stack::empty(),
+        (txns, None),
+        &state_view,
+        state_and_body_hash,
+    )
+    .map_err(|err| format!("failed to apply generated commands: {err:?}"))?;
+
+    txns_with_witness
+        .iter()
+        .map(|txn| {
+            let TransactionSnarkScanStateTransactionWithWitnessStableV2 {
+                transaction_with_info,
+                state_hash: _,
+                statement,
+                init_stack,
+                first_pass_ledger_witness,
+                second_pass_ledger_witness,
+                block_global_slot,
+            } = txn.into();
+            let (transaction, status) = transaction_with_info.varying.into();
+            let MinaStateSnarkedLedgerStatePendingCoinbaseStackStateInitStackStableV1::Base(
+                init_stack,
+            ) = init_stack
+            else {
+                return Err("merge in base transaction".into());
+            };
+            let tx_witness = TransactionWitnessStableV2 {
+                transaction,
+                first_pass_ledger: first_pass_ledger_witness,
+                second_pass_ledger: second_pass_ledger_witness,
+                protocol_state_body: state.body.clone(),
+                init_stack,
+                status,
+                block_global_slot,
+            };
+            Ok(
+                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Transition(
+                    statement, tx_witness,
+                ),
+            )
+        })
+        .collect()
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let res = f();
+    (res, start.elapsed())
+}
+
+/// Resets peak resident memory of the process, so that it can be
+/// measured for each circuit separately.
+fn peak_memory_reset() {
+    let _ = fs::write("/proc/self/clear_refs", "5");
+}
+
+/// Peak resident memory of the process, in bytes, since the start or
+/// the last [`peak_memory_reset`].
+fn peak_memory() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let kb = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn default_jobs() -> (
+        SokMessage,
+        Vec<SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single>,
+    ) {
+        read_spec(DEFAULT_SPEC).expect("default spec decodes")
+    }
+
+    fn default_state() -> GenerateState {
+        let (_, jobs) = default_jobs();
+        jobs.iter()
+            .find_map(GenerateState::from_job)
+            .expect("default spec has a transaction")
+    }
+
+    #[test]
+    fn bench_times() {
+        assert!(BenchTimes::new(&[]).is_none());
+
+        let times = [1, 2, 6].map(Duration::from_millis);
+        let BenchTimes { min, mean, max } = BenchTimes::new(&times).unwrap();
+        assert_eq!((min, mean, max), (1, 3, 6));
+    }
+
+    #[test]
+    fn proof_times_exclude_witness() {
+        let prove = [10, 20].map(Duration::from_millis);
+        let witness = [4, 25].map(Duration::from_millis);
+        assert_eq!(
+            proof_times(&prove, &witness),
+            [6, 0].map(Duration::from_millis)
+        );
+        assert!(proof_times(&prove, &[]).is_empty());
+    }
+
+    #[test]
+    fn witness_result_only_witness() {
+        assert!(matches!(witness_result(Ok(1), false), Ok(Some(1))));
+        assert!(matches!(
+            witness_result::<()>(Err(ProofError::ConstraintsOk), true),
+            Ok(None)
+        ));
+        assert!(matches!(
+            witness_result::<()>(Err(ProofError::ConstraintsOk), false),
+            Err(ProofError::ConstraintsOk)
+        ));
+    }
+
+    #[test]
+    fn block_input_too_short() {
+        let path = std::env::temp_dir().join(format!("bench-block-input-{}", std::process::id()));
+        fs::write(&path, [0; 4]).unwrap();
+        let res = read_block_input(&path);
+        let _ = fs::remove_file(&path);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn default_spec_is_used() {
+        let bench = Bench {
+            spec: vec![],
+            block_input: vec![],
+            generated_payments: 0,
+            generated_zkapps: 0,
+            iterations: 1,
+        };
+        let specs = bench.specs().unwrap();
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].1, DEFAULT_SPEC);
+
+        let (message, jobs) = default_jobs();
+        assert!(!jobs.is_empty());
+        for job in &jobs {
+            let job = BenchJob::from_single(job, &message);
+            assert!(job.statement().is_some());
+        }
+    }
+
+    #[test]
+    fn generated_payments_can_be_merged() {
+        let jobs = generate_payments(2, &default_state()).unwrap();
+        assert_eq!(jobs.len(), 2);
+
+        let message = SokMessage::create(Fee::zero(), gen_keypair().public.into_compressed());
+        let statements = jobs
+            .iter()
+            .map(|job| {
+                let job = BenchJob::from_single(job, &message);
+                assert!(matches!(job.circuit(), Circuit::Transaction));
+                job.statement().unwrap()
+            })
+            .collect::<Vec<_>>();
+        statements[0].merge(&statements[1]).unwrap();
+    }
+
+    #[test]
+    fn generated_zkapp_is_signed() {
+        let jobs = generate_zkapp(&default_state()).unwrap();
+        assert_eq!(jobs.len(), 1);
+
+        let message = SokMessage::create(Fee::zero(), gen_keypair().public.into_compressed());
+        let job = BenchJob::from_single(&jobs[0], &message);
+        assert!(matches!(job.circuit(), Circuit::Zkapp));
+    }
+}
@@ -1,6 +1,12 @@
+pub mod bench;
+pub use bench::Bench;
+
 pub mod precalculate_block_verifier_index_and_srs;
 pub use precalculate_block_verifier_index_and_srs::PrecalculateBlockVerifierIndexAndSrs;
 
+pub mod worker;
+pub use worker::Worker;
+
 #[derive(Debug, clap::Args)]
 pub struct Snark {
     #[command(subcommand)]
@@ -10,12 +16,16 @@ pub struct Snark {
 #[derive(Debug, clap::Subcommand)]
 pub enum SnarkCommand {
     PrecalculateBlockVerifierIndexAndSrs(PrecalculateBlockVerifierIndexAndSrs),
+    Bench(Bench),
+    Worker(Worker),
 }
 
 impl Snark {
     pub fn run(self) -> Result<(), crate::CommandError> {
         match self.command {
             SnarkCommand::PrecalculateBlockVerifierIndexAndSrs(v) => v.run(),
+            SnarkCommand::Bench(v) => v.run(),
+            SnarkCommand::Worker(v) => v.run(),
         }
     }
 }
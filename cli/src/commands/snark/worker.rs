@@ -0,0 +1,165 @@
+use std::mem::size_of;
+use std::time::{Duration, Instant};
+
+use ledger::proofs::gates::get_provers;
+use ledger::scan_state::scan_state::transaction_snark::SokMessage;
+use mina_p2p_messages::binprot::{BinProtRead, BinProtWrite};
+use mina_p2p_messages::number::Number;
+use mina_p2p_messages::rpc::{SnarkWorkerGetWorkV2, SnarkWorkerSubmitWorkV2};
+use mina_p2p_messages::rpc_kernel::RpcMethod;
+use mina_p2p_messages::v2::{
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single,
+    SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery,
+    SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQueryMetrics,
+    SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQueryMetricsA1, TransactionSnarkWorkTStableV2Proofs,
+};
+use node::rpc::RpcSnarkerWorkSubmitResponse;
+use openmina_node_native::ext_snark_worker::prove_single;
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+
+const OCTET_STREAM: &str = "application/octet-stream";
+
+/// Remote snark worker, which proves jobs handed out by a snarker node.
+///
+/// Jobs are taken from the node's `/snarker/work/get` and the proofs are
+/// sent to `/snarker/work/submit`. Payloads are those of the
+/// `Snark_worker.Rpcs` `get_work` and `submit_work`, carried over the
+/// http rpc of the node, which must be started with `--snarker`. Jobs
+/// are committed to and proven with the key and fee of the node.
+#[derive(Debug, clap::Args)]
+pub struct Worker {
+    /// Address of the http rpc of the snarker node.
+    #[arg(long, short, env, default_value = "http://127.0.0.1:3000")]
+    pub coordinator: String,
+
+    /// Admin token of the node rpc, if it requires authentication.
+    #[arg(long, env, hide_env_values = true)]
+    pub rpc_admin_token: Option<String>,
+
+    /// Seconds to wait before asking again, if the node has no jobs.
+    #[arg(long, default_value_t = 10)]
+    pub idle_secs: u64,
+}
+
+impl Worker {
+    pub fn run(self) -> Result<(), crate::CommandError> {
+        let client = Client::builder().timeout(None).build()?;
+        let coordinator = self.coordinator.trim_end_matches('/');
+
+        eprintln!("building provers...");
+        let provers = get_provers();
+
+        loop {
+            let Some((spec, prover)) = self.get_work(&client, coordinator)?.0 else {
+                std::thread::sleep(Duration::from_secs(self.idle_secs));
+                continue;
+            };
+            let message = SokMessage::create((&spec.fee).into(), (&prover).into());
+
+            let prove = |single: &SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single| {
+                let t = Instant::now();
+                let proof = prove_single(single, &message, &provers)
+                    .map_err(|err| format!("proving failed: {err:?}"))?;
+                let metrics = (Number(t.elapsed().as_secs_f64()), metrics_kind(single));
+                Ok::<_, String>((proof, metrics))
+            };
+            let (proofs, metrics) = match &spec.instances {
+                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances::One(single) => {
+                    let (proof, metrics) = prove(single)?;
+                    (
+                        TransactionSnarkWorkTStableV2Proofs::One(proof),
+                        SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQueryMetrics::One(metrics),
+                    )
+                }
+                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances::Two((
+                    first,
+                    second,
+                )) => {
+                    let (proof1, metrics1) = prove(first)?;
+                    let (proof2, metrics2) = prove(second)?;
+                    (
+                        TransactionSnarkWorkTStableV2Proofs::Two((proof1, proof2)),
+                        SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQueryMetrics::Two((
+                            metrics1, metrics2,
+                        )),
+                    )
+                }
+            };
+
+            let work = SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery {
+                proofs,
+                metrics,
+                spec,
+                prover,
+            };
+            match self.submit_work(&client, coordinator, work)? {
+                RpcSnarkerWorkSubmitResponse::Ok => eprintln!("work submitted"),
+                resp => eprintln!("work rejected: {resp:?}"),
+            }
+        }
+    }
+
+    fn request(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.rpc_admin_token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    fn get_work(
+        &self,
+        client: &Client,
+        coordinator: &str,
+    ) -> Result<<SnarkWorkerGetWorkV2 as RpcMethod>::Response, crate::CommandError> {
+        let request = client
+            .post(format!("{coordinator}/snarker/work/get"))
+            .header(ACCEPT, OCTET_STREAM);
+        let resp = self.request(request).send()?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(format!("get work: {status}: {}", resp.text()?).into());
+        }
+        // binprot encoded, prefixed with 64-bit little-endian length.
+        let body = resp.bytes()?;
+        let mut bytes = body
+            .get(size_of::<u64>()..)
+            .ok_or("get work: response too short")?;
+        Ok(<SnarkWorkerGetWorkV2 as RpcMethod>::Response::binprot_read(
+            &mut bytes,
+        )?)
+    }
+
+    fn submit_work(
+        &self,
+        client: &Client,
+        coordinator: &str,
+        work: <SnarkWorkerSubmitWorkV2 as RpcMethod>::Query,
+    ) -> Result<RpcSnarkerWorkSubmitResponse, crate::CommandError> {
+        let mut encoded = vec![];
+        work.binprot_write(&mut encoded)?;
+        let mut body = Vec::with_capacity(encoded.len() + size_of::<u64>());
+        body.extend((encoded.len() as u64).to_le_bytes());
+        body.extend(encoded);
+
+        let request = client
+            .post(format!("{coordinator}/snarker/work/submit"))
+            .header(CONTENT_TYPE, OCTET_STREAM)
+            .body(body);
+        Ok(self.request(request).send()?.json()?)
+    }
+}
+
+fn metrics_kind(
+    single: &SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single,
+) -> SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQueryMetricsA1 {
+    match single {
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Transition(..) => {
+            SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQueryMetricsA1::Transition
+        }
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Merge(_) => {
+            SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQueryMetricsA1::Merge
+        }
+    }
+}
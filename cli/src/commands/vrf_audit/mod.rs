@@ -0,0 +1,405 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use ledger::{Account, AccountId, AccountIndex, BaseLedger, Database, Mask, TokenId};
+use mina_p2p_messages::binprot::BinProtRead;
+use mina_p2p_messages::v2::{LedgerHash, MinaBaseLedgerHash0StableV1, MinaBlockHeaderStableV2};
+use mina_signer::{CompressedPubKey, Keypair};
+use node::account::{AccountPublicKey, AccountSecretKey};
+use openmina_node_native::archive::{
+    ArchiveBlockRow, ArchiveBlocksQuery, ArchiveChainStatus, ArchiveDb,
+};
+use rayon::prelude::*;
+use serde::Serialize;
+use vrf::{
+    check_vrf_threshold, evaluate_vrf, BigInt, VrfEvaluationInput, VrfEvaluationOutput,
+    VrfThresholdCheckInput, VrfThresholdCheckOutput,
+};
+
+/// Audit slot wins of archived blocks against a staking ledger.
+///
+/// For every block in the height range which used the given staking ledger,
+/// checks that the block's stake winner is in the ledger, delegates to the
+/// block creator and that the vrf output in the block wins the slot for
+/// the winner's stake.
+///
+/// That the vrf output was evaluated from the epoch seed can only be
+/// checked with the producer's private key. If it is given, vrf is also
+/// evaluated for every slot in the range and slots won by the producer,
+/// but without its block in the archive, are reported as missed.
+#[derive(Debug, clap::Args)]
+pub struct VrfAudit {
+    /// Path to the archive database written by `openmina node --archive-path`.
+    #[arg(long, env)]
+    pub archive_path: PathBuf,
+
+    /// Staking ledger of the audited epoch, in the same format as the
+    /// genesis ledger.
+    #[arg(long)]
+    pub staking_ledger: PathBuf,
+
+    #[arg(long)]
+    pub from_height: Option<u32>,
+
+    #[arg(long)]
+    pub to_height: Option<u32>,
+
+    /// Only audit blocks with this status (pending, canonical or orphaned).
+    #[arg(long)]
+    pub chain_status: Option<ArchiveChainStatus>,
+
+    /// Only audit blocks created by this producer.
+    #[arg(long)]
+    pub producer: Option<AccountPublicKey>,
+
+    /// Private key of the producer, to also check slots it didn't win.
+    #[arg(long, env = "OPENMINA_PRODUCER_SEC_KEY")]
+    pub producer_key: Option<AccountSecretKey>,
+}
+
+#[derive(Serialize, Debug)]
+struct VrfAuditReport {
+    staking_ledger_hash: LedgerHash,
+    blocks: Vec<BlockAudit>,
+    /// Blocks in the range which used a different staking ledger.
+    skipped_blocks: Vec<String>,
+    /// Slots won by the producer without its block in the archive.
+    ///
+    /// Only checked if the producer's private key is given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    missed_slots: Option<Vec<MissedSlot>>,
+}
+
+#[derive(Serialize, Debug)]
+struct BlockAudit {
+    state_hash: String,
+    height: u32,
+    chain_status: ArchiveChainStatus,
+    creator: String,
+    stake_winner: String,
+    delegator_index: Option<AccountIndex>,
+    delegated_stake: Option<u64>,
+    vrf: Option<VrfThresholdCheckOutput>,
+    /// Empty if the block passed the audit.
+    problems: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct MissedSlot {
+    global_slot: u32,
+    delegator: String,
+    delegator_index: AccountIndex,
+}
+
+/// Seed and total currency of the epoch, which together with the staking
+/// ledger are the inputs for vrf evaluation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct EpochInputs {
+    seed: String,
+    total_currency: u64,
+}
+
+impl VrfAudit {
+    pub fn run(self) -> Result<(), crate::CommandError> {
+        let (ledger_hash, ledger) = load_ledger(&self.staking_ledger)?;
+        let db = ArchiveDb::open_read_only(&self.archive_path)?;
+        let mut blocks = db.blocks_with_headers(&ArchiveBlocksQuery {
+            from_height: self.from_height,
+            to_height: self.to_height,
+            chain_status: self.chain_status,
+            limit: u32::MAX,
+        })?;
+        blocks.reverse();
+
+        let producer_keypair = self.producer_key.map(Keypair::from);
+        let producer = producer_keypair
+            .as_ref()
+            .map(|keypair| AccountPublicKey::from(keypair.public.clone()))
+            .or(self.producer);
+
+        let mut report = VrfAuditReport {
+            staking_ledger_hash: ledger_hash.clone(),
+            blocks: vec![],
+            skipped_blocks: vec![],
+            missed_slots: None,
+        };
+        // Slots of the audited blocks, grouped by the epoch, so that missed
+        // slots can be searched in the same range.
+        let mut epochs = BTreeMap::<EpochInputs, BTreeSet<u32>>::new();
+        let mut producer_slots = BTreeSet::new();
+
+        for (row, header) in blocks {
+            let consensus_state = &header.protocol_state.body.consensus_state;
+            let epoch_data = &consensus_state.staking_epoch_data;
+            if epoch_data.ledger.hash != ledger_hash {
+                report.skipped_blocks.push(row.state_hash);
+                continue;
+            }
+            let global_slot = consensus_state
+                .curr_global_slot_since_hard_fork
+                .slot_number
+                .as_u32();
+            let epoch = EpochInputs {
+                seed: epoch_data.seed.to_string(),
+                total_currency: epoch_data.ledger.total_currency.as_u64(),
+            };
+            epochs.entry(epoch.clone()).or_default().insert(global_slot);
+
+            let creator = AccountPublicKey::from(consensus_state.block_creator.clone());
+            if producer
+                .as_ref()
+                .map_or(false, |producer| producer != &creator)
+            {
+                continue;
+            }
+            producer_slots.insert(global_slot);
+
+            let mut audit = audit_block(&ledger, row, &header, &epoch);
+
+            if let (Some(keypair), Some(index), Some(stake)) = (
+                &producer_keypair,
+                &audit.delegator_index,
+                audit.delegated_stake,
+            ) {
+                let vrf_input = VrfEvaluationInput::new(
+                    keypair.clone(),
+                    epoch.seed.clone(),
+                    audit.stake_winner.clone(),
+                    global_slot,
+                    index.clone(),
+                    stake.into(),
+                    epoch.total_currency.into(),
+                );
+                let expected = match evaluate_vrf(vrf_input)? {
+                    VrfEvaluationOutput::SlotWon(won_slot) => Some(won_slot.vrf_output_bytes),
+                    VrfEvaluationOutput::SlotLost(_) => None,
+                };
+                let last_vrf_output: &[u8] = consensus_state.last_vrf_output.as_ref();
+                if expected.as_deref() != Some(last_vrf_output) {
+                    audit
+                        .problems
+                        .push("vrf output doesn't match the producer's evaluation".to_owned());
+                }
+            }
+            report.blocks.push(audit);
+        }
+
+        if let (Some(keypair), Some(producer)) = (producer_keypair, producer) {
+            let delegators = delegators(&ledger, &producer);
+            let mut missed_slots = vec![];
+            for (epoch, slots) in &epochs {
+                let (Some(first), Some(last)) = (slots.first(), slots.last()) else {
+                    continue;
+                };
+                let won = (*first..=*last)
+                    .into_par_iter()
+                    .filter(|slot| !producer_slots.contains(slot))
+                    .map(|slot| first_winner(&keypair, epoch, &delegators, slot))
+                    .collect::<Result<Vec<_>, _>>()?;
+                missed_slots.extend(won.into_iter().flatten());
+            }
+            report.missed_slots = Some(missed_slots);
+        }
+
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        Ok(())
+    }
+}
+
+/// Loads the ledger the same way as the node loads the genesis ledger.
+fn load_ledger(path: &Path) -> Result<(LedgerHash, Mask), crate::CommandError> {
+    let mut reader = File::open(path)?;
+    let top_hash = Option::<LedgerHash>::binprot_read(&mut reader)?;
+    let accounts = Vec::<Account>::binprot_read(&mut reader)?;
+
+    let mut mask = Mask::new_root(Database::create(35));
+    for account in accounts {
+        let account_id = account.id();
+        mask.get_or_create_account(account_id, account)
+            .map_err(|err| format!("failed to add account: {err:?}"))?;
+    }
+
+    let top_hash =
+        top_hash.unwrap_or_else(|| MinaBaseLedgerHash0StableV1(mask.merkle_root().into()).into());
+    Ok((top_hash, mask))
+}
+
+fn audit_block(
+    ledger: &Mask,
+    row: ArchiveBlockRow,
+    header: &MinaBlockHeaderStableV2,
+    epoch: &EpochInputs,
+) -> BlockAudit {
+    let consensus_state = &header.protocol_state.body.consensus_state;
+    let global_slot = consensus_state
+        .curr_global_slot_since_hard_fork
+        .slot_number
+        .as_u32();
+    let creator: CompressedPubKey = (&consensus_state.block_creator).into();
+    let winner: CompressedPubKey = (&consensus_state.block_stake_winner).into();
+    let mut audit = BlockAudit {
+        state_hash: row.state_hash,
+        height: row.height,
+        chain_status: row.chain_status,
+        creator: creator.into_address(),
+        stake_winner: winner.into_address(),
+        delegator_index: None,
+        delegated_stake: None,
+        vrf: None,
+        problems: vec![],
+    };
+
+    let Some((index, account)) = ledger
+        .index_of_account(AccountId::new(winner.clone(), TokenId::default()))
+        .and_then(|index| Some((index.clone(), ledger.get_at_index(index)?)))
+    else {
+        audit
+            .problems
+            .push("stake winner is not in the staking ledger".to_owned());
+        return audit;
+    };
+    let stake = account.balance.as_u64();
+    audit.delegator_index = Some(index.clone());
+    audit.delegated_stake = Some(stake);
+
+    if account.delegate.as_ref().unwrap_or(&winner) != &creator {
+        audit
+            .problems
+            .push("stake winner doesn't delegate to the block creator".to_owned());
+    }
+
+    let vrf_input = VrfThresholdCheckInput::new(
+        creator,
+        global_slot,
+        index,
+        stake.into(),
+        epoch.total_currency.into(),
+        AsRef::<[u8]>::as_ref(&consensus_state.last_vrf_output).to_vec(),
+    );
+    match check_vrf_threshold(vrf_input) {
+        Ok(output) => {
+            if !output.threshold_met {
+                audit
+                    .problems
+                    .push("vrf output doesn't win the slot for the winner's stake".to_owned());
+            }
+            audit.vrf = Some(output);
+        }
+        Err(err) => audit.problems.push(format!("invalid vrf output: {err}")),
+    }
+    audit
+}
+
+/// Default token accounts delegating to the producer, including the
+/// producer's own account if it isn't delegated.
+fn delegators(ledger: &Mask, producer: &AccountPublicKey) -> Vec<(AccountIndex, String, u64)> {
+    let producer = CompressedPubKey::from(producer.clone());
+    let mut accounts = vec![];
+    ledger.iter(|account| {
+        // only default token accounts can delegate, others have no delegate.
+        if account.token_id.is_default()
+            && account.delegate.as_ref().unwrap_or(&account.public_key) == &producer
+        {
+            accounts.push(account.id());
+        }
+    });
+    accounts
+        .into_iter()
+        .filter_map(|id| {
+            let index = ledger.index_of_account(id.clone())?;
+            let account = ledger.get_at_index(index.clone())?;
+            Some((
+                index,
+                id.public_key.into_address(),
+                account.balance.as_u64(),
+            ))
+        })
+        .collect()
+}
+
+/// First delegator which wins the slot for the producer, same as the
+/// node's vrf evaluator picks it.
+fn first_winner(
+    keypair: &Keypair,
+    epoch: &EpochInputs,
+    delegators: &[(AccountIndex, String, u64)],
+    global_slot: u32,
+) -> Result<Option<MissedSlot>, vrf::VrfError> {
+    for (index, delegator, stake) in delegators {
+        let vrf_input = VrfEvaluationInput::new(
+            keypair.clone(),
+            epoch.seed.clone(),
+            delegator.clone(),
+            global_slot,
+            index.clone(),
+            BigInt::from(*stake),
+            BigInt::from(epoch.total_currency),
+        );
+        if let VrfEvaluationOutput::SlotWon(_) = evaluate_vrf(vrf_input)? {
+            return Ok(Some(MissedSlot {
+                global_slot,
+                delegator: delegator.clone(),
+                delegator_index: index.clone(),
+            }));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::scan_state::currency::Balance;
+
+    use super::*;
+
+    fn public_key(n: u8) -> CompressedPubKey {
+        let mut bytes = [0; 32];
+        bytes[31] = n;
+        Keypair::from(AccountSecretKey::from_bytes(&bytes).unwrap())
+            .public
+            .into_compressed()
+    }
+
+    fn add_account(ledger: &mut Mask, account: Account) {
+        ledger.get_or_create_account(account.id(), account).unwrap();
+    }
+
+    #[test]
+    fn delegators_of_default_token_only() {
+        let (producer, delegator, other) = (public_key(1), public_key(2), public_key(3));
+        let mut ledger = Mask::new_root(Database::create(10));
+
+        let own = AccountId::new(producer.clone(), TokenId::default());
+        add_account(
+            &mut ledger,
+            Account::create_with(own, Balance::from_u64(100)),
+        );
+        // custom token accounts have no delegate.
+        let token = AccountId::new(producer.clone(), TokenId::from(7));
+        add_account(
+            &mut ledger,
+            Account::create_with(token, Balance::from_u64(1_000)),
+        );
+
+        let id = AccountId::new(delegator.clone(), TokenId::default());
+        let mut account = Account::create_with(id, Balance::from_u64(20));
+        account.delegate = Some(producer.clone());
+        add_account(&mut ledger, account);
+
+        let id = AccountId::new(other, TokenId::default());
+        add_account(&mut ledger, Account::create_with(id, Balance::from_u64(3)));
+
+        let mut found = delegators(&ledger, &producer.clone().into())
+            .into_iter()
+            .map(|(_, address, stake)| (address, stake))
+            .collect::<Vec<_>>();
+        found.sort();
+        let mut expected = vec![
+            (producer.into_address(), 100),
+            (delegator.into_address(), 20),
+        ];
+        expected.sort();
+        assert_eq!(found, expected);
+    }
+}
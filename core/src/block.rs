@@ -341,3 +341,26 @@ fn staged_ledger_hashes(header: &BlockHeader) -> &MinaBaseStagedLedgerHashStable
         .blockchain_state
         .staged_ledger_hash
 }
+
+/// Block of the `new_state` gossip message fixture in `mina-p2p-messages`,
+/// for tests.
+#[cfg(any(test, feature = "test-utils"))]
+pub fn test_block() -> ArcBlockWithHash {
+    test_block_with(|_| {})
+}
+
+/// Same as [`test_block`], but changed by `f` before its hash is computed.
+#[cfg(any(test, feature = "test-utils"))]
+pub fn test_block_with(f: impl FnOnce(&mut Block)) -> ArcBlockWithHash {
+    use mina_p2p_messages::{binprot::BinProtRead, gossip::GossipNetMessageV2};
+
+    const NEW_STATE: &[u8] =
+        include_bytes!("../../mina-p2p-messages/tests/files/v2/gossip/new_state.bin");
+    let GossipNetMessageV2::NewState(mut block) =
+        GossipNetMessageV2::binprot_read(&mut &NEW_STATE[..]).expect("invalid fixture")
+    else {
+        panic!("fixture isn't a new state message");
+    };
+    f(&mut block);
+    BlockWithHash::new(Arc::new(block))
+}
@@ -1,11 +1,32 @@
-export interface ScanStateLeaf {
-  status: ScanStateLeafStatus;
-  job_id?: string;
-  bundle_job_id?: string;
-  job?: {
+export type ScanStateLeaf = ScanStateEmptyLeaf | ScanStateJobLeaf;
+
+interface ScanStateLeafBase {
+  //frontend data
+  jobIndex?: number;
+  treeIndex?: number;
+  scrolling?: boolean;
+}
+
+export interface ScanStateEmptyLeaf extends ScanStateLeafBase {
+  status: ScanStateLeafStatus.Empty;
+  job_id?: undefined;
+  bundle_job_id?: undefined;
+  job?: undefined;
+  seq_no?: undefined;
+  statement?: undefined;
+  commitment?: undefined;
+  snark?: undefined;
+}
+
+export interface ScanStateJobLeaf extends ScanStateLeafBase {
+  status: ScanStateLeafStatus.Todo | ScanStateLeafStatus.Pending | ScanStateLeafStatus.Done;
+  job_id: string;
+  bundle_job_id: string;
+  job: {
     kind: string;
-  }
-  seq_no?: number;
+  };
+  seq_no: number;
+  statement: ScanStateLeafStatement;
   commitment?: any;
   snark?: {
     snarker: string;
@@ -13,10 +34,17 @@ export interface ScanStateLeaf {
     received_t?: number;
     sender?: string;
   };
-  //frontend data
-  jobIndex?: number;
-  treeIndex?: number;
-  scrolling?: boolean;
+}
+
+export interface ScanStateLeafStatement {
+  source_first_pass_ledger: string;
+  source_second_pass_ledger: string;
+  target_first_pass_ledger: string;
+  target_second_pass_ledger: string;
+  connecting_ledger_left: string;
+  connecting_ledger_right: string;
+  fee_excess: any;
+  supply_increase: any;
 }
 
 export enum ScanStateLeafStatus {
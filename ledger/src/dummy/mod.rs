@@ -5,7 +5,7 @@ use mina_p2p_messages::v2::MinaBaseProofStableV2;
 use mina_p2p_messages::v2::PicklesProofProofsVerifiedMaxStableV2;
 use mina_p2p_messages::v2::TransactionSnarkProofStableV2;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "generators"))]
 use crate::VerificationKey;
 
 #[cfg(test)]
@@ -66,7 +66,7 @@ pub fn dummy_blockchain_proof() -> Arc<MinaBaseProofStableV2> {
 ///
 /// Core.Printf.eprintf !"vk=%{sexp: (Side_loaded_verification_key.t, Frozen_ledger_hash.t) With_hash.t}\n%!" vk;
 /// Core.Printf.eprintf !"vk_binprot=[%s]\n%!" s;
-#[cfg(test)] // Used for tests only
+#[cfg(any(test, feature = "generators"))] // Used for tests only
 pub fn trivial_verification_key() -> VerificationKey {
     use mina_p2p_messages::v2::MinaBaseVerificationKeyWireStableV1;
 
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use mina_signer::{Keypair, Signature};
+use mina_signer::{Keypair, NetworkId, Signature, Signer};
 
 use crate::scan_state::transaction_logic::{
     for_tests::HashableCompressedPubKey,
@@ -32,15 +32,15 @@ pub fn replace_authorizations(
 ) {
     let (txn_commitment, full_txn_commitment) = get_transaction_commitments(zkapp_command);
 
-    let sign_for_account_update = |use_full_commitment: bool, _kp: &Keypair| {
-        let _commitment = if use_full_commitment {
+    let sign_for_account_update = |use_full_commitment: bool, kp: &Keypair| -> Signature {
+        let commitment = if use_full_commitment {
             full_txn_commitment
         } else {
             txn_commitment
         };
 
-        // TODO: Really sign the zkapp
-        Signature::dummy()
+        let mut signer = mina_signer::create_kimchi(NetworkId::TESTNET);
+        signer.sign(kp, &commitment)
     };
 
     let fee_payer_kp = keymap
@@ -426,6 +426,35 @@ fn verify_with(
     )
 }
 
+/// Same as [`verify_with`], but verifies all `proofs` at once, sharing
+/// the final opening check between them.
+fn batch_verify_with(
+    verifier_index: &VerifierIndex<Pallas>,
+    proofs: &[PreparedProof],
+) -> Result<(), VerifyError> {
+    use kimchi::groupmap::GroupMap;
+    use kimchi::mina_curves::pasta::PallasParameters;
+    use kimchi::verifier::Context;
+    use mina_poseidon::sponge::{DefaultFqSponge, DefaultFrSponge};
+
+    type SpongeParams = mina_poseidon::constants::PlonkSpongeConstantsKimchi;
+    type EFqSponge = DefaultFqSponge<PallasParameters, SpongeParams>;
+    type EFrSponge = DefaultFrSponge<Fq, SpongeParams>;
+
+    let group_map = GroupMap::<Fp>::setup();
+
+    let contexts = proofs
+        .iter()
+        .map(|prepared| Context {
+            verifier_index,
+            proof: &prepared.proof,
+            public_input: &prepared.public_input,
+        })
+        .collect::<Vec<_>>();
+
+    kimchi::verifier::batch_verify::<Pallas, EFqSponge, EFrSponge>(&group_map, &contexts)
+}
+
 fn run_checks(
     proof: &PicklesProofProofsVerified2ReprStableV2,
     verifier_index: &VerifierIndex<Pallas>,
@@ -598,23 +627,44 @@ pub fn verify_block(
     verifier_index: &VerifierIndex<Pallas>,
     srs: &SRS<Vesta>,
 ) -> bool {
-    let MinaBlockHeaderStableV2 {
-        protocol_state,
-        protocol_state_proof,
-        ..
-    } = &header;
+    verify_blocks([header], verifier_index, srs)
+}
 
+/// Verifies proofs of all `headers` in one batch. Fails if any of them
+/// is invalid, in which case they have to be verified one by one to
+/// find the invalid one.
+pub fn verify_blocks<'a>(
+    headers: impl IntoIterator<Item = &'a MinaBlockHeaderStableV2>,
+    verifier_index: &VerifierIndex<Pallas>,
+    srs: &SRS<Vesta>,
+) -> bool {
     let vk = VK {
         commitments: PlonkVerificationKeyEvals::from(verifier_index),
         index: verifier_index,
         data: (),
     };
 
-    let accum_check = accumulator_check::accumulator_check(srs, protocol_state_proof);
-    let verified = verify_impl(&MinaHash::hash(protocol_state), protocol_state_proof, &vk);
+    let proofs = headers
+        .into_iter()
+        .map(|header| {
+            let MinaBlockHeaderStableV2 {
+                protocol_state,
+                protocol_state_proof,
+                ..
+            } = header;
+            (MinaHash::hash(protocol_state), &protocol_state_proof.0)
+        })
+        .collect::<Vec<_>>();
+
+    let accum_check =
+        accumulator_check::batch_accumulator_check(srs, proofs.iter().map(|(_, proof)| *proof));
+    let verified = batch_verify_impl(proofs.iter().map(|(hash, proof)| (hash, *proof)), &vk);
     accum_check && verified
 }
 
+/// Verifies all `proofs` in one batch. Fails if any of them is invalid,
+/// in which case they have to be verified one by one to find the
+/// invalid one.
 pub fn verify_transaction<'a>(
     proofs: impl IntoIterator<Item = (&'a Statement<SokDigest>, &'a TransactionSnarkProofStableV2)>,
     verifier_index: &VerifierIndex<Pallas>,
@@ -626,11 +676,15 @@ pub fn verify_transaction<'a>(
         data: (),
     };
 
-    proofs.into_iter().all(|(statement, transaction_proof)| {
-        let accum_check = accumulator_check::accumulator_check(srs, transaction_proof);
-        let verified = verify_impl(statement, transaction_proof, &vk);
-        accum_check && verified
-    })
+    let proofs = proofs
+        .into_iter()
+        .map(|(statement, proof)| (statement, &proof.0))
+        .collect::<Vec<_>>();
+
+    let accum_check =
+        accumulator_check::batch_accumulator_check(srs, proofs.iter().map(|(_, proof)| *proof));
+    let verified = batch_verify_impl(proofs, &vk);
+    accum_check && verified
 }
 
 /// https://github.com/MinaProtocol/mina/blob/bfd1009abdbee78979ff0343cc73a3480e862f58/src/lib/crypto/kimchi_bindings/stubs/src/pasta_fq_plonk_proof.rs#L116
@@ -658,11 +712,20 @@ pub fn verify_zkapp(
     ok
 }
 
-fn verify_impl<AppState>(
+/// Proof converted to kimchi, together with its public input, ready to
+/// be verified.
+struct PreparedProof {
+    proof: ProverProof<Pallas>,
+    public_input: Vec<Fq>,
+    /// Result of the checks, which aren't part of kimchi verification.
+    checks: bool,
+}
+
+fn prepare_proof<AppState>(
     app_state: &AppState,
     proof: &PicklesProofProofsVerified2ReprStableV2,
     vk: &VK,
-) -> bool
+) -> PreparedProof
 where
     AppState: ToFieldElements<Fp>,
 {
@@ -686,16 +749,61 @@ where
     );
 
     let npublic_input = vk.index.public;
-    let public_inputs = prepared_statement.to_public_input(npublic_input);
+    let public_input = prepared_statement.to_public_input(npublic_input);
     let proof = make_padded_proof_from_p2p(proof);
 
-    let result = verify_with(vk.index, &proof, &public_inputs);
+    PreparedProof {
+        proof,
+        public_input,
+        checks,
+    }
+}
+
+fn verify_impl<AppState>(
+    app_state: &AppState,
+    proof: &PicklesProofProofsVerified2ReprStableV2,
+    vk: &VK,
+) -> bool
+where
+    AppState: ToFieldElements<Fp>,
+{
+    let prepared = prepare_proof(app_state, proof, vk);
+
+    let result = verify_with(vk.index, &prepared.proof, &prepared.public_input);
 
     if let Err(e) = result {
         eprintln!("verify error={:?}", e);
     };
 
-    result.is_ok() && checks
+    result.is_ok() && prepared.checks
+}
+
+fn batch_verify_impl<'a, AppState>(
+    proofs: impl IntoIterator<Item = (&'a AppState, &'a PicklesProofProofsVerified2ReprStableV2)>,
+    vk: &VK,
+) -> bool
+where
+    AppState: 'a + ToFieldElements<Fp>,
+{
+    let prepared = proofs
+        .into_iter()
+        .map(|(app_state, proof)| prepare_proof(app_state, proof, vk))
+        .collect::<Vec<_>>();
+
+    if !prepared.iter().all(|prepared| prepared.checks) {
+        return false;
+    }
+    if prepared.is_empty() {
+        return true;
+    }
+
+    let result = batch_verify_with(vk.index, &prepared);
+
+    if let Err(e) = &result {
+        eprintln!("batch verify error={:?}", e);
+    };
+
+    result.is_ok()
 }
 
 // #[cfg(test)]
@@ -1463,33 +1463,41 @@ impl From<&Memo> for MinaBaseSignedCommandMemoStableV1 {
     }
 }
 
+impl From<&MinaBaseSignedCommandPayloadStableV2>
+    for transaction_logic::signed_command::SignedCommandPayload
+{
+    fn from(payload: &MinaBaseSignedCommandPayloadStableV2) -> Self {
+        Self {
+            common: transaction_logic::signed_command::Common {
+                fee: (&payload.common.fee).into(),
+                fee_payer_pk: (&payload.common.fee_payer_pk).into(),
+                nonce: (&payload.common.nonce).into(),
+                valid_until: (&payload.common.valid_until).into(),
+                memo: (&payload.common.memo).into(),
+            },
+            body: match &payload.body {
+                MinaBaseSignedCommandPayloadBodyStableV2::Payment(payload) => {
+                    transaction_logic::signed_command::Body::Payment(PaymentPayload {
+                        receiver_pk: (&payload.receiver_pk).into(),
+                        amount: payload.amount.clone().into(),
+                    })
+                }
+                MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+                    MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+                ) => transaction_logic::signed_command::Body::StakeDelegation(
+                    StakeDelegationPayload::SetDelegate {
+                        new_delegate: new_delegate.into(),
+                    },
+                ),
+            },
+        }
+    }
+}
+
 impl From<&MinaBaseSignedCommandStableV2> for SignedCommand {
     fn from(cmd: &MinaBaseSignedCommandStableV2) -> Self {
         Self {
-            payload: transaction_logic::signed_command::SignedCommandPayload {
-                common: transaction_logic::signed_command::Common {
-                    fee: (&cmd.payload.common.fee).into(),
-                    fee_payer_pk: (&cmd.payload.common.fee_payer_pk).into(),
-                    nonce: (&cmd.payload.common.nonce).into(),
-                    valid_until: (&cmd.payload.common.valid_until).into(),
-                    memo: (&cmd.payload.common.memo).into(),
-                },
-                body: match &cmd.payload.body {
-                    MinaBaseSignedCommandPayloadBodyStableV2::Payment(payload) => {
-                        transaction_logic::signed_command::Body::Payment(PaymentPayload {
-                            receiver_pk: (&payload.receiver_pk).into(),
-                            amount: payload.amount.clone().into(),
-                        })
-                    }
-                    MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
-                        MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
-                    ) => transaction_logic::signed_command::Body::StakeDelegation(
-                        StakeDelegationPayload::SetDelegate {
-                            new_delegate: new_delegate.into(),
-                        },
-                    ),
-                },
-            },
+            payload: (&cmd.payload).into(),
             signer: (&cmd.signer).into(),
             signature: (&*cmd.signature).into(),
         }
@@ -7602,7 +7602,7 @@ where
     l
 }
 
-#[cfg(test)]
+#[cfg(any(test, feature = "generators"))]
 pub mod for_tests {
     use std::collections::{HashMap, HashSet};
 
@@ -272,6 +272,22 @@ mina_rpc!(GetEpochLedgerV1, "get_epoch_ledger", 1, LedgerHashV1Versioned, RpcRes
 
 mina_rpc!(GetEpochLedgerV2, "get_epoch_ledger", 2, LedgerHashV1, RpcResult<MinaBaseSparseLedgerBaseStableV2, CharString>);
 
+// `Snark_worker.Rpcs`, between the daemon and its snark workers.
+mina_rpc!(
+    SnarkWorkerGetWorkV2,
+    "get_work",
+    2,
+    (),
+    v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse
+);
+mina_rpc!(
+    SnarkWorkerSubmitWorkV2,
+    "submit_work",
+    2,
+    v2::SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery,
+    ()
+);
+
 /// Registry for uniformly JSONifying RPC payload data.
 ///
 /// ```
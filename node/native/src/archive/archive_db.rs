@@ -0,0 +1,817 @@
+use std::path::Path;
+use std::str::FromStr;
+
+use mina_p2p_messages::binprot::{BinProtRead, BinProtWrite};
+use mina_p2p_messages::v2::{
+    MinaBaseAccountBinableArgStableV2, MinaBaseSignedCommandPayloadBodyStableV2,
+    MinaBaseStakeDelegationStableV2, MinaBaseTransactionStatusStableV2,
+    MinaBaseUserCommandStableV2, MinaBlockHeaderStableV2, MinaTransactionTransactionStableV2,
+    StateHash,
+};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension, Row, Transaction};
+use serde::{Deserialize, Serialize};
+
+use node::account::AccountPublicKey;
+use node::core::block::ArcBlockWithHash;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS blocks (
+    state_hash TEXT PRIMARY KEY NOT NULL,
+    parent_hash TEXT NOT NULL,
+    height INTEGER NOT NULL,
+    global_slot_since_genesis INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL,
+    creator TEXT NOT NULL,
+    coinbase_receiver TEXT NOT NULL,
+    snarked_ledger_hash TEXT NOT NULL,
+    staged_ledger_hash TEXT NOT NULL,
+    chain_status TEXT NOT NULL DEFAULT 'pending',
+    header BLOB NOT NULL
+);
+CREATE INDEX IF NOT EXISTS blocks_height ON blocks (height);
+CREATE INDEX IF NOT EXISTS blocks_chain_status ON blocks (chain_status);
+
+CREATE TABLE IF NOT EXISTS user_commands (
+    block_hash TEXT NOT NULL REFERENCES blocks (state_hash),
+    sequence_no INTEGER NOT NULL,
+    hash TEXT,
+    kind TEXT NOT NULL,
+    fee_payer TEXT NOT NULL,
+    receiver TEXT,
+    amount INTEGER,
+    fee INTEGER NOT NULL,
+    nonce INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    failure_reason TEXT,
+    command TEXT NOT NULL,
+    PRIMARY KEY (block_hash, sequence_no)
+);
+CREATE INDEX IF NOT EXISTS user_commands_fee_payer ON user_commands (fee_payer);
+CREATE INDEX IF NOT EXISTS user_commands_receiver ON user_commands (receiver);
+
+CREATE TABLE IF NOT EXISTS internal_commands (
+    block_hash TEXT NOT NULL REFERENCES blocks (state_hash),
+    sequence_no INTEGER NOT NULL,
+    secondary_sequence_no INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    receiver TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    failure_reason TEXT,
+    PRIMARY KEY (block_hash, sequence_no, secondary_sequence_no)
+);
+CREATE INDEX IF NOT EXISTS internal_commands_receiver ON internal_commands (receiver);
+
+CREATE TABLE IF NOT EXISTS accounts_accessed (
+    block_hash TEXT NOT NULL REFERENCES blocks (state_hash),
+    public_key TEXT NOT NULL,
+    token_id TEXT NOT NULL,
+    balance INTEGER NOT NULL,
+    nonce INTEGER NOT NULL,
+    delegate TEXT,
+    account TEXT NOT NULL,
+    PRIMARY KEY (block_hash, public_key, token_id)
+);
+CREATE INDEX IF NOT EXISTS accounts_accessed_public_key ON accounts_accessed (public_key);
+
+CREATE TABLE IF NOT EXISTS unapplied_blocks (
+    state_hash TEXT PRIMARY KEY NOT NULL REFERENCES blocks (state_hash)
+);
+";
+
+const BLOCK_COLUMNS: &str = "state_hash, parent_hash, height, global_slot_since_genesis, \
+     timestamp, creator, coinbase_receiver, snarked_ledger_hash, staged_ledger_hash, chain_status";
+
+const USER_COMMAND_COLUMNS: &str = "c.block_hash, b.height, b.chain_status, c.sequence_no, \
+     c.hash, c.kind, c.fee_payer, c.receiver, c.amount, c.fee, c.nonce, c.status, \
+     c.failure_reason";
+
+/// Block applied by the transition frontier, with the data that needs to be
+/// archived alongside it.
+pub struct ArchiveBlock {
+    pub block: ArcBlockWithHash,
+    /// Transactions applied by the block, in the order of application.
+    pub transactions: Vec<(
+        MinaTransactionTransactionStableV2,
+        MinaBaseTransactionStatusStableV2,
+    )>,
+    /// Accounts accessed by the block's transactions, as they are after
+    /// the block was applied.
+    pub accounts: Vec<MinaBaseAccountBinableArgStableV2>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveChainStatus {
+    /// Block is on the best chain, but not yet final.
+    Pending,
+    /// Block is the root of the transition frontier or its ancestor.
+    Canonical,
+    /// Block is not part of the canonical chain.
+    Orphaned,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ArchiveBlockRow {
+    pub state_hash: String,
+    pub parent_hash: String,
+    pub height: u32,
+    pub global_slot_since_genesis: u32,
+    /// Block timestamp in milliseconds.
+    pub timestamp: u64,
+    pub creator: String,
+    pub coinbase_receiver: String,
+    pub snarked_ledger_hash: String,
+    pub staged_ledger_hash: String,
+    pub chain_status: ArchiveChainStatus,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ArchiveUserCommandRow {
+    pub block_hash: String,
+    pub block_height: u32,
+    pub block_chain_status: ArchiveChainStatus,
+    pub sequence_no: u32,
+    /// Missing for zkApp commands, as hashing them is not supported yet.
+    pub hash: Option<String>,
+    pub kind: String,
+    pub fee_payer: String,
+    pub receiver: Option<String>,
+    pub amount: Option<u64>,
+    pub fee: u64,
+    pub nonce: u32,
+    pub status: String,
+    pub failure_reason: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ArchiveInternalCommandRow {
+    pub sequence_no: u32,
+    pub secondary_sequence_no: u32,
+    pub kind: String,
+    pub receiver: String,
+    pub amount: u64,
+    pub status: String,
+    pub failure_reason: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ArchiveAccountRow {
+    pub block_hash: String,
+    pub block_height: u32,
+    pub block_chain_status: ArchiveChainStatus,
+    pub public_key: String,
+    pub token_id: String,
+    pub balance: u64,
+    pub nonce: u32,
+    pub delegate: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ArchiveBlockDetails {
+    pub block: ArchiveBlockRow,
+    pub user_commands: Vec<ArchiveUserCommandRow>,
+    pub internal_commands: Vec<ArchiveInternalCommandRow>,
+    pub accounts: Vec<ArchiveAccountRow>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ArchiveBlocksQuery {
+    pub from_height: Option<u32>,
+    pub to_height: Option<u32>,
+    pub chain_status: Option<ArchiveChainStatus>,
+    pub limit: u32,
+}
+
+pub struct ArchiveDb {
+    conn: Connection,
+}
+
+impl ArchiveDb {
+    /// Opens the archive database for writing, creating it if needed.
+    pub fn open<P: AsRef<Path>>(path: P) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// Opens an existing archive database for queries.
+    pub fn open_read_only<P: AsRef<Path>>(path: P) -> rusqlite::Result<Self> {
+        let conn = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Whether the block is archived together with its commands.
+    pub fn block_is_applied(&self, state_hash: &StateHash) -> rusqlite::Result<bool> {
+        self.conn
+            .query_row(
+                "SELECT 1 FROM blocks WHERE state_hash = ?1 \
+                 AND state_hash NOT IN (SELECT state_hash FROM unapplied_blocks)",
+                params![state_hash.to_string()],
+                |_| Ok(()),
+            )
+            .optional()
+            .map(|v| v.is_some())
+    }
+
+    /// Hashes and heights of the blocks, which are not yet final.
+    pub fn pending_blocks(&self) -> rusqlite::Result<Vec<(String, u32)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT state_hash, height FROM blocks WHERE chain_status = 'pending'")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    /// Stores the block together with its commands and accessed accounts.
+    ///
+    /// Does nothing if the block is already archived, unless it was
+    /// archived by [`ArchiveDb::fork_block_add`] without its commands.
+    pub fn block_add(&mut self, archive_block: &ArchiveBlock) -> rusqlite::Result<()> {
+        let ArchiveBlock {
+            block,
+            transactions,
+            accounts,
+        } = archive_block;
+        let block_hash = block.hash().to_string();
+
+        let tx = self.conn.transaction()?;
+        let inserted = block_insert(&tx, block)?;
+        let was_unapplied = tx.execute(
+            "DELETE FROM unapplied_blocks WHERE state_hash = ?1",
+            params![block_hash],
+        )?;
+        if !inserted && was_unapplied == 0 {
+            return Ok(());
+        }
+
+        for (sequence_no, (transaction, status)) in transactions.iter().enumerate() {
+            let (status, failure_reason) = status_to_sql(status);
+            match transaction {
+                MinaTransactionTransactionStableV2::Command(command) => {
+                    let command = command.as_ref();
+                    let hash = command.hash().ok().map(|hash| hash.to_string());
+                    let (kind, fee_payer, receiver, amount, fee, nonce) = match command {
+                        MinaBaseUserCommandStableV2::SignedCommand(cmd) => {
+                            let common = &cmd.payload.common;
+                            let (kind, receiver, amount) = match &cmd.payload.body {
+                                MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => (
+                                    "payment",
+                                    payment.receiver_pk.to_string(),
+                                    Some(payment.amount.0 .0.as_u64()),
+                                ),
+                                MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+                                    MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+                                ) => ("stake_delegation", new_delegate.to_string(), None),
+                            };
+                            (
+                                kind,
+                                common.fee_payer_pk.to_string(),
+                                Some(receiver),
+                                amount,
+                                common.fee.0 .0.as_u64(),
+                                common.nonce.0.as_u32(),
+                            )
+                        }
+                        MinaBaseUserCommandStableV2::ZkappCommand(cmd) => {
+                            let body = &cmd.fee_payer.body;
+                            (
+                                "zkapp",
+                                body.public_key.to_string(),
+                                None,
+                                None,
+                                body.fee.0 .0.as_u64(),
+                                body.nonce.0.as_u32(),
+                            )
+                        }
+                    };
+                    let command = serde_json::to_string(command)
+                        .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))?;
+                    tx.execute(
+                        "INSERT INTO user_commands (block_hash, sequence_no, hash, kind, \
+                         fee_payer, receiver, amount, fee, nonce, status, failure_reason, \
+                         command) \
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                        params![
+                            block_hash,
+                            sequence_no,
+                            hash,
+                            kind,
+                            fee_payer,
+                            receiver,
+                            amount,
+                            fee,
+                            nonce,
+                            status,
+                            failure_reason,
+                            command,
+                        ],
+                    )?;
+                }
+                MinaTransactionTransactionStableV2::FeeTransfer(_)
+                | MinaTransactionTransactionStableV2::Coinbase(_) => {
+                    let internal_commands = internal_commands(transaction);
+                    for (secondary_sequence_no, (kind, receiver, amount)) in
+                        internal_commands.into_iter().enumerate()
+                    {
+                        tx.execute(
+                            "INSERT INTO internal_commands (block_hash, sequence_no, \
+                             secondary_sequence_no, kind, receiver, amount, status, \
+                             failure_reason) \
+                             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                            params![
+                                block_hash,
+                                sequence_no,
+                                secondary_sequence_no,
+                                kind,
+                                receiver,
+                                amount,
+                                status,
+                                failure_reason,
+                            ],
+                        )?;
+                    }
+                }
+            }
+        }
+
+        for account in accounts {
+            let account_json = serde_json::to_string(account)
+                .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))?;
+            tx.execute(
+                "INSERT OR REPLACE INTO accounts_accessed (block_hash, public_key, token_id, \
+                 balance, nonce, delegate, account) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    block_hash,
+                    account.public_key.to_string(),
+                    account.token_id.to_string(),
+                    account.balance.0 .0 .0.as_u64(),
+                    account.nonce.0.as_u32(),
+                    account.delegate.as_ref().map(|key| key.to_string()),
+                    account_json,
+                ],
+            )?;
+        }
+
+        tx.commit()
+    }
+
+    /// Stores only the header of the block, which was never applied, as
+    /// consensus decided not to switch to it.
+    ///
+    /// Does nothing if the block is already archived.
+    pub fn fork_block_add(&mut self, block: &ArcBlockWithHash) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        if block_insert(&tx, block)? {
+            tx.execute(
+                "INSERT INTO unapplied_blocks (state_hash) VALUES (?1)",
+                params![block.hash().to_string()],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Marks the new `root` and its pending ancestors as canonical and
+    /// all the other pending blocks at or below the root height as orphaned.
+    pub fn best_chain_update(
+        &mut self,
+        root: &StateHash,
+        root_height: u32,
+    ) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "WITH RECURSIVE canonical (state_hash) AS (
+                VALUES (?1)
+                UNION
+                SELECT blocks.parent_hash FROM blocks
+                JOIN canonical ON blocks.state_hash = canonical.state_hash
+                WHERE blocks.chain_status = 'pending'
+            )
+            UPDATE blocks SET chain_status = 'canonical'
+            WHERE chain_status = 'pending' AND state_hash IN canonical",
+            params![root.to_string()],
+        )?;
+        tx.execute(
+            "UPDATE blocks SET chain_status = 'orphaned' \
+             WHERE chain_status = 'pending' AND height <= ?1",
+            params![root_height],
+        )?;
+        tx.commit()
+    }
+
+    pub fn blocks(&self, query: &ArchiveBlocksQuery) -> rusqlite::Result<Vec<ArchiveBlockRow>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {BLOCK_COLUMNS} FROM blocks \
+             WHERE (?1 IS NULL OR height >= ?1) \
+             AND (?2 IS NULL OR height <= ?2) \
+             AND (?3 IS NULL OR chain_status = ?3) \
+             ORDER BY height DESC, state_hash LIMIT ?4"
+        ))?;
+        let rows = stmt.query_map(
+            params![
+                query.from_height,
+                query.to_height,
+                query.chain_status.map(|status| status.as_str()),
+                query.limit,
+            ],
+            block_from_row,
+        )?;
+        rows.collect()
+    }
+
+    /// Same as [`ArchiveDb::blocks`], but also returns headers of the blocks.
+    pub fn blocks_with_headers(
+        &self,
+        query: &ArchiveBlocksQuery,
+    ) -> rusqlite::Result<Vec<(ArchiveBlockRow, MinaBlockHeaderStableV2)>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {BLOCK_COLUMNS}, header FROM blocks \
+             WHERE (?1 IS NULL OR height >= ?1) \
+             AND (?2 IS NULL OR height <= ?2) \
+             AND (?3 IS NULL OR chain_status = ?3) \
+             ORDER BY height DESC, state_hash LIMIT ?4"
+        ))?;
+        let rows = stmt.query_map(
+            params![
+                query.from_height,
+                query.to_height,
+                query.chain_status.map(|status| status.as_str()),
+                query.limit,
+            ],
+            |row| {
+                let header: Vec<u8> = row.get(10)?;
+                let header = MinaBlockHeaderStableV2::binprot_read(&mut header.as_slice())
+                    .map_err(|err| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            10,
+                            rusqlite::types::Type::Blob,
+                            Box::new(err),
+                        )
+                    })?;
+                Ok((block_from_row(row)?, header))
+            },
+        )?;
+        rows.collect()
+    }
+
+    pub fn block(&self, state_hash: &StateHash) -> rusqlite::Result<Option<ArchiveBlockDetails>> {
+        let state_hash = state_hash.to_string();
+        let Some(block) = self
+            .conn
+            .query_row(
+                &format!("SELECT {BLOCK_COLUMNS} FROM blocks WHERE state_hash = ?1"),
+                params![state_hash],
+                block_from_row,
+            )
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {USER_COMMAND_COLUMNS} FROM user_commands c \
+             JOIN blocks b ON b.state_hash = c.block_hash \
+             WHERE c.block_hash = ?1 ORDER BY c.sequence_no"
+        ))?;
+        let user_commands = stmt
+            .query_map(params![state_hash], user_command_from_row)?
+            .collect::<Result<_, _>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT sequence_no, secondary_sequence_no, kind, receiver, amount, status, \
+             failure_reason FROM internal_commands \
+             WHERE block_hash = ?1 ORDER BY sequence_no, secondary_sequence_no",
+        )?;
+        let internal_commands = stmt
+            .query_map(params![state_hash], |row| {
+                Ok(ArchiveInternalCommandRow {
+                    sequence_no: row.get(0)?,
+                    secondary_sequence_no: row.get(1)?,
+                    kind: row.get(2)?,
+                    receiver: row.get(3)?,
+                    amount: row.get(4)?,
+                    status: row.get(5)?,
+                    failure_reason: row.get(6)?,
+                })
+            })?
+            .collect::<Result<_, _>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT a.block_hash, b.height, b.chain_status, a.public_key, a.token_id, \
+             a.balance, a.nonce, a.delegate FROM accounts_accessed a \
+             JOIN blocks b ON b.state_hash = a.block_hash \
+             WHERE a.block_hash = ?1 ORDER BY a.public_key, a.token_id",
+        )?;
+        let accounts = stmt
+            .query_map(params![state_hash], account_from_row)?
+            .collect::<Result<_, _>>()?;
+
+        Ok(Some(ArchiveBlockDetails {
+            block,
+            user_commands,
+            internal_commands,
+            accounts,
+        }))
+    }
+
+    /// State of the account after each archived block that changed it,
+    /// starting from the latest one.
+    pub fn account_history(
+        &self,
+        public_key: &AccountPublicKey,
+        chain_status: Option<ArchiveChainStatus>,
+        limit: u32,
+    ) -> rusqlite::Result<Vec<ArchiveAccountRow>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.block_hash, b.height, b.chain_status, a.public_key, a.token_id, \
+             a.balance, a.nonce, a.delegate FROM accounts_accessed a \
+             JOIN blocks b ON b.state_hash = a.block_hash \
+             WHERE a.public_key = ?1 AND (?2 IS NULL OR b.chain_status = ?2) \
+             ORDER BY b.height DESC, a.token_id LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                public_key.to_string(),
+                chain_status.map(|status| status.as_str()),
+                limit
+            ],
+            account_from_row,
+        )?;
+        rows.collect()
+    }
+
+    /// User commands where the account is either the fee payer or the
+    /// receiver, starting from the latest one.
+    pub fn account_user_commands(
+        &self,
+        public_key: &AccountPublicKey,
+        chain_status: Option<ArchiveChainStatus>,
+        limit: u32,
+    ) -> rusqlite::Result<Vec<ArchiveUserCommandRow>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {USER_COMMAND_COLUMNS} FROM user_commands c \
+             JOIN blocks b ON b.state_hash = c.block_hash \
+             WHERE (c.fee_payer = ?1 OR c.receiver = ?1) \
+             AND (?2 IS NULL OR b.chain_status = ?2) \
+             ORDER BY b.height DESC, c.sequence_no DESC LIMIT ?3"
+        ))?;
+        let rows = stmt.query_map(
+            params![
+                public_key.to_string(),
+                chain_status.map(|status| status.as_str()),
+                limit
+            ],
+            user_command_from_row,
+        )?;
+        rows.collect()
+    }
+}
+
+impl ArchiveChainStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Canonical => "canonical",
+            Self::Orphaned => "orphaned",
+        }
+    }
+}
+
+impl FromStr for ArchiveChainStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(Self::Pending),
+            "canonical" => Ok(Self::Canonical),
+            "orphaned" => Ok(Self::Orphaned),
+            _ => Err(format!("unknown chain status: {s}")),
+        }
+    }
+}
+
+/// Inserts the block's row. Returns `false` if it is already there.
+fn block_insert(tx: &Transaction, block: &ArcBlockWithHash) -> rusqlite::Result<bool> {
+    let consensus_state = block.consensus_state();
+    let blockchain_state = &block.header().protocol_state.body.blockchain_state;
+    let timestamp_ms = blockchain_state.timestamp.0 .0.as_u64();
+    let mut header = Vec::new();
+    block
+        .header()
+        .binprot_write(&mut header)
+        .map_err(|err| rusqlite::Error::ToSqlConversionFailure(Box::new(err)))?;
+
+    let inserted = tx.execute(
+        "INSERT OR IGNORE INTO blocks (state_hash, parent_hash, height, \
+         global_slot_since_genesis, timestamp, creator, coinbase_receiver, \
+         snarked_ledger_hash, staged_ledger_hash, header) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            block.hash().to_string(),
+            block.pred_hash().to_string(),
+            block.height(),
+            block.global_slot_since_genesis(),
+            timestamp_ms,
+            consensus_state.block_creator.to_string(),
+            consensus_state.coinbase_receiver.to_string(),
+            block.snarked_ledger_hash().to_string(),
+            block.staged_ledger_hash().to_string(),
+            header,
+        ],
+    )?;
+    Ok(inserted > 0)
+}
+
+fn chain_status_from_sql(row: &Row, idx: usize) -> rusqlite::Result<ArchiveChainStatus> {
+    let status: String = row.get(idx)?;
+    status.parse().map_err(|err: String| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, err.into())
+    })
+}
+
+fn status_to_sql(status: &MinaBaseTransactionStatusStableV2) -> (&'static str, Option<String>) {
+    match status {
+        MinaBaseTransactionStatusStableV2::Applied => ("applied", None),
+        MinaBaseTransactionStatusStableV2::Failed(failures) => {
+            ("failed", serde_json::to_string(failures).ok())
+        }
+    }
+}
+
+/// Splits fee transfer or coinbase into `(kind, receiver, amount)` rows,
+/// same as the OCaml archive does.
+fn internal_commands(
+    transaction: &MinaTransactionTransactionStableV2,
+) -> Vec<(&'static str, String, u64)> {
+    match transaction {
+        MinaTransactionTransactionStableV2::Command(_) => vec![],
+        MinaTransactionTransactionStableV2::FeeTransfer(fee_transfer) => {
+            use mina_p2p_messages::v2::MinaBaseFeeTransferStableV2 as FeeTransfer;
+            let transfers = match fee_transfer {
+                FeeTransfer::One(v) => vec![v],
+                FeeTransfer::Two((v1, v2)) => vec![v1, v2],
+            };
+            transfers
+                .into_iter()
+                .map(|v| {
+                    (
+                        "fee_transfer",
+                        v.receiver_pk.to_string(),
+                        v.fee.0 .0.as_u64(),
+                    )
+                })
+                .collect()
+        }
+        MinaTransactionTransactionStableV2::Coinbase(coinbase) => {
+            let amount = coinbase.amount.0 .0.as_u64();
+            match &coinbase.fee_transfer {
+                None => vec![("coinbase", coinbase.receiver.to_string(), amount)],
+                Some(fee_transfer) => {
+                    let fee = fee_transfer.fee.0 .0.as_u64();
+                    vec![
+                        (
+                            "coinbase",
+                            coinbase.receiver.to_string(),
+                            amount.saturating_sub(fee),
+                        ),
+                        (
+                            "fee_transfer_via_coinbase",
+                            fee_transfer.receiver_pk.to_string(),
+                            fee,
+                        ),
+                    ]
+                }
+            }
+        }
+    }
+}
+
+fn block_from_row(row: &Row) -> rusqlite::Result<ArchiveBlockRow> {
+    Ok(ArchiveBlockRow {
+        state_hash: row.get(0)?,
+        parent_hash: row.get(1)?,
+        height: row.get(2)?,
+        global_slot_since_genesis: row.get(3)?,
+        timestamp: row.get(4)?,
+        creator: row.get(5)?,
+        coinbase_receiver: row.get(6)?,
+        snarked_ledger_hash: row.get(7)?,
+        staged_ledger_hash: row.get(8)?,
+        chain_status: chain_status_from_sql(row, 9)?,
+    })
+}
+
+fn user_command_from_row(row: &Row) -> rusqlite::Result<ArchiveUserCommandRow> {
+    Ok(ArchiveUserCommandRow {
+        block_hash: row.get(0)?,
+        block_height: row.get(1)?,
+        block_chain_status: chain_status_from_sql(row, 2)?,
+        sequence_no: row.get(3)?,
+        hash: row.get(4)?,
+        kind: row.get(5)?,
+        fee_payer: row.get(6)?,
+        receiver: row.get(7)?,
+        amount: row.get(8)?,
+        fee: row.get(9)?,
+        nonce: row.get(10)?,
+        status: row.get(11)?,
+        failure_reason: row.get(12)?,
+    })
+}
+
+fn account_from_row(row: &Row) -> rusqlite::Result<ArchiveAccountRow> {
+    Ok(ArchiveAccountRow {
+        block_hash: row.get(0)?,
+        block_height: row.get(1)?,
+        block_chain_status: chain_status_from_sql(row, 2)?,
+        public_key: row.get(3)?,
+        token_id: row.get(4)?,
+        balance: row.get(5)?,
+        nonce: row.get(6)?,
+        delegate: row.get(7)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::v2::{
+        MinaNumbersGlobalSlotSinceHardForkMStableV1, UnsignedExtendedUInt32StableV1,
+    };
+    use openmina_core::block::{test_block, test_block_with};
+
+    use super::*;
+
+    /// Child of `parent`, blocks with the same parent are told apart by
+    /// `slot_offset`.
+    fn child(parent: &ArcBlockWithHash, slot_offset: u32) -> ArcBlockWithHash {
+        test_block_with(|block| {
+            let protocol_state = &mut block.header.protocol_state;
+            protocol_state.previous_state_hash = parent.hash.clone();
+            let consensus_state = &mut protocol_state.body.consensus_state;
+            consensus_state.blockchain_length =
+                UnsignedExtendedUInt32StableV1((parent.height() + 1).into());
+            let slot = consensus_state.global_slot() + 1 + slot_offset;
+            consensus_state.curr_global_slot_since_hard_fork.slot_number =
+                MinaNumbersGlobalSlotSinceHardForkMStableV1::SinceHardFork(slot.into());
+        })
+    }
+
+    fn applied(block: ArcBlockWithHash) -> ArchiveBlock {
+        ArchiveBlock {
+            block,
+            transactions: vec![],
+            accounts: vec![],
+        }
+    }
+
+    fn chain_status(db: &ArchiveDb, block: &ArcBlockWithHash) -> ArchiveChainStatus {
+        db.block(block.hash()).unwrap().unwrap().block.chain_status
+    }
+
+    #[test]
+    fn best_chain_update_marks_canonical_and_orphaned() {
+        let b1 = child(&test_block(), 0);
+        let b2 = child(&b1, 0);
+        let b3 = child(&b2, 0);
+        let fork = child(&b1, 1);
+        let mut db = ArchiveDb::open(":memory:").unwrap();
+        db.block_add(&applied(b1.clone())).unwrap();
+        db.block_add(&applied(b2.clone())).unwrap();
+        db.block_add(&applied(b3.clone())).unwrap();
+        db.fork_block_add(&fork).unwrap();
+        assert_eq!(db.pending_blocks().unwrap().len(), 4);
+
+        db.best_chain_update(b2.hash(), b2.height()).unwrap();
+        assert_eq!(chain_status(&db, &b1), ArchiveChainStatus::Canonical);
+        assert_eq!(chain_status(&db, &b2), ArchiveChainStatus::Canonical);
+        assert_eq!(chain_status(&db, &b3), ArchiveChainStatus::Pending);
+        assert_eq!(chain_status(&db, &fork), ArchiveChainStatus::Orphaned);
+
+        let pending = db.pending_blocks().unwrap();
+        assert_eq!(pending, vec![(b3.hash().to_string(), b3.height())]);
+    }
+
+    #[test]
+    fn fork_block_is_completed_once_applied() {
+        let mut db = ArchiveDb::open(":memory:").unwrap();
+        let block = child(&test_block(), 0);
+        let hash = block.hash();
+
+        db.fork_block_add(&block).unwrap();
+        assert!(!db.block_is_applied(hash).unwrap());
+
+        db.block_add(&applied(block.clone())).unwrap();
+        assert!(db.block_is_applied(hash).unwrap());
+
+        // adding it again, in any way, changes nothing.
+        db.block_add(&applied(block.clone())).unwrap();
+        db.fork_block_add(&block).unwrap();
+        assert!(db.block_is_applied(hash).unwrap());
+        assert_eq!(db.pending_blocks().unwrap().len(), 1);
+    }
+}
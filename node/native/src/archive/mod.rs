@@ -0,0 +1,163 @@
+mod archive_db;
+pub use archive_db::*;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use ledger::scan_state::transaction_logic::{Transaction, WithStatus};
+use ledger::Account;
+use mina_p2p_messages::v2::{MinaBaseTransactionStatusStableV2, StateHash};
+use node::core::block::ArcBlockWithHash;
+use node::core::channels::mpsc;
+use node::transition_frontier::TransitionFrontierArchiveService;
+
+use crate::NodeService;
+
+pub struct ArchiveService {
+    cmd_sender: mpsc::UnboundedSender<ArchiveCmd>,
+    /// Blocks sent to the archive, which are still above the root.
+    archived: BTreeMap<StateHash, u32>,
+}
+
+enum ArchiveCmd {
+    /// Block applied by the transition frontier, with its transactions and
+    /// the accounts they accessed, read from its staged ledger.
+    BlockAdd(ArcBlockWithHash, Vec<WithStatus<Transaction>>, Vec<Account>),
+    ForkBlockAdd(ArcBlockWithHash),
+    BestChainUpdate {
+        root: StateHash,
+        root_height: u32,
+    },
+}
+
+impl NodeService {
+    /// Opens (or creates) the archive database at `path` and starts the
+    /// thread writing applied blocks into it.
+    pub fn archive_start<P: AsRef<Path>>(&mut self, path: P) -> rusqlite::Result<()> {
+        let db = ArchiveDb::open(path)?;
+        let (cmd_sender, cmd_receiver) = mpsc::unbounded_channel();
+
+        // blocks archived before the restart, so that they aren't
+        // processed again.
+        let archived = db
+            .pending_blocks()?
+            .into_iter()
+            .filter_map(|(hash, height)| Some((hash.parse().ok()?, height)))
+            .collect();
+        self.archive = Some(ArchiveService {
+            cmd_sender,
+            archived,
+        });
+
+        std::thread::Builder::new()
+            .name("openmina_archive".to_owned())
+            .spawn(move || archive_writer(db, cmd_receiver))
+            .unwrap();
+        Ok(())
+    }
+}
+
+fn archive_writer(mut db: ArchiveDb, mut cmd_receiver: mpsc::UnboundedReceiver<ArchiveCmd>) {
+    while let Some(cmd) = cmd_receiver.blocking_recv() {
+        let (summary, res) = match cmd {
+            ArchiveCmd::BlockAdd(block, transactions, accounts) => (
+                format!("failed to archive block {}", block.hash()),
+                archive_block_add(&mut db, block, &transactions, &accounts),
+            ),
+            ArchiveCmd::ForkBlockAdd(block) => (
+                format!("failed to archive fork block {}", block.hash()),
+                db.fork_block_add(&block).map_err(|err| err.to_string()),
+            ),
+            ArchiveCmd::BestChainUpdate { root, root_height } => (
+                format!("failed to update chain status for root {root}"),
+                db.best_chain_update(&root, root_height)
+                    .map_err(|err| err.to_string()),
+            ),
+        };
+        if let Err(err) = res {
+            openmina_core::log::error!(openmina_core::log::system_time();
+                kind = "ArchiveError",
+                summary = summary,
+                error = err);
+        }
+    }
+}
+
+fn archive_block_add(
+    db: &mut ArchiveDb,
+    block: ArcBlockWithHash,
+    transactions: &[WithStatus<Transaction>],
+    accounts: &[Account],
+) -> Result<(), String> {
+    if db
+        .block_is_applied(block.hash())
+        .map_err(|err| err.to_string())?
+    {
+        return Ok(());
+    }
+    let block_add = ArchiveBlock {
+        block,
+        transactions: transactions
+            .iter()
+            .map(|tx| {
+                (
+                    (&tx.data).into(),
+                    MinaBaseTransactionStatusStableV2::from(&tx.status),
+                )
+            })
+            .collect(),
+        accounts: accounts.iter().map(Into::into).collect(),
+    };
+    db.block_add(&block_add).map_err(|err| err.to_string())
+}
+
+impl TransitionFrontierArchiveService for NodeService {
+    fn archive_best_chain_update(&mut self, best_chain: &[ArcBlockWithHash]) {
+        let Some(archive) = self.archive.as_mut() else {
+            return;
+        };
+        let Some(root) = best_chain.first() else {
+            return;
+        };
+
+        for block in best_chain {
+            if archive.archived.contains_key(block.hash()) {
+                continue;
+            }
+            // accounts must be read here, as the staged ledger of the block
+            // is merged into its successors and dropped once it is pruned.
+            let (transactions, accounts) =
+                match self.ledger.block_transactions_and_accounts(block) {
+                    Ok(v) => v,
+                    Err(err) => {
+                        openmina_core::log::warn!(openmina_core::log::system_time();
+                            kind = "ArchiveError",
+                            summary = format!("skipping block {}", block.hash()),
+                            error = err);
+                        continue;
+                    }
+                };
+            archive
+                .archived
+                .insert(block.hash().clone(), block.height());
+            let _ = archive
+                .cmd_sender
+                .send(ArchiveCmd::BlockAdd(block.clone(), transactions, accounts));
+        }
+
+        let root_height = root.height();
+        archive.archived.retain(|_, height| *height >= root_height);
+        let _ = archive.cmd_sender.send(ArchiveCmd::BestChainUpdate {
+            root: root.hash().clone(),
+            root_height,
+        });
+    }
+
+    fn archive_fork_block_add(&mut self, block: &ArcBlockWithHash) {
+        if let Some(archive) = self.archive.as_ref() {
+            let _ = archive
+                .cmd_sender
+                .send(ArchiveCmd::ForkBlockAdd(block.clone()));
+        }
+    }
+}
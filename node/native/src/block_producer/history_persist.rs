@@ -0,0 +1,137 @@
+//! History of won slots, persisted as a json file, so that it survives
+//! restarts of the node.
+//!
+//! File is replaced atomically on each write, so it's never partially
+//! written.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use node::block_producer::{BlockProducerHistory, BlockProducerHistoryPersistService};
+use node::core::channels::mpsc;
+
+use crate::NodeService;
+
+pub struct BlockProducerHistoryPersist {
+    history_sender: mpsc::UnboundedSender<BlockProducerHistory>,
+    /// History loaded from the file on start, not yet taken by the node.
+    loaded: Option<BlockProducerHistory>,
+}
+
+/// Reads history from the file at `path`. Missing file means there is
+/// no history yet.
+fn history_read(path: &Path) -> io::Result<Option<BlockProducerHistory>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    serde_json::from_reader(BufReader::new(file))
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn history_write(path: &Path, history: &BlockProducerHistory) -> io::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    {
+        let mut file = BufWriter::new(File::create(&tmp_path)?);
+        serde_json::to_writer(&mut file, history)?;
+        file.into_inner()?.sync_all()?;
+    }
+    std::fs::rename(&tmp_path, path)
+}
+
+impl NodeService {
+    /// Reads history of won slots persisted at `path` and starts the
+    /// thread writing it there.
+    pub fn block_producer_history_persist_start<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> io::Result<()> {
+        let path = path.as_ref().to_owned();
+        let loaded = history_read(&path)?;
+        let (history_sender, history_receiver) = mpsc::unbounded_channel();
+
+        self.block_producer_history_persist = Some(BlockProducerHistoryPersist {
+            history_sender,
+            loaded,
+        });
+
+        std::thread::Builder::new()
+            .name("openmina_block_producer_history_persist".to_owned())
+            .spawn(move || history_writer(path, history_receiver))
+            .unwrap();
+        Ok(())
+    }
+}
+
+fn history_writer(
+    path: PathBuf,
+    mut history_receiver: mpsc::UnboundedReceiver<BlockProducerHistory>,
+) {
+    while let Some(mut history) = history_receiver.blocking_recv() {
+        // only the latest history needs to be written.
+        while let Ok(newer) = history_receiver.try_recv() {
+            history = newer;
+        }
+        if let Err(err) = history_write(&path, &history) {
+            openmina_core::log::error!(openmina_core::log::system_time();
+                kind = "BlockProducerHistoryPersistError",
+                summary = "failed to write block producer history",
+                error = err.to_string());
+        }
+    }
+}
+
+impl BlockProducerHistoryPersistService for NodeService {
+    fn block_producer_history_persisted_take(&mut self) -> Option<BlockProducerHistory> {
+        self.block_producer_history_persist
+            .as_mut()
+            .and_then(|persist| persist.loaded.take())
+    }
+
+    fn block_producer_history_persist(&mut self, history: &BlockProducerHistory) {
+        if let Some(persist) = self.block_producer_history_persist.as_ref() {
+            let _ = persist.history_sender.send(history.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+
+    fn tmp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("openmina-bp-history-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    #[test]
+    fn write_read_round_trip() {
+        let path = tmp_path("round_trip.json");
+        assert!(history_read(&path).unwrap().is_none());
+
+        let history = BlockProducerHistory {
+            epochs: BTreeMap::from([(3, BTreeMap::new()), (4, BTreeMap::new())]),
+        };
+        history_write(&path, &history).unwrap();
+        let read = history_read(&path).unwrap().unwrap();
+        assert_eq!(
+            read.epochs.keys().collect::<Vec<_>>(),
+            history.epochs.keys().collect::<Vec<_>>()
+        );
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn corrupted_file_is_an_error() {
+        let path = tmp_path("corrupted.json");
+        std::fs::write(&path, b"{\"epochs\":").unwrap();
+        let err = history_read(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,7 +1,12 @@
+mod history_persist;
+pub use history_persist::BlockProducerHistoryPersist;
 mod vrf_evaluator;
 
-use mina_signer::Keypair;
-use node::{block_producer::vrf_evaluator::VrfEvaluatorInput, core::channels::mpsc};
+use std::sync::Arc;
+
+use node::{
+    account::AccountSigner, block_producer::vrf_evaluator::VrfEvaluatorInput, core::channels::mpsc,
+};
 
 use crate::NodeService;
 
@@ -18,7 +23,9 @@ impl BlockProducerService {
 }
 
 impl NodeService {
-    pub fn block_producer_start(&mut self, producer_keypair: Keypair) {
+    /// Starts the vrf evaluator. Producer keys are only accessed through
+    /// the `signer`, which may be remote.
+    pub fn block_producer_start(&mut self, signer: Arc<dyn AccountSigner>) {
         let event_sender = self.event_sender.clone();
         let (vrf_evaluation_sender, vrf_evaluation_receiver) =
             mpsc::unbounded_channel::<VrfEvaluatorInput>();
@@ -28,11 +35,7 @@ impl NodeService {
         std::thread::Builder::new()
             .name("openmina_vrf_evaluator".to_owned())
             .spawn(move || {
-                vrf_evaluator::vrf_evaluator(
-                    event_sender,
-                    vrf_evaluation_receiver,
-                    producer_keypair,
-                );
+                vrf_evaluator::vrf_evaluator(event_sender, vrf_evaluation_receiver, signer);
             })
             .unwrap();
     }
@@ -1,13 +1,14 @@
-use mina_signer::Keypair;
+use std::sync::Arc;
+
 use node::{
+    account::AccountSigner,
     block_producer::{
-        vrf_evaluator::{VrfEvaluationOutputWithHash, VrfEvaluatorInput},
+        vrf_evaluator::{VrfEvaluationBatchWithHash, VrfEvaluatorInput},
         BlockProducerEvent,
     },
     event_source::Event,
 };
 use openmina_core::channels::mpsc::{UnboundedReceiver, UnboundedSender};
-use vrf::{VrfEvaluationInput, VrfEvaluationOutput};
 
 use crate::NodeService;
 use node::block_producer::BlockProducerVrfEvaluatorEvent;
@@ -15,39 +16,31 @@ use node::block_producer::BlockProducerVrfEvaluatorEvent;
 pub fn vrf_evaluator(
     event_sender: UnboundedSender<Event>,
     mut vrf_evaluation_receiver: UnboundedReceiver<VrfEvaluatorInput>,
-    keypair: Keypair,
+    signer: Arc<dyn AccountSigner>,
 ) {
     while let Some(vrf_evaluator_input) = vrf_evaluation_receiver.blocking_recv() {
-        let mut vrf_result = VrfEvaluationOutput::SlotLost(vrf_evaluator_input.global_slot);
-
-        for (index, account) in vrf_evaluator_input.delegator_table.iter() {
-            let vrf_input = VrfEvaluationInput::new(
-                keypair.clone(),
-                vrf_evaluator_input.epoch_seed.clone(),
-                account.0.to_string(),
-                vrf_evaluator_input.global_slot,
-                index.clone(),
-                account.1.into(),
-                vrf_evaluator_input.total_currency.into(),
-            );
-            vrf_result = vrf::evaluate_vrf(vrf_input).unwrap();
+        let result = signer.evaluate_vrf(&vrf_evaluator_input);
 
-            // the first delegate that won the slot
-            if let VrfEvaluationOutput::SlotWon(_) = vrf_result {
-                break;
-            }
-        }
-        let vrf_result_with_hash = VrfEvaluationOutputWithHash::new(
-            vrf_result,
-            vrf_evaluator_input.staking_ledger_hash.clone(),
-        );
+        let VrfEvaluatorInput {
+            producer,
+            slots,
+            staking_ledger_hash,
+            ..
+        } = vrf_evaluator_input;
+        let event = match result {
+            Ok(won_slots) => BlockProducerVrfEvaluatorEvent::Evaluated(
+                VrfEvaluationBatchWithHash::new(producer, slots, won_slots, staking_ledger_hash),
+            ),
+            // the state machine retries the evaluation later.
+            Err(error) => BlockProducerVrfEvaluatorEvent::EvaluationFailed {
+                producer,
+                slots,
+                staking_ledger_hash,
+                error,
+            },
+        };
         // send the result back to the state machine
-        let _ = event_sender.send(
-            BlockProducerEvent::VrfEvaluator(BlockProducerVrfEvaluatorEvent::Evaluated(
-                vrf_result_with_hash,
-            ))
-            .into(),
-        );
+        let _ = event_sender.send(BlockProducerEvent::VrfEvaluator(event).into());
     }
 }
 
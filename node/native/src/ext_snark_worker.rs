@@ -17,9 +17,10 @@ use mina_p2p_messages::v2::{
 use node::core::channels::{mpsc, oneshot};
 use node::event_source::Event;
 use node::external_snark_worker::{
-    ExternalSnarkWorkerError, ExternalSnarkWorkerEvent, ExternalSnarkWorkerService,
-    ExternalSnarkWorkerWorkError, SnarkWorkSpec,
+    ExternalSnarkWorkerError, ExternalSnarkWorkerEvent, ExternalSnarkWorkerId,
+    ExternalSnarkWorkerService, ExternalSnarkWorkerWorkError, SnarkWorkSpec,
 };
+use node::SnarkerWorkerKind;
 
 use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
 use tokio::process::Command;
@@ -108,7 +109,7 @@ where
 
 /// Facade for external worker process.
 pub struct ExternalSnarkWorkerFacade {
-    data_chan: mpsc::Sender<SnarkWorkSpec>,
+    data_chan: mpsc::Sender<(SnarkWorkSpec, CurrencyFeeStableV1)>,
     cancel_chan: mpsc::Sender<()>,
     kill_chan: oneshot::Sender<()>,
 }
@@ -191,16 +192,21 @@ async fn stderr_reader<R: AsyncRead + Unpin>(r: R) -> Result<(), SnarkerError> {
 }
 
 macro_rules! send_event {
-    ($channel:expr, $event:expr) => {
-        _ = $channel.send(node::event_source::Event::ExternalSnarkWorker($event));
+    ($channel:expr, $worker_id:expr, $event:expr) => {
+        _ = $channel.send(node::event_source::Event::ExternalSnarkWorker(
+            $worker_id, $event,
+        ));
     };
 }
 
+mod in_process;
+pub use in_process::prove_single;
+
 impl ExternalSnarkWorkerFacade {
     fn start<P: AsRef<OsStr>>(
+        worker_id: ExternalSnarkWorkerId,
         path: P,
         public_key: NonZeroCurvePoint,
-        fee: CurrencyFeeStableV1,
         event_sender: mpsc::UnboundedSender<Event>,
     ) -> Result<Self, SnarkerError> {
         let (data_chan, mut data_rx) = mpsc::channel(1);
@@ -215,7 +221,7 @@ impl ExternalSnarkWorkerFacade {
 
         // TODO(akoptelov) make the block return terminal errors instead of sending them down the channel and exit.
         std::thread::Builder::new()
-            .name("external-snark-worker".into())
+            .name(format!("external-snark-worker-{worker_id}"))
             .spawn(move || {
                 let runtime = tokio::runtime::Builder::new_current_thread()
                     .enable_all()
@@ -232,7 +238,11 @@ impl ExternalSnarkWorkerFacade {
                     {
                         Ok(v) => v,
                         Err(err) => {
-                            send_event!(event_sender_clone, SnarkerError::from(err).into());
+                            send_event!(
+                                event_sender_clone,
+                                worker_id,
+                                SnarkerError::from(err).into()
+                            );
                             return;
                         }
                     };
@@ -246,7 +256,7 @@ impl ExternalSnarkWorkerFacade {
                             // readiness
                             let request = ExternalSnarkWorkerRequest::await_readiness();
                             if let Err(err) = write_binprot(request, &mut child_stdin).await {
-                                send_event!(event_sender_clone, err.into());
+                                send_event!(event_sender_clone, worker_id, err.into());
                                 return;
                             }
                             let response = read_binprot(&mut child_stdout).await;
@@ -254,12 +264,14 @@ impl ExternalSnarkWorkerFacade {
                                 Ok(v) if v => {
                                     send_event!(
                                         event_sender_clone,
+                                        worker_id,
                                         ExternalSnarkWorkerEvent::Started
                                     );
                                 }
                                 Ok(_) => {
                                     send_event!(
                                         event_sender_clone,
+                                        worker_id,
                                         SnarkerError::Broken(
                                             "snarker responded `false` on readiness request".into()
                                         )
@@ -268,45 +280,51 @@ impl ExternalSnarkWorkerFacade {
                                     return;
                                 }
                                 Err(err) => {
-                                    send_event!(event_sender_clone, err.into());
+                                    send_event!(event_sender_clone, worker_id, err.into());
                                     return;
                                 }
                             }
 
                             loop {
-                                let Some(spec) = data_rx.recv().await else {
+                                let Some((spec, fee)) = data_rx.recv().await else {
                                     return;
                                 };
                                 let request = ExternalSnarkWorkerRequest::perform_job(
                                     spec,
                                     public_key.clone(),
-                                    fee.clone(),
+                                    fee,
                                 );
                                 if let Err(err) = write_binprot(request, &mut child_stdin).await {
-                                    send_event!(event_sender_clone, err.into());
+                                    send_event!(event_sender_clone, worker_id, err.into());
                                     return;
                                 }
                                 let response = read_binprot(&mut child_stdout).await;
                                 match response {
                                     Ok(result) => match result {
                                         ExternalSnarkWorkerResult::Ok(Some(v)) => {
-                                            send_event!(event_sender_clone, Arc::new(v).into());
+                                            send_event!(
+                                                event_sender_clone,
+                                                worker_id,
+                                                Arc::new(v).into()
+                                            );
                                         }
                                         ExternalSnarkWorkerResult::Ok(None) => {
                                             send_event!(
                                                 event_sender_clone,
+                                                worker_id,
                                                 ExternalSnarkWorkerEvent::WorkCancelled
                                             );
                                         }
                                         ExternalSnarkWorkerResult::Err(err) => {
                                             send_event!(
                                                 event_sender_clone,
+                                                worker_id,
                                                 ExternalSnarkWorkerWorkError::Error(err).into()
                                             );
                                         }
                                     },
                                     Err(err) => {
-                                        send_event!(event_sender_clone, err.into());
+                                        send_event!(event_sender_clone, worker_id, err.into());
                                     }
                                 }
                             }
@@ -322,7 +340,11 @@ impl ExternalSnarkWorkerFacade {
                                 if let Err(err) =
                                     nix::sys::signal::kill(pid, nix::sys::signal::Signal::SIGINT)
                                 {
-                                    send_event!(event_sender_clone, SnarkerError::from(err).into());
+                                    send_event!(
+                                        event_sender_clone,
+                                        worker_id,
+                                        SnarkerError::from(err).into()
+                                    );
                                 }
                             }
                         });
@@ -332,16 +354,28 @@ impl ExternalSnarkWorkerFacade {
                         let event_sender_clone = event_sender.clone();
                         tokio::spawn(async move {
                             if let Err(err) = stderr_reader(child_stderr).await {
-                                send_event!(event_sender_clone, SnarkerError::from(err).into());
+                                send_event!(
+                                    event_sender_clone,
+                                    worker_id,
+                                    SnarkerError::from(err).into()
+                                );
                             }
                         });
 
                         tokio::select! {
                             _ = kill_rx => {
                                 if let Err(err) = child.kill().await {
-                                    send_event!(event_sender, SnarkerError::from(err).into());
+                                    send_event!(
+                                        event_sender,
+                                        worker_id,
+                                        SnarkerError::from(err).into()
+                                    );
                                 } else {
-                                    send_event!(event_sender, ExternalSnarkWorkerEvent::Killed);
+                                    send_event!(
+                                        event_sender,
+                                        worker_id,
+                                        ExternalSnarkWorkerEvent::Killed
+                                    );
                                 }
                                 return;
                             }
@@ -366,9 +400,13 @@ impl ExternalSnarkWorkerFacade {
             .map_err(|_| SnarkerError::Broken("already cancelled".into()))
     }
 
-    fn submit(&mut self, spec: SnarkWorkSpec) -> Result<(), SnarkerError> {
+    fn submit(
+        &mut self,
+        spec: SnarkWorkSpec,
+        fee: CurrencyFeeStableV1,
+    ) -> Result<(), SnarkerError> {
         self.data_chan
-            .try_send(spec)
+            .try_send((spec, fee))
             .map_err(|_| SnarkerError::Busy)
     }
 
@@ -382,50 +420,63 @@ impl ExternalSnarkWorkerFacade {
 impl ExternalSnarkWorkerService for NodeService {
     fn start<P: AsRef<OsStr>>(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
+        kind: SnarkerWorkerKind,
         path: P,
         public_key: NonZeroCurvePoint,
-        fee: CurrencyFeeStableV1,
     ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         if self.replayer.is_some() {
             return Ok(());
         }
-        let cmd_sender =
-            ExternalSnarkWorkerFacade::start(path, public_key, fee, self.event_sender.clone())?;
-        self.snark_worker_sender = Some(cmd_sender);
+        let event_sender = self.event_sender.clone();
+        let cmd_sender = match kind {
+            SnarkerWorkerKind::External => {
+                ExternalSnarkWorkerFacade::start(worker_id, path, public_key, event_sender)?
+            }
+            SnarkerWorkerKind::InProcess => {
+                ExternalSnarkWorkerFacade::start_in_process(worker_id, public_key, event_sender)?
+            }
+        };
+        self.snark_workers.insert(worker_id, cmd_sender);
         Ok(())
     }
 
     fn submit(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
         spec: SnarkWorkSpec,
+        fee: CurrencyFeeStableV1,
     ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         if self.replayer.is_some() {
             return Ok(());
         }
-        self.snark_worker_sender
-            .as_mut()
+        self.snark_workers
+            .get_mut(&worker_id)
             .ok_or(SnarkerError::NotRunning)
-            .and_then(|sender| sender.submit(spec))?;
+            .and_then(|sender| sender.submit(spec, fee))?;
         Ok(())
     }
 
-    fn cancel(&mut self) -> Result<(), ExternalSnarkWorkerError> {
+    fn cancel(&mut self, worker_id: ExternalSnarkWorkerId) -> Result<(), ExternalSnarkWorkerError> {
         if self.replayer.is_some() {
             return Ok(());
         }
-        self.snark_worker_sender
-            .as_mut()
+        self.snark_workers
+            .get_mut(&worker_id)
             .ok_or(SnarkerError::NotRunning)
             .and_then(|sender| sender.cancel())?;
         Ok(())
     }
 
-    fn kill(&mut self) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
+    fn kill(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+    ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         if self.replayer.is_some() {
             return Ok(());
         }
-        self.snark_worker_sender
-            .take()
+        self.snark_workers
+            .remove(&worker_id)
             .ok_or(SnarkerError::NotRunning)
             .and_then(|sender| sender.kill())?;
         Ok(())
@@ -454,7 +505,7 @@ mod tests {
     macro_rules! expect_event {
         ($source:expr, $event:pat) => {
             let result = $source.recv().await.expect("failed to receive an event");
-            let Event::ExternalSnarkWorker(result) = result else {
+            let Event::ExternalSnarkWorker(_, result) = result else {
                 panic!("unexpected event kind");
             };
             let $event = result else {
@@ -476,13 +527,9 @@ mod tests {
     async fn test_kill() {
         let (event_tx, mut event_rx) = mpsc::unbounded_channel();
         let cmd_sender = ExternalSnarkWorkerFacade::start(
+            0,
             mina_exe_path(),
             NonZeroCurvePoint::default(),
-            CurrencyFeeStableV1(
-                mina_p2p_messages::v2::UnsignedExtendedUInt64Int64ForVersionTagsStableV1(
-                    10_u64.into(),
-                ),
-            ),
             event_tx,
         )
         .unwrap();
@@ -517,11 +564,12 @@ mod tests {
 
         let (event_tx, mut event_rx) = mpsc::unbounded_channel();
         let mut cmd_sender =
-            ExternalSnarkWorkerFacade::start(mina_exe_path(), public_key, fee, event_tx).unwrap();
+            ExternalSnarkWorkerFacade::start(0, mina_exe_path(), public_key, event_tx)
+                .unwrap();
 
         expect_event!(event_rx, ExternalSnarkWorkerEvent::Started);
 
-        cmd_sender.submit(instances).unwrap();
+        cmd_sender.submit(instances, fee).unwrap();
         expect_event!(event_rx, ExternalSnarkWorkerEvent::WorkResult(_));
 
         cmd_sender.kill().expect("cannot kill worker");
@@ -536,11 +584,12 @@ mod tests {
 
         let (event_tx, mut event_rx) = mpsc::unbounded_channel();
         let mut cmd_sender =
-            ExternalSnarkWorkerFacade::start(mina_exe_path(), public_key, fee, event_tx).unwrap();
+            ExternalSnarkWorkerFacade::start(0, mina_exe_path(), public_key, event_tx)
+                .unwrap();
 
         expect_event!(event_rx, ExternalSnarkWorkerEvent::Started);
 
-        cmd_sender.submit(instances.clone()).unwrap();
+        cmd_sender.submit(instances.clone(), fee.clone()).unwrap();
 
         // ensure that for 5 seconds no feedback is received
         let _ = tokio::time::timeout(Duration::from_secs(5), event_rx.recv())
@@ -552,7 +601,7 @@ mod tests {
         cmd_sender.cancel().unwrap();
         expect_event!(event_rx, ExternalSnarkWorkerEvent::WorkCancelled);
 
-        cmd_sender.submit(instances).unwrap();
+        cmd_sender.submit(instances, fee).unwrap();
         expect_event!(event_rx, ExternalSnarkWorkerEvent::WorkResult(_));
 
         cmd_sender.kill().expect("cannot kill worker");
@@ -567,11 +616,12 @@ mod tests {
 
         let (event_tx, mut event_rx) = mpsc::unbounded_channel();
         let mut cmd_sender =
-            ExternalSnarkWorkerFacade::start(mina_exe_path(), public_key, fee, event_tx).unwrap();
+            ExternalSnarkWorkerFacade::start(0, mina_exe_path(), public_key, event_tx)
+                .unwrap();
 
         expect_event!(event_rx, ExternalSnarkWorkerEvent::Started);
 
-        cmd_sender.submit(instances.clone()).unwrap();
+        cmd_sender.submit(instances.clone(), fee.clone()).unwrap();
 
         // ensure that for 5 seconds no feedback is received
         let _ = tokio::time::timeout(Duration::from_secs(5), event_rx.recv())
@@ -583,7 +633,7 @@ mod tests {
         cmd_sender.cancel().unwrap();
         expect_event!(event_rx, ExternalSnarkWorkerEvent::WorkCancelled);
 
-        cmd_sender.submit(instances.clone()).unwrap();
+        cmd_sender.submit(instances.clone(), fee.clone()).unwrap();
 
         // ensure that for 5 seconds no feedback is received
         let _ = tokio::time::timeout(Duration::from_secs(5), event_rx.recv())
@@ -595,7 +645,7 @@ mod tests {
         cmd_sender.cancel().unwrap();
         expect_event!(event_rx, ExternalSnarkWorkerEvent::WorkCancelled);
 
-        cmd_sender.submit(instances).unwrap();
+        cmd_sender.submit(instances, fee).unwrap();
         expect_event!(event_rx, ExternalSnarkWorkerEvent::WorkResult(_));
 
         cmd_sender.kill().expect("cannot kill worker");
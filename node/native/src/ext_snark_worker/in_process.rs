@@ -0,0 +1,171 @@
+use std::sync::Arc;
+
+use ledger::proofs::gates::{get_provers, Provers};
+use ledger::proofs::merge::MergeParams;
+use ledger::proofs::transaction::{ProofError, TransactionParams};
+use ledger::proofs::zkapp::{LedgerProof, ZkappParams};
+use ledger::proofs::{generate_merge_proof, generate_tx_proof, generate_zkapp_proof};
+use ledger::scan_state::scan_state::transaction_snark::{SokMessage, Statement};
+use mina_p2p_messages::v2::{
+    LedgerProofProdStableV2, MinaBaseUserCommandStableV2, MinaTransactionTransactionStableV2,
+    NonZeroCurvePoint, SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single, TransactionSnarkWorkTStableV2Proofs,
+};
+
+use node::core::channels::{mpsc, oneshot};
+use node::event_source::Event;
+use node::external_snark_worker::{
+    ExternalSnarkWorkerEvent, ExternalSnarkWorkerId, ExternalSnarkWorkerWorkError, SnarkWorkSpec,
+};
+
+use super::{ExternalSnarkWorkerFacade, SnarkerError};
+
+impl ExternalSnarkWorkerFacade {
+    /// Starts a worker, which proves jobs on its own thread, instead of
+    /// in the external snark worker process.
+    ///
+    /// Proving can't be interrupted, so cancelled or killed worker
+    /// reports it only once the job it is working on is done. If the
+    /// job was done before the cancel was received, its result is
+    /// reported instead.
+    pub(super) fn start_in_process(
+        worker_id: ExternalSnarkWorkerId,
+        public_key: NonZeroCurvePoint,
+        event_sender: mpsc::UnboundedSender<Event>,
+    ) -> Result<Self, SnarkerError> {
+        let (data_chan, mut data_rx) = mpsc::channel(1);
+        let (cancel_chan, mut cancel_rx) = mpsc::channel(1);
+        let (kill_chan, mut kill_rx) = oneshot::channel();
+
+        std::thread::Builder::new()
+            .name(format!("in-process-snark-worker-{worker_id}"))
+            .spawn(move || {
+                // slow on the first call, as circuits get compiled.
+                let provers = get_provers();
+                send_event!(event_sender, worker_id, ExternalSnarkWorkerEvent::Started);
+
+                // channel is closed once the facade is killed.
+                while let Some((spec, fee)) = data_rx.blocking_recv() {
+                    // cancel received after the previous job was done,
+                    // its result was taken instead.
+                    while cancel_rx.try_recv().is_ok() {}
+                    let message = SokMessage::create((&fee).into(), (&public_key).into());
+                    let result = prove(&spec, &message, &provers);
+                    if cancel_rx.try_recv().is_ok() {
+                        send_event!(
+                            event_sender,
+                            worker_id,
+                            ExternalSnarkWorkerEvent::WorkCancelled
+                        );
+                        continue;
+                    }
+                    match result {
+                        Ok(v) => {
+                            send_event!(event_sender, worker_id, Arc::new(v).into());
+                        }
+                        Err(err) => {
+                            send_event!(
+                                event_sender,
+                                worker_id,
+                                ExternalSnarkWorkerWorkError::Error(format!("{err:?}")).into()
+                            );
+                        }
+                    }
+                }
+
+                if kill_rx.try_recv().is_ok() {
+                    send_event!(event_sender, worker_id, ExternalSnarkWorkerEvent::Killed);
+                }
+            })?;
+
+        Ok(ExternalSnarkWorkerFacade {
+            data_chan,
+            cancel_chan,
+            kill_chan,
+        })
+    }
+}
+
+fn prove(
+    spec: &SnarkWorkSpec,
+    message: &SokMessage,
+    provers: &Provers,
+) -> Result<TransactionSnarkWorkTStableV2Proofs, ProofError> {
+    Ok(match spec {
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances::One(single) => {
+            TransactionSnarkWorkTStableV2Proofs::One(prove_single(single, message, provers)?)
+        }
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Instances::Two((first, second)) => {
+            TransactionSnarkWorkTStableV2Proofs::Two((
+                prove_single(first, message, provers)?,
+                prove_single(second, message, provers)?,
+            ))
+        }
+    })
+}
+
+/// Proves a single job of the snark work spec, with the given sok
+/// message. Also used by the remote snark worker.
+pub fn prove_single(
+    single: &SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single,
+    message: &SokMessage,
+    provers: &Provers,
+) -> Result<LedgerProofProdStableV2, ProofError> {
+    match single {
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Transition(
+            statement,
+            tx_witness,
+        ) => {
+            let is_zkapp = match &tx_witness.transaction {
+                MinaTransactionTransactionStableV2::Command(cmd) => {
+                    matches!(&**cmd, MinaBaseUserCommandStableV2::ZkappCommand(_))
+                }
+                _ => false,
+            };
+            if is_zkapp {
+                let proof = generate_zkapp_proof(ZkappParams {
+                    statement,
+                    tx_witness,
+                    message,
+                    step_opt_signed_opt_signed_prover: &provers
+                        .zkapp_step_opt_signed_opt_signed_prover,
+                    step_opt_signed_prover: &provers.zkapp_step_opt_signed_prover,
+                    step_proof_prover: &provers.zkapp_step_proof_prover,
+                    merge_step_prover: &provers.merge_step_prover,
+                    tx_wrap_prover: &provers.tx_wrap_prover,
+                    opt_signed_path: None,
+                    proved_path: None,
+                })?;
+                return Ok((&proof).into());
+            }
+            let proof = generate_tx_proof(TransactionParams {
+                statement,
+                tx_witness,
+                message,
+                tx_step_prover: &provers.tx_step_prover,
+                tx_wrap_prover: &provers.tx_wrap_prover,
+                only_verify_constraints: false,
+                expected_step_proof: None,
+                ocaml_wrap_witness: None,
+            })?;
+            let statement = Statement::<()>::from(&**statement).with_digest(message.digest());
+            Ok((&LedgerProof { statement, proof }).into())
+        }
+        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0Single::Merge(merge) => {
+            let (statement, proof1, proof2) = &**merge;
+            let proofs = [proof1.clone(), proof2.clone()];
+            let proof = generate_merge_proof(MergeParams {
+                statement: (&**statement).into(),
+                proofs: &proofs,
+                message,
+                step_prover: &provers.merge_step_prover,
+                wrap_prover: &provers.tx_wrap_prover,
+                only_verify_constraints: false,
+                expected_step_proof: None,
+                ocaml_wrap_witness: None,
+            })?;
+            let statement = Statement::<()>::from(&**statement).with_digest(message.digest());
+            Ok((&LedgerProof { statement, proof }).into())
+        }
+    }
+}
@@ -1,6 +1,13 @@
-use std::{mem::size_of, str::FromStr};
+use std::{
+    collections::BTreeMap,
+    mem::size_of,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+};
 
-use mina_p2p_messages::binprot::BinProtWrite;
+use mina_p2p_messages::binprot::{BinProtRead, BinProtWrite};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use warp::{
     http::HeaderValue,
@@ -9,10 +16,19 @@ use warp::{
     Filter, Rejection, Reply,
 };
 
+use mina_p2p_messages::v2::{
+    LedgerHash, NonZeroCurvePoint, SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery, TokenIdKeyHash,
+};
 use node::rpc::{
-    ActionStatsQuery, RpcPeerInfo, RpcRequest, RpcScanStateSummaryGetQuery,
-    RpcScanStateSummaryGetResponse, RpcSnarkPoolJobGetResponse, RpcSnarkerWorkersResponse,
-    SyncStatsQuery,
+    ActionStatsQuery, RpcAccountProofGetError, RpcAccountProofGetQuery, RpcAccountProofGetResponse,
+    RpcBlockGetError, RpcBlockGetQuery, RpcBlockGetResponse, RpcBlockProducerEpochDataGetResponse,
+    RpcBlockProducerHistoryGetResponse, RpcBlockProducerScheduleGetResponse, RpcPeerInfo,
+    RpcRequest, RpcRole, RpcScanStateSummaryGetQuery, RpcScanStateSummaryGetResponse,
+    RpcSnarkMarketStatsGetResponse, RpcSnarkPoolJobGetResponse, RpcSnarkerWorkGetResponse,
+    RpcSnarkerWorkSubmitResponse, RpcSnarkerWorkersResponse, RpcSnarkersReliabilityResponse,
+    RpcWatchedAccountBlocksGetQuery, RpcWatchedAccountBlocksGetResponse,
+    RpcWatchedAccountsAddResponse, RpcWatchedAccountsGetResponse, RpcWatchedAccountsRemoveResponse,
+    SnarkMarketStatsQuery, SyncStatsQuery,
 };
 use openmina_core::snark::SnarkJobId;
 
@@ -21,7 +37,40 @@ use super::rpc::{
     RpcSnarkerJobSpecResponse, RpcStateGetResponse, RpcSyncStatsGetResponse,
 };
 
-pub async fn run(port: u16, rpc_sender: super::RpcSender) {
+pub struct HttpServerConfig {
+    pub bind_address: IpAddr,
+    pub port: u16,
+    /// If set, admin routes are only served on this port, bound to
+    /// `admin_bind_address`, instead of the main port.
+    pub admin_port: Option<u16>,
+    pub admin_bind_address: IpAddr,
+    /// Authentication is disabled if `None`. Admin routes on the main
+    /// port are then only served to clients on the loopback interface.
+    pub auth: Option<RpcAuth>,
+    /// Chain id of the network, identifies the network in the Rosetta API.
+    pub chain_id: String,
+}
+
+impl HttpServerConfig {
+    /// Serves all routes on all interfaces without authentication,
+    /// except for admin routes, which are only served to local clients.
+    pub fn new(port: u16, chain_id: String) -> Self {
+        Self {
+            bind_address: Ipv4Addr::UNSPECIFIED.into(),
+            port,
+            admin_port: None,
+            admin_bind_address: Ipv4Addr::LOCALHOST.into(),
+            auth: None,
+            chain_id,
+        }
+    }
+}
+
+pub async fn run(config: HttpServerConfig, rpc_sender: super::RpcSender) {
+    let public_rpc_sender = rpc_sender.with_role(RpcRole::Public);
+    let admin_rpc_sender = rpc_sender.with_role(RpcRole::Admin);
+    let rpc_sender = rpc_sender.with_role(RpcRole::ReadOnly);
+
     #[cfg(feature = "p2p-webrtc")]
     let signaling = {
         use node::p2p::{
@@ -34,7 +83,7 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
 
         use super::rpc::RpcP2pConnectionIncomingResponse;
 
-        let rpc_sender_clone = rpc_sender.clone();
+        let rpc_sender_clone = public_rpc_sender.clone();
         warp::path!("mina" / "webrtc" / "signal")
             .and(warp::post())
             .and(warp::filters::body::json())
@@ -75,8 +124,7 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
             })
     };
 
-    // TODO(binier): make endpoint only accessible locally.
-    let rpc_sender_clone = rpc_sender.clone();
+    let rpc_sender_clone = admin_rpc_sender.clone();
     let state_get = warp::path!("state").and(warp::get()).then(move || {
         let rpc_sender_clone = rpc_sender_clone.clone();
         async move {
@@ -100,7 +148,6 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
             }
         });
 
-    // TODO(binier): make endpoint only accessible locally.
     let stats = {
         let rpc_sender_clone = rpc_sender.clone();
         #[derive(Deserialize, Default)]
@@ -162,7 +209,47 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
                 }
             });
 
-        action_stats.or(sync_stats)
+        let rpc_sender_clone = rpc_sender.clone();
+        #[derive(Deserialize, Default)]
+        struct SnarkMarketQueryParams {
+            interval_secs: Option<u64>,
+            limit: Option<usize>,
+            job_id: Option<String>,
+        }
+        let snark_market_stats = warp::path!("stats" / "snark-market")
+            .and(warp::get())
+            .and(optq::<SnarkMarketQueryParams>())
+            .then(move |query: SnarkMarketQueryParams| {
+                let rpc_sender_clone = rpc_sender_clone.clone();
+                async move {
+                    let job_id = match query.job_id.map(|s| SnarkJobId::from_str(&s)).transpose() {
+                        Ok(v) => v,
+                        Err(_) => {
+                            return with_json_reply(&"invalid_input", StatusCode::BAD_REQUEST)
+                        }
+                    };
+                    let result: Option<RpcSnarkMarketStatsGetResponse> = rpc_sender_clone
+                        .oneshot_request(RpcRequest::SnarkMarketStatsGet(SnarkMarketStatsQuery {
+                            interval_secs: query.interval_secs,
+                            limit: query.limit,
+                            job_id,
+                        }))
+                        .await;
+
+                    match result {
+                        None => with_json_reply(
+                            &"response channel dropped",
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                        ),
+                        Some(Ok(stats)) => with_json_reply(&stats, StatusCode::OK),
+                        Some(Err(err)) => {
+                            with_json_reply(&err.to_string(), StatusCode::BAD_REQUEST)
+                        }
+                    }
+                }
+            });
+
+        action_stats.or(sync_stats).or(snark_market_stats)
     };
 
     let rpc_sender_clone = rpc_sender.clone();
@@ -246,8 +333,7 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
         }
     });
 
-    // TODO(binier): make endpoint only accessible locally.
-    let rpc_sender_clone = rpc_sender.clone();
+    let rpc_sender_clone = admin_rpc_sender.clone();
     let snarker_job_commit = warp::path!("snarker" / "job" / "commit")
         .and(warp::post())
         .and(warp::filters::body::bytes())
@@ -280,6 +366,87 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
             }
         });
 
+    // Remote snark workers (`openmina snark worker`). Payloads are those
+    // of `Snark_worker.Rpcs` `get_work` and `submit_work`, over http
+    // instead of async rpc.
+    let rpc_sender_clone = admin_rpc_sender.clone();
+    let snarker_work_get = warp::path!("snarker" / "work" / "get")
+        .and(warp::post())
+        .and(warp::header::optional("accept"))
+        .then(move |accept: Option<String>| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::SnarkerWorkGet)
+                    .await
+                    .map_or_else(
+                        || {
+                            JsonOrBinary::error(
+                                "response channel dropped",
+                                StatusCode::INTERNAL_SERVER_ERROR,
+                            )
+                        },
+                        |resp| match resp {
+                            RpcSnarkerWorkGetResponse::Ok(work)
+                                if accept.as_deref() == Some("application/octet-stream") =>
+                            {
+                                JsonOrBinary::binary(work)
+                            }
+                            RpcSnarkerWorkGetResponse::Ok(work) => JsonOrBinary::json(work),
+                            RpcSnarkerWorkGetResponse::Err(err) => {
+                                JsonOrBinary::error(err, StatusCode::INTERNAL_SERVER_ERROR)
+                            }
+                            RpcSnarkerWorkGetResponse::SnarkerDisabled => {
+                                JsonOrBinary::error("snarker disabled", StatusCode::BAD_REQUEST)
+                            }
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = admin_rpc_sender.clone();
+    let snarker_work_submit = warp::path!("snarker" / "work" / "submit")
+        .and(warp::post())
+        .and(warp::header::optional("content-type"))
+        .and(warp::body::content_length_limit(
+            SNARKER_WORK_SUBMIT_MAX_LEN,
+        ))
+        .and(warp::filters::body::bytes())
+        .then(move |content_type: Option<String>, body: bytes::Bytes| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let work = if content_type.as_deref() == Some("application/octet-stream") {
+                    // binprot encoded, prefixed with 64-bit little-endian length.
+                    body.get(size_of::<u64>()..).and_then(|mut bytes| {
+                        SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery::binprot_read(&mut bytes)
+                            .ok()
+                    })
+                } else {
+                    serde_json::from_slice(&body).ok()
+                };
+                let Some(work) = work else {
+                    return with_json_reply(&"invalid_input", StatusCode::BAD_REQUEST);
+                };
+
+                let res: Option<RpcSnarkerWorkSubmitResponse> = rpc_sender_clone
+                    .oneshot_request(RpcRequest::SnarkerWorkSubmit(Box::new(work)))
+                    .await;
+                match res {
+                    None => with_json_reply(
+                        &"response channel dropped",
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ),
+                    Some(resp) => {
+                        let status = match &resp {
+                            RpcSnarkerWorkSubmitResponse::Ok => StatusCode::CREATED,
+                            _ => StatusCode::BAD_REQUEST,
+                        };
+                        with_json_reply(&resp, status)
+                    }
+                }
+            }
+        });
+
     #[derive(Deserialize)]
     struct JobIdParam {
         id: SnarkJobId,
@@ -342,6 +509,24 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
             }
         });
 
+    let rpc_sender_clone = rpc_sender.clone();
+    let snarkers_reliability = warp::path!("snarker" / "reliability")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::SnarkersReliability)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: RpcSnarkersReliabilityResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
     let rpc_sender_clone = rpc_sender.clone();
     let snarker_config = warp::path!("snarker" / "config")
         .and(warp::get())
@@ -360,30 +545,198 @@ pub async fn run(port: u16, rpc_sender: super::RpcSender) {
             }
         });
 
-    let cors = warp::cors().allow_any_origin();
-    #[cfg(not(feature = "p2p-webrtc"))]
-    let routes = state_get;
+    let public_routes = healthcheck(public_rpc_sender.clone()).or(readiness(public_rpc_sender));
     #[cfg(feature = "p2p-webrtc")]
-    let routes = signaling.or(state_get);
-    let routes = routes
-        .or(peers_get)
-        .or(stats)
-        .or(scan_state_summary_get)
-        .or(snark_pool_jobs_get)
-        .or(snark_pool_job_get)
-        .or(snarker_config)
-        .or(snarker_job_commit)
-        .or(snarker_job_spec)
-        .or(snark_workers)
-        .or(healthcheck(rpc_sender.clone()))
-        .or(readiness(rpc_sender.clone()))
-        .or(super::graphql::routes(rpc_sender))
-        .with(cors);
-    warp::serve(routes).run(([0, 0, 0, 0], port)).await;
+    let public_routes = signaling.or(public_routes);
+
+    // Without authentication, anyone who can reach the main port would
+    // be an admin, unless it's only reachable locally anyway.
+    let admin_local_only =
+        config.auth.is_none() && config.admin_port.is_none() && !config.bind_address.is_loopback();
+    let auth = config.auth.map(Arc::new);
+    let read_only_routes = require_role(auth.clone(), RpcRole::ReadOnly).and(
+        peers_get
+            .or(stats)
+            .or(scan_state_summary_get)
+            .or(snark_pool_jobs_get)
+            .or(snark_pool_job_get)
+            .or(snarker_config)
+            .or(snarker_job_spec)
+            .or(snark_workers)
+            .or(snarkers_reliability)
+            .or(watched_accounts(rpc_sender.clone()))
+            .or(account_proof(rpc_sender.clone()))
+            .or(block_get(rpc_sender.clone()))
+            .or(block_producer(rpc_sender.clone()))
+            .or(super::rosetta::routes(rpc_sender.clone(), &config.chain_id))
+            .or(super::graphql::routes(rpc_sender)),
+    );
+    let admin_routes = require_local(admin_local_only)
+        .and(require_role(auth, RpcRole::Admin))
+        .and(
+            state_get
+                .or(snarker_job_commit)
+                .or(snarker_work_get)
+                .or(snarker_work_submit)
+                .or(watched_accounts_manage(admin_rpc_sender)),
+        );
+
+    let cors = warp::cors()
+        .allow_any_origin()
+        .allow_headers(["authorization", "content-type"])
+        .allow_methods(["GET", "POST", "DELETE"]);
+    let routes = public_routes.or(read_only_routes);
+    let addr = (config.bind_address, config.port);
+    match config.admin_port {
+        None => {
+            let routes = routes
+                .or(admin_routes)
+                .recover(recover_auth_rejection)
+                .with(cors);
+            warp::serve(routes).run(addr).await;
+        }
+        Some(admin_port) => {
+            let routes = routes.recover(recover_auth_rejection).with(cors.clone());
+            let admin_routes = admin_routes.recover(recover_auth_rejection).with(cors);
+            let admin_addr = (config.admin_bind_address, admin_port);
+            tokio::join!(
+                warp::serve(routes).run(addr),
+                warp::serve(admin_routes).run(admin_addr),
+            );
+        }
+    }
+}
+
+/// Tokens accepted by the http server and roles they grant.
+#[derive(Debug, Default, Clone)]
+pub struct RpcAuth {
+    tokens: BTreeMap<String, RpcRole>,
+}
+
+impl RpcAuth {
+    pub fn add_token(&mut self, token: String, role: RpcRole) {
+        self.tokens.insert(token, role);
+    }
+
+    /// Loads tokens from a file with a `<role> <token>` pair per line.
+    ///
+    /// Empty lines and lines starting with `#` are ignored.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        let mut auth = Self::default();
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (role, token) = line
+                .split_once(char::is_whitespace)
+                .map(|(role, token)| (role, token.trim()))
+                .filter(|(_, token)| !token.is_empty())
+                .ok_or_else(|| {
+                    format!("{}:{}: expected `<role> <token>`", path.display(), i + 1)
+                })?;
+            let role = role
+                .parse()
+                .map_err(|err| format!("{}:{}: {err}", path.display(), i + 1))?;
+            auth.add_token(token.to_owned(), role);
+        }
+        Ok(auth)
+    }
+
+    /// Role granted by the token.
+    ///
+    /// Compares against every known token in constant time, so that
+    /// response timing doesn't leak how much of a token matched.
+    fn role(&self, token: &str) -> Option<RpcRole> {
+        self.tokens.iter().fold(None, |found, (known, role)| {
+            if constant_time_eq(known.as_bytes(), token.as_bytes()) {
+                Some(*role)
+            } else {
+                found
+            }
+        })
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[derive(Debug)]
+struct Unauthorized;
+
+impl warp::reject::Reject for Unauthorized {}
+
+#[derive(Debug)]
+struct Forbidden;
+
+impl warp::reject::Reject for Forbidden {}
+
+/// Rejects requests without an `Authorization: Bearer <token>` header
+/// granting at least `role`. Lets everything through if `auth` is `None`.
+fn require_role(
+    auth: Option<Arc<RpcAuth>>,
+    role: RpcRole,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and_then(move |header: Option<String>| {
+            let auth = auth.clone();
+            async move {
+                let Some(auth) = auth else {
+                    return Ok(());
+                };
+                let token_role = header
+                    .as_deref()
+                    .and_then(|header| header.strip_prefix("Bearer "))
+                    .and_then(|token| auth.role(token.trim()));
+                match token_role {
+                    None => Err(warp::reject::custom(Unauthorized)),
+                    Some(token_role) if token_role < role => Err(warp::reject::custom(Forbidden)),
+                    Some(_) => Ok(()),
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Rejects requests from clients outside of the loopback interface, if
+/// `enabled`.
+fn require_local(enabled: bool) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::addr::remote()
+        .and_then(move |addr: Option<SocketAddr>| async move {
+            if !enabled || addr.map_or(false, |addr| addr.ip().is_loopback()) {
+                Ok(())
+            } else {
+                Err(warp::reject::custom(Forbidden))
+            }
+        })
+        .untuple_one()
+}
+
+async fn recover_auth_rejection(rejection: Rejection) -> Result<impl Reply, Rejection> {
+    if rejection.find::<Unauthorized>().is_some() {
+        Ok(with_json_reply(
+            &"missing or invalid auth token",
+            StatusCode::UNAUTHORIZED,
+        ))
+    } else if rejection.find::<Forbidden>().is_some() {
+        Ok(with_json_reply(
+            &"auth token doesn't grant access to this endpoint",
+            StatusCode::FORBIDDEN,
+        ))
+    } else {
+        Err(rejection)
+    }
 }
 
 const DROPPED_CHANNEL: &str = "response channel dropped";
 
+/// Submitted work holds at most two ledger proofs, which take a few tens
+/// of kilobytes even when json encoded.
+const SNARKER_WORK_SUBMIT_MAX_LEN: u64 = 1024 * 1024;
+
 fn healthcheck(
     rpc_sender: super::RpcSender,
 ) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
@@ -434,6 +787,272 @@ fn readiness(
     })
 }
 
+fn watched_accounts(
+    rpc_sender: super::RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let dropped_channel_response =
+        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR);
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let list = warp::path!("watched-accounts")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::WatchedAccountsGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: RpcWatchedAccountsGetResponse| {
+                            with_json_reply(&reply, StatusCode::OK)
+                        },
+                    )
+            }
+        });
+
+    #[derive(Deserialize, Default)]
+    struct BlocksQueryParams {
+        from_height: Option<u32>,
+    }
+
+    let rpc_sender_clone = rpc_sender;
+    let blocks = warp::path!("watched-accounts" / NonZeroCurvePoint / "blocks")
+        .and(warp::get())
+        .and(optq::<BlocksQueryParams>())
+        .then(move |pub_key, query: BlocksQueryParams| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                let query = RpcWatchedAccountBlocksGetQuery {
+                    pub_key,
+                    from_height: query.from_height,
+                };
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::WatchedAccountBlocksGet(query))
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: RpcWatchedAccountBlocksGetResponse| match reply {
+                            None => with_json_reply(&"account not watched", StatusCode::NOT_FOUND),
+                            Some(blocks) => with_json_reply(&blocks, StatusCode::OK),
+                        },
+                    )
+            }
+        });
+
+    list.or(blocks)
+}
+
+fn block_producer(
+    rpc_sender: super::RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let dropped_channel_response =
+        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR);
+    let disabled_response = || with_json_reply(&"block producer disabled", StatusCode::NOT_FOUND);
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let schedule = warp::path!("block-producer" / "schedule")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::BlockProducerScheduleGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: RpcBlockProducerScheduleGetResponse| match reply {
+                            None => disabled_response(),
+                            Some(schedule) => with_json_reply(&schedule, StatusCode::OK),
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let history = warp::path!("block-producer" / "history")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::BlockProducerHistoryGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: RpcBlockProducerHistoryGetResponse| match reply {
+                            None => disabled_response(),
+                            Some(history) => with_json_reply(&history, StatusCode::OK),
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender;
+    let epoch_data = warp::path!("block-producer" / "epoch-data")
+        .and(warp::get())
+        .then(move || {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::BlockProducerEpochDataGet)
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: RpcBlockProducerEpochDataGetResponse| match reply {
+                            None => disabled_response(),
+                            Some(epoch_data) => with_json_reply(&epoch_data, StatusCode::OK),
+                        },
+                    )
+            }
+        });
+
+    schedule.or(history).or(epoch_data)
+}
+
+fn watched_accounts_manage(
+    rpc_sender: super::RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let dropped_channel_response =
+        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR);
+
+    let rpc_sender_clone = rpc_sender.clone();
+    let add = warp::path!("watched-accounts" / NonZeroCurvePoint)
+        .and(warp::post())
+        .then(move |pub_key| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::WatchedAccountsAdd { pub_key })
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: RpcWatchedAccountsAddResponse| {
+                            let status = match &reply {
+                                RpcWatchedAccountsAddResponse::Ok => StatusCode::CREATED,
+                                RpcWatchedAccountsAddResponse::AlreadyWatched => {
+                                    StatusCode::BAD_REQUEST
+                                }
+                            };
+                            with_json_reply(&reply, status)
+                        },
+                    )
+            }
+        });
+
+    let rpc_sender_clone = rpc_sender;
+    let remove = warp::path!("watched-accounts" / NonZeroCurvePoint)
+        .and(warp::delete())
+        .then(move |pub_key| {
+            let rpc_sender_clone = rpc_sender_clone.clone();
+            async move {
+                rpc_sender_clone
+                    .oneshot_request(RpcRequest::WatchedAccountsRemove { pub_key })
+                    .await
+                    .map_or_else(
+                        dropped_channel_response,
+                        |reply: RpcWatchedAccountsRemoveResponse| {
+                            let status = match &reply {
+                                RpcWatchedAccountsRemoveResponse::Ok => StatusCode::OK,
+                                RpcWatchedAccountsRemoveResponse::NotWatched => {
+                                    StatusCode::NOT_FOUND
+                                }
+                            };
+                            with_json_reply(&reply, status)
+                        },
+                    )
+            }
+        });
+
+    add.or(remove)
+}
+
+fn account_proof(
+    rpc_sender: super::RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    #[derive(Deserialize, Default)]
+    struct ProofQueryParams {
+        token: Option<TokenIdKeyHash>,
+        ledger_hash: Option<LedgerHash>,
+    }
+
+    warp::path!("accounts" / NonZeroCurvePoint / "proof")
+        .and(warp::get())
+        .and(optq::<ProofQueryParams>())
+        .then(move |public_key, query: ProofQueryParams| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                rpc_sender
+                    .oneshot_request(RpcRequest::AccountProofGet(RpcAccountProofGetQuery {
+                        public_key,
+                        token: query.token,
+                        ledger_hash: query.ledger_hash,
+                    }))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcAccountProofGetResponse| match reply {
+                            Ok(proof) => with_json_reply(&proof, StatusCode::OK),
+                            Err(err) => {
+                                let status = match &err {
+                                    RpcAccountProofGetError::NoBestTip => {
+                                        StatusCode::SERVICE_UNAVAILABLE
+                                    }
+                                    RpcAccountProofGetError::LedgerNotFound { .. }
+                                    | RpcAccountProofGetError::AccountNotFound { .. } => {
+                                        StatusCode::NOT_FOUND
+                                    }
+                                };
+                                with_json_reply(&err, status)
+                            }
+                        },
+                    )
+            }
+        })
+}
+
+/// Block of our best chain with applied transactions, by height or hash.
+fn block_get(
+    rpc_sender: super::RpcSender,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    warp::path!("blocks" / String)
+        .and(warp::get())
+        .then(move |id: String| {
+            let rpc_sender = rpc_sender.clone();
+            async move {
+                let query = if let Ok(height) = id.parse() {
+                    RpcBlockGetQuery::ForBlockWithHeight(height)
+                } else if let Ok(hash) = id.parse() {
+                    RpcBlockGetQuery::ForBlockWithHash(hash)
+                } else {
+                    return with_json_reply(
+                        &"expected block height or hash",
+                        StatusCode::BAD_REQUEST,
+                    );
+                };
+                rpc_sender
+                    .oneshot_request(RpcRequest::BlockGet(query))
+                    .await
+                    .map_or_else(
+                        || with_json_reply(&DROPPED_CHANNEL, StatusCode::INTERNAL_SERVER_ERROR),
+                        |reply: RpcBlockGetResponse| match reply {
+                            Ok(block) => with_json_reply(&block, StatusCode::OK),
+                            Err(err) => {
+                                let status = match &err {
+                                    RpcBlockGetError::NoBestTip => StatusCode::SERVICE_UNAVAILABLE,
+                                    RpcBlockGetError::BlockNotFound => StatusCode::NOT_FOUND,
+                                    RpcBlockGetError::TransactionsUnavailable { .. } => {
+                                        StatusCode::INTERNAL_SERVER_ERROR
+                                    }
+                                };
+                                with_json_reply(&err, status)
+                            }
+                        },
+                    )
+            }
+        })
+}
+
 use warp::filters::BoxedFilter;
 use warp::reply::{json, Json, WithStatus};
 
@@ -506,3 +1125,117 @@ impl Reply for JsonOrBinary {
 fn with_json_reply<T: Serialize>(reply: &T, status: StatusCode) -> WithStatus<Json> {
     with_status(json(reply), status)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn auth_file(name: &str, content: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("openmina-rpc-auth-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn auth() -> RpcAuth {
+        let mut auth = RpcAuth::default();
+        auth.add_token("reader".to_owned(), RpcRole::ReadOnly);
+        auth.add_token("admin".to_owned(), RpcRole::Admin);
+        auth
+    }
+
+    #[test]
+    fn load_tokens() {
+        let path = auth_file(
+            "tokens",
+            "# comment\n\nread-only reader\n  admin   admin  \n#admin commented\n",
+        );
+        let auth = RpcAuth::load(&path).unwrap();
+        assert_eq!(auth.tokens.len(), 2);
+        assert_eq!(auth.role("reader"), Some(RpcRole::ReadOnly));
+        assert_eq!(auth.role("admin"), Some(RpcRole::Admin));
+        assert_eq!(auth.role("commented"), None);
+    }
+
+    #[test]
+    fn load_rejects_invalid_lines() {
+        let path = auth_file("bad_role", "read-only reader\nroot token\n");
+        let err = RpcAuth::load(&path).unwrap_err();
+        assert!(err.contains(":2: invalid rpc role `root`"), "{err}");
+
+        let path = auth_file("missing_token", "admin\n");
+        let err = RpcAuth::load(&path).unwrap_err();
+        assert!(err.contains(":1: expected `<role> <token>`"), "{err}");
+
+        assert!(RpcAuth::load(&auth_file("empty", ""))
+            .unwrap()
+            .tokens
+            .is_empty());
+        assert!(RpcAuth::load(Path::new("/nonexistent/openmina-tokens")).is_err());
+    }
+
+    #[test]
+    fn role_lookup() {
+        let auth = auth();
+        assert_eq!(auth.role("reader"), Some(RpcRole::ReadOnly));
+        assert_eq!(auth.role("admin"), Some(RpcRole::Admin));
+        assert_eq!(auth.role("admi"), None);
+        assert_eq!(auth.role("admin2"), None);
+        assert_eq!(auth.role(""), None);
+    }
+
+    fn admin_route(
+        auth: Option<RpcAuth>,
+        local_only: bool,
+    ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+        require_local(local_only)
+            .and(require_role(auth.map(Arc::new), RpcRole::Admin))
+            .and(warp::path!("state"))
+            .map(|| "ok")
+            .recover(recover_auth_rejection)
+    }
+
+    async fn status<F>(filter: &F, token: Option<&str>) -> StatusCode
+    where
+        F: Filter + 'static,
+        F::Extract: Reply + Send,
+    {
+        let mut req = warp::test::request().path("/state");
+        if let Some(token) = token {
+            req = req.header("authorization", format!("Bearer {token}"));
+        }
+        req.reply(filter).await.status()
+    }
+
+    #[tokio::test]
+    async fn admin_route_requires_admin_token() {
+        let filter = admin_route(Some(auth()), false);
+        assert_eq!(status(&filter, None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            status(&filter, Some("wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(status(&filter, Some("reader")).await, StatusCode::FORBIDDEN);
+        assert_eq!(status(&filter, Some("admin")).await, StatusCode::OK);
+
+        let filter = admin_route(None, false);
+        assert_eq!(status(&filter, None).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn admin_route_without_auth_is_local_only() {
+        let filter = admin_route(None, true);
+        let reply = |addr: &str| {
+            warp::test::request()
+                .path("/state")
+                .remote_addr(addr.parse().unwrap())
+                .reply(&filter)
+        };
+        assert_eq!(reply("127.0.0.1:5000").await.status(), StatusCode::OK);
+        assert_eq!(reply("[::1]:5000").await.status(), StatusCode::OK);
+        assert_eq!(reply("10.0.0.1:5000").await.status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -1,8 +1,13 @@
+pub mod archive;
 pub mod block_producer;
 pub mod ext_snark_worker;
 pub mod graphql;
 pub mod http_server;
+pub mod remote_signer;
+pub mod rosetta;
 pub mod rpc;
+pub mod snark_pool_persist;
+pub mod snark_verifier;
 pub mod tracing;
 
 mod service;
@@ -0,0 +1,69 @@
+use node::{
+    account::{AccountPublicKey, AccountSigner, AccountSignerError},
+    block_producer::vrf_evaluator::VrfEvaluatorInput,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use vrf::VrfWonSlot;
+
+use super::{http, RemoteSignerAddr, PUBLIC_KEYS_PATH, VRF_EVALUATE_PATH};
+
+/// [`AccountSigner`] which forwards requests to a remote signer.
+#[derive(Debug, Clone)]
+pub struct AccountRemoteSigner {
+    addr: RemoteSignerAddr,
+}
+
+impl AccountRemoteSigner {
+    pub fn new(addr: RemoteSignerAddr) -> Self {
+        Self { addr }
+    }
+
+    fn request<Req: Serialize, Resp: DeserializeOwned>(
+        &self,
+        method: &str,
+        path: &str,
+        req: Option<&Req>,
+    ) -> Result<Resp, AccountSignerError> {
+        let io_err = |err: std::io::Error| AccountSignerError::IOError(err.to_string());
+
+        let body = match req {
+            Some(req) => serde_json::to_vec(req)
+                .map_err(|err| AccountSignerError::Remote(err.to_string()))?,
+            None => Vec::new(),
+        };
+        let mut stream = self.addr.connect().map_err(io_err)?;
+        http::write_message(&mut stream, &format!("{method} {path} HTTP/1.1"), &body)
+            .map_err(io_err)?;
+        let resp = http::read_message(&mut stream).map_err(io_err)?;
+
+        let status = resp
+            .start_line
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse::<u16>().ok())
+            .ok_or_else(|| {
+                AccountSignerError::Remote(format!("invalid status line: {}", resp.start_line))
+            })?;
+        if (200..300).contains(&status) {
+            serde_json::from_slice(&resp.body)
+                .map_err(|err| AccountSignerError::Remote(err.to_string()))
+        } else {
+            Err(serde_json::from_slice(&resp.body).unwrap_or_else(|_| {
+                AccountSignerError::Remote(format!("unexpected response: {}", resp.start_line))
+            }))
+        }
+    }
+}
+
+impl AccountSigner for AccountRemoteSigner {
+    fn public_keys(&self) -> Result<Vec<AccountPublicKey>, AccountSignerError> {
+        self.request::<(), _>("GET", PUBLIC_KEYS_PATH, None)
+    }
+
+    fn evaluate_vrf(
+        &self,
+        input: &VrfEvaluatorInput,
+    ) -> Result<Vec<VrfWonSlot>, AccountSignerError> {
+        self.request("POST", VRF_EVALUATE_PATH, Some(input))
+    }
+}
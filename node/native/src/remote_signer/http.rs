@@ -0,0 +1,69 @@
+//! Just enough of HTTP/1.1 for the signer protocol, where every
+//! message carries a `Content-Length` and the connection is closed
+//! after the response.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+
+/// Maximum size of a message body, large enough for delegator tables of
+/// any realistic producer.
+const MAX_BODY_LEN: usize = 256 * 1024 * 1024;
+
+pub struct Message {
+    /// Request line or status line.
+    pub start_line: String,
+    pub body: Vec<u8>,
+}
+
+pub fn read_message(stream: impl Read) -> io::Result<Message> {
+    let mut reader = BufReader::new(stream);
+    let mut start_line = String::new();
+    reader.read_line(&mut start_line)?;
+
+    let mut content_len = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(invalid_data("unexpected end of headers"));
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            return Err(invalid_data("malformed header"));
+        };
+        if name.eq_ignore_ascii_case("content-length") {
+            content_len = value
+                .trim()
+                .parse()
+                .map_err(|_| invalid_data("invalid content-length"))?;
+        }
+    }
+    if content_len > MAX_BODY_LEN {
+        return Err(invalid_data("body too large"));
+    }
+
+    let mut body = vec![0; content_len];
+    reader.read_exact(&mut body)?;
+    Ok(Message {
+        start_line: start_line.trim_end().to_owned(),
+        body,
+    })
+}
+
+pub fn write_message(mut stream: impl Write, start_line: &str, body: &[u8]) -> io::Result<()> {
+    write!(
+        stream,
+        "{start_line}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,83 @@
+//! Remote signer protocol.
+//!
+//! Lets the node use producer keys held by a separate process, through
+//! [`node::account::AccountSigner`].
+//!
+//! The protocol is plain HTTP/1.1 with JSON bodies, over a Unix socket.
+//! Each request is sent on a new connection (`Connection: close`).
+//!
+//! Requests aren't authenticated, access to the signer is controlled by
+//! permissions of the socket, so it isn't served over TCP. A signer on a
+//! different machine can be reached by forwarding its socket, e.g. with
+//! `ssh -L <local path>:<remote path>`.
+//!
+//! | Request              | Body                | Response             |
+//! |----------------------|---------------------|----------------------|
+//! | `GET /public-keys`   |                     | `[AccountPublicKey]` |
+//! | `POST /vrf/evaluate` | `VrfEvaluatorInput` | `[VrfWonSlot]`       |
+//!
+//! Signing of user commands isn't part of the protocol, as requests
+//! aren't authenticated and anyone able to connect could spend funds of
+//! the accounts.
+//!
+//! Failed requests are answered with a non 2xx status and
+//! [`node::account::AccountSignerError`] as the body.
+
+mod client;
+pub use client::AccountRemoteSigner;
+
+mod server;
+pub use server::serve;
+
+mod http;
+
+use std::{fmt, io, os::unix::net::UnixStream, path::PathBuf, str::FromStr};
+
+pub const PUBLIC_KEYS_PATH: &str = "/public-keys";
+pub const VRF_EVALUATE_PATH: &str = "/vrf/evaluate";
+
+/// Address of the remote signer, `unix:<path>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteSignerAddr(pub PathBuf);
+
+impl RemoteSignerAddr {
+    fn connect(&self) -> io::Result<UnixStream> {
+        UnixStream::connect(&self.0)
+    }
+}
+
+impl FromStr for RemoteSignerAddr {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("unix:") {
+            Some(path) if !path.is_empty() => Ok(Self(path.into())),
+            _ => Err(format!(
+                "invalid remote signer address `{s}`, expected `unix:<path>`"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for RemoteSignerAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unix:{}", self.0.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_addr() {
+        let addr: RemoteSignerAddr = "unix:/run/signer.sock".parse().unwrap();
+        assert_eq!(addr, RemoteSignerAddr("/run/signer.sock".into()));
+        assert_eq!(addr.to_string(), "unix:/run/signer.sock");
+
+        assert!("unix:".parse::<RemoteSignerAddr>().is_err());
+        assert!("/run/signer.sock".parse::<RemoteSignerAddr>().is_err());
+        // not authenticated, so not served over tcp.
+        assert!("http://127.0.0.1:3100".parse::<RemoteSignerAddr>().is_err());
+    }
+}
@@ -0,0 +1,188 @@
+use std::{
+    fs, io,
+    os::unix::{
+        fs::{DirBuilderExt, PermissionsExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::Path,
+    sync::Arc,
+};
+
+use node::account::{AccountSigner, AccountSignerError};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{http, RemoteSignerAddr, PUBLIC_KEYS_PATH, VRF_EVALUATE_PATH};
+
+/// Serves `signer` on `addr` until an error accepting a connection.
+///
+/// The socket is created with permissions only for the owner, so that
+/// other users on the host can't use the keys. An existing file at the
+/// path is replaced.
+pub fn serve(addr: &RemoteSignerAddr, signer: Arc<dyn AccountSigner>) -> io::Result<()> {
+    let listener = bind_private(&addr.0)?;
+    for stream in listener.incoming() {
+        spawn_handler(stream?, signer.clone());
+    }
+    Ok(())
+}
+
+/// Binds the socket inside a new directory accessible only by the owner
+/// and restricts its permissions there, before moving it to `path`, so
+/// that it's never reachable by other users.
+fn bind_private(path: &Path) -> io::Result<UnixListener> {
+    let file_name = path.file_name().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "socket path has no file name")
+    })?;
+    let mut private_dir_name = std::ffi::OsString::from(".");
+    private_dir_name.push(file_name);
+    private_dir_name.push(".bind");
+    let private_dir = path.with_file_name(private_dir_name);
+
+    if private_dir.exists() {
+        fs::remove_dir_all(&private_dir)?;
+    }
+    fs::DirBuilder::new().mode(0o700).create(&private_dir)?;
+    let bind = || -> io::Result<UnixListener> {
+        let tmp_path = private_dir.join("socket");
+        let listener = UnixListener::bind(&tmp_path)?;
+        fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o600))?;
+        fs::rename(&tmp_path, path)?;
+        Ok(listener)
+    };
+    let result = bind();
+    let _ = fs::remove_dir_all(&private_dir);
+    result
+}
+
+fn spawn_handler(mut stream: UnixStream, signer: Arc<dyn AccountSigner>) {
+    std::thread::spawn(move || {
+        let (status, body) = match http::read_message(&mut stream) {
+            Ok(req) => handle(&req.start_line, &req.body, signer.as_ref()),
+            Err(err) => error_response(
+                "400 Bad Request",
+                AccountSignerError::IOError(err.to_string()),
+            ),
+        };
+        let _ = http::write_message(&mut stream, &format!("HTTP/1.1 {status}"), &body);
+    });
+}
+
+fn handle(request_line: &str, body: &[u8], signer: &dyn AccountSigner) -> (&'static str, Vec<u8>) {
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    match (method, path) {
+        ("GET", PUBLIC_KEYS_PATH) => respond(signer.public_keys()),
+        ("POST", VRF_EVALUATE_PATH) => match parse(body) {
+            Ok(input) => respond(signer.evaluate_vrf(&input)),
+            Err(resp) => resp,
+        },
+        _ => error_response(
+            "404 Not Found",
+            AccountSignerError::Remote(format!("unknown request: {method} {path}")),
+        ),
+    }
+}
+
+fn parse<T: DeserializeOwned>(body: &[u8]) -> Result<T, (&'static str, Vec<u8>)> {
+    serde_json::from_slice(body).map_err(|err| {
+        error_response(
+            "400 Bad Request",
+            AccountSignerError::Remote(format!("invalid request body: {err}")),
+        )
+    })
+}
+
+fn respond<T: Serialize>(result: Result<T, AccountSignerError>) -> (&'static str, Vec<u8>) {
+    match result.map(|resp| serde_json::to_vec(&resp)) {
+        Ok(Ok(body)) => ("200 OK", body),
+        Ok(Err(err)) => error_response(
+            "500 Internal Server Error",
+            AccountSignerError::Remote(err.to_string()),
+        ),
+        Err(err @ AccountSignerError::UnknownAccount(_)) => error_response("404 Not Found", err),
+        Err(err) => error_response("500 Internal Server Error", err),
+    }
+}
+
+fn error_response(status: &'static str, err: AccountSignerError) -> (&'static str, Vec<u8>) {
+    (status, serde_json::to_vec(&err).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use node::account::{AccountLocalSigner, AccountPublicKey, AccountSecretKey};
+
+    use crate::remote_signer::AccountRemoteSigner;
+
+    use super::*;
+
+    fn signer() -> AccountLocalSigner {
+        let mut bytes = [0; 32];
+        bytes[31] = 1;
+        AccountLocalSigner::new([AccountSecretKey::from_bytes(&bytes).unwrap()])
+    }
+
+    fn socket_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("openmina-signer-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join(name)
+    }
+
+    #[test]
+    fn public_keys() {
+        let signer = signer();
+        let (status, body) = handle("GET /public-keys HTTP/1.1", &[], &signer);
+        assert_eq!(status, "200 OK");
+        let keys: Vec<AccountPublicKey> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(keys, signer.public_keys().unwrap());
+    }
+
+    #[test]
+    fn user_commands_are_not_signed() {
+        let (status, body) = handle("POST /sign/user-command HTTP/1.1", b"{}", &signer());
+        assert_eq!(status, "404 Not Found");
+        let err: AccountSignerError = serde_json::from_slice(&body).unwrap();
+        assert!(matches!(err, AccountSignerError::Remote(_)));
+    }
+
+    #[test]
+    fn invalid_body() {
+        let (status, _) = handle("POST /vrf/evaluate HTTP/1.1", b"not json", &signer());
+        assert_eq!(status, "400 Bad Request");
+    }
+
+    #[test]
+    fn socket_only_accessible_by_owner() {
+        let path = socket_path("private.sock");
+        fs::write(&path, b"stale").unwrap();
+
+        let _listener = bind_private(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let leftovers = fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().ends_with(".bind"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn remote_signer_over_unix_socket() {
+        let path = socket_path("serve.sock");
+        let addr = RemoteSignerAddr(path.clone());
+        let server_addr = addr.clone();
+        std::thread::spawn(move || serve(&server_addr, Arc::new(signer())));
+        while !path.exists() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        let remote = AccountRemoteSigner::new(addr);
+        assert_eq!(
+            remote.public_keys().unwrap(),
+            signer().public_keys().unwrap()
+        );
+    }
+}
@@ -0,0 +1,664 @@
+//! Rosetta Construction API.
+//!
+//! Only payments and stake delegations of MINA are supported. Transactions
+//! are exchanged as hex encoded binprot: `unsigned_transaction` is the
+//! signed command payload, `signed_transaction` is the signed command.
+//!
+//! Signing payload is the unsigned transaction. It must be signed with the
+//! legacy Schnorr-Poseidon signature and the signature is encoded as
+//! `rx || s`, both as 32 bytes little endian.
+
+use ledger::scan_state::currency;
+use ledger::scan_state::transaction_logic::{signed_command::SignedCommand, verifiable, Memo};
+use mina_p2p_messages::bigint::BigInt;
+use mina_p2p_messages::binprot::{BinProtRead, BinProtWrite};
+use mina_p2p_messages::v2::{
+    MinaBasePaymentPayloadStableV2, MinaBaseSignatureStableV1, MinaBaseSignedCommandMemoStableV1,
+    MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseSignedCommandPayloadCommonStableV2,
+    MinaBaseSignedCommandPayloadStableV2, MinaBaseSignedCommandStableV2,
+    MinaBaseStakeDelegationStableV2, NonZeroCurvePoint, NonZeroCurvePointUncompressedStableV1,
+};
+use node::rpc::{RpcAccountProofGetQuery, RpcAccountProofGetResponse, RpcRequest};
+use o1_utils::FieldHelpers;
+
+use super::operations::{
+    self, account_identifier, amount, default_token, signed_command_operations, OP_DELEGATE_CHANGE,
+    OP_FEE_PAYMENT, OP_PAYMENT_RECEIVER_INC, OP_PAYMENT_SOURCE_DEC,
+};
+use super::types::{
+    ConstructionCombineRequest, ConstructionCombineResponse, ConstructionDeriveRequest,
+    ConstructionDeriveResponse, ConstructionHashRequest, ConstructionMetadata,
+    ConstructionMetadataRequest, ConstructionMetadataResponse, ConstructionOptions,
+    ConstructionParseRequest, ConstructionParseResponse, ConstructionPayloadsRequest,
+    ConstructionPayloadsResponse, ConstructionPreprocessRequest, ConstructionPreprocessResponse,
+    Operation, SigningPayload, TransactionIdentifier, TransactionIdentifierResponse,
+    TransactionMetadata,
+};
+use super::{account_proof_error, parse_address, request, RosettaError};
+
+const CURVE_TYPE: &str = "pallas";
+const SIGNATURE_TYPE: &str = "schnorr_poseidon";
+/// Fee suggested by `/construction/metadata`, 0.1 MINA.
+const SUGGESTED_FEE: u64 = 100_000_000;
+const MEMO_MAX_LENGTH: usize = 32;
+
+/// What the operations ask for, before nonce and metadata are known.
+struct Intent {
+    sender: NonZeroCurvePoint,
+    fee: u64,
+    body: MinaBaseSignedCommandPayloadBodyStableV2,
+}
+
+pub(super) fn derive(
+    req: ConstructionDeriveRequest,
+) -> Result<ConstructionDeriveResponse, RosettaError> {
+    if req.public_key.curve_type != CURVE_TYPE {
+        return Err(RosettaError::InvalidRequest(format!(
+            "unsupported curve type: {}",
+            req.public_key.curve_type
+        )));
+    }
+    let public_key = public_key_from_hex(&req.public_key.hex_bytes)?;
+    Ok(ConstructionDeriveResponse {
+        account_identifier: account_identifier(&public_key, &default_token()),
+    })
+}
+
+pub(super) fn preprocess(
+    req: ConstructionPreprocessRequest,
+) -> Result<ConstructionPreprocessResponse, RosettaError> {
+    let intent = intent(&req.operations)?;
+    let transaction = req.metadata.unwrap_or_default();
+    memo(&transaction)?;
+    valid_until(&transaction)?;
+
+    Ok(ConstructionPreprocessResponse {
+        options: ConstructionOptions {
+            sender: intent.sender.to_string(),
+            transaction,
+        },
+        required_public_keys: vec![account_identifier(&intent.sender, &default_token())],
+    })
+}
+
+pub(super) async fn metadata(
+    rpc_sender: &crate::RpcSender,
+    req: ConstructionMetadataRequest,
+) -> Result<ConstructionMetadataResponse, RosettaError> {
+    let public_key = parse_address(&req.options.sender)?;
+    let resp: RpcAccountProofGetResponse = request(
+        rpc_sender,
+        RpcRequest::AccountProofGet(RpcAccountProofGetQuery {
+            public_key,
+            token: None,
+            ledger_hash: None,
+        }),
+    )
+    .await?;
+    let account = resp.map_err(account_proof_error)?.account;
+
+    Ok(ConstructionMetadataResponse {
+        metadata: ConstructionMetadata {
+            sender: req.options.sender,
+            nonce: account.nonce.0.as_u32().to_string(),
+            transaction: req.options.transaction,
+        },
+        suggested_fee: vec![amount(SUGGESTED_FEE as i128, &default_token())],
+    })
+}
+
+pub(super) fn payloads(
+    req: ConstructionPayloadsRequest,
+) -> Result<ConstructionPayloadsResponse, RosettaError> {
+    let intent = intent(&req.operations)?;
+    if intent.sender.to_string() != req.metadata.sender {
+        return Err(RosettaError::InvalidRequest(
+            "operations sender doesn't match the metadata".to_owned(),
+        ));
+    }
+    let nonce = req
+        .metadata
+        .nonce
+        .parse()
+        .map_err(|_| RosettaError::InvalidRequest("invalid nonce".to_owned()))?;
+
+    let payload = MinaBaseSignedCommandPayloadStableV2 {
+        common: MinaBaseSignedCommandPayloadCommonStableV2 {
+            fee: (&currency::Fee::from_u64(intent.fee)).into(),
+            fee_payer_pk: intent.sender.clone(),
+            nonce: (&currency::Nonce::from_u32(nonce)).into(),
+            valid_until: (&valid_until(&req.metadata.transaction)?).into(),
+            memo: (&memo(&req.metadata.transaction)?).into(),
+        },
+        body: intent.body,
+    };
+    let unsigned_transaction = to_hex(&payload)?;
+
+    Ok(ConstructionPayloadsResponse {
+        payloads: vec![SigningPayload {
+            account_identifier: account_identifier(&intent.sender, &default_token()),
+            hex_bytes: unsigned_transaction.clone(),
+            signature_type: SIGNATURE_TYPE.to_owned(),
+        }],
+        unsigned_transaction,
+    })
+}
+
+pub(super) fn combine(
+    req: ConstructionCombineRequest,
+) -> Result<ConstructionCombineResponse, RosettaError> {
+    let payload: MinaBaseSignedCommandPayloadStableV2 = from_hex(&req.unsigned_transaction)?;
+    validate_payload(&payload)?;
+
+    let [signature] = req.signatures.as_slice() else {
+        return Err(RosettaError::InvalidRequest(
+            "expected exactly one signature".to_owned(),
+        ));
+    };
+    if signature.signature_type != SIGNATURE_TYPE {
+        return Err(RosettaError::InvalidRequest(format!(
+            "unsupported signature type: {}",
+            signature.signature_type
+        )));
+    }
+    if public_key_from_hex(&signature.public_key.hex_bytes)? != payload.common.fee_payer_pk {
+        return Err(RosettaError::InvalidSignature);
+    }
+    let bytes = hex::decode(&signature.hex_bytes).map_err(|_| RosettaError::InvalidSignature)?;
+    if bytes.len() != 64 {
+        return Err(RosettaError::InvalidSignature);
+    }
+    let rx = <[u8; 32]>::try_from(&bytes[..32]).unwrap();
+    let rx = BigInt::from(Box::new(rx))
+        .to_fp()
+        .map_err(|_| RosettaError::InvalidSignature)?;
+    let s = mina_signer::ScalarField::from_bytes(&bytes[32..])
+        .map_err(|_| RosettaError::InvalidSignature)?;
+
+    let signed = MinaBaseSignedCommandStableV2 {
+        signer: payload.common.fee_payer_pk.clone(),
+        payload,
+        signature: MinaBaseSignatureStableV1::from(&mina_signer::Signature { rx, s }).into(),
+    };
+    let cmd = Box::new(SignedCommand::from(&signed));
+    if verifiable::check_only_for_signature(cmd).is_err() {
+        return Err(RosettaError::InvalidSignature);
+    }
+
+    Ok(ConstructionCombineResponse {
+        signed_transaction: to_hex(&signed)?,
+    })
+}
+
+pub(super) fn parse(
+    req: ConstructionParseRequest,
+) -> Result<ConstructionParseResponse, RosettaError> {
+    let (payload, signers) = if req.signed {
+        let signed: MinaBaseSignedCommandStableV2 = from_hex(&req.transaction)?;
+        let signer = account_identifier(&signed.signer, &default_token());
+        (signed.payload, vec![signer])
+    } else {
+        (from_hex(&req.transaction)?, vec![])
+    };
+    validate_payload(&payload)?;
+
+    let valid_until: currency::Slot = (&payload.common.valid_until).into();
+    let metadata = TransactionMetadata {
+        memo: memo_text(&payload.common.memo),
+        valid_until: (valid_until != currency::Slot::max())
+            .then(|| valid_until.as_u32().to_string()),
+    };
+
+    Ok(ConstructionParseResponse {
+        operations: signed_command_operations(&payload, None),
+        account_identifier_signers: signers,
+        metadata: Some(metadata),
+    })
+}
+
+pub(super) fn hash(
+    req: ConstructionHashRequest,
+) -> Result<TransactionIdentifierResponse, RosettaError> {
+    let signed: MinaBaseSignedCommandStableV2 = from_hex(&req.signed_transaction)?;
+    let hash = signed
+        .hash()
+        .map_err(|err| RosettaError::Internal(err.to_string()))?;
+
+    Ok(TransactionIdentifierResponse {
+        transaction_identifier: TransactionIdentifier {
+            hash: hash.to_string(),
+        },
+    })
+}
+
+/// Public key is encoded as 32 bytes big endian `x` coordinate, with the
+/// most significant bit set if `y` is odd.
+fn public_key_from_hex(hex_bytes: &str) -> Result<NonZeroCurvePoint, RosettaError> {
+    let invalid = || RosettaError::InvalidRequest("invalid public key".to_owned());
+    let mut bytes: [u8; 32] = hex::decode(hex_bytes)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(invalid)?;
+    bytes.reverse();
+    let is_odd = bytes[31] & 0x80 != 0;
+    bytes[31] &= 0x7f;
+
+    let public_key: NonZeroCurvePoint = NonZeroCurvePointUncompressedStableV1 {
+        x: BigInt::from(Box::new(bytes)),
+        is_odd,
+    }
+    .into();
+    validate_public_key(&public_key)?;
+    Ok(public_key)
+}
+
+/// Checks that the key is a point on the curve, as conversions in the
+/// `ledger` crate assume that.
+pub(super) fn validate_public_key(public_key: &NonZeroCurvePoint) -> Result<(), RosettaError> {
+    public_key
+        .x
+        .to_fp()
+        .ok()
+        .and_then(|x| mina_signer::CurvePoint::get_point_from_x(x, public_key.is_odd))
+        .map(|_| ())
+        .ok_or_else(|| RosettaError::InvalidRequest("invalid public key".to_owned()))
+}
+
+fn validate_payload(payload: &MinaBaseSignedCommandPayloadStableV2) -> Result<(), RosettaError> {
+    if payload.common.memo.0.as_ref().len() != std::mem::size_of::<Memo>() {
+        return Err(RosettaError::InvalidRequest("invalid memo".to_owned()));
+    }
+    validate_public_key(&payload.common.fee_payer_pk)?;
+    match &payload.body {
+        MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => {
+            validate_public_key(&payment.receiver_pk)
+        }
+        MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+            MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+        ) => validate_public_key(new_delegate),
+    }
+}
+
+/// Operations must be in the same order as `/construction/parse` returns
+/// them.
+fn intent(operations: &[Operation]) -> Result<Intent, RosettaError> {
+    let kinds = operations
+        .iter()
+        .map(|op| op.kind.as_str())
+        .collect::<Vec<_>>();
+    let sender = match operations.first() {
+        Some(op) if op.kind == OP_FEE_PAYMENT => operation_account(op)?,
+        _ => {
+            return Err(RosettaError::InvalidRequest(format!(
+                "first operation must be `{OP_FEE_PAYMENT}`"
+            )))
+        }
+    };
+    let fee = to_u64(-operation_value(&operations[0])?)?;
+    let same_sender = |op: &Operation| match operation_account(op)? == sender {
+        true => Ok(()),
+        false => Err(RosettaError::InvalidRequest(
+            "fee payer and the source account must be the same".to_owned(),
+        )),
+    };
+
+    let body = match kinds.as_slice() {
+        [OP_FEE_PAYMENT, OP_PAYMENT_SOURCE_DEC, OP_PAYMENT_RECEIVER_INC] => {
+            let (source, receiver) = (&operations[1], &operations[2]);
+            same_sender(source)?;
+            let value = operation_value(receiver)?;
+            if operation_value(source)? != -value {
+                return Err(RosettaError::InvalidRequest(
+                    "payment source and receiver amounts don't match".to_owned(),
+                ));
+            }
+            MinaBaseSignedCommandPayloadBodyStableV2::Payment(MinaBasePaymentPayloadStableV2 {
+                receiver_pk: operation_account(receiver)?,
+                amount: currency::Amount::from_u64(to_u64(value)?).into(),
+            })
+        }
+        [OP_FEE_PAYMENT, OP_DELEGATE_CHANGE] => {
+            let delegation = &operations[1];
+            same_sender(delegation)?;
+            let new_delegate = delegation
+                .metadata
+                .as_ref()
+                .and_then(|v| v.get("delegate_change_target")?.as_str())
+                .ok_or_else(|| {
+                    RosettaError::InvalidRequest(
+                        "`delegate_change_target` metadata is missing".to_owned(),
+                    )
+                })?;
+            MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+                MinaBaseStakeDelegationStableV2::SetDelegate {
+                    new_delegate: parse_address(new_delegate)?,
+                },
+            )
+        }
+        _ => {
+            return Err(RosettaError::InvalidRequest(format!(
+                "unsupported operations: {kinds:?}"
+            )))
+        }
+    };
+
+    Ok(Intent { sender, fee, body })
+}
+
+fn operation_account(op: &Operation) -> Result<NonZeroCurvePoint, RosettaError> {
+    let account = op.account.as_ref().ok_or_else(|| {
+        RosettaError::InvalidRequest(format!(
+            "operation {} is missing account",
+            op.operation_identifier.index
+        ))
+    })?;
+    if account.metadata.is_some() {
+        return Err(RosettaError::InvalidRequest(
+            "only the default token is supported".to_owned(),
+        ));
+    }
+    parse_address(&account.address)
+}
+
+fn operation_value(op: &Operation) -> Result<i128, RosettaError> {
+    let amount = op.amount.as_ref().ok_or_else(|| {
+        RosettaError::InvalidRequest(format!(
+            "operation {} is missing amount",
+            op.operation_identifier.index
+        ))
+    })?;
+    if amount.currency != operations::currency(&default_token()) {
+        return Err(RosettaError::InvalidRequest(
+            "only MINA transfers are supported".to_owned(),
+        ));
+    }
+    amount
+        .value
+        .parse()
+        .map_err(|_| RosettaError::InvalidRequest(format!("invalid amount: {}", amount.value)))
+}
+
+fn to_u64(value: i128) -> Result<u64, RosettaError> {
+    u64::try_from(value)
+        .map_err(|_| RosettaError::InvalidRequest(format!("amount out of range: {value}")))
+}
+
+fn memo(transaction: &TransactionMetadata) -> Result<Memo, RosettaError> {
+    let Some(text) = &transaction.memo else {
+        return Ok(Memo::empty());
+    };
+    let bytes = text.as_bytes();
+    if bytes.len() > MEMO_MAX_LENGTH {
+        return Err(RosettaError::InvalidRequest(format!(
+            "memo must be at most {MEMO_MAX_LENGTH} bytes"
+        )));
+    }
+    // Same layout as `Memo::empty`: bytes tag, length, then the bytes.
+    let mut memo = Memo::empty();
+    memo.0[1] = bytes.len() as u8;
+    memo.0[2..2 + bytes.len()].copy_from_slice(bytes);
+    Ok(memo)
+}
+
+fn memo_text(memo: &MinaBaseSignedCommandMemoStableV1) -> Option<String> {
+    match memo.0.as_ref() {
+        [1, len, bytes @ ..] if *len > 0 && (*len as usize) <= bytes.len() => {
+            String::from_utf8(bytes[..*len as usize].to_vec()).ok()
+        }
+        _ => None,
+    }
+}
+
+fn valid_until(transaction: &TransactionMetadata) -> Result<currency::Slot, RosettaError> {
+    match &transaction.valid_until {
+        None => Ok(currency::Slot::max()),
+        Some(slot) => slot
+            .parse()
+            .map(currency::Slot::from_u32)
+            .map_err(|_| RosettaError::InvalidRequest(format!("invalid valid_until: {slot}"))),
+    }
+}
+
+fn from_hex<T: BinProtRead>(hex_bytes: &str) -> Result<T, RosettaError> {
+    let bytes = hex::decode(hex_bytes)
+        .map_err(|_| RosettaError::InvalidRequest("transaction must be hex".to_owned()))?;
+    T::binprot_read(&mut bytes.as_slice())
+        .map_err(|err| RosettaError::InvalidRequest(format!("invalid transaction: {err:?}")))
+}
+
+fn to_hex<T: BinProtWrite>(value: &T) -> Result<String, RosettaError> {
+    let mut bytes = vec![];
+    value
+        .binprot_write(&mut bytes)
+        .map_err(|err| RosettaError::Internal(err.to_string()))?;
+    Ok(hex::encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::scan_state::transaction_logic::{
+        signed_command::SignedCommandPayload, transaction_union_payload::TransactionUnionPayload,
+    };
+    use mina_signer::{CompressedPubKey, Keypair, Signer};
+    use node::account::AccountSecretKey;
+
+    use super::super::types::{NetworkIdentifier, OperationIdentifier, PublicKey, Signature};
+    use super::*;
+
+    fn keypair(n: u8) -> Keypair {
+        let mut bytes = [0; 32];
+        bytes[31] = n;
+        AccountSecretKey::from_bytes(&bytes).unwrap().into()
+    }
+
+    fn address(keypair: &Keypair) -> NonZeroCurvePoint {
+        node::account::AccountPublicKey::from(keypair.public.clone()).into()
+    }
+
+    fn public_key_hex(keypair: &Keypair) -> String {
+        let CompressedPubKey { x, is_odd } = keypair.public.into_compressed();
+        let mut bytes = x.to_bytes();
+        if is_odd {
+            bytes[31] |= 0x80;
+        }
+        bytes.reverse();
+        hex::encode(bytes)
+    }
+
+    fn network() -> NetworkIdentifier {
+        super::super::network_identifier("0123abcd")
+    }
+
+    fn op(index: u32, kind: &str, account: &NonZeroCurvePoint, value: i128) -> Operation {
+        Operation {
+            operation_identifier: OperationIdentifier { index },
+            related_operations: vec![],
+            kind: kind.to_owned(),
+            status: None,
+            account: Some(account_identifier(account, &default_token())),
+            amount: Some(amount(value, &default_token())),
+            metadata: None,
+        }
+    }
+
+    fn payment(sender: &NonZeroCurvePoint, receiver: &NonZeroCurvePoint) -> Vec<Operation> {
+        let mut receiver_inc = op(2, OP_PAYMENT_RECEIVER_INC, receiver, 5_000);
+        receiver_inc.related_operations = vec![OperationIdentifier { index: 1 }];
+        vec![
+            op(0, OP_FEE_PAYMENT, sender, -100),
+            op(1, OP_PAYMENT_SOURCE_DEC, sender, -5_000),
+            receiver_inc,
+        ]
+    }
+
+    fn assert_same_operations(actual: &[Operation], expected: &[Operation]) {
+        let key = |op: &Operation| {
+            (
+                op.operation_identifier,
+                op.related_operations.clone(),
+                op.kind.clone(),
+                op.status.clone(),
+                op.account.clone(),
+                op.amount.as_ref().map(|v| v.value.clone()),
+            )
+        };
+        let actual = actual.iter().map(key).collect::<Vec<_>>();
+        let expected = expected.iter().map(key).collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn derive_public_key() {
+        let keypair = keypair(1);
+        let resp = derive(ConstructionDeriveRequest {
+            network_identifier: network(),
+            public_key: PublicKey {
+                hex_bytes: public_key_hex(&keypair),
+                curve_type: CURVE_TYPE.to_owned(),
+            },
+        })
+        .unwrap();
+        assert_eq!(
+            resp.account_identifier.address,
+            address(&keypair).to_string()
+        );
+        assert!(resp.account_identifier.metadata.is_none());
+    }
+
+    /// Same flow as `rosetta-cli check:construction`, up to the submission.
+    #[test]
+    fn payment_construction_flow() {
+        let (sender_key, receiver) = (keypair(1), address(&keypair(2)));
+        let sender = address(&sender_key);
+        let operations = payment(&sender, &receiver);
+        let transaction = TransactionMetadata {
+            memo: Some("hello".to_owned()),
+            valid_until: Some("1000".to_owned()),
+        };
+
+        let preprocess = preprocess(ConstructionPreprocessRequest {
+            network_identifier: network(),
+            operations: operations.clone(),
+            metadata: Some(transaction.clone()),
+        })
+        .unwrap();
+        assert_eq!(preprocess.options.sender, sender.to_string());
+
+        let payloads = payloads(ConstructionPayloadsRequest {
+            network_identifier: network(),
+            operations: operations.clone(),
+            metadata: ConstructionMetadata {
+                sender: preprocess.options.sender,
+                nonce: "3".to_owned(),
+                transaction: preprocess.options.transaction,
+            },
+        })
+        .unwrap();
+        let [signing_payload] = payloads.payloads.as_slice() else {
+            panic!("expected exactly one signing payload");
+        };
+        assert_eq!(signing_payload.hex_bytes, payloads.unsigned_transaction);
+
+        let unsigned = parse(ConstructionParseRequest {
+            network_identifier: network(),
+            signed: false,
+            transaction: payloads.unsigned_transaction.clone(),
+        })
+        .unwrap();
+        assert_same_operations(&unsigned.operations, &operations);
+        assert!(unsigned.account_identifier_signers.is_empty());
+        let metadata = unsigned.metadata.unwrap();
+        assert_eq!(metadata.memo, transaction.memo);
+        assert_eq!(metadata.valid_until, transaction.valid_until);
+
+        let payload: MinaBaseSignedCommandPayloadStableV2 =
+            from_hex(&payloads.unsigned_transaction).unwrap();
+        let payload = SignedCommandPayload::from(&payload);
+        let signature = mina_signer::create_legacy(mina_signer::NetworkId::TESTNET).sign(
+            &sender_key,
+            &TransactionUnionPayload::of_user_command_payload(&payload),
+        );
+        let signature = Signature {
+            signing_payload: signing_payload.clone(),
+            public_key: PublicKey {
+                hex_bytes: public_key_hex(&sender_key),
+                curve_type: CURVE_TYPE.to_owned(),
+            },
+            signature_type: SIGNATURE_TYPE.to_owned(),
+            hex_bytes: hex::encode([signature.rx.to_bytes(), signature.s.to_bytes()].concat()),
+        };
+        let combined = combine(ConstructionCombineRequest {
+            network_identifier: network(),
+            unsigned_transaction: payloads.unsigned_transaction.clone(),
+            signatures: vec![signature.clone()],
+        })
+        .unwrap();
+
+        let signed = parse(ConstructionParseRequest {
+            network_identifier: network(),
+            signed: true,
+            transaction: combined.signed_transaction.clone(),
+        })
+        .unwrap();
+        assert_same_operations(&signed.operations, &operations);
+        assert_eq!(
+            signed.account_identifier_signers,
+            vec![account_identifier(&sender, &default_token())]
+        );
+
+        let hash = hash(ConstructionHashRequest {
+            network_identifier: network(),
+            signed_transaction: combined.signed_transaction,
+        })
+        .unwrap();
+        assert!(!hash.transaction_identifier.hash.is_empty());
+
+        // Signature by another key must be rejected.
+        let mut other = signature;
+        other.public_key.hex_bytes = public_key_hex(&keypair(2));
+        let err = combine(ConstructionCombineRequest {
+            network_identifier: network(),
+            unsigned_transaction: payloads.unsigned_transaction,
+            signatures: vec![other],
+        });
+        assert!(matches!(err, Err(RosettaError::InvalidSignature)));
+    }
+
+    #[test]
+    fn intent_rejects_mismatched_operations() {
+        let (sender, receiver) = (address(&keypair(1)), address(&keypair(2)));
+
+        let mut operations = payment(&sender, &receiver);
+        operations[2].amount = Some(amount(4_000, &default_token()));
+        assert!(intent(&operations).is_err());
+
+        let mut operations = payment(&sender, &receiver);
+        operations[1].account = Some(account_identifier(&receiver, &default_token()));
+        assert!(intent(&operations).is_err());
+
+        let mut operations = payment(&sender, &receiver);
+        operations.swap(0, 1);
+        assert!(intent(&operations).is_err());
+
+        let intent = intent(&payment(&sender, &receiver)).unwrap();
+        assert_eq!(intent.sender, sender);
+        assert_eq!(intent.fee, 100);
+    }
+
+    #[test]
+    fn memo_round_trip() {
+        let transaction = TransactionMetadata {
+            memo: Some("a".repeat(MEMO_MAX_LENGTH)),
+            valid_until: None,
+        };
+        let memo = memo(&transaction).unwrap();
+        assert_eq!(memo_text(&(&memo).into()), transaction.memo);
+        assert_eq!(memo_text(&(&Memo::empty()).into()), None);
+
+        let too_long = TransactionMetadata {
+            memo: Some("a".repeat(MEMO_MAX_LENGTH + 1)),
+            valid_until: None,
+        };
+        assert!(super::memo(&too_long).is_err());
+    }
+}
@@ -0,0 +1,584 @@
+//! [Rosetta](https://www.rosetta-api.org/) Data and Construction API served
+//! by the node, so that exchanges can integrate without running a separate
+//! Rosetta service.
+//!
+//! Blocks and balances are only available for blocks in our best chain,
+//! which is between the transition frontier's root and the best tip.
+//!
+//! The node has no transaction pool, so `/mempool` is always empty and
+//! `/construction/submit` isn't served. Transactions built with the
+//! Construction API must be submitted through another node.
+
+mod construction;
+mod operations;
+pub mod types;
+
+use mina_p2p_messages::v2::{NonZeroCurvePoint, TokenIdKeyHash};
+use node::rpc::{
+    PeerConnectionStatus, RpcAccountProofGetError, RpcAccountProofGetQuery,
+    RpcAccountProofGetResponse, RpcBlockGetError, RpcBlockGetQuery, RpcBlockGetResponse,
+    RpcPeersGetResponse, RpcRequest, RpcTransitionFrontierBlock,
+    RpcTransitionFrontierStatusGetResponse,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use warp::{
+    filters::path::FullPath,
+    hyper::StatusCode,
+    reply::{json, with_status},
+    Filter, Rejection, Reply,
+};
+
+use self::operations::{amount, OPERATION_TYPES, STATUS_FAILED, STATUS_SUCCESS};
+use self::types::{
+    AccountBalanceMetadata, AccountBalanceRequest, AccountBalanceResponse, Allow, Block,
+    BlockIdentifier, BlockRequest, BlockResponse, MempoolResponse, MempoolTransactionRequest,
+    NetworkIdentifier, NetworkListResponse, NetworkOptionsResponse, NetworkRequest,
+    NetworkStatusResponse, OperationStatus, PartialBlockIdentifier, Peer, SyncStatus, Version,
+};
+
+pub const ROSETTA_VERSION: &str = "1.4.13";
+pub const BLOCKCHAIN: &str = "mina";
+/// Names of the networks with a well known chain id. Other networks are
+/// identified by their chain id.
+const NETWORK_NAMES: [(&str, &str); 1] = [(
+    "fd7d111973bf5a9e3e87384f560fdead2f272589ca00b6d9e357fca9839631da",
+    "berkeley",
+)];
+/// Height of the genesis block, Mina starts counting from 1.
+const GENESIS_BLOCK_HEIGHT: u32 = 1;
+
+#[derive(Debug, Clone)]
+pub enum RosettaError {
+    NetworkNotSupported,
+    NodeNotReady,
+    BlockNotFound,
+    AccountNotFound,
+    InvalidRequest(String),
+    InvalidSignature,
+    TransactionNotFound,
+    Internal(String),
+}
+
+impl RosettaError {
+    /// Errors (without details) the API may return, for `/network/options`.
+    fn all() -> Vec<Self> {
+        vec![
+            Self::NetworkNotSupported,
+            Self::NodeNotReady,
+            Self::BlockNotFound,
+            Self::AccountNotFound,
+            Self::InvalidRequest(String::new()),
+            Self::InvalidSignature,
+            Self::TransactionNotFound,
+            Self::Internal(String::new()),
+        ]
+    }
+
+    fn code(&self) -> u32 {
+        match self {
+            Self::NetworkNotSupported => 1,
+            Self::NodeNotReady => 2,
+            Self::BlockNotFound => 3,
+            Self::AccountNotFound => 4,
+            Self::InvalidRequest(_) => 5,
+            Self::InvalidSignature => 6,
+            Self::Internal(_) => 7,
+            Self::TransactionNotFound => 8,
+        }
+    }
+
+    fn message(&self) -> &'static str {
+        match self {
+            Self::NetworkNotSupported => "Network not supported",
+            Self::NodeNotReady => "Node is not ready",
+            Self::BlockNotFound => "Block not found",
+            Self::AccountNotFound => "Account not found",
+            Self::InvalidRequest(_) => "Invalid request",
+            Self::InvalidSignature => "Invalid signature",
+            Self::TransactionNotFound => "Transaction not found",
+            Self::Internal(_) => "Internal error",
+        }
+    }
+
+    fn to_error(&self) -> types::Error {
+        let details = match self {
+            Self::InvalidRequest(v) | Self::Internal(v) if !v.is_empty() => Some(v.as_str()),
+            _ => None,
+        };
+        types::Error {
+            code: self.code(),
+            message: self.message().to_owned(),
+            retriable: matches!(self, Self::NodeNotReady | Self::BlockNotFound),
+            details: details.map(|error| serde_json::json!({ "error": error })),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Endpoint {
+    NetworkList,
+    NetworkStatus,
+    NetworkOptions,
+    Block,
+    AccountBalance,
+    Mempool,
+    MempoolTransaction,
+    ConstructionDerive,
+    ConstructionPreprocess,
+    ConstructionMetadata,
+    ConstructionPayloads,
+    ConstructionCombine,
+    ConstructionParse,
+    ConstructionHash,
+}
+
+impl Endpoint {
+    fn from_path(path: &str) -> Option<Self> {
+        Some(match path {
+            "/network/list" => Self::NetworkList,
+            "/network/status" => Self::NetworkStatus,
+            "/network/options" => Self::NetworkOptions,
+            "/block" => Self::Block,
+            "/account/balance" => Self::AccountBalance,
+            "/mempool" => Self::Mempool,
+            "/mempool/transaction" => Self::MempoolTransaction,
+            "/construction/derive" => Self::ConstructionDerive,
+            "/construction/preprocess" => Self::ConstructionPreprocess,
+            "/construction/metadata" => Self::ConstructionMetadata,
+            "/construction/payloads" => Self::ConstructionPayloads,
+            "/construction/combine" => Self::ConstructionCombine,
+            "/construction/parse" => Self::ConstructionParse,
+            "/construction/hash" => Self::ConstructionHash,
+            _ => return None,
+        })
+    }
+}
+
+pub fn routes(
+    rpc_sender: super::RpcSender,
+    chain_id: &str,
+) -> impl Filter<Error = Rejection, Extract = impl Reply> + Clone {
+    let network = network_identifier(chain_id);
+    warp::post()
+        .and(warp::path::full())
+        .and_then(|path: FullPath| async move {
+            Endpoint::from_path(path.as_str()).ok_or_else(warp::reject::not_found)
+        })
+        .and(warp::body::json())
+        .then(move |endpoint: Endpoint, body: serde_json::Value| {
+            let rpc_sender = rpc_sender.clone();
+            let network = network.clone();
+            async move {
+                match handle(&rpc_sender, &network, endpoint, body).await {
+                    Ok(reply) => with_status(json(&reply), StatusCode::OK),
+                    Err(err) => {
+                        with_status(json(&err.to_error()), StatusCode::INTERNAL_SERVER_ERROR)
+                    }
+                }
+            }
+        })
+}
+
+async fn handle(
+    rpc_sender: &super::RpcSender,
+    network: &NetworkIdentifier,
+    endpoint: Endpoint,
+    body: serde_json::Value,
+) -> Result<serde_json::Value, RosettaError> {
+    if !matches!(endpoint, Endpoint::NetworkList) {
+        check_network(network, &body)?;
+    }
+    match endpoint {
+        Endpoint::NetworkList => reply(NetworkListResponse {
+            network_identifiers: vec![network.clone()],
+        }),
+        Endpoint::NetworkStatus => reply(network_status(rpc_sender).await?),
+        Endpoint::NetworkOptions => reply(network_options()),
+        Endpoint::Block => reply(block(rpc_sender, parse(body)?).await?),
+        Endpoint::AccountBalance => reply(account_balance(rpc_sender, parse(body)?).await?),
+        Endpoint::Mempool => reply(MempoolResponse {
+            transaction_identifiers: vec![],
+        }),
+        Endpoint::MempoolTransaction => {
+            let _: MempoolTransactionRequest = parse(body)?;
+            Err(RosettaError::TransactionNotFound)
+        }
+        Endpoint::ConstructionDerive => reply(construction::derive(parse(body)?)?),
+        Endpoint::ConstructionPreprocess => reply(construction::preprocess(parse(body)?)?),
+        Endpoint::ConstructionMetadata => {
+            reply(construction::metadata(rpc_sender, parse(body)?).await?)
+        }
+        Endpoint::ConstructionPayloads => reply(construction::payloads(parse(body)?)?),
+        Endpoint::ConstructionCombine => reply(construction::combine(parse(body)?)?),
+        Endpoint::ConstructionParse => reply(construction::parse(parse(body)?)?),
+        Endpoint::ConstructionHash => reply(construction::hash(parse(body)?)?),
+    }
+}
+
+fn parse<T: DeserializeOwned>(body: serde_json::Value) -> Result<T, RosettaError> {
+    serde_json::from_value(body).map_err(|err| RosettaError::InvalidRequest(err.to_string()))
+}
+
+fn reply<T: Serialize>(value: T) -> Result<serde_json::Value, RosettaError> {
+    serde_json::to_value(value).map_err(|err| RosettaError::Internal(err.to_string()))
+}
+
+async fn request<T>(rpc_sender: &super::RpcSender, req: RpcRequest) -> Result<T, RosettaError>
+where
+    T: 'static + Send + Serialize,
+{
+    rpc_sender
+        .oneshot_request(req)
+        .await
+        .ok_or_else(|| RosettaError::Internal("response channel dropped".to_owned()))
+}
+
+fn network_identifier(chain_id: &str) -> NetworkIdentifier {
+    let network = NETWORK_NAMES
+        .iter()
+        .find(|(id, _)| *id == chain_id)
+        .map_or(chain_id, |(_, name)| name);
+    NetworkIdentifier {
+        blockchain: BLOCKCHAIN.to_owned(),
+        network: network.to_owned(),
+    }
+}
+
+/// Every request, except for `/network/list`, must be for our network.
+fn check_network(
+    network: &NetworkIdentifier,
+    body: &serde_json::Value,
+) -> Result<(), RosettaError> {
+    let req: NetworkRequest = serde_json::from_value(body.clone())
+        .map_err(|err| RosettaError::InvalidRequest(err.to_string()))?;
+    if &req.network_identifier != network {
+        return Err(RosettaError::NetworkNotSupported);
+    }
+    Ok(())
+}
+
+fn parse_address(address: &str) -> Result<NonZeroCurvePoint, RosettaError> {
+    let public_key = address
+        .parse()
+        .map_err(|_| RosettaError::InvalidRequest(format!("invalid address: {address}")))?;
+    construction::validate_public_key(&public_key)?;
+    Ok(public_key)
+}
+
+fn account_proof_error(err: RpcAccountProofGetError) -> RosettaError {
+    match err {
+        RpcAccountProofGetError::NoBestTip => RosettaError::NodeNotReady,
+        RpcAccountProofGetError::LedgerNotFound { .. } => RosettaError::BlockNotFound,
+        RpcAccountProofGetError::AccountNotFound { .. } => RosettaError::AccountNotFound,
+    }
+}
+
+fn block_identifier(block: &RpcTransitionFrontierBlock) -> BlockIdentifier {
+    BlockIdentifier {
+        index: block.height,
+        hash: block.hash.to_string(),
+    }
+}
+
+fn block_query(block: &PartialBlockIdentifier) -> Result<RpcBlockGetQuery, RosettaError> {
+    Ok(match (&block.hash, block.index) {
+        (Some(hash), _) => RpcBlockGetQuery::ForBlockWithHash(
+            hash.parse()
+                .map_err(|_| RosettaError::InvalidRequest(format!("invalid block hash: {hash}")))?,
+        ),
+        (None, Some(index)) => RpcBlockGetQuery::ForBlockWithHeight(index),
+        (None, None) => RpcBlockGetQuery::ForBestTip,
+    })
+}
+
+async fn block_get(
+    rpc_sender: &super::RpcSender,
+    block: &PartialBlockIdentifier,
+) -> Result<node::rpc::RpcBlockWithTransactions, RosettaError> {
+    let resp: RpcBlockGetResponse =
+        request(rpc_sender, RpcRequest::BlockGet(block_query(block)?)).await?;
+    let resp = resp.map_err(|err| match err {
+        RpcBlockGetError::NoBestTip => RosettaError::NodeNotReady,
+        RpcBlockGetError::BlockNotFound => RosettaError::BlockNotFound,
+        RpcBlockGetError::TransactionsUnavailable { error } => RosettaError::Internal(error),
+    })?;
+    if block
+        .index
+        .map_or(false, |index| index != resp.block.height())
+    {
+        return Err(RosettaError::BlockNotFound);
+    }
+    Ok(resp)
+}
+
+async fn network_status(
+    rpc_sender: &super::RpcSender,
+) -> Result<NetworkStatusResponse, RosettaError> {
+    let status: RpcTransitionFrontierStatusGetResponse =
+        request(rpc_sender, RpcRequest::TransitionFrontierStatusGet).await?;
+    let status = status.ok_or(RosettaError::NodeNotReady)?;
+    let peers: RpcPeersGetResponse = request(rpc_sender, RpcRequest::PeersGet).await?;
+
+    Ok(NetworkStatusResponse {
+        current_block_identifier: block_identifier(&status.best_tip),
+        current_block_timestamp: status.best_tip.timestamp,
+        genesis_block_identifier: BlockIdentifier {
+            index: GENESIS_BLOCK_HEIGHT,
+            hash: status.genesis_block_hash.to_string(),
+        },
+        oldest_block_identifier: block_identifier(&status.root),
+        sync_status: SyncStatus {
+            synced: status.is_synced,
+        },
+        peers: peers
+            .into_iter()
+            .filter(|peer| matches!(peer.connection_status, PeerConnectionStatus::Connected))
+            .map(|peer| Peer {
+                peer_id: peer.peer_id.to_string(),
+            })
+            .collect(),
+    })
+}
+
+fn network_options() -> NetworkOptionsResponse {
+    NetworkOptionsResponse {
+        version: Version {
+            rosetta_version: ROSETTA_VERSION.to_owned(),
+            node_version: env!("CARGO_PKG_VERSION").to_owned(),
+        },
+        allow: Allow {
+            operation_statuses: vec![
+                OperationStatus {
+                    status: STATUS_SUCCESS.to_owned(),
+                    successful: true,
+                },
+                OperationStatus {
+                    status: STATUS_FAILED.to_owned(),
+                    successful: false,
+                },
+            ],
+            operation_types: OPERATION_TYPES.iter().map(|v| v.to_string()).collect(),
+            errors: RosettaError::all().iter().map(|v| v.to_error()).collect(),
+            historical_balance_lookup: true,
+            call_methods: vec![],
+            balance_exemptions: vec![],
+            mempool_coins: false,
+        },
+    }
+}
+
+/// Rosetta expects the genesis block to be its own parent.
+fn parent_block_identifier(block: &BlockIdentifier, pred_hash: String) -> BlockIdentifier {
+    if block.index <= GENESIS_BLOCK_HEIGHT {
+        return block.clone();
+    }
+    BlockIdentifier {
+        index: block.index - 1,
+        hash: pred_hash,
+    }
+}
+
+async fn block(
+    rpc_sender: &super::RpcSender,
+    req: BlockRequest,
+) -> Result<BlockResponse, RosettaError> {
+    let resp = block_get(rpc_sender, &req.block_identifier).await?;
+    let block = &resp.block;
+    let block_identifier = BlockIdentifier {
+        index: block.height(),
+        hash: block.hash().to_string(),
+    };
+
+    Ok(BlockResponse {
+        block: Block {
+            parent_block_identifier: parent_block_identifier(
+                &block_identifier,
+                block.pred_hash().to_string(),
+            ),
+            block_identifier,
+            timestamp: u64::from(block.timestamp()) / 1_000_000,
+            transactions: operations::block_transactions(block.hash(), &resp.transactions),
+        },
+    })
+}
+
+async fn account_balance(
+    rpc_sender: &super::RpcSender,
+    req: AccountBalanceRequest,
+) -> Result<AccountBalanceResponse, RosettaError> {
+    let public_key = parse_address(&req.account_identifier.address)?;
+    let token = req
+        .account_identifier
+        .metadata
+        .as_ref()
+        .map(|metadata| {
+            metadata.token_id.parse::<TokenIdKeyHash>().map_err(|_| {
+                RosettaError::InvalidRequest(format!("invalid token id: {}", metadata.token_id))
+            })
+        })
+        .transpose()?;
+    let ledger_hash = match req.block_identifier {
+        Some(block) if block.hash.is_some() || block.index.is_some() => {
+            let resp = block_get(rpc_sender, &block).await?;
+            Some(resp.block.staged_ledger_hash().clone())
+        }
+        _ => None,
+    };
+
+    let resp: RpcAccountProofGetResponse = request(
+        rpc_sender,
+        RpcRequest::AccountProofGet(RpcAccountProofGetQuery {
+            public_key,
+            token,
+            ledger_hash,
+        }),
+    )
+    .await?;
+    let proof = resp.map_err(account_proof_error)?;
+    // Ledger was taken from the best chain, so the block must be there,
+    // unless the best chain changed in the meantime.
+    let block = proof.block.ok_or(RosettaError::BlockNotFound)?;
+    let account = proof.account;
+
+    Ok(AccountBalanceResponse {
+        block_identifier: BlockIdentifier {
+            index: block.height,
+            hash: block.hash.to_string(),
+        },
+        balances: vec![amount(
+            account.balance.0 .0 .0.as_u64() as i128,
+            &account.token_id,
+        )],
+        metadata: AccountBalanceMetadata {
+            nonce: account.nonce.0.as_u32().to_string(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use warp::{http::Response, hyper::body::Bytes};
+
+    use super::*;
+
+    const BERKELEY_CHAIN_ID: &str =
+        "fd7d111973bf5a9e3e87384f560fdead2f272589ca00b6d9e357fca9839631da";
+
+    fn request_for(network: &NetworkIdentifier) -> serde_json::Value {
+        serde_json::json!({
+            "network_identifier": network,
+            "block_identifier": { "index": 5 },
+        })
+    }
+
+    #[test]
+    fn network_from_chain_id() {
+        assert_eq!(network_identifier(BERKELEY_CHAIN_ID).network, "berkeley");
+        let network = network_identifier("0123abcd");
+        assert_eq!(network.blockchain, BLOCKCHAIN);
+        assert_eq!(network.network, "0123abcd");
+    }
+
+    #[test]
+    fn check_network_rejects_other_networks() {
+        let network = network_identifier(BERKELEY_CHAIN_ID);
+        assert!(check_network(&network, &request_for(&network)).is_ok());
+
+        let other = network_identifier("0123abcd");
+        assert!(matches!(
+            check_network(&network, &request_for(&other)),
+            Err(RosettaError::NetworkNotSupported)
+        ));
+        assert!(matches!(
+            check_network(&network, &serde_json::json!({})),
+            Err(RosettaError::InvalidRequest(_))
+        ));
+    }
+
+    async fn post(path: &str, body: serde_json::Value) -> Response<Bytes> {
+        let (tx, _rx) = node::core::channels::mpsc::channel(1);
+        let filter = routes(crate::RpcSender::new(tx), BERKELEY_CHAIN_ID);
+        warp::test::request()
+            .method("POST")
+            .path(path)
+            .json(&body)
+            .reply(&filter)
+            .await
+    }
+
+    fn json_body(resp: &Response<Bytes>) -> serde_json::Value {
+        serde_json::from_slice(resp.body()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn mempool_is_empty() {
+        let network = network_identifier(BERKELEY_CHAIN_ID);
+        let resp = post(
+            "/mempool",
+            serde_json::json!({ "network_identifier": network }),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            json_body(&resp),
+            serde_json::json!({ "transaction_identifiers": [] })
+        );
+
+        let req = serde_json::json!({
+            "network_identifier": network,
+            "transaction_identifier": { "hash": "CkpZ" },
+        });
+        let resp = post("/mempool/transaction", req).await;
+        assert_eq!(resp.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let err = json_body(&resp);
+        assert_eq!(err["code"], RosettaError::TransactionNotFound.code());
+        assert_eq!(err["retriable"], false);
+    }
+
+    #[tokio::test]
+    async fn submit_is_not_routed() {
+        let req = serde_json::json!({
+            "network_identifier": network_identifier(BERKELEY_CHAIN_ID),
+            "signed_transaction": "",
+        });
+        let resp = post("/construction/submit", req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn network_options_errors_are_distinct() {
+        let options = network_options();
+        let errors = &options.allow.errors;
+        let codes = errors.iter().map(|v| v.code).collect::<BTreeSet<_>>();
+        assert_eq!(codes.len(), errors.len());
+        // Errors returned with details must match the advertised ones.
+        let err = RosettaError::InvalidRequest("bad".to_owned()).to_error();
+        let listed = errors.iter().find(|v| v.code == err.code).unwrap();
+        assert_eq!(listed.message, err.message);
+        assert_eq!(listed.retriable, err.retriable);
+        assert!(listed.details.is_none());
+        assert!(err.details.is_some());
+
+        let types = &options.allow.operation_types;
+        assert_eq!(types.iter().collect::<BTreeSet<_>>().len(), types.len());
+    }
+
+    #[test]
+    fn genesis_block_is_its_own_parent() {
+        let genesis = BlockIdentifier {
+            index: GENESIS_BLOCK_HEIGHT,
+            hash: "genesis".to_owned(),
+        };
+        let parent = parent_block_identifier(&genesis, "pred".to_owned());
+        assert_eq!((parent.index, parent.hash), (genesis.index, genesis.hash));
+
+        let block = BlockIdentifier {
+            index: 10,
+            hash: "block".to_owned(),
+        };
+        let parent = parent_block_identifier(&block, "pred".to_owned());
+        assert_eq!((parent.index, parent.hash.as_str()), (9, "pred"));
+    }
+}
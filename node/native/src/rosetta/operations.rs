@@ -0,0 +1,264 @@
+//! Conversion of Mina transactions into Rosetta operations.
+
+use ledger::TokenId;
+use mina_p2p_messages::v2::{
+    MinaBaseFeeTransferStableV2, MinaBaseSignedCommandPayloadBodyStableV2,
+    MinaBaseSignedCommandPayloadStableV2, MinaBaseStakeDelegationStableV2,
+    MinaBaseTransactionStatusStableV2, MinaBaseUserCommandStableV2,
+    MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesAA, MinaTransactionTransactionStableV2,
+    NonZeroCurvePoint, SgnStableV1, StateHash, TokenIdKeyHash,
+};
+use node::rpc::RpcBlockTransaction;
+
+use super::types::{
+    AccountIdentifier, AccountIdentifierMetadata, Amount, Currency, CurrencyMetadata, Operation,
+    OperationIdentifier, Transaction, TransactionIdentifier,
+};
+
+pub const OP_FEE_PAYMENT: &str = "fee_payment";
+pub const OP_PAYMENT_SOURCE_DEC: &str = "payment_source_dec";
+pub const OP_PAYMENT_RECEIVER_INC: &str = "payment_receiver_inc";
+pub const OP_DELEGATE_CHANGE: &str = "delegate_change";
+pub const OP_COINBASE_INC: &str = "coinbase_inc";
+pub const OP_FEE_RECEIVER_INC: &str = "fee_receiver_inc";
+pub const OP_ZKAPP_FEE_PAYER_DEC: &str = "zkapp_fee_payer_dec";
+pub const OP_ZKAPP_BALANCE_UPDATE: &str = "zkapp_balance_update";
+
+pub const OPERATION_TYPES: [&str; 8] = [
+    OP_FEE_PAYMENT,
+    OP_PAYMENT_SOURCE_DEC,
+    OP_PAYMENT_RECEIVER_INC,
+    OP_DELEGATE_CHANGE,
+    OP_COINBASE_INC,
+    OP_FEE_RECEIVER_INC,
+    OP_ZKAPP_FEE_PAYER_DEC,
+    OP_ZKAPP_BALANCE_UPDATE,
+];
+
+pub const STATUS_SUCCESS: &str = "Success";
+pub const STATUS_FAILED: &str = "Failed";
+
+pub const MINA_SYMBOL: &str = "MINA";
+pub const MINA_DECIMALS: u32 = 9;
+
+pub fn default_token() -> TokenIdKeyHash {
+    TokenId::default().into()
+}
+
+/// MINA for the default token, `MINA+` with the token id in the metadata
+/// for custom tokens.
+pub fn currency(token: &TokenIdKeyHash) -> Currency {
+    if token == &default_token() {
+        Currency {
+            symbol: MINA_SYMBOL.to_owned(),
+            decimals: MINA_DECIMALS,
+            metadata: None,
+        }
+    } else {
+        Currency {
+            symbol: format!("{MINA_SYMBOL}+"),
+            decimals: MINA_DECIMALS,
+            metadata: Some(CurrencyMetadata {
+                token_id: token.to_string(),
+            }),
+        }
+    }
+}
+
+pub fn account_identifier(
+    public_key: &NonZeroCurvePoint,
+    token: &TokenIdKeyHash,
+) -> AccountIdentifier {
+    AccountIdentifier {
+        address: public_key.to_string(),
+        metadata: (token != &default_token()).then(|| AccountIdentifierMetadata {
+            token_id: token.to_string(),
+        }),
+    }
+}
+
+pub fn amount(value: i128, token: &TokenIdKeyHash) -> Amount {
+    Amount {
+        value: value.to_string(),
+        currency: currency(token),
+    }
+}
+
+/// Collects operations of a single transaction, assigning them indexes.
+struct Operations {
+    /// Status of the operations, `None` for transactions which aren't
+    /// part of a block yet.
+    status: Option<&'static str>,
+    list: Vec<Operation>,
+}
+
+impl Operations {
+    fn new(status: Option<&'static str>) -> Self {
+        Self {
+            status,
+            list: vec![],
+        }
+    }
+
+    fn push(
+        &mut self,
+        kind: &str,
+        public_key: &NonZeroCurvePoint,
+        value: Option<i128>,
+    ) -> &mut Operation {
+        let token = default_token();
+        self.push_with_token(kind, public_key, &token, value)
+    }
+
+    fn push_with_token(
+        &mut self,
+        kind: &str,
+        public_key: &NonZeroCurvePoint,
+        token: &TokenIdKeyHash,
+        value: Option<i128>,
+    ) -> &mut Operation {
+        let index = self.list.len() as u32;
+        self.list.push(Operation {
+            operation_identifier: OperationIdentifier { index },
+            related_operations: vec![],
+            kind: kind.to_owned(),
+            status: self.status.map(ToOwned::to_owned),
+            account: Some(account_identifier(public_key, token)),
+            amount: value.map(|value| amount(value, token)),
+            metadata: None,
+        });
+        self.list.last_mut().unwrap()
+    }
+}
+
+/// Operations of a signed command.
+///
+/// Fee is charged even if the command failed, so if `status` is `Some`,
+/// the fee payment is always successful.
+pub fn signed_command_operations(
+    payload: &MinaBaseSignedCommandPayloadStableV2,
+    status: Option<&'static str>,
+) -> Vec<Operation> {
+    let common = &payload.common;
+    let mut ops = Operations::new(status);
+
+    let fee = common.fee.0 .0.as_u64() as i128;
+    ops.push(OP_FEE_PAYMENT, &common.fee_payer_pk, Some(-fee))
+        .status = status.map(|_| STATUS_SUCCESS.to_owned());
+
+    match &payload.body {
+        MinaBaseSignedCommandPayloadBodyStableV2::Payment(payment) => {
+            let value = payment.amount.0 .0.as_u64() as i128;
+            let source = ops
+                .push(OP_PAYMENT_SOURCE_DEC, &common.fee_payer_pk, Some(-value))
+                .operation_identifier;
+            ops.push(OP_PAYMENT_RECEIVER_INC, &payment.receiver_pk, Some(value))
+                .related_operations = vec![source];
+        }
+        MinaBaseSignedCommandPayloadBodyStableV2::StakeDelegation(
+            MinaBaseStakeDelegationStableV2::SetDelegate { new_delegate },
+        ) => {
+            ops.push(OP_DELEGATE_CHANGE, &common.fee_payer_pk, None)
+                .metadata = Some(serde_json::json!({
+                "delegate_change_target": new_delegate.to_string(),
+            }));
+        }
+    }
+    ops.list
+}
+
+fn zkapp_balance_updates(
+    ops: &mut Operations,
+    tree: &MinaBaseZkappCommandTStableV1WireStableV1AccountUpdatesAA,
+) {
+    let body = &tree.account_update.body;
+    let magnitude = body.balance_change.magnitude.0 .0.as_u64() as i128;
+    if magnitude != 0 {
+        let value = match body.balance_change.sgn {
+            SgnStableV1::Pos => magnitude,
+            SgnStableV1::Neg => -magnitude,
+        };
+        ops.push_with_token(
+            OP_ZKAPP_BALANCE_UPDATE,
+            &body.public_key,
+            &body.token_id,
+            Some(value),
+        );
+    }
+    for call in tree.calls.iter() {
+        zkapp_balance_updates(ops, &call.elt);
+    }
+}
+
+/// Converts transactions applied by the block into Rosetta transactions.
+///
+/// Fee transfers, coinbases and zkapp commands don't have a hash we can
+/// compute, so they are identified by `{block_hash}:{sequence_no}`.
+pub fn block_transactions(
+    block_hash: &StateHash,
+    transactions: &[RpcBlockTransaction],
+) -> Vec<Transaction> {
+    transactions
+        .iter()
+        .enumerate()
+        .map(|(sequence_no, tx)| {
+            let status = match &tx.status {
+                MinaBaseTransactionStatusStableV2::Applied => STATUS_SUCCESS,
+                MinaBaseTransactionStatusStableV2::Failed(_) => STATUS_FAILED,
+            };
+            let mut hash = None;
+            let operations = match &tx.transaction {
+                MinaTransactionTransactionStableV2::Command(command) => {
+                    hash = command.hash().ok().map(|hash| hash.to_string());
+                    match command.as_ref() {
+                        MinaBaseUserCommandStableV2::SignedCommand(cmd) => {
+                            signed_command_operations(&cmd.payload, Some(status))
+                        }
+                        MinaBaseUserCommandStableV2::ZkappCommand(cmd) => {
+                            let mut ops = Operations::new(Some(status));
+                            let fee_payer = &cmd.fee_payer.body;
+                            let fee = fee_payer.fee.0 .0.as_u64() as i128;
+                            ops.push(OP_ZKAPP_FEE_PAYER_DEC, &fee_payer.public_key, Some(-fee))
+                                .status = Some(STATUS_SUCCESS.to_owned());
+                            for update in cmd.account_updates.iter() {
+                                zkapp_balance_updates(&mut ops, &update.elt);
+                            }
+                            ops.list
+                        }
+                    }
+                }
+                MinaTransactionTransactionStableV2::FeeTransfer(fee_transfer) => {
+                    let transfers = match fee_transfer {
+                        MinaBaseFeeTransferStableV2::One(v) => vec![v],
+                        MinaBaseFeeTransferStableV2::Two((v1, v2)) => vec![v1, v2],
+                    };
+                    let mut ops = Operations::new(Some(status));
+                    for transfer in transfers {
+                        let fee = transfer.fee.0 .0.as_u64() as i128;
+                        ops.push(OP_FEE_RECEIVER_INC, &transfer.receiver_pk, Some(fee));
+                    }
+                    ops.list
+                }
+                MinaTransactionTransactionStableV2::Coinbase(coinbase) => {
+                    let mut ops = Operations::new(Some(status));
+                    let amount = coinbase.amount.0 .0.as_u64() as i128;
+                    let fee = coinbase
+                        .fee_transfer
+                        .as_ref()
+                        .map_or(0, |v| v.fee.0 .0.as_u64() as i128);
+                    ops.push(OP_COINBASE_INC, &coinbase.receiver, Some(amount - fee));
+                    if let Some(fee_transfer) = &coinbase.fee_transfer {
+                        ops.push(OP_FEE_RECEIVER_INC, &fee_transfer.receiver_pk, Some(fee));
+                    }
+                    ops.list
+                }
+            };
+            Transaction {
+                transaction_identifier: TransactionIdentifier {
+                    hash: hash.unwrap_or_else(|| format!("{block_hash}:{sequence_no}")),
+                },
+                operations,
+            }
+        })
+        .collect()
+}
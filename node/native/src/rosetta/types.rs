@@ -0,0 +1,347 @@
+//! Subset of the [Rosetta API](https://www.rosetta-api.org/) models
+//! used by the node.
+//!
+//! Field names follow the Rosetta specification, so that existing Rosetta
+//! clients can talk to the node directly.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NetworkIdentifier {
+    pub blockchain: String,
+    pub network: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockIdentifier {
+    pub index: u32,
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PartialBlockIdentifier {
+    pub index: Option<u32>,
+    pub hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionIdentifier {
+    pub hash: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccountIdentifier {
+    pub address: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<AccountIdentifierMetadata>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AccountIdentifierMetadata {
+    pub token_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Currency {
+    pub symbol: String,
+    pub decimals: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<CurrencyMetadata>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CurrencyMetadata {
+    pub token_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Amount {
+    /// Signed integer in the smallest unit of the currency.
+    pub value: String,
+    pub currency: Currency,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OperationIdentifier {
+    pub index: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Operation {
+    pub operation_identifier: OperationIdentifier,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_operations: Vec<OperationIdentifier>,
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<AccountIdentifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Amount>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Transaction {
+    pub transaction_identifier: TransactionIdentifier,
+    pub operations: Vec<Operation>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Block {
+    pub block_identifier: BlockIdentifier,
+    pub parent_block_identifier: BlockIdentifier,
+    /// Milliseconds since unix epoch.
+    pub timestamp: u64,
+    pub transactions: Vec<Transaction>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Peer {
+    pub peer_id: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct SyncStatus {
+    pub synced: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Version {
+    pub rosetta_version: String,
+    pub node_version: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct OperationStatus {
+    pub status: String,
+    pub successful: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Allow {
+    pub operation_statuses: Vec<OperationStatus>,
+    pub operation_types: Vec<String>,
+    pub errors: Vec<Error>,
+    pub historical_balance_lookup: bool,
+    pub call_methods: Vec<String>,
+    pub balance_exemptions: Vec<serde_json::Value>,
+    pub mempool_coins: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct Error {
+    pub code: u32,
+    pub message: String,
+    pub retriable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublicKey {
+    pub hex_bytes: String,
+    pub curve_type: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SigningPayload {
+    pub account_identifier: AccountIdentifier,
+    pub hex_bytes: String,
+    pub signature_type: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Signature {
+    pub signing_payload: SigningPayload,
+    pub public_key: PublicKey,
+    pub signature_type: String,
+    pub hex_bytes: String,
+}
+
+// Data API
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct NetworkRequest {
+    pub network_identifier: NetworkIdentifier,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NetworkListResponse {
+    pub network_identifiers: Vec<NetworkIdentifier>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NetworkStatusResponse {
+    pub current_block_identifier: BlockIdentifier,
+    pub current_block_timestamp: u64,
+    pub genesis_block_identifier: BlockIdentifier,
+    pub oldest_block_identifier: BlockIdentifier,
+    pub sync_status: SyncStatus,
+    pub peers: Vec<Peer>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct NetworkOptionsResponse {
+    pub version: Version,
+    pub allow: Allow,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct BlockRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub block_identifier: PartialBlockIdentifier,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct BlockResponse {
+    pub block: Block,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AccountBalanceRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub account_identifier: AccountIdentifier,
+    #[serde(default)]
+    pub block_identifier: Option<PartialBlockIdentifier>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AccountBalanceResponse {
+    pub block_identifier: BlockIdentifier,
+    pub balances: Vec<Amount>,
+    pub metadata: AccountBalanceMetadata,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AccountBalanceMetadata {
+    pub nonce: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct MempoolResponse {
+    pub transaction_identifiers: Vec<TransactionIdentifier>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct MempoolTransactionRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub transaction_identifier: TransactionIdentifier,
+}
+
+// Construction API
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ConstructionDeriveRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub public_key: PublicKey,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ConstructionDeriveResponse {
+    pub account_identifier: AccountIdentifier,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ConstructionPreprocessRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub operations: Vec<Operation>,
+    #[serde(default)]
+    pub metadata: Option<TransactionMetadata>,
+}
+
+/// Optional fields of the transaction, which aren't expressed by the
+/// operations.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TransactionMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConstructionOptions {
+    pub sender: String,
+    #[serde(flatten)]
+    pub transaction: TransactionMetadata,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ConstructionPreprocessResponse {
+    pub options: ConstructionOptions,
+    pub required_public_keys: Vec<AccountIdentifier>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ConstructionMetadataRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub options: ConstructionOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConstructionMetadata {
+    pub sender: String,
+    pub nonce: String,
+    #[serde(flatten)]
+    pub transaction: TransactionMetadata,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ConstructionMetadataResponse {
+    pub metadata: ConstructionMetadata,
+    pub suggested_fee: Vec<Amount>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ConstructionPayloadsRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub operations: Vec<Operation>,
+    pub metadata: ConstructionMetadata,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ConstructionPayloadsResponse {
+    pub unsigned_transaction: String,
+    pub payloads: Vec<SigningPayload>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ConstructionCombineRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub unsigned_transaction: String,
+    pub signatures: Vec<Signature>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ConstructionCombineResponse {
+    pub signed_transaction: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ConstructionParseRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub signed: bool,
+    pub transaction: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct ConstructionParseResponse {
+    pub operations: Vec<Operation>,
+    pub account_identifier_signers: Vec<AccountIdentifier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<TransactionMetadata>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ConstructionHashRequest {
+    pub network_identifier: NetworkIdentifier,
+    pub signed_transaction: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct TransactionIdentifierResponse {
+    pub transaction_identifier: TransactionIdentifier,
+}
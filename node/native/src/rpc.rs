@@ -93,6 +93,10 @@ impl node::rpc::RpcService for NodeService {
     }
 
     rpc_service_impl!(respond_sync_stats_get, RpcSyncStatsGetResponse);
+    rpc_service_impl!(
+        respond_snark_market_stats_get,
+        node::rpc::RpcSnarkMarketStatsGetResponse
+    );
     rpc_service_impl!(respond_action_stats_get, RpcActionStatsGetResponse);
     rpc_service_impl!(respond_peers_get, RpcPeersGetResponse);
     rpc_service_impl!(
@@ -146,12 +150,61 @@ impl node::rpc::RpcService for NodeService {
         respond_snarker_workers,
         node::rpc::RpcSnarkerWorkersResponse
     );
+    rpc_service_impl!(
+        respond_snarkers_reliability,
+        node::rpc::RpcSnarkersReliabilityResponse
+    );
+    rpc_service_impl!(
+        respond_snarker_work_get,
+        node::rpc::RpcSnarkerWorkGetResponse
+    );
+    rpc_service_impl!(
+        respond_snarker_work_submit,
+        node::rpc::RpcSnarkerWorkSubmitResponse
+    );
     rpc_service_impl!(
         respond_snarker_config_get,
         node::rpc::RpcSnarkerConfigGetResponse
     );
     rpc_service_impl!(respond_health_check, RpcHealthCheckResponse);
     rpc_service_impl!(respond_readiness_check, RpcReadinessCheckResponse);
+    rpc_service_impl!(
+        respond_watched_accounts_add,
+        node::rpc::RpcWatchedAccountsAddResponse
+    );
+    rpc_service_impl!(
+        respond_watched_accounts_remove,
+        node::rpc::RpcWatchedAccountsRemoveResponse
+    );
+    rpc_service_impl!(
+        respond_watched_accounts_get,
+        node::rpc::RpcWatchedAccountsGetResponse
+    );
+    rpc_service_impl!(
+        respond_watched_account_blocks_get,
+        node::rpc::RpcWatchedAccountBlocksGetResponse
+    );
+    rpc_service_impl!(
+        respond_account_proof_get,
+        node::rpc::RpcAccountProofGetResponse
+    );
+    rpc_service_impl!(respond_block_get, node::rpc::RpcBlockGetResponse);
+    rpc_service_impl!(
+        respond_transition_frontier_status_get,
+        node::rpc::RpcTransitionFrontierStatusGetResponse
+    );
+    rpc_service_impl!(
+        respond_block_producer_schedule_get,
+        node::rpc::RpcBlockProducerScheduleGetResponse
+    );
+    rpc_service_impl!(
+        respond_block_producer_history_get,
+        node::rpc::RpcBlockProducerHistoryGetResponse
+    );
+    rpc_service_impl!(
+        respond_block_producer_epoch_data_get,
+        node::rpc::RpcBlockProducerEpochDataGetResponse
+    );
 }
 
 impl node::core::invariants::InvariantService for NodeService {
@@ -2,8 +2,6 @@ use std::collections::{BTreeMap, VecDeque};
 
 use std::sync::{Arc, Mutex};
 
-use ledger::scan_state::scan_state::transaction_snark::{SokDigest, Statement};
-use mina_p2p_messages::v2::{LedgerProofProdStableV2, TransactionSnarkWorkTStableV2Proofs};
 use openmina_core::invariants::InvariantsState;
 use rand::prelude::*;
 use redux::ActionMeta;
@@ -12,6 +10,7 @@ use serde::Serialize;
 use node::core::channels::{mpsc, oneshot};
 use node::core::snark::{Snark, SnarkJobId};
 use node::event_source::Event;
+use node::external_snark_worker::ExternalSnarkWorkerId;
 use node::ledger::LedgerCtx;
 use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
 use node::p2p::service_impl::libp2p::Libp2pService;
@@ -19,20 +18,23 @@ use node::p2p::service_impl::webrtc::{Cmd, P2pServiceWebrtc, PeerState};
 use node::p2p::service_impl::webrtc_with_libp2p::P2pServiceWebrtcWithLibp2p;
 use node::p2p::service_impl::TaskSpawner;
 use node::p2p::{P2pEvent, PeerId};
-use node::rpc::{RpcP2pConnectionOutgoingResponse, RpcRequest};
+use node::rpc::{RpcP2pConnectionOutgoingResponse, RpcRequest, RpcRole};
 use node::service::{EventSourceService, Recorder};
 use node::snark::block_verify::{
-    SnarkBlockVerifyError, SnarkBlockVerifyId, SnarkBlockVerifyService, VerifiableBlockWithHash,
+    SnarkBlockVerifyId, SnarkBlockVerifyService, VerifiableBlockWithHash,
 };
-use node::snark::work_verify::{SnarkWorkVerifyError, SnarkWorkVerifyId, SnarkWorkVerifyService};
-use node::snark::{SnarkEvent, VerifierIndex, VerifierSRS};
+use node::snark::work_verify::{SnarkWorkVerifyId, SnarkWorkVerifyService};
+use node::snark::{VerifierIndex, VerifierSRS};
 use node::snark_pool::{JobState, SnarkPoolService};
 use node::stats::Stats;
 use node::ActionKind;
 
-use crate::block_producer::BlockProducerService;
+use crate::archive::ArchiveService;
+use crate::block_producer::{BlockProducerHistoryPersist, BlockProducerService};
 use crate::ext_snark_worker;
 use crate::rpc::RpcService;
+use crate::snark_pool_persist::SnarkPoolPersist;
+use crate::snark_verifier::SnarkVerifier;
 
 pub struct NodeService {
     pub rng: StdRng,
@@ -47,7 +49,11 @@ pub struct NodeService {
     pub peers: BTreeMap<PeerId, PeerState>,
     pub libp2p: Libp2pService,
     pub block_producer: Option<BlockProducerService>,
-    pub snark_worker_sender: Option<ext_snark_worker::ExternalSnarkWorkerFacade>,
+    pub block_producer_history_persist: Option<BlockProducerHistoryPersist>,
+    pub snark_workers: BTreeMap<ExternalSnarkWorkerId, ext_snark_worker::ExternalSnarkWorkerFacade>,
+    pub archive: Option<ArchiveService>,
+    pub snark_pool_persist: Option<SnarkPoolPersist>,
+    pub snark_verifier: SnarkVerifier,
     pub rpc: RpcService,
     pub stats: Stats,
     pub recorder: Recorder,
@@ -185,24 +191,13 @@ impl SnarkBlockVerifyService for NodeService {
         if self.replayer.is_some() {
             return;
         }
-        let tx = self.event_sender.clone();
-        rayon::spawn_fifo(move || {
-            let header = block.header_ref();
-            let result = {
-                let verifier_srs = verifier_srs.lock().expect("Failed to lock the SRS");
-                if !ledger::proofs::verification::verify_block(
-                    header,
-                    &verifier_index,
-                    &verifier_srs,
-                ) {
-                    Err(SnarkBlockVerifyError::VerificationFailed)
-                } else {
-                    Ok(())
-                }
-            };
-
-            let _ = tx.send(SnarkEvent::BlockVerify(req_id, result).into());
-        });
+        self.snark_verifier.block_verify(
+            &self.event_sender,
+            req_id,
+            verifier_index,
+            verifier_srs,
+            block,
+        );
     }
 }
 
@@ -217,39 +212,13 @@ impl SnarkWorkVerifyService for NodeService {
         if self.replayer.is_some() {
             return;
         }
-        let tx = self.event_sender.clone();
-        rayon::spawn_fifo(move || {
-            let result = {
-                let conv = |proof: &LedgerProofProdStableV2| {
-                    (
-                        Statement::<SokDigest>::from(&proof.0.statement),
-                        proof.proof.clone(),
-                    )
-                };
-                let works = work
-                    .into_iter()
-                    .flat_map(|work| match &*work.proofs {
-                        TransactionSnarkWorkTStableV2Proofs::One(v) => [Some(conv(v)), None],
-                        TransactionSnarkWorkTStableV2Proofs::Two((v1, v2)) => {
-                            [Some(conv(v1)), Some(conv(v2))]
-                        }
-                    })
-                    .filter_map(|v| v)
-                    .collect::<Vec<_>>();
-                let verifier_srs = verifier_srs.lock().expect("Failed to lock SRS");
-                if !ledger::proofs::verification::verify_transaction(
-                    works.iter().map(|(v1, v2)| (v1, v2)),
-                    &verifier_index,
-                    &verifier_srs,
-                ) {
-                    Err(SnarkWorkVerifyError::VerificationFailed)
-                } else {
-                    Ok(())
-                }
-            };
-
-            let _ = tx.send(SnarkEvent::WorkVerify(req_id, result).into());
-        });
+        self.snark_verifier.work_verify(
+            &self.event_sender,
+            req_id,
+            verifier_index,
+            verifier_srs,
+            work,
+        );
     }
 }
 
@@ -318,17 +287,50 @@ pub struct NodeRpcRequest {
 #[derive(Clone)]
 pub struct RpcSender {
     tx: mpsc::Sender<NodeRpcRequest>,
+    /// Highest role of requests this sender is allowed to make.
+    role: RpcRole,
 }
 
 impl RpcSender {
     pub fn new(tx: mpsc::Sender<NodeRpcRequest>) -> Self {
-        Self { tx }
+        Self {
+            tx,
+            role: RpcRole::Admin,
+        }
+    }
+
+    /// Sender which only lets through requests up to the given `role`.
+    ///
+    /// Guards against http routes being exposed under a lower
+    /// role than the requests they make.
+    pub fn with_role(&self, role: RpcRole) -> Self {
+        Self {
+            tx: self.tx.clone(),
+            role: role.min(self.role),
+        }
+    }
+
+    fn is_allowed(&self, req: &RpcRequest) -> bool {
+        if req.role() <= self.role {
+            return true;
+        }
+        let t = openmina_core::log::system_time();
+        let summary = format!(
+            "request requires {:?} role, sender is limited to {:?}",
+            req.role(),
+            self.role
+        );
+        openmina_core::log::error!(t; kind = "RpcRoleViolation", summary = summary, request = format!("{req:?}"));
+        false
     }
 
     pub async fn oneshot_request<T>(&self, req: RpcRequest) -> Option<T>
     where
         T: 'static + Send + Serialize,
     {
+        if !self.is_allowed(&req) {
+            return None;
+        }
         let (tx, rx) = oneshot::channel::<T>();
         let responder = Box::new(tx);
         let sender = self.tx.clone();
@@ -346,6 +348,10 @@ impl RpcSender {
         T: 'static + Send + Serialize,
     {
         let (tx, rx) = mpsc::channel::<T>(expected_messages);
+        if !self.is_allowed(&req) {
+            // `tx` is dropped, so the receiver gets no messages.
+            return rx;
+        }
         let responder = Box::new(tx);
         let sender = self.tx.clone();
         let _ = sender.send(NodeRpcRequest { req, responder }).await;
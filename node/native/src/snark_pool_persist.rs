@@ -0,0 +1,393 @@
+//! Log of verified snarks and our own commitments, so that a restarted
+//! node doesn't have to fetch and verify them from peers again.
+//!
+//! Each entry in the log is binprot encoded and prefixed with its 64-bit
+//! little-endian length. Entries are appended as they are added to the
+//! snark pool and the log is rewritten, once jobs are removed from it.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+
+use mina_p2p_messages::binprot::{
+    self,
+    macros::{BinProtRead, BinProtWrite},
+    BinProtRead, BinProtWrite,
+};
+use node::core::channels::mpsc;
+use node::core::snark::{Snark, SnarkJobCommitment, SnarkJobId};
+use node::snark_pool::{SnarkPoolPersistService, SnarkPoolPersisted};
+use redux::Timestamp;
+
+use crate::NodeService;
+
+/// Entries hold a snark with at most two ledger proofs, which are
+/// much smaller than this. Longer length prefix means a corrupted log.
+const MAX_ENTRY_SIZE: u64 = 1024 * 1024;
+
+pub struct SnarkPoolPersist {
+    cmd_sender: mpsc::UnboundedSender<SnarkPoolPersistCmd>,
+    /// Entries loaded from the log on start, not yet taken by the node.
+    loaded: Option<SnarkPoolPersisted>,
+}
+
+enum SnarkPoolPersistCmd {
+    Append(SnarkPoolLogEntry),
+    Retain(BTreeSet<SnarkJobId>),
+}
+
+/// Entry of the log, with the time in nanoseconds, when the snark or
+/// commitment was added to the pool.
+#[derive(BinProtRead, BinProtWrite, Debug, Clone, PartialEq)]
+enum SnarkPoolLogEntry {
+    Snark(Snark, u64),
+    Commitment(SnarkJobCommitment, u64),
+}
+
+impl SnarkPoolLogEntry {
+    fn job_id(&self) -> SnarkJobId {
+        match self {
+            Self::Snark(snark, _) => snark.job_id(),
+            Self::Commitment(commitment, _) => commitment.job_id.clone(),
+        }
+    }
+}
+
+/// Latest persisted snark and commitment for the job.
+#[derive(Default)]
+struct SnarkPoolLogJob {
+    snark: Option<(Snark, Timestamp)>,
+    commitment: Option<(SnarkJobCommitment, Timestamp)>,
+}
+
+impl SnarkPoolLogJob {
+    fn add(&mut self, entry: SnarkPoolLogEntry) {
+        match entry {
+            SnarkPoolLogEntry::Snark(snark, t) => self.snark = Some((snark, Timestamp::new(t))),
+            SnarkPoolLogEntry::Commitment(commitment, t) => {
+                self.commitment = Some((commitment, Timestamp::new(t)))
+            }
+        }
+    }
+
+    fn entries(&self) -> impl '_ + Iterator<Item = SnarkPoolLogEntry> {
+        let snark = self
+            .snark
+            .clone()
+            .map(|(snark, t)| SnarkPoolLogEntry::Snark(snark, t.into()));
+        let commitment = self
+            .commitment
+            .clone()
+            .map(|(commitment, t)| SnarkPoolLogEntry::Commitment(commitment, t.into()));
+        snark.into_iter().chain(commitment)
+    }
+}
+
+struct SnarkPoolLog {
+    path: PathBuf,
+    file: BufWriter<File>,
+    jobs: BTreeMap<SnarkJobId, SnarkPoolLogJob>,
+}
+
+impl SnarkPoolLog {
+    /// Opens (or creates) the log at `path` and reads entries from it.
+    ///
+    /// Reading stops at the first entry, which can't be decoded, as it is
+    /// most likely partially written before the node was stopped. Log is
+    /// rewritten without it.
+    fn open(path: PathBuf) -> io::Result<Self> {
+        let mut jobs = BTreeMap::<SnarkJobId, SnarkPoolLogJob>::new();
+        match File::open(&path) {
+            Ok(file) => {
+                let mut reader = BufReader::new(file);
+                loop {
+                    match read_entry(&mut reader) {
+                        Ok(Some(entry)) => jobs.entry(entry.job_id()).or_default().add(entry),
+                        Ok(None) => break,
+                        Err(err) => {
+                            openmina_core::log::warn!(openmina_core::log::system_time();
+                                kind = "SnarkPoolPersistError",
+                                summary = "truncating snark pool log at corrupted entry",
+                                error = err.to_string());
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+
+        let file = Self::rewrite(&path, &jobs)?;
+        Ok(Self { path, file, jobs })
+    }
+
+    fn rewrite(
+        path: &Path,
+        jobs: &BTreeMap<SnarkJobId, SnarkPoolLogJob>,
+    ) -> io::Result<BufWriter<File>> {
+        let tmp_path = path.with_extension("tmp");
+        {
+            let mut file = BufWriter::new(File::create(&tmp_path)?);
+            for entry in jobs.values().flat_map(SnarkPoolLogJob::entries) {
+                write_entry(&mut file, &entry)?;
+            }
+            file.into_inner()?.sync_all()?;
+        }
+        std::fs::rename(&tmp_path, path)?;
+        let file = OpenOptions::new().append(true).open(path)?;
+        Ok(BufWriter::new(file))
+    }
+
+    fn append(&mut self, entry: SnarkPoolLogEntry) -> io::Result<()> {
+        write_entry(&mut self.file, &entry)?;
+        self.file.flush()?;
+        self.jobs.entry(entry.job_id()).or_default().add(entry);
+        Ok(())
+    }
+
+    fn retain(&mut self, job_ids: &BTreeSet<SnarkJobId>) -> io::Result<()> {
+        let len = self.jobs.len();
+        self.jobs.retain(|job_id, _| job_ids.contains(job_id));
+        if self.jobs.len() == len {
+            return Ok(());
+        }
+        self.file = Self::rewrite(&self.path, &self.jobs)?;
+        Ok(())
+    }
+
+    fn loaded(&self) -> SnarkPoolPersisted {
+        let snarks = self.jobs.values().filter_map(|job| job.snark.clone());
+        let commitments = self.jobs.values().filter_map(|job| job.commitment.clone());
+        (snarks.collect(), commitments.collect())
+    }
+}
+
+fn read_entry<R: Read>(r: &mut R) -> io::Result<Option<SnarkPoolLogEntry>> {
+    let mut len = [0; size_of::<u64>()];
+    match r.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let len = u64::from_le_bytes(len);
+    if len > MAX_ENTRY_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("entry length {len} exceeds maximum {MAX_ENTRY_SIZE}"),
+        ));
+    }
+    let mut buf = vec![0; len as usize];
+    r.read_exact(&mut buf)?;
+    SnarkPoolLogEntry::binprot_read(&mut buf.as_slice())
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("{err:?}")))
+}
+
+fn write_entry<W: Write>(w: &mut W, entry: &SnarkPoolLogEntry) -> io::Result<()> {
+    let mut buf = Vec::new();
+    entry.binprot_write(&mut buf)?;
+    w.write_all(&(buf.len() as u64).to_le_bytes())?;
+    w.write_all(&buf)
+}
+
+impl NodeService {
+    /// Opens (or creates) the snark pool log at `path` and starts the
+    /// thread writing snarks and commitments into it.
+    pub fn snark_pool_persist_start<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let log = SnarkPoolLog::open(path.as_ref().to_owned())?;
+        let (cmd_sender, cmd_receiver) = mpsc::unbounded_channel();
+
+        self.snark_pool_persist = Some(SnarkPoolPersist {
+            cmd_sender,
+            loaded: Some(log.loaded()),
+        });
+
+        std::thread::Builder::new()
+            .name("openmina_snark_pool_persist".to_owned())
+            .spawn(move || snark_pool_log_writer(log, cmd_receiver))
+            .unwrap();
+        Ok(())
+    }
+}
+
+fn snark_pool_log_writer(
+    mut log: SnarkPoolLog,
+    mut cmd_receiver: mpsc::UnboundedReceiver<SnarkPoolPersistCmd>,
+) {
+    while let Some(cmd) = cmd_receiver.blocking_recv() {
+        let res = match cmd {
+            SnarkPoolPersistCmd::Append(entry) => log.append(entry),
+            SnarkPoolPersistCmd::Retain(job_ids) => log.retain(&job_ids),
+        };
+        if let Err(err) = res {
+            openmina_core::log::error!(openmina_core::log::system_time();
+                kind = "SnarkPoolPersistError",
+                summary = "failed to write snark pool log",
+                error = err.to_string());
+        }
+    }
+}
+
+impl SnarkPoolPersistService for NodeService {
+    fn snark_pool_persisted_take(&mut self) -> SnarkPoolPersisted {
+        self.snark_pool_persist
+            .as_mut()
+            .and_then(|persist| persist.loaded.take())
+            .unwrap_or_default()
+    }
+
+    fn snark_pool_persist_snark(&mut self, snark: &Snark, received_t: Timestamp) {
+        if let Some(persist) = self.snark_pool_persist.as_ref() {
+            let entry = SnarkPoolLogEntry::Snark(snark.clone(), received_t.into());
+            let _ = persist.cmd_sender.send(SnarkPoolPersistCmd::Append(entry));
+        }
+    }
+
+    fn snark_pool_persist_commitment(
+        &mut self,
+        commitment: &SnarkJobCommitment,
+        received_t: Timestamp,
+    ) {
+        if let Some(persist) = self.snark_pool_persist.as_ref() {
+            let entry = SnarkPoolLogEntry::Commitment(commitment.clone(), received_t.into());
+            let _ = persist.cmd_sender.send(SnarkPoolPersistCmd::Append(entry));
+        }
+    }
+
+    fn snark_pool_persist_retain(&mut self, job_ids: BTreeSet<SnarkJobId>) {
+        if let Some(persist) = self.snark_pool_persist.as_ref() {
+            let _ = persist
+                .cmd_sender
+                .send(SnarkPoolPersistCmd::Retain(job_ids));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::scan_state::currency::{Fee, Magnitude};
+    use mina_p2p_messages::gossip::GossipNetMessageV2;
+    use mina_p2p_messages::v2::NetworkPoolSnarkPoolDiffVersionedStableV2;
+
+    use super::*;
+
+    fn tmp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("openmina-snark-pool-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn snark(nanomina: u64) -> Snark {
+        const SNARK_POOL_DIFF: &[u8] =
+            include_bytes!("../../../mina-p2p-messages/tests/files/v2/gossip/snark_pool_diff.bin");
+        let GossipNetMessageV2::SnarkPoolDiff {
+            message: NetworkPoolSnarkPoolDiffVersionedStableV2::AddSolvedWork(work),
+            ..
+        } = GossipNetMessageV2::binprot_read(&mut &SNARK_POOL_DIFF[..]).unwrap()
+        else {
+            unreachable!()
+        };
+        let mut snark = Snark::from(work.1);
+        snark.fee = (&Fee::from_u64(nanomina)).into();
+        snark
+    }
+
+    fn commitment(snark: &Snark) -> SnarkJobCommitment {
+        SnarkJobCommitment::new(1, snark.job_id(), snark.fee.clone(), snark.snarker.clone())
+    }
+
+    #[test]
+    fn log_round_trip() {
+        let path = tmp_path("round_trip.bin");
+        let mut log = SnarkPoolLog::open(path.clone()).unwrap();
+        assert_eq!(log.loaded(), (vec![], vec![]));
+
+        let (old, new) = (snark(200), snark(100));
+        log.append(SnarkPoolLogEntry::Snark(old, 5)).unwrap();
+        log.append(SnarkPoolLogEntry::Commitment(commitment(&new), 7))
+            .unwrap();
+        log.append(SnarkPoolLogEntry::Snark(new.clone(), 9))
+            .unwrap();
+        drop(log);
+
+        // latest entry of each kind for the job is kept.
+        let mut log = SnarkPoolLog::open(path.clone()).unwrap();
+        let expected = (
+            vec![(new.clone(), Timestamp::new(9))],
+            vec![(commitment(&new), Timestamp::new(7))],
+        );
+        assert_eq!(log.loaded(), expected);
+
+        log.retain(&BTreeSet::from([new.job_id()])).unwrap();
+        assert_eq!(log.loaded(), expected);
+        log.retain(&BTreeSet::new()).unwrap();
+        drop(log);
+        let log = SnarkPoolLog::open(path.clone()).unwrap();
+        assert_eq!(log.loaded(), (vec![], vec![]));
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn log_truncated_at_corrupted_entry() {
+        let path = tmp_path("corrupted.bin");
+        let snark = snark(100);
+        let mut log = SnarkPoolLog::open(path.clone()).unwrap();
+        log.append(SnarkPoolLogEntry::Snark(snark.clone(), 5))
+            .unwrap();
+        drop(log);
+        let valid_len = std::fs::metadata(&path).unwrap().len();
+
+        // entry, which was partially written when the node was stopped.
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&1000u64.to_le_bytes()).unwrap();
+        file.write_all(&[1, 2, 3]).unwrap();
+        drop(file);
+
+        let mut log = SnarkPoolLog::open(path.clone()).unwrap();
+        assert_eq!(log.loaded().0, vec![(snark.clone(), Timestamp::new(5))]);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), valid_len);
+
+        // entries appended after the truncation are read back.
+        log.append(SnarkPoolLogEntry::Commitment(commitment(&snark), 7))
+            .unwrap();
+        drop(log);
+        let log = SnarkPoolLog::open(path).unwrap();
+        assert_eq!(
+            log.loaded(),
+            (
+                vec![(snark.clone(), Timestamp::new(5))],
+                vec![(commitment(&snark), Timestamp::new(7))],
+            )
+        );
+    }
+
+    #[test]
+    fn log_entry_not_decodable() {
+        let mut buf = vec![];
+        buf.extend(2u64.to_le_bytes());
+        buf.extend([0xff, 0xff]);
+        let err = read_entry(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(read_entry(&mut &b""[..]).unwrap().is_none());
+    }
+
+    #[test]
+    fn log_entry_length_garbage() {
+        let mut buf = vec![];
+        buf.extend(u64::MAX.to_le_bytes());
+        buf.extend([1, 2, 3]);
+        let err = read_entry(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let path = tmp_path("garbage_length.bin");
+        std::fs::write(&path, &buf).unwrap();
+        let log = SnarkPoolLog::open(path.clone()).unwrap();
+        assert_eq!(log.loaded(), (vec![], vec![]));
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+    }
+}
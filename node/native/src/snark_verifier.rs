@@ -0,0 +1,337 @@
+//! Verification of snark work and block proofs in batches.
+//!
+//! Requests arriving within [`BATCH_WINDOW`] of each other are verified
+//! in one pass, on the rayon thread pool, so that batches can be verified
+//! in parallel. If the batch fails, requests in it are verified one by
+//! one, so that only the invalid ones are rejected.
+
+use std::collections::BTreeSet;
+use std::sync::{mpsc as std_mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ledger::scan_state::scan_state::transaction_snark::{SokDigest, Statement};
+use mina_p2p_messages::v2::{LedgerProofProdStableV2, TransactionSnarkWorkTStableV2Proofs};
+
+use node::core::channels::mpsc;
+use node::core::snark::Snark;
+use node::event_source::Event;
+use node::snark::block_verify::{
+    SnarkBlockVerifyError, SnarkBlockVerifyId, VerifiableBlockWithHash,
+};
+use node::snark::work_verify::{SnarkWorkVerifyError, SnarkWorkVerifyId};
+use node::snark::{SnarkEvent, VerifierIndex, VerifierSRS};
+
+/// How long to wait for more requests, after the first one in the batch
+/// is received.
+const BATCH_WINDOW: Duration = Duration::from_millis(50);
+const BATCH_MAX_LEN: usize = 64;
+
+struct VerifyRequest<Id, T> {
+    req_id: Id,
+    verifier_index: Arc<VerifierIndex>,
+    verifier_srs: Arc<Mutex<VerifierSRS>>,
+    data: T,
+}
+
+type WorkVerifyRequest = VerifyRequest<SnarkWorkVerifyId, Vec<Snark>>;
+type BlockVerifyRequest = VerifyRequest<SnarkBlockVerifyId, VerifiableBlockWithHash>;
+
+#[derive(Default)]
+pub struct SnarkVerifier {
+    work: Option<std_mpsc::Sender<WorkVerifyRequest>>,
+    block: Option<std_mpsc::Sender<BlockVerifyRequest>>,
+}
+
+impl SnarkVerifier {
+    pub fn work_verify(
+        &mut self,
+        event_sender: &mpsc::UnboundedSender<Event>,
+        req_id: SnarkWorkVerifyId,
+        verifier_index: Arc<VerifierIndex>,
+        verifier_srs: Arc<Mutex<VerifierSRS>>,
+        work: Vec<Snark>,
+    ) {
+        let sender = self.work.get_or_insert_with(|| {
+            let event_sender = event_sender.clone();
+            spawn_batch_verifier("openmina_work_verifier", move |batch| {
+                work_verify_batch(&event_sender, batch)
+            })
+        });
+        let _ = sender.send(VerifyRequest {
+            req_id,
+            verifier_index,
+            verifier_srs,
+            data: work,
+        });
+    }
+
+    pub fn block_verify(
+        &mut self,
+        event_sender: &mpsc::UnboundedSender<Event>,
+        req_id: SnarkBlockVerifyId,
+        verifier_index: Arc<VerifierIndex>,
+        verifier_srs: Arc<Mutex<VerifierSRS>>,
+        block: VerifiableBlockWithHash,
+    ) {
+        let sender = self.block.get_or_insert_with(|| {
+            let event_sender = event_sender.clone();
+            spawn_batch_verifier("openmina_block_verifier", move |batch| {
+                block_verify_batch(&event_sender, batch)
+            })
+        });
+        let _ = sender.send(VerifyRequest {
+            req_id,
+            verifier_index,
+            verifier_srs,
+            data: block,
+        });
+    }
+}
+
+fn spawn_batch_verifier<R, F>(name: &str, verify: F) -> std_mpsc::Sender<R>
+where
+    R: 'static + Send,
+    F: 'static + Send + Sync + Fn(Vec<R>),
+{
+    let (tx, rx) = std_mpsc::channel();
+    let verify = Arc::new(verify);
+    std::thread::Builder::new()
+        .name(name.to_owned())
+        .spawn(move || {
+            while let Ok(first) = rx.recv() {
+                let batch = collect_batch(&rx, first);
+                let verify = verify.clone();
+                rayon::spawn_fifo(move || verify(batch));
+            }
+        })
+        .unwrap();
+    tx
+}
+
+/// Collects requests for a batch, starting with `first`. Waits up to
+/// [`BATCH_WINDOW`] for more, unless `first` is the only request queued.
+fn collect_batch<R>(rx: &std_mpsc::Receiver<R>, first: R) -> Vec<R> {
+    let mut batch = vec![first];
+    batch.extend(rx.try_iter().take(BATCH_MAX_LEN - 1));
+    if batch.len() == 1 {
+        return batch;
+    }
+    let deadline = Instant::now() + BATCH_WINDOW;
+    while batch.len() < BATCH_MAX_LEN {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(timeout) {
+            Ok(req) => batch.push(req),
+            Err(_) => break,
+        }
+    }
+    batch
+}
+
+/// Verifies all requests in the `batch` together and if that fails,
+/// each of them separately. Returns whether each request is valid.
+fn verify_with_fallback<R, F>(batch: &[R], verify: F) -> Vec<bool>
+where
+    F: Fn(&[&R]) -> bool,
+{
+    if verify(&batch.iter().collect::<Vec<_>>()) {
+        return vec![true; batch.len()];
+    }
+    if batch.len() == 1 {
+        return vec![false];
+    }
+    batch.iter().map(|req| verify(&[req])).collect()
+}
+
+fn work_verify_batch(event_sender: &mpsc::UnboundedSender<Event>, batch: Vec<WorkVerifyRequest>) {
+    let Some(first) = batch.first() else {
+        return;
+    };
+    // verifier index and srs come from the node's state, so they are
+    // the same for all requests.
+    let verifier_index = first.verifier_index.clone();
+    let verifier_srs = first.verifier_srs.clone();
+    let verifier_srs = verifier_srs.lock().expect("Failed to lock SRS");
+
+    let verify = |work: &[&Snark]| verify_work(work, &verifier_index, &verifier_srs);
+
+    let results = verify_with_fallback(&batch, |reqs| {
+        verify(&reqs.iter().flat_map(|req| &req.data).collect::<Vec<_>>())
+    });
+
+    for (req, is_valid) in batch.iter().zip(results) {
+        let result = if is_valid {
+            Ok(())
+        } else {
+            // Not proving each snark again just to find the invalid one,
+            // the whole request is rejected anyway.
+            let job_ids = req.data.iter().map(|work| work.job_id().to_string());
+            let snarkers = req
+                .data
+                .iter()
+                .map(|work| work.snarker.to_string())
+                .collect::<BTreeSet<_>>();
+            openmina_core::log::warn!(openmina_core::log::system_time();
+                kind = "SnarkWorkVerifyError",
+                summary = format!("invalid snarks in request {}", req.req_id),
+                snarkers = snarkers.into_iter().collect::<Vec<_>>().join(","),
+                job_ids = job_ids.collect::<Vec<_>>().join(","));
+            Err(SnarkWorkVerifyError::VerificationFailed)
+        };
+        let _ = event_sender.send(SnarkEvent::WorkVerify(req.req_id, result).into());
+    }
+}
+
+/// Verifies proofs of all snarks in the `work` together.
+fn verify_work(
+    work: &[&Snark],
+    verifier_index: &VerifierIndex,
+    verifier_srs: &VerifierSRS,
+) -> bool {
+    let conv = |proof: &LedgerProofProdStableV2| {
+        (
+            Statement::<SokDigest>::from(&proof.0.statement),
+            proof.proof.clone(),
+        )
+    };
+    let proofs = work
+        .iter()
+        .flat_map(|work| match &*work.proofs {
+            TransactionSnarkWorkTStableV2Proofs::One(v) => [Some(conv(v)), None],
+            TransactionSnarkWorkTStableV2Proofs::Two((v1, v2)) => [Some(conv(v1)), Some(conv(v2))],
+        })
+        .flatten()
+        .collect::<Vec<_>>();
+    ledger::proofs::verification::verify_transaction(
+        proofs.iter().map(|(v1, v2)| (v1, v2)),
+        verifier_index,
+        verifier_srs,
+    )
+}
+
+fn block_verify_batch(event_sender: &mpsc::UnboundedSender<Event>, batch: Vec<BlockVerifyRequest>) {
+    let Some(first) = batch.first() else {
+        return;
+    };
+    let verifier_index = first.verifier_index.clone();
+    let verifier_srs = first.verifier_srs.clone();
+    let verifier_srs = verifier_srs.lock().expect("Failed to lock the SRS");
+
+    let results = verify_with_fallback(&batch, |reqs| {
+        ledger::proofs::verification::verify_blocks(
+            reqs.iter().map(|req| req.data.header_ref()),
+            &verifier_index,
+            &verifier_srs,
+        )
+    });
+
+    for (req, is_valid) in batch.iter().zip(results) {
+        let result = if is_valid {
+            Ok(())
+        } else {
+            Err(SnarkBlockVerifyError::VerificationFailed)
+        };
+        let _ = event_sender.send(SnarkEvent::BlockVerify(req.req_id, result).into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use mina_p2p_messages::binprot::BinProtRead;
+    use mina_p2p_messages::gossip::GossipNetMessageV2;
+    use mina_p2p_messages::v2::NetworkPoolSnarkPoolDiffVersionedStableV2;
+    use node::snark::{get_srs, get_verifier_index, VerifierKind};
+
+    use super::*;
+
+    fn snark() -> Snark {
+        const SNARK_POOL_DIFF: &[u8] =
+            include_bytes!("../../../mina-p2p-messages/tests/files/v2/gossip/snark_pool_diff.bin");
+        let GossipNetMessageV2::SnarkPoolDiff {
+            message: NetworkPoolSnarkPoolDiffVersionedStableV2::AddSolvedWork(work),
+            ..
+        } = GossipNetMessageV2::binprot_read(&mut &SNARK_POOL_DIFF[..]).unwrap()
+        else {
+            unreachable!()
+        };
+        Snark::from(work.1)
+    }
+
+    /// Same snark, but with the statement not matching its proofs.
+    fn tampered(snark: &Snark) -> Snark {
+        let mut snark = snark.clone();
+        let tamper = |proof: &mut LedgerProofProdStableV2| {
+            let sok_digest = &mut proof.0.statement.sok_digest;
+            let mut bytes = sok_digest.to_vec();
+            bytes[0] ^= 1;
+            *sok_digest = bytes.into();
+        };
+        match Arc::make_mut(&mut snark.proofs) {
+            TransactionSnarkWorkTStableV2Proofs::One(v) => tamper(v),
+            TransactionSnarkWorkTStableV2Proofs::Two((v1, _)) => tamper(v1),
+        }
+        snark
+    }
+
+    #[test]
+    fn fallback_not_needed_for_valid_batch() {
+        let calls = Cell::new(0);
+        let results = verify_with_fallback(&[1, 2, 3], |_| {
+            calls.set(calls.get() + 1);
+            true
+        });
+        assert_eq!(results, vec![true; 3]);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn fallback_finds_invalid_request() {
+        let results = verify_with_fallback(&[1, 2, 3, 4], |reqs| reqs.iter().all(|req| **req != 3));
+        assert_eq!(results, vec![true, true, false, true]);
+
+        let results = verify_with_fallback(&[3], |reqs| reqs.iter().all(|req| **req != 3));
+        assert_eq!(results, vec![false]);
+    }
+
+    #[test]
+    fn lone_request_not_delayed() {
+        let (tx, rx) = std_mpsc::channel::<usize>();
+        let t = Instant::now();
+        assert_eq!(collect_batch(&rx, 0), vec![0]);
+        assert!(t.elapsed() < BATCH_WINDOW);
+        drop(tx);
+    }
+
+    #[test]
+    fn queued_requests_batched_up_to_max_len() {
+        let (tx, rx) = std_mpsc::channel();
+        (0..BATCH_MAX_LEN + 5).for_each(|i| tx.send(i).unwrap());
+
+        let first = rx.recv().unwrap();
+        let batch = collect_batch(&rx, first);
+        assert_eq!(batch, (0..BATCH_MAX_LEN).collect::<Vec<_>>());
+        assert_eq!(rx.try_iter().count(), 5);
+    }
+
+    #[test]
+    fn work_batch_verification_agrees_with_individual() {
+        let verifier_index: Arc<VerifierIndex> =
+            get_verifier_index(VerifierKind::Transaction).into();
+        let verifier_srs = get_srs();
+        let verifier_srs = verifier_srs.lock().unwrap();
+        let verify = |work: &[&Snark]| verify_work(work, &verifier_index, &verifier_srs);
+
+        let valid = snark();
+        let invalid = tampered(&valid);
+        let batch = vec![valid.clone(), invalid, valid];
+
+        let individual = batch.iter().map(|work| verify(&[work])).collect::<Vec<_>>();
+        assert!(!individual[1]);
+        assert_eq!(individual[0], individual[2]);
+        assert!(!verify(&batch.iter().collect::<Vec<_>>()));
+        assert_eq!(verify(&[&batch[0], &batch[2]]), individual[0]);
+
+        assert_eq!(verify_with_fallback(&batch, verify), individual);
+    }
+}
@@ -3,3 +3,6 @@ pub use secret_key::AccountSecretKey;
 
 mod public_key;
 pub use public_key::AccountPublicKey;
+
+mod signer;
+pub use signer::*;
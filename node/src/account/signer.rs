@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+
+use mina_signer::Keypair;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use vrf::{VrfEvaluationInput, VrfEvaluationOutput, VrfWonSlot};
+
+use crate::block_producer::vrf_evaluator::VrfEvaluatorInput;
+
+use super::{AccountPublicKey, AccountSecretKey};
+
+/// Performs operations which need private keys of our accounts (block
+/// producer keys), so that the node itself doesn't have to hold them.
+///
+/// Methods are blocking, as a remote signer might be behind a socket.
+pub trait AccountSigner: Send + Sync {
+    /// Public keys of the accounts that the signer holds private keys for.
+    fn public_keys(&self) -> Result<Vec<AccountPublicKey>, AccountSignerError>;
+
+    /// Evaluates vrf for `input.producer` over `input.slots` and returns
+    /// the won slots.
+    fn evaluate_vrf(
+        &self,
+        input: &VrfEvaluatorInput,
+    ) -> Result<Vec<VrfWonSlot>, AccountSignerError>;
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, thiserror::Error)]
+pub enum AccountSignerError {
+    #[error("signer doesn't hold the key for account {_0}")]
+    UnknownAccount(AccountPublicKey),
+    #[error("vrf evaluation failed: {_0}")]
+    Vrf(String),
+    #[error("I/O error: {_0}")]
+    IOError(String),
+    #[error("remote signer error: {_0}")]
+    Remote(String),
+}
+
+/// Signer holding the keys in the node process.
+#[derive(Default, Clone)]
+pub struct AccountLocalSigner {
+    keypairs: BTreeMap<AccountPublicKey, Keypair>,
+}
+
+impl AccountLocalSigner {
+    pub fn new(keys: impl IntoIterator<Item = AccountSecretKey>) -> Self {
+        let keypairs = keys
+            .into_iter()
+            .map(|key| (key.public_key(), key.into()))
+            .collect();
+        Self { keypairs }
+    }
+
+    fn keypair(&self, account: &AccountPublicKey) -> Result<&Keypair, AccountSignerError> {
+        self.keypairs
+            .get(account)
+            .ok_or_else(|| AccountSignerError::UnknownAccount(account.clone()))
+    }
+}
+
+impl AccountSigner for AccountLocalSigner {
+    fn public_keys(&self) -> Result<Vec<AccountPublicKey>, AccountSignerError> {
+        Ok(self.keypairs.keys().cloned().collect())
+    }
+
+    fn evaluate_vrf(
+        &self,
+        input: &VrfEvaluatorInput,
+    ) -> Result<Vec<VrfWonSlot>, AccountSignerError> {
+        let keypair = self.keypair(&input.producer)?;
+
+        // slots are evaluated in parallel, delegators of a slot in order.
+        input
+            .slots
+            .clone()
+            .into_par_iter()
+            .filter_map(|global_slot| {
+                input
+                    .delegator_table
+                    .iter()
+                    .find_map(|(index, (account, stake))| {
+                        let vrf_input = VrfEvaluationInput::new(
+                            keypair.clone(),
+                            input.epoch_seed.clone(),
+                            account.to_string(),
+                            global_slot,
+                            index.clone(),
+                            (*stake).into(),
+                            input.total_currency.into(),
+                        );
+                        // the first delegate that won the slot
+                        match vrf::evaluate_vrf(vrf_input) {
+                            Ok(VrfEvaluationOutput::SlotWon(won_slot)) => Some(Ok(won_slot)),
+                            Ok(VrfEvaluationOutput::SlotLost(_)) => None,
+                            Err(err) => Some(Err(AccountSignerError::Vrf(err.to_string()))),
+                        }
+                    })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::block_producer::vrf_evaluator::DelegatorTable;
+
+    use super::*;
+
+    fn key(n: u8) -> AccountSecretKey {
+        let mut bytes = [0; 32];
+        bytes[31] = n;
+        AccountSecretKey::from_bytes(&bytes).unwrap()
+    }
+
+    #[test]
+    fn unknown_account() {
+        let signer = AccountLocalSigner::new([key(1)]);
+        assert_eq!(signer.public_keys().unwrap(), vec![key(1).public_key()]);
+
+        let input = VrfEvaluatorInput::new(
+            key(3).public_key(),
+            "seed".to_owned(),
+            Arc::new(DelegatorTable::new()),
+            0..=0,
+            1_000,
+            "jx5YAT36bv62M8mPcREYYfZWXaKqqMzDCP8wmc21uf4CfDKAHCr"
+                .parse()
+                .unwrap(),
+        );
+        let err = signer.evaluate_vrf(&input).unwrap_err();
+        let AccountSignerError::UnknownAccount(account) = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(account, key(3).public_key());
+    }
+}
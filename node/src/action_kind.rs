@@ -38,6 +38,7 @@ use crate::snark::work_verify::SnarkWorkVerifyAction;
 use crate::snark::SnarkAction;
 use crate::snark_pool::candidate::SnarkPoolCandidateAction;
 use crate::snark_pool::SnarkPoolAction;
+use crate::transition_frontier::genesis::TransitionFrontierGenesisAction;
 use crate::transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedAction;
 use crate::transition_frontier::sync::ledger::staged::TransitionFrontierSyncLedgerStagedAction;
 use crate::transition_frontier::sync::ledger::TransitionFrontierSyncLedgerAction;
@@ -54,10 +55,12 @@ use crate::{Action, ActionKindGet, CheckTimeoutsAction};
 pub enum ActionKind {
     None,
     BlockProducerBestTipUpdate,
+    BlockProducerBlockDryRunFinished,
     BlockProducerBlockInject,
     BlockProducerBlockInjected,
     BlockProducerBlockProduced,
     BlockProducerBlockUnprovenBuild,
+    BlockProducerHistoryRestore,
     BlockProducerStagedLedgerDiffCreateInit,
     BlockProducerStagedLedgerDiffCreatePending,
     BlockProducerStagedLedgerDiffCreateSuccess,
@@ -68,6 +71,8 @@ pub enum ActionKind {
     BlockProducerWonSlotWait,
     BlockProducerVrfEvaluatorEpochDataUpdate,
     BlockProducerVrfEvaluatorEvaluateVrf,
+    BlockProducerVrfEvaluatorEvaluationFailed,
+    BlockProducerVrfEvaluatorEvaluationRetry,
     BlockProducerVrfEvaluatorEvaluationSuccess,
     BlockProducerVrfEvaluatorUpdateProducerAndDelegates,
     BlockProducerVrfEvaluatorUpdateProducerAndDelegatesSuccess,
@@ -77,6 +82,8 @@ pub enum ActionKind {
     ConsensusBlockReceived,
     ConsensusBlockSnarkVerifyPending,
     ConsensusBlockSnarkVerifySuccess,
+    ConsensusClockSkewDetected,
+    ConsensusClockSkewResolved,
     ConsensusDetectForkRange,
     ConsensusLongRangeForkResolve,
     ConsensusPrune,
@@ -177,7 +184,12 @@ pub enum ActionKind {
     P2pListenNew,
     P2pPeerBestTipUpdate,
     P2pPeerReady,
+    RpcAccountProofGet,
     RpcActionStatsGet,
+    RpcBlockGet,
+    RpcBlockProducerEpochDataGet,
+    RpcBlockProducerHistoryGet,
+    RpcBlockProducerScheduleGet,
     RpcFinish,
     RpcGlobalStateGet,
     RpcHealthCheck,
@@ -193,13 +205,22 @@ pub enum ActionKind {
     RpcPeersGet,
     RpcReadinessCheck,
     RpcScanStateSummaryGet,
+    RpcSnarkMarketStatsGet,
     RpcSnarkPoolAvailableJobsGet,
     RpcSnarkPoolJobGet,
     RpcSnarkerConfigGet,
     RpcSnarkerJobCommit,
     RpcSnarkerJobSpec,
+    RpcSnarkerWorkGet,
+    RpcSnarkerWorkSubmit,
     RpcSnarkerWorkersGet,
+    RpcSnarkersReliabilityGet,
     RpcSyncStatsGet,
+    RpcTransitionFrontierStatusGet,
+    RpcWatchedAccountBlocksGet,
+    RpcWatchedAccountsAdd,
+    RpcWatchedAccountsGet,
+    RpcWatchedAccountsRemove,
     SnarkBlockVerifyError,
     SnarkBlockVerifyFinish,
     SnarkBlockVerifyInit,
@@ -213,6 +234,8 @@ pub enum ActionKind {
     SnarkPoolJobsUpdate,
     SnarkPoolP2pSend,
     SnarkPoolP2pSendAll,
+    SnarkPoolRestore,
+    SnarkPoolSnarkerFeeUpdate,
     SnarkPoolWorkAdd,
     SnarkPoolCandidateInfoReceived,
     SnarkPoolCandidatePeerPrune,
@@ -229,6 +252,11 @@ pub enum ActionKind {
     SnarkWorkVerifyInit,
     SnarkWorkVerifyPending,
     SnarkWorkVerifySuccess,
+    TransitionFrontierGenesisInject,
+    TransitionFrontierGenesisLedgerLoadInit,
+    TransitionFrontierGenesisLedgerLoadPending,
+    TransitionFrontierGenesisLedgerLoadSuccess,
+    TransitionFrontierGenesisProduce,
     TransitionFrontierSyncBestTipUpdate,
     TransitionFrontierSyncBlocksFetchSuccess,
     TransitionFrontierSyncBlocksNextApplyInit,
@@ -285,11 +313,12 @@ pub enum ActionKind {
     WatchedAccountsLedgerInitialStateGetPending,
     WatchedAccountsLedgerInitialStateGetRetry,
     WatchedAccountsLedgerInitialStateGetSuccess,
+    WatchedAccountsRemove,
     WatchedAccountsTransactionsIncludedInBlock,
 }
 
 impl ActionKind {
-    pub const COUNT: u16 = 234;
+    pub const COUNT: u16 = 262;
 }
 
 impl std::fmt::Display for ActionKind {
@@ -367,6 +396,8 @@ impl ActionKindGet for ConsensusAction {
             Self::LongRangeForkResolve { .. } => ActionKind::ConsensusLongRangeForkResolve,
             Self::BestTipUpdate { .. } => ActionKind::ConsensusBestTipUpdate,
             Self::Prune => ActionKind::ConsensusPrune,
+            Self::ClockSkewDetected { .. } => ActionKind::ConsensusClockSkewDetected,
+            Self::ClockSkewResolved => ActionKind::ConsensusClockSkewResolved,
         }
     }
 }
@@ -374,6 +405,7 @@ impl ActionKindGet for ConsensusAction {
 impl ActionKindGet for TransitionFrontierAction {
     fn kind(&self) -> ActionKind {
         match self {
+            Self::Genesis(a) => a.kind(),
             Self::Sync(a) => a.kind(),
             Self::Synced(a) => a.kind(),
         }
@@ -385,6 +417,8 @@ impl ActionKindGet for SnarkPoolAction {
         match self {
             Self::Candidate(a) => a.kind(),
             Self::JobsUpdate { .. } => ActionKind::SnarkPoolJobsUpdate,
+            Self::Restore { .. } => ActionKind::SnarkPoolRestore,
+            Self::SnarkerFeeUpdate { .. } => ActionKind::SnarkPoolSnarkerFeeUpdate,
             Self::AutoCreateCommitment => ActionKind::SnarkPoolAutoCreateCommitment,
             Self::CommitmentCreate { .. } => ActionKind::SnarkPoolCommitmentCreate,
             Self::CommitmentAdd { .. } => ActionKind::SnarkPoolCommitmentAdd,
@@ -400,18 +434,18 @@ impl ActionKindGet for SnarkPoolAction {
 impl ActionKindGet for ExternalSnarkWorkerAction {
     fn kind(&self) -> ActionKind {
         match self {
-            Self::Start => ActionKind::ExternalSnarkWorkerStart,
-            Self::Started => ActionKind::ExternalSnarkWorkerStarted,
+            Self::Start { .. } => ActionKind::ExternalSnarkWorkerStart,
+            Self::Started { .. } => ActionKind::ExternalSnarkWorkerStarted,
             Self::StartTimeout { .. } => ActionKind::ExternalSnarkWorkerStartTimeout,
-            Self::Kill => ActionKind::ExternalSnarkWorkerKill,
-            Self::Killed => ActionKind::ExternalSnarkWorkerKilled,
+            Self::Kill { .. } => ActionKind::ExternalSnarkWorkerKill,
+            Self::Killed { .. } => ActionKind::ExternalSnarkWorkerKilled,
             Self::SubmitWork { .. } => ActionKind::ExternalSnarkWorkerSubmitWork,
             Self::WorkResult { .. } => ActionKind::ExternalSnarkWorkerWorkResult,
             Self::WorkError { .. } => ActionKind::ExternalSnarkWorkerWorkError,
             Self::WorkTimeout { .. } => ActionKind::ExternalSnarkWorkerWorkTimeout,
-            Self::CancelWork => ActionKind::ExternalSnarkWorkerCancelWork,
-            Self::WorkCancelled => ActionKind::ExternalSnarkWorkerWorkCancelled,
-            Self::PruneWork => ActionKind::ExternalSnarkWorkerPruneWork,
+            Self::CancelWork { .. } => ActionKind::ExternalSnarkWorkerCancelWork,
+            Self::WorkCancelled { .. } => ActionKind::ExternalSnarkWorkerWorkCancelled,
+            Self::PruneWork { .. } => ActionKind::ExternalSnarkWorkerPruneWork,
             Self::Error { .. } => ActionKind::ExternalSnarkWorkerError,
         }
     }
@@ -438,6 +472,8 @@ impl ActionKindGet for BlockProducerAction {
             Self::BlockProduced => ActionKind::BlockProducerBlockProduced,
             Self::BlockInject => ActionKind::BlockProducerBlockInject,
             Self::BlockInjected => ActionKind::BlockProducerBlockInjected,
+            Self::BlockDryRunFinished => ActionKind::BlockProducerBlockDryRunFinished,
+            Self::HistoryRestore { .. } => ActionKind::BlockProducerHistoryRestore,
         }
     }
 }
@@ -448,6 +484,7 @@ impl ActionKindGet for RpcAction {
             Self::GlobalStateGet { .. } => ActionKind::RpcGlobalStateGet,
             Self::ActionStatsGet { .. } => ActionKind::RpcActionStatsGet,
             Self::SyncStatsGet { .. } => ActionKind::RpcSyncStatsGet,
+            Self::SnarkMarketStatsGet { .. } => ActionKind::RpcSnarkMarketStatsGet,
             Self::PeersGet { .. } => ActionKind::RpcPeersGet,
             Self::P2pConnectionOutgoingInit { .. } => ActionKind::RpcP2pConnectionOutgoingInit,
             Self::P2pConnectionOutgoingPending { .. } => {
@@ -475,8 +512,21 @@ impl ActionKindGet for RpcAction {
             Self::SnarkerJobCommit { .. } => ActionKind::RpcSnarkerJobCommit,
             Self::SnarkerJobSpec { .. } => ActionKind::RpcSnarkerJobSpec,
             Self::SnarkerWorkersGet { .. } => ActionKind::RpcSnarkerWorkersGet,
+            Self::SnarkersReliabilityGet { .. } => ActionKind::RpcSnarkersReliabilityGet,
+            Self::SnarkerWorkGet { .. } => ActionKind::RpcSnarkerWorkGet,
+            Self::SnarkerWorkSubmit { .. } => ActionKind::RpcSnarkerWorkSubmit,
             Self::HealthCheck { .. } => ActionKind::RpcHealthCheck,
             Self::ReadinessCheck { .. } => ActionKind::RpcReadinessCheck,
+            Self::WatchedAccountsAdd { .. } => ActionKind::RpcWatchedAccountsAdd,
+            Self::WatchedAccountsRemove { .. } => ActionKind::RpcWatchedAccountsRemove,
+            Self::WatchedAccountsGet { .. } => ActionKind::RpcWatchedAccountsGet,
+            Self::WatchedAccountBlocksGet { .. } => ActionKind::RpcWatchedAccountBlocksGet,
+            Self::AccountProofGet { .. } => ActionKind::RpcAccountProofGet,
+            Self::BlockGet { .. } => ActionKind::RpcBlockGet,
+            Self::TransitionFrontierStatusGet { .. } => ActionKind::RpcTransitionFrontierStatusGet,
+            Self::BlockProducerScheduleGet { .. } => ActionKind::RpcBlockProducerScheduleGet,
+            Self::BlockProducerHistoryGet { .. } => ActionKind::RpcBlockProducerHistoryGet,
+            Self::BlockProducerEpochDataGet { .. } => ActionKind::RpcBlockProducerEpochDataGet,
             Self::Finish { .. } => ActionKind::RpcFinish,
         }
     }
@@ -486,6 +536,7 @@ impl ActionKindGet for WatchedAccountsAction {
     fn kind(&self) -> ActionKind {
         match self {
             Self::Add { .. } => ActionKind::WatchedAccountsAdd,
+            Self::Remove { .. } => ActionKind::WatchedAccountsRemove,
             Self::LedgerInitialStateGetInit { .. } => {
                 ActionKind::WatchedAccountsLedgerInitialStateGetInit
             }
@@ -603,6 +654,20 @@ impl ActionKindGet for SnarkWorkVerifyAction {
     }
 }
 
+impl ActionKindGet for TransitionFrontierGenesisAction {
+    fn kind(&self) -> ActionKind {
+        match self {
+            Self::LedgerLoadInit => ActionKind::TransitionFrontierGenesisLedgerLoadInit,
+            Self::LedgerLoadPending => ActionKind::TransitionFrontierGenesisLedgerLoadPending,
+            Self::LedgerLoadSuccess { .. } => {
+                ActionKind::TransitionFrontierGenesisLedgerLoadSuccess
+            }
+            Self::Produce => ActionKind::TransitionFrontierGenesisProduce,
+            Self::Inject => ActionKind::TransitionFrontierGenesisInject,
+        }
+    }
+}
+
 impl ActionKindGet for TransitionFrontierSyncAction {
     fn kind(&self) -> ActionKind {
         match self {
@@ -680,6 +745,8 @@ impl ActionKindGet for BlockProducerVrfEvaluatorAction {
             Self::EvaluationSuccess { .. } => {
                 ActionKind::BlockProducerVrfEvaluatorEvaluationSuccess
             }
+            Self::EvaluationFailed { .. } => ActionKind::BlockProducerVrfEvaluatorEvaluationFailed,
+            Self::EvaluationRetry => ActionKind::BlockProducerVrfEvaluatorEvaluationRetry,
             Self::UpdateProducerAndDelegates { .. } => {
                 ActionKind::BlockProducerVrfEvaluatorUpdateProducerAndDelegates
             }
@@ -6,7 +6,10 @@ use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 
 use super::vrf_evaluator::BlockProducerVrfEvaluatorAction;
-use super::{BlockProducerCurrentState, BlockProducerWonSlot, BlockProducerWonSlotDiscardReason};
+use super::{
+    BlockProducerCurrentState, BlockProducerHistory, BlockProducerWonSlot,
+    BlockProducerWonSlotDiscardReason,
+};
 
 pub type BlockProducerActionWithMeta = redux::ActionWithMeta<BlockProducerAction>;
 pub type BlockProducerActionWithMetaRef<'a> = redux::ActionWithMeta<&'a BlockProducerAction>;
@@ -38,6 +41,13 @@ pub enum BlockProducerAction {
     BlockProduced,
     BlockInject,
     BlockInjected,
+    /// Instead of [`BlockProducerAction::BlockInject`] in dry run mode.
+    BlockDryRunFinished,
+    /// Restore history of won slots persisted by the previous run of
+    /// the node.
+    HistoryRestore {
+        history: BlockProducerHistory,
+    },
 }
 
 impl redux::EnablingCondition<crate::State> for BlockProducerAction {
@@ -53,7 +63,7 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
                     }
                     let best_tip = state.transition_frontier.best_tip()?;
                     let cur_global_slot = state.cur_global_slot()?;
-                    let next = this.vrf_evaluator.next_won_slot(cur_global_slot, best_tip);
+                    let next = this.next_won_slot(cur_global_slot, best_tip);
                     Some(next.is_some())
                 })
                 .is_some_and(|v| v),
@@ -67,10 +77,11 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
                     && won_slot > best_tip
             }),
             BlockProducerAction::WonSlotWait => state.block_producer.with(false, |this| {
-                this.current.won_slot_should_wait(state.time())
+                this.current.won_slot_should_wait(state.slot_timing_time())
             }),
             BlockProducerAction::WonSlotProduceInit => state.block_producer.with(false, |this| {
-                this.current.won_slot_should_produce(state.time())
+                this.current
+                    .won_slot_should_produce(state.slot_timing_time())
             }),
             BlockProducerAction::StagedLedgerDiffCreateInit => {
                 state.block_producer.with(false, |this| {
@@ -108,12 +119,16 @@ impl redux::EnablingCondition<crate::State> for BlockProducerAction {
                     BlockProducerCurrentState::BlockUnprovenBuilt { .. }
                 )
             }),
-            BlockProducerAction::BlockInject => state.block_producer.with(false, |this| {
-                matches!(this.current, BlockProducerCurrentState::Produced { .. })
-            }),
+            BlockProducerAction::BlockInject => state.block_producer.produced_block_should_inject(),
             BlockProducerAction::BlockInjected => state.block_producer.with(false, |this| {
                 matches!(this.current, BlockProducerCurrentState::Produced { .. })
             }),
+            BlockProducerAction::BlockDryRunFinished => {
+                state.block_producer.produced_block_should_finish_dry_run()
+            }
+            BlockProducerAction::HistoryRestore { .. } => {
+                state.block_producer.with(false, |_| true)
+            }
             BlockProducerAction::WonSlotDiscard { reason } => {
                 let current_reason = state.block_producer.with(None, |bp| {
                     let best_tip = state.transition_frontier.best_tip()?;
@@ -3,23 +3,36 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BlockProducerConfig {
-    pub pub_key: NonZeroCurvePoint,
+    /// Keys we produce blocks for. Each of them has its own vrf evaluator.
+    pub pub_keys: Vec<NonZeroCurvePoint>,
     pub custom_coinbase_receiver: Option<NonZeroCurvePoint>,
     pub proposed_protocol_version: Option<ProtocolVersionStableV2>,
+    /// Go through block production for won slots, but never inject the
+    /// produced block into the transition frontier, so it's never
+    /// broadcasted either.
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 impl BlockProducerConfig {
-    pub fn new(pub_key: NonZeroCurvePoint) -> Self {
+    pub fn new(pub_keys: Vec<NonZeroCurvePoint>) -> Self {
         Self {
-            pub_key,
+            pub_keys,
             custom_coinbase_receiver: None,
             proposed_protocol_version: None,
+            dry_run: false,
         }
     }
 
-    pub fn coinbase_receiver(&self) -> &NonZeroCurvePoint {
-        self.custom_coinbase_receiver
-            .as_ref()
-            .unwrap_or(&self.pub_key)
+    pub fn is_producer(&self, pub_key: &NonZeroCurvePoint) -> bool {
+        self.pub_keys.contains(pub_key)
+    }
+
+    /// Coinbase receiver for blocks created by `producer`.
+    pub fn coinbase_receiver<'a>(
+        &'a self,
+        producer: &'a NonZeroCurvePoint,
+    ) -> &'a NonZeroCurvePoint {
+        self.custom_coinbase_receiver.as_ref().unwrap_or(producer)
     }
 }
@@ -9,15 +9,24 @@ pub fn block_producer_effects<S: crate::Service>(
     action: BlockProducerActionWithMeta,
 ) {
     let (action, meta) = action.split();
+    let history_changed = matches!(
+        action,
+        BlockProducerAction::BestTipUpdate { .. }
+            | BlockProducerAction::WonSlot { .. }
+            | BlockProducerAction::WonSlotDiscard { .. }
+            | BlockProducerAction::BlockProduced
+            | BlockProducerAction::BlockInjected
+            | BlockProducerAction::BlockDryRunFinished
+            | BlockProducerAction::HistoryRestore { .. }
+    );
 
     match action {
         BlockProducerAction::VrfEvaluator(ref a) => {
             // TODO: does the order matter? can this clone be avoided?
             a.clone().effects(&meta, store);
             match a {
-                BlockProducerVrfEvaluatorAction::EvaluationSuccess { vrf_output, .. } => {
-                    let has_won_slot = matches!(vrf_output, vrf::VrfEvaluationOutput::SlotWon(_));
-                    if has_won_slot {
+                BlockProducerVrfEvaluatorAction::EvaluationSuccess { won_slots, .. } => {
+                    if !won_slots.is_empty() {
                         store.dispatch(BlockProducerAction::WonSlotSearch);
                     }
                 }
@@ -25,21 +34,29 @@ pub fn block_producer_effects<S: crate::Service>(
             }
         }
         BlockProducerAction::BestTipUpdate { best_tip } => {
+            if let Some(history) = store.service.block_producer_history_persisted_take() {
+                store.dispatch(BlockProducerAction::HistoryRestore { history });
+            }
+
             let best_tip_staking_ledger = best_tip.staking_epoch_ledger_hash();
             let protocol_state = &best_tip.block.header.protocol_state.body;
 
-            let vrf_evaluator_current_epoch_ledger = store
-                .state()
-                .block_producer
-                .vrf_evaluator()
-                .and_then(|vrf_evaluator| {
-                    vrf_evaluator
-                        .current_epoch_data
-                        .as_ref()
-                        .map(|epoch_data| &epoch_data.ledger)
-                });
+            let vrf_evaluators_outdated =
+                store
+                    .state()
+                    .block_producer
+                    .vrf_evaluators()
+                    .map_or(false, |vrf_evaluators| {
+                        vrf_evaluators.values().any(|vrf_evaluator| {
+                            vrf_evaluator
+                                .current_epoch_data
+                                .as_ref()
+                                .map(|epoch_data| &epoch_data.ledger)
+                                != Some(best_tip_staking_ledger)
+                        })
+                    });
 
-            if vrf_evaluator_current_epoch_ledger != Some(best_tip_staking_ledger) {
+            if vrf_evaluators_outdated {
                 store.dispatch(BlockProducerVrfEvaluatorAction::EpochDataUpdate {
                     new_epoch_number: protocol_state.consensus_state.epoch_count.as_u32(),
                     epoch_data: protocol_state.consensus_state.staking_epoch_data.clone(),
@@ -59,7 +76,7 @@ pub fn block_producer_effects<S: crate::Service>(
             if let Some(won_slot) = store.state().block_producer.with(None, |bp| {
                 let best_tip = store.state().transition_frontier.best_tip()?;
                 let cur_global_slot = store.state().cur_global_slot()?;
-                bp.vrf_evaluator.next_won_slot(cur_global_slot, best_tip)
+                bp.next_won_slot(cur_global_slot, best_tip)
             }) {
                 store.dispatch(BlockProducerAction::WonSlot { won_slot });
             }
@@ -77,7 +94,10 @@ pub fn block_producer_effects<S: crate::Service>(
             let Some((won_slot, pred_block, coinbase_receiver)) = None.or_else(|| {
                 let pred_block = state.block_producer.current_parent_chain()?.last()?;
                 let won_slot = state.block_producer.current_won_slot()?;
-                let coinbase_receiver = state.block_producer.config()?.coinbase_receiver();
+                let coinbase_receiver = state
+                    .block_producer
+                    .config()?
+                    .coinbase_receiver(&won_slot.producer);
                 Some((won_slot, pred_block, coinbase_receiver))
             }) else {
                 return;
@@ -118,7 +138,9 @@ pub fn block_producer_effects<S: crate::Service>(
             store.dispatch(BlockProducerAction::BlockProduced);
         }
         BlockProducerAction::BlockProduced => {
-            store.dispatch(BlockProducerAction::BlockInject);
+            if !store.dispatch(BlockProducerAction::BlockInject) {
+                store.dispatch(BlockProducerAction::BlockDryRunFinished);
+            }
         }
         BlockProducerAction::BlockInject => {
             let Some((best_tip, root_block, blocks_inbetween)) = None.or_else(|| {
@@ -139,7 +161,7 @@ pub fn block_producer_effects<S: crate::Service>(
                 store.dispatch(BlockProducerAction::BlockInjected);
             }
         }
-        BlockProducerAction::BlockInjected => {
+        BlockProducerAction::BlockInjected | BlockProducerAction::BlockDryRunFinished => {
             store.dispatch(BlockProducerAction::WonSlotSearch);
         }
         BlockProducerAction::WonSlotDiscard { .. } => {
@@ -147,5 +169,12 @@ pub fn block_producer_effects<S: crate::Service>(
         }
         BlockProducerAction::StagedLedgerDiffCreatePending => {}
         BlockProducerAction::WonSlotWait => {}
+        BlockProducerAction::HistoryRestore { .. } => {}
+    }
+
+    if history_changed {
+        if let Some(history) = store.state.get().block_producer.history() {
+            store.service.block_producer_history_persist(history);
+        }
     }
 }
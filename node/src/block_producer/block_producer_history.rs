@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+
+use mina_p2p_messages::v2::StateHash;
+use openmina_core::block::ArcBlockWithHash;
+use serde::{Deserialize, Serialize};
+
+use super::{BlockProducerWonSlot, BlockProducerWonSlotDiscardReason};
+
+/// Number of epochs, including the current one, for which the history
+/// of won slots is kept.
+pub const BLOCK_PRODUCER_HISTORY_EPOCHS: u32 = 4;
+
+/// What happened to the won slots which we tried to produce a block for.
+///
+/// Slots are grouped by epoch and only the last
+/// [`BLOCK_PRODUCER_HISTORY_EPOCHS`] epochs are kept.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct BlockProducerHistory {
+    /// Entries by epoch and global slot.
+    pub epochs: BTreeMap<u32, BTreeMap<u32, BlockProducerHistoryEntry>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BlockProducerHistoryEntry {
+    pub won_slot: BlockProducerWonSlot,
+    pub outcome: BlockProducerWonSlotOutcome,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum BlockProducerWonSlotOutcome {
+    /// Waiting for the slot or producing the block.
+    Pending { time: redux::Timestamp },
+    Produced {
+        time: redux::Timestamp,
+        block_hash: StateHash,
+    },
+    Injected {
+        time: redux::Timestamp,
+        block_hash: StateHash,
+    },
+    Discarded {
+        time: redux::Timestamp,
+        reason: BlockProducerWonSlotDiscardReason,
+    },
+    /// Block was injected, but our best chain switched to a fork
+    /// without it.
+    Orphaned {
+        time: redux::Timestamp,
+        block_hash: StateHash,
+    },
+    /// Block was produced in dry run mode and wasn't injected.
+    DryRun {
+        time: redux::Timestamp,
+        block_hash: StateHash,
+    },
+    /// Node was stopped before the block was injected.
+    Interrupted { time: redux::Timestamp },
+}
+
+impl BlockProducerHistory {
+    pub fn get(&self, won_slot: &BlockProducerWonSlot) -> Option<&BlockProducerHistoryEntry> {
+        self.epochs
+            .get(&won_slot_epoch(won_slot))?
+            .get(&won_slot.global_slot())
+    }
+
+    fn get_mut(
+        &mut self,
+        won_slot: &BlockProducerWonSlot,
+    ) -> Option<&mut BlockProducerHistoryEntry> {
+        self.epochs
+            .get_mut(&won_slot_epoch(won_slot))?
+            .get_mut(&won_slot.global_slot())
+    }
+
+    pub fn won_slot_add(&mut self, time: redux::Timestamp, won_slot: BlockProducerWonSlot) {
+        let epoch = won_slot_epoch(&won_slot);
+        self.epochs.entry(epoch).or_default().insert(
+            won_slot.global_slot(),
+            BlockProducerHistoryEntry {
+                won_slot,
+                outcome: BlockProducerWonSlotOutcome::Pending { time },
+            },
+        );
+
+        self.prune();
+    }
+
+    /// Merges history persisted by the previous run of the node. Entries
+    /// added since the start take precedence. Won slots, which weren't
+    /// finished when the node was stopped, are marked as interrupted.
+    pub fn restore(&mut self, time: redux::Timestamp, restored: BlockProducerHistory) {
+        for (epoch, slots) in restored.epochs {
+            let epoch_slots = self.epochs.entry(epoch).or_default();
+            for (slot, mut entry) in slots {
+                if let BlockProducerWonSlotOutcome::Pending { .. }
+                | BlockProducerWonSlotOutcome::Produced { .. } = entry.outcome
+                {
+                    entry.outcome = BlockProducerWonSlotOutcome::Interrupted { time };
+                }
+                epoch_slots.entry(slot).or_insert(entry);
+            }
+        }
+        self.prune();
+    }
+
+    /// Drops epochs older than the last [`BLOCK_PRODUCER_HISTORY_EPOCHS`].
+    fn prune(&mut self) {
+        let Some(&last_epoch) = self.epochs.keys().next_back() else {
+            return;
+        };
+        let first_kept_epoch = last_epoch.saturating_sub(BLOCK_PRODUCER_HISTORY_EPOCHS - 1);
+        self.epochs = self.epochs.split_off(&first_kept_epoch);
+    }
+
+    pub fn won_slot_produced(
+        &mut self,
+        time: redux::Timestamp,
+        won_slot: &BlockProducerWonSlot,
+        block_hash: StateHash,
+    ) {
+        if let Some(entry) = self.get_mut(won_slot) {
+            entry.outcome = BlockProducerWonSlotOutcome::Produced { time, block_hash };
+        }
+    }
+
+    pub fn won_slot_injected(
+        &mut self,
+        time: redux::Timestamp,
+        won_slot: &BlockProducerWonSlot,
+        block_hash: StateHash,
+    ) {
+        if let Some(entry) = self.get_mut(won_slot) {
+            entry.outcome = BlockProducerWonSlotOutcome::Injected { time, block_hash };
+        }
+    }
+
+    pub fn won_slot_dry_run_finished(
+        &mut self,
+        time: redux::Timestamp,
+        won_slot: &BlockProducerWonSlot,
+        block_hash: StateHash,
+    ) {
+        if let Some(entry) = self.get_mut(won_slot) {
+            entry.outcome = BlockProducerWonSlotOutcome::DryRun { time, block_hash };
+        }
+    }
+
+    /// Discarding after the block was injected only means that we moved
+    /// on from the slot, so it's ignored. Orphaning of injected blocks is
+    /// detected by [`Self::best_chain_update`].
+    pub fn won_slot_discarded(
+        &mut self,
+        time: redux::Timestamp,
+        won_slot: &BlockProducerWonSlot,
+        reason: BlockProducerWonSlotDiscardReason,
+    ) {
+        if let Some(entry) = self.get_mut(won_slot) {
+            if let BlockProducerWonSlotOutcome::Pending { .. }
+            | BlockProducerWonSlotOutcome::Produced { .. } = entry.outcome
+            {
+                entry.outcome = BlockProducerWonSlotOutcome::Discarded { time, reason };
+            }
+        }
+    }
+
+    /// Marks injected blocks, which are no longer part of the best chain,
+    /// as orphaned, and orphaned ones which are part of it again, as
+    /// injected.
+    ///
+    /// Blocks older than the root of the best chain are final.
+    pub fn best_chain_update(&mut self, time: redux::Timestamp, best_chain: &[ArcBlockWithHash]) {
+        let Some(root) = best_chain.first() else {
+            return;
+        };
+        let root_slot = root.global_slot();
+        let entries = self
+            .epochs
+            .values_mut()
+            .flat_map(|slots| slots.range_mut(root_slot..))
+            .map(|(_, entry)| entry);
+
+        for entry in entries {
+            let (block_hash, was_orphaned) = match &entry.outcome {
+                BlockProducerWonSlotOutcome::Injected { block_hash, .. } => (block_hash, false),
+                BlockProducerWonSlotOutcome::Orphaned { block_hash, .. } => (block_hash, true),
+                _ => continue,
+            };
+            let in_best_chain = best_chain.iter().any(|block| block.hash() == block_hash);
+            let block_hash = block_hash.clone();
+            if was_orphaned && in_best_chain {
+                entry.outcome = BlockProducerWonSlotOutcome::Injected { time, block_hash };
+            } else if !was_orphaned && !in_best_chain {
+                entry.outcome = BlockProducerWonSlotOutcome::Orphaned { time, block_hash };
+            }
+        }
+    }
+}
+
+fn won_slot_epoch(won_slot: &BlockProducerWonSlot) -> u32 {
+    won_slot.global_slot() / won_slot.global_slot.slots_per_epoch.as_u32()
+}
+
+#[cfg(test)]
+mod tests {
+    use openmina_core::block::test_block;
+    use vrf::VrfWonSlot;
+
+    use crate::block_producer::vrf_evaluator::VrfWonSlotWithHash;
+
+    use super::*;
+
+    const PRODUCER: &str = "B62qrztYfPinaKqpXaYGY6QJ3SSW2NNKs7SajBLF1iFNXW9BoALN2Aq";
+    const SLOTS_PER_EPOCH: u32 = 7140;
+
+    fn time(secs: u64) -> redux::Timestamp {
+        redux::Timestamp::ZERO + secs * 1_000_000_000
+    }
+
+    fn won_slot(global_slot: u32) -> BlockProducerWonSlot {
+        let won_slot = VrfWonSlot {
+            producer: PRODUCER.to_owned(),
+            winner_account: PRODUCER.to_owned(),
+            vrf_output: String::new(),
+            vrf_output_bytes: vec![0; 32],
+            vrf_fractional: 0.0,
+            global_slot,
+            account_index: ledger::AccountIndex(0),
+            vrf_hash: Box::new([0; 32]).into(),
+        };
+        let ledger = "jx5YAT36bv62M8mPcREYYfZWXaKqqMzDCP8wmc21uf4CfDKAHCr";
+        let won_slot = VrfWonSlotWithHash::new(won_slot, ledger.parse().unwrap());
+        BlockProducerWonSlot::from_vrf_won_slot(&won_slot, redux::Timestamp::ZERO, SLOTS_PER_EPOCH)
+    }
+
+    fn other_hash() -> StateHash {
+        "3NKeMoncuHab5ScarV5ViyF16cJPT4taWNSaTLS64Dp67wuXigPZ"
+            .parse()
+            .unwrap()
+    }
+
+    fn outcome(history: &BlockProducerHistory, global_slot: u32) -> &BlockProducerWonSlotOutcome {
+        &history.get(&won_slot(global_slot)).unwrap().outcome
+    }
+
+    #[test]
+    fn won_slot_add_keeps_last_epochs() {
+        let mut history = BlockProducerHistory::default();
+        for epoch in 0..=BLOCK_PRODUCER_HISTORY_EPOCHS {
+            history.won_slot_add(time(0), won_slot(epoch * SLOTS_PER_EPOCH + 1));
+        }
+        assert_eq!(
+            history.epochs.keys().copied().collect::<Vec<_>>(),
+            (1..=BLOCK_PRODUCER_HISTORY_EPOCHS).collect::<Vec<_>>()
+        );
+        assert!(history.get(&won_slot(1)).is_none());
+    }
+
+    #[test]
+    fn discard_after_injection_is_ignored() {
+        let mut history = BlockProducerHistory::default();
+        let reason = BlockProducerWonSlotDiscardReason::BestTipSuperior;
+        history.won_slot_add(time(0), won_slot(10));
+        history.won_slot_add(time(0), won_slot(20));
+        history.won_slot_injected(time(1), &won_slot(20), other_hash());
+
+        history.won_slot_discarded(time(2), &won_slot(10), reason.clone());
+        history.won_slot_discarded(time(2), &won_slot(20), reason);
+        assert!(matches!(
+            outcome(&history, 10),
+            BlockProducerWonSlotOutcome::Discarded { .. }
+        ));
+        assert!(matches!(
+            outcome(&history, 20),
+            BlockProducerWonSlotOutcome::Injected { .. }
+        ));
+    }
+
+    #[test]
+    fn best_chain_update_orphans_and_restores() {
+        let block = test_block();
+        let slot = block.global_slot();
+        let mut history = BlockProducerHistory::default();
+        history.won_slot_add(time(0), won_slot(slot));
+        history.won_slot_injected(time(1), &won_slot(slot), other_hash());
+
+        history.best_chain_update(time(2), &[block.clone()]);
+        assert!(matches!(
+            outcome(&history, slot),
+            BlockProducerWonSlotOutcome::Orphaned { .. }
+        ));
+
+        // our block is part of the best chain again.
+        let entry = history.get_mut(&won_slot(slot)).unwrap();
+        entry.outcome = BlockProducerWonSlotOutcome::Orphaned {
+            time: time(3),
+            block_hash: block.hash().clone(),
+        };
+        history.best_chain_update(time(4), &[block.clone()]);
+        assert!(matches!(
+            outcome(&history, slot),
+            BlockProducerWonSlotOutcome::Injected { time: t, .. } if *t == time(4)
+        ));
+
+        // blocks before the root are final.
+        history.won_slot_add(time(5), won_slot(slot - 1));
+        history.won_slot_injected(time(5), &won_slot(slot - 1), other_hash());
+        history.best_chain_update(time(6), &[block]);
+        assert!(matches!(
+            outcome(&history, slot - 1),
+            BlockProducerWonSlotOutcome::Injected { .. }
+        ));
+    }
+
+    #[test]
+    fn restore_merges_and_interrupts_unfinished() {
+        let mut restored = BlockProducerHistory::default();
+        restored.won_slot_add(time(0), won_slot(10));
+        restored.won_slot_add(time(0), won_slot(20));
+        restored.won_slot_add(time(0), won_slot(30));
+        restored.won_slot_produced(time(1), &won_slot(20), other_hash());
+        restored.won_slot_injected(time(1), &won_slot(30), other_hash());
+
+        let mut history = BlockProducerHistory::default();
+        history.won_slot_add(time(5), won_slot(10));
+        history.restore(time(6), restored);
+
+        assert!(matches!(
+            outcome(&history, 10),
+            BlockProducerWonSlotOutcome::Pending { time: t } if *t == time(5)
+        ));
+        assert!(matches!(
+            outcome(&history, 20),
+            BlockProducerWonSlotOutcome::Interrupted { time: t } if *t == time(6)
+        ));
+        assert!(matches!(
+            outcome(&history, 30),
+            BlockProducerWonSlotOutcome::Injected { .. }
+        ));
+    }
+
+    #[test]
+    fn restore_keeps_last_epochs() {
+        let mut restored = BlockProducerHistory::default();
+        restored.won_slot_add(time(0), won_slot(1));
+
+        let mut history = BlockProducerHistory::default();
+        history.won_slot_add(
+            time(0),
+            won_slot(BLOCK_PRODUCER_HISTORY_EPOCHS * SLOTS_PER_EPOCH),
+        );
+        history.restore(time(1), restored);
+        assert!(history.get(&won_slot(1)).is_none());
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let mut history = BlockProducerHistory::default();
+        history.won_slot_add(time(0), won_slot(10));
+        history.won_slot_injected(time(1), &won_slot(10), other_hash());
+
+        let json = serde_json::to_string(&history).unwrap();
+        let decoded: BlockProducerHistory = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+    }
+}
@@ -43,10 +43,16 @@ impl BlockProducerEnabled {
         let (action, meta) = action.split();
         match action {
             BlockProducerAction::VrfEvaluator(action) => {
-                self.vrf_evaluator.reducer(meta.with_action(action))
+                // actions without a producer apply to all of our keys.
+                let action_producer = action.producer();
+                for (producer, vrf_evaluator) in self.vrf_evaluators.iter_mut() {
+                    if action_producer.map_or(true, |p| p == producer) {
+                        vrf_evaluator.reducer(meta.clone().with_action(action), producer);
+                    }
+                }
             }
             BlockProducerAction::BestTipUpdate { best_tip } => {
-                self.vrf_evaluator.current_best_tip_slot = best_tip
+                let best_tip_slot = best_tip
                     .block
                     .header
                     .protocol_state
@@ -56,14 +62,24 @@ impl BlockProducerEnabled {
                     .slot_number
                     .as_u32();
 
-                // set the genesis timestamp on the first best tip update
-                // TODO: move/remove once we can generate the genesis block
-                if self.vrf_evaluator.genesis_timestamp == redux::Timestamp::ZERO {
-                    self.vrf_evaluator.genesis_timestamp = best_tip.genesis_timestamp();
+                for vrf_evaluator in self.vrf_evaluators.values_mut() {
+                    vrf_evaluator.current_best_tip_slot = best_tip_slot;
+                    // slots before the best tip can no longer be produced.
+                    vrf_evaluator.won_slots = vrf_evaluator.won_slots.split_off(&best_tip_slot);
+
+                    // set the genesis timestamp on the first best tip update
+                    // TODO: move/remove once we can generate the genesis block
+                    if vrf_evaluator.genesis_timestamp == redux::Timestamp::ZERO {
+                        vrf_evaluator.genesis_timestamp = best_tip.genesis_timestamp();
+                    }
+                    vrf_evaluator.slots_per_epoch = best_tip.constants().slots_per_epoch.as_u32();
                 }
+
+                self.history.best_chain_update(meta.time(), best_chain);
             }
             BlockProducerAction::WonSlotSearch => {}
             BlockProducerAction::WonSlot { won_slot } => {
+                self.history.won_slot_add(meta.time(), won_slot.clone());
                 self.current = BlockProducerCurrentState::WonSlot {
                     time: meta.time(),
                     won_slot: won_slot.clone(),
@@ -71,6 +87,8 @@ impl BlockProducerEnabled {
             }
             BlockProducerAction::WonSlotDiscard { reason } => {
                 if let Some(won_slot) = self.current.won_slot() {
+                    self.history
+                        .won_slot_discarded(meta.time(), won_slot, reason.clone());
                     self.current = BlockProducerCurrentState::WonSlotDiscarded {
                         time: meta.time(),
                         won_slot: won_slot.clone(),
@@ -181,8 +199,8 @@ impl BlockProducerEnabled {
                 let block_stake_winner = won_slot.delegator.0.clone();
                 let vrf_truncated_output = won_slot.vrf_output.clone();
                 let vrf_hash = won_slot.vrf_hash.to_fp().unwrap();
-                let block_creator = self.config.pub_key.clone();
-                let coinbase_receiver = self.config.coinbase_receiver().clone();
+                let block_creator = won_slot.producer.clone();
+                let coinbase_receiver = self.config.coinbase_receiver(&won_slot.producer).clone();
                 let proposed_protocol_version_opt = self.config.proposed_protocol_version.clone();
 
                 let ledger_proof_statement = ledger_proof_statement_from_emitted_proof(
@@ -437,6 +455,8 @@ impl BlockProducerEnabled {
                     ..
                 } = &mut self.current
                 {
+                    self.history
+                        .won_slot_produced(meta.time(), won_slot, block.hash().clone());
                     self.current = BlockProducerCurrentState::Produced {
                         time: meta.time(),
                         won_slot: won_slot.clone(),
@@ -454,6 +474,8 @@ impl BlockProducerEnabled {
                     ..
                 } = &mut self.current
                 {
+                    self.history
+                        .won_slot_injected(meta.time(), won_slot, block.hash().clone());
                     self.current = BlockProducerCurrentState::Injected {
                         time: meta.time(),
                         won_slot: won_slot.clone(),
@@ -462,6 +484,31 @@ impl BlockProducerEnabled {
                     };
                 }
             }
+            BlockProducerAction::BlockDryRunFinished => {
+                if let BlockProducerCurrentState::Produced {
+                    won_slot,
+                    chain,
+                    block,
+                    ..
+                } = &mut self.current
+                {
+                    self.history.won_slot_dry_run_finished(
+                        meta.time(),
+                        won_slot,
+                        block.hash().clone(),
+                    );
+                    self.current = BlockProducerCurrentState::DryRunFinished {
+                        time: meta.time(),
+                        won_slot: won_slot.clone(),
+                        chain: std::mem::take(chain),
+                        block: block.clone(),
+                    };
+                }
+            }
+            BlockProducerAction::HistoryRestore { history } => {
+                self.history.restore(meta.time(), history.clone());
+                self.history.best_chain_update(meta.time(), best_chain);
+            }
         }
     }
 }
@@ -9,7 +9,7 @@ use openmina_core::{
     snark::{Snark, SnarkJobId},
 };
 
-use super::BlockProducerWonSlot;
+use super::{BlockProducerHistory, BlockProducerWonSlot};
 
 pub struct StagedLedgerDiffCreateOutput {
     pub diff: StagedLedgerDiffDiffStableV2,
@@ -29,3 +29,12 @@ pub trait BlockProducerService: redux::Service {
         supercharge_coinbase: bool,
     ) -> Result<StagedLedgerDiffCreateOutput, String>;
 }
+
+pub trait BlockProducerHistoryPersistService: redux::Service {
+    /// Takes history of won slots persisted by the previous run of the
+    /// node. Subsequent calls return nothing.
+    fn block_producer_history_persisted_take(&mut self) -> Option<BlockProducerHistory>;
+
+    /// Persist history of won slots, replacing the previously persisted one.
+    fn block_producer_history_persist(&mut self, history: &BlockProducerHistory);
+}
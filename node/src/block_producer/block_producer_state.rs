@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use mina_p2p_messages::v2::{
     ConsensusBodyReferenceStableV1, LedgerProofProdStableV2, MinaBaseStagedLedgerHashStableV1,
     NonZeroCurvePoint, StagedLedgerDiffDiffStableV2,
@@ -5,8 +7,11 @@ use mina_p2p_messages::v2::{
 use openmina_core::{block::ArcBlockWithHash, consensus::consensus_take};
 use serde::{Deserialize, Serialize};
 
+use crate::account::AccountPublicKey;
+
 use super::{
-    vrf_evaluator::BlockProducerVrfEvaluatorState, BlockProducerConfig, BlockProducerWonSlot,
+    vrf_evaluator::BlockProducerVrfEvaluatorState, BlockProducerConfig, BlockProducerHistory,
+    BlockProducerWonSlot,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -15,8 +20,12 @@ pub struct BlockProducerState(Option<BlockProducerEnabled>);
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BlockProducerEnabled {
     pub config: BlockProducerConfig,
-    pub vrf_evaluator: BlockProducerVrfEvaluatorState,
+    /// Vrf evaluator of each of our producer keys.
+    pub vrf_evaluators: BTreeMap<AccountPublicKey, BlockProducerVrfEvaluatorState>,
+    /// Only one block is produced at a time, for the best won slot
+    /// among all of our keys.
     pub current: BlockProducerCurrentState,
+    pub history: BlockProducerHistory,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -83,6 +92,14 @@ pub enum BlockProducerCurrentState {
         chain: Vec<ArcBlockWithHash>,
         block: ArcBlockWithHash,
     },
+    /// Block was produced in dry run mode, it won't be injected.
+    DryRunFinished {
+        time: redux::Timestamp,
+        won_slot: BlockProducerWonSlot,
+        /// Chain that we are extending.
+        chain: Vec<ArcBlockWithHash>,
+        block: ArcBlockWithHash,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Clone)]
@@ -94,10 +111,20 @@ pub enum BlockProducerWonSlotDiscardReason {
 
 impl BlockProducerState {
     pub fn new(now: redux::Timestamp, config: Option<BlockProducerConfig>) -> Self {
-        Self(config.map(|config| BlockProducerEnabled {
-            config: config.clone(),
-            vrf_evaluator: BlockProducerVrfEvaluatorState::new(now),
-            current: BlockProducerCurrentState::Idle { time: now },
+        Self(config.map(|config| {
+            BlockProducerEnabled {
+                vrf_evaluators: config
+                    .pub_keys
+                    .iter()
+                    .map(|pub_key| {
+                        let producer = AccountPublicKey::from(pub_key.clone());
+                        (producer, BlockProducerVrfEvaluatorState::new(now))
+                    })
+                    .collect(),
+                config,
+                current: BlockProducerCurrentState::Idle { time: now },
+                history: Default::default(),
+            }
         }))
     }
 
@@ -121,8 +148,12 @@ impl BlockProducerState {
         self.with(None, |this| Some(&this.config))
     }
 
+    pub fn is_dry_run(&self) -> bool {
+        self.with(false, |this| this.config.dry_run)
+    }
+
     pub fn is_me(&self, producer: &NonZeroCurvePoint) -> bool {
-        self.with(false, |this| producer == &this.config.pub_key)
+        self.with(false, |this| this.config.is_producer(producer))
     }
 
     pub fn is_producing(&self) -> bool {
@@ -150,21 +181,72 @@ impl BlockProducerState {
         self.with(None, |this| this.current.produced_block_with_chain())
     }
 
-    pub fn vrf_evaluator(&self) -> Option<&BlockProducerVrfEvaluatorState> {
-        self.with(None, |this| Some(&this.vrf_evaluator))
+    pub fn dry_run_block(&self) -> Option<&ArcBlockWithHash> {
+        self.with(None, |this| this.current.dry_run_block())
+    }
+
+    /// Whether the produced block should be injected into the transition
+    /// frontier. Never the case in dry run, which is finished instead.
+    pub fn produced_block_should_inject(&self) -> bool {
+        self.with(false, |this| {
+            !this.config.dry_run
+                && matches!(this.current, BlockProducerCurrentState::Produced { .. })
+        })
+    }
+
+    pub fn produced_block_should_finish_dry_run(&self) -> bool {
+        self.with(false, |this| {
+            this.config.dry_run
+                && matches!(this.current, BlockProducerCurrentState::Produced { .. })
+        })
+    }
+
+    pub fn vrf_evaluator(
+        &self,
+        producer: &AccountPublicKey,
+    ) -> Option<&BlockProducerVrfEvaluatorState> {
+        self.with(None, |this| this.vrf_evaluators.get(producer))
     }
 
-    pub fn vrf_evaluator_with_config(
+    pub fn vrf_evaluators(
         &self,
-    ) -> Option<(&BlockProducerVrfEvaluatorState, &BlockProducerConfig)> {
-        self.with(None, |this| Some((&this.vrf_evaluator, &this.config)))
+    ) -> Option<&BTreeMap<AccountPublicKey, BlockProducerVrfEvaluatorState>> {
+        self.with(None, |this| Some(&this.vrf_evaluators))
+    }
+
+    pub fn history(&self) -> Option<&BlockProducerHistory> {
+        self.with(None, |this| Some(&this.history))
+    }
+}
+
+impl BlockProducerEnabled {
+    /// Next won slot which we can produce a block for, among all of our keys.
+    ///
+    /// If several of our keys won the same slot, only the best candidate
+    /// (the one which would win the fork against the others) is returned.
+    pub fn next_won_slot(
+        &self,
+        cur_global_slot: u32,
+        best_tip: &ArcBlockWithHash,
+    ) -> Option<BlockProducerWonSlot> {
+        self.vrf_evaluators
+            .values()
+            .filter_map(|vrf_evaluator| vrf_evaluator.next_won_slot(cur_global_slot, best_tip))
+            .min_by(|a, b| {
+                a.global_slot()
+                    .cmp(&b.global_slot())
+                    .then_with(|| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal))
+            })
     }
 }
 
 impl BlockProducerCurrentState {
     pub fn won_slot_should_search(&self) -> bool {
         match self {
-            Self::Idle { .. } | Self::WonSlotDiscarded { .. } | Self::Injected { .. } => true,
+            Self::Idle { .. }
+            | Self::WonSlotDiscarded { .. }
+            | Self::Injected { .. }
+            | Self::DryRunFinished { .. } => true,
             Self::WonSlot { .. }
             | Self::WonSlotWait { .. }
             | Self::WonSlotProduceInit { .. }
@@ -186,7 +268,8 @@ impl BlockProducerCurrentState {
             | Self::StagedLedgerDiffCreateSuccess { won_slot, .. }
             | Self::BlockUnprovenBuilt { won_slot, .. }
             | Self::Produced { won_slot, .. }
-            | Self::Injected { won_slot, .. } => Some(won_slot),
+            | Self::Injected { won_slot, .. }
+            | Self::DryRunFinished { won_slot, .. } => Some(won_slot),
         }
     }
 
@@ -201,7 +284,8 @@ impl BlockProducerCurrentState {
             | Self::StagedLedgerDiffCreateSuccess { chain, .. }
             | Self::BlockUnprovenBuilt { chain, .. }
             | Self::Produced { chain, .. }
-            | Self::Injected { chain, .. } => Some(chain),
+            | Self::Injected { chain, .. }
+            | Self::DryRunFinished { chain, .. } => Some(chain),
         }
     }
 
@@ -256,7 +340,8 @@ impl BlockProducerCurrentState {
             | Self::WonSlotDiscarded { .. }
             | Self::WonSlot { .. }
             | Self::WonSlotWait { .. }
-            | Self::Injected { .. } => false,
+            | Self::Injected { .. }
+            | Self::DryRunFinished { .. } => false,
             Self::WonSlotProduceInit { .. }
             | Self::StagedLedgerDiffCreatePending { .. }
             | Self::StagedLedgerDiffCreateSuccess { .. }
@@ -278,4 +363,245 @@ impl BlockProducerCurrentState {
             _ => None,
         }
     }
+
+    pub fn dry_run_block(&self) -> Option<&ArcBlockWithHash> {
+        match self {
+            Self::DryRunFinished { block, .. } => Some(block),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use openmina_core::block::test_block;
+    use vrf::VrfWonSlot;
+
+    use crate::account::AccountSignerError;
+    use crate::block_producer::vrf_evaluator::{
+        BlockProducerVrfEvaluatorAction, DelegatorTable, EpochData, VrfEvaluatorInput,
+        VrfWonSlotWithHash,
+    };
+    use crate::block_producer::BlockProducerAction;
+
+    use super::*;
+
+    const PRODUCER_A: &str = "B62qrztYfPinaKqpXaYGY6QJ3SSW2NNKs7SajBLF1iFNXW9BoALN2Aq";
+    const PRODUCER_B: &str = "B62qmkso2Knz9pxo5V9YEZFJ9Frq57GZfKgem1DVTKiYH9D5H3n2DGS";
+    const LEDGER: &str = "jx5YAT36bv62M8mPcREYYfZWXaKqqMzDCP8wmc21uf4CfDKAHCr";
+
+    fn key(address: &str) -> AccountPublicKey {
+        address.parse().unwrap()
+    }
+
+    fn enabled() -> BlockProducerEnabled {
+        let config = BlockProducerConfig::new(vec![key(PRODUCER_A).into(), key(PRODUCER_B).into()]);
+        BlockProducerState::new(redux::Timestamp::ZERO, Some(config))
+            .0
+            .unwrap()
+    }
+
+    fn reduce(state: &mut BlockProducerEnabled, action: BlockProducerVrfEvaluatorAction) {
+        let action = BlockProducerAction::VrfEvaluator(action);
+        state.reducer(redux::ActionMeta::ZERO.with_action(&action), &[]);
+    }
+
+    fn won_slot(producer: &str, global_slot: u32, vrf_output_byte: u8) -> VrfWonSlotWithHash {
+        let mut vrf_output_bytes = vec![0; 32];
+        vrf_output_bytes[0] = vrf_output_byte;
+        let won_slot = VrfWonSlot {
+            producer: producer.to_owned(),
+            winner_account: producer.to_owned(),
+            vrf_output: String::new(),
+            vrf_output_bytes,
+            vrf_fractional: 0.0,
+            global_slot,
+            account_index: ledger::AccountIndex(0),
+            vrf_hash: Box::new([0; 32]).into(),
+        };
+        VrfWonSlotWithHash::new(won_slot, LEDGER.parse().unwrap())
+    }
+
+    #[test]
+    fn vrf_evaluator_for_each_key() {
+        let state = BlockProducerState(Some(enabled()));
+        assert!(state.vrf_evaluator(&key(PRODUCER_A)).is_some());
+        assert!(state.vrf_evaluator(&key(PRODUCER_B)).is_some());
+        assert_eq!(state.vrf_evaluators().unwrap().len(), 2);
+        assert!(state.is_me(&key(PRODUCER_A).into()));
+        assert!(state.is_me(&key(PRODUCER_B).into()));
+    }
+
+    #[test]
+    fn vrf_actions_reach_their_producer_only() {
+        let mut state = enabled();
+        for vrf_evaluator in state.vrf_evaluators.values_mut() {
+            let epoch_data = EpochData::new("seed".to_owned(), LEDGER.parse().unwrap(), 1_000);
+            vrf_evaluator.current_epoch_data = Some(epoch_data.clone());
+            vrf_evaluator.next_epoch_data = Some(epoch_data);
+        }
+
+        let table = |balance| {
+            let mut table = DelegatorTable::new();
+            table.insert(ledger::AccountIndex(balance), (key(PRODUCER_A), balance));
+            Arc::new(table)
+        };
+        let tables = BTreeMap::from([(key(PRODUCER_A), table(1)), (key(PRODUCER_B), table(2))]);
+        reduce(
+            &mut state,
+            BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegatesSuccess {
+                current_epoch_producer_and_delegators: tables.clone(),
+                next_epoch_producer_and_delegators: tables,
+                staking_ledger_hash: LEDGER.parse().unwrap(),
+            },
+        );
+        for (producer, balance) in [(PRODUCER_A, 1), (PRODUCER_B, 2)] {
+            let epoch_data = state.vrf_evaluators[&key(producer)]
+                .current_epoch_data
+                .as_ref()
+                .unwrap();
+            assert_eq!(epoch_data.delegator_table, table(balance));
+        }
+
+        let vrf_input = VrfEvaluatorInput::new(
+            key(PRODUCER_B),
+            "seed".to_owned(),
+            table(2),
+            100..=219,
+            1_000,
+            LEDGER.parse().unwrap(),
+        );
+        reduce(
+            &mut state,
+            BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input },
+        );
+        assert!(state.vrf_evaluators[&key(PRODUCER_A)]
+            .pending_evaluations
+            .is_empty());
+        assert!(state.vrf_evaluators[&key(PRODUCER_B)]
+            .pending_evaluations
+            .contains_key(&100));
+    }
+
+    #[test]
+    fn next_won_slot_among_all_keys() {
+        let best_tip = test_block();
+        let slot = best_tip.global_slot();
+        let mut state = enabled();
+
+        let won_slots =
+            |state: &mut BlockProducerEnabled, producer, won_slot: VrfWonSlotWithHash| {
+                let vrf_evaluator = state.vrf_evaluators.get_mut(&key(producer)).unwrap();
+                vrf_evaluator.won_slots.clear();
+                vrf_evaluator
+                    .won_slots
+                    .insert(won_slot.won_slot.global_slot, won_slot);
+            };
+
+        won_slots(&mut state, PRODUCER_A, won_slot(PRODUCER_A, slot + 10, 1));
+        won_slots(&mut state, PRODUCER_B, won_slot(PRODUCER_B, slot + 5, 1));
+        let next = state.next_won_slot(0, &best_tip).unwrap();
+        assert_eq!(next.global_slot(), slot + 5);
+        assert_eq!(next.producer, NonZeroCurvePoint::from(key(PRODUCER_B)));
+
+        // both won the same slot, the stronger candidate is produced.
+        won_slots(&mut state, PRODUCER_A, won_slot(PRODUCER_A, slot + 5, 1));
+        won_slots(&mut state, PRODUCER_B, won_slot(PRODUCER_B, slot + 5, 2));
+        let next = state.next_won_slot(0, &best_tip).unwrap();
+        let candidates = state
+            .vrf_evaluators
+            .values()
+            .map(|v| v.next_won_slot(0, &best_tip).unwrap())
+            .collect::<Vec<_>>();
+        assert!(candidates.iter().all(|candidate| candidate <= &next));
+
+        // slots which don't beat the best tip are skipped.
+        won_slots(&mut state, PRODUCER_A, won_slot(PRODUCER_A, slot - 1, 1));
+        won_slots(&mut state, PRODUCER_B, won_slot(PRODUCER_B, slot - 2, 1));
+        assert!(state.next_won_slot(0, &best_tip).is_none());
+    }
+
+    #[test]
+    fn failed_evaluation_stays_pending_until_retried() {
+        let mut state = enabled();
+        let table = Arc::new(DelegatorTable::new());
+        let vrf_input = VrfEvaluatorInput::new(
+            key(PRODUCER_A),
+            "seed".to_owned(),
+            table,
+            100..=219,
+            1_000,
+            LEDGER.parse().unwrap(),
+        );
+        let pending = |state: &BlockProducerEnabled| {
+            state.vrf_evaluators[&key(PRODUCER_A)].pending_evaluations[&100].clone()
+        };
+
+        reduce(
+            &mut state,
+            BlockProducerVrfEvaluatorAction::EvaluateVrf {
+                vrf_input: vrf_input.clone(),
+            },
+        );
+        reduce(
+            &mut state,
+            BlockProducerVrfEvaluatorAction::EvaluationFailed {
+                producer: key(PRODUCER_A),
+                slots: 100..=219,
+                staking_ledger_hash: LEDGER.parse().unwrap(),
+                error: AccountSignerError::IOError("connection refused".to_owned()),
+            },
+        );
+        assert!(pending(&state).failed.is_some());
+
+        reduce(
+            &mut state,
+            BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input },
+        );
+        assert!(pending(&state).failed.is_none());
+    }
+
+    #[test]
+    fn dry_run_never_injects_produced_block() {
+        let best_tip = test_block();
+        let produced = |dry_run| {
+            let config = BlockProducerConfig {
+                dry_run,
+                ..BlockProducerConfig::new(vec![key(PRODUCER_A).into()])
+            };
+            let mut state = BlockProducerState::new(redux::Timestamp::ZERO, Some(config));
+            let won_slot = won_slot(PRODUCER_A, best_tip.global_slot() + 1, 1);
+            let won_slot =
+                BlockProducerWonSlot::from_vrf_won_slot(&won_slot, redux::Timestamp::ZERO, 7140);
+            state.with_mut((), |this| {
+                this.current = BlockProducerCurrentState::Produced {
+                    time: redux::Timestamp::ZERO,
+                    won_slot,
+                    chain: vec![best_tip.clone()],
+                    block: best_tip.clone(),
+                }
+            });
+            state
+        };
+
+        let state = produced(false);
+        assert!(state.produced_block_should_inject());
+        assert!(!state.produced_block_should_finish_dry_run());
+
+        let mut state = produced(true);
+        assert!(!state.produced_block_should_inject());
+        assert!(state.produced_block_should_finish_dry_run());
+
+        let action = BlockProducerAction::BlockDryRunFinished;
+        state.reducer(redux::ActionMeta::ZERO.with_action(&action), &[]);
+        assert_eq!(
+            state.dry_run_block().map(|block| block.hash()),
+            Some(best_tip.hash())
+        );
+        assert!(state.produced_block().is_none());
+        assert!(!state.produced_block_should_inject());
+        assert!(!state.produced_block_should_finish_dry_run());
+    }
 }
@@ -6,6 +6,9 @@ pub use block_producer_config::*;
 mod block_producer_state;
 pub use block_producer_state::*;
 
+mod block_producer_history;
+pub use block_producer_history::*;
+
 mod block_producer_event;
 pub use block_producer_event::*;
 
@@ -42,6 +45,8 @@ use self::vrf_evaluator::VrfWonSlotWithHash;
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub struct BlockProducerWonSlot {
     pub slot_time: redux::Timestamp,
+    /// Our key which won the slot and will be the block creator.
+    pub producer: NonZeroCurvePoint,
     pub delegator: (NonZeroCurvePoint, AccountIndex),
     pub global_slot: ConsensusGlobalSlotStableV1,
     pub vrf_output: ConsensusVrfOutputTruncatedStableV1,
@@ -56,6 +61,7 @@ impl BlockProducerWonSlot {
     pub fn from_vrf_won_slot(
         won_slot_with_hash: &VrfWonSlotWithHash,
         genesis_timestamp: redux::Timestamp,
+        slots_per_epoch: u32,
     ) -> Self {
         let VrfWonSlotWithHash {
             won_slot,
@@ -64,6 +70,9 @@ impl BlockProducerWonSlot {
 
         let slot_time = Self::calculate_slot_time(genesis_timestamp, won_slot.global_slot);
 
+        let producer = AccountPublicKey::from(
+            CompressedPubKey::from_address(&won_slot.producer).unwrap(),
+        );
         let winner_pub_key = AccountPublicKey::from(
             CompressedPubKey::from_address(&won_slot.winner_account).unwrap(),
         );
@@ -72,11 +81,12 @@ impl BlockProducerWonSlot {
             slot_number: MinaNumbersGlobalSlotSinceHardForkMStableV1::SinceHardFork(
                 won_slot.global_slot.into(),
             ),
-            slots_per_epoch: 7140.into(), // TODO
+            slots_per_epoch: slots_per_epoch.into(),
         };
 
         Self {
             slot_time,
+            producer: producer.into(),
             delegator,
             global_slot,
             vrf_output: ConsensusVrfOutputTruncatedStableV1(
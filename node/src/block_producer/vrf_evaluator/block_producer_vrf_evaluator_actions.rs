@@ -1,13 +1,15 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 
-use crate::account::AccountPublicKey;
+use crate::account::{AccountPublicKey, AccountSignerError};
 use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorStatus;
 use mina_p2p_messages::v2::{
     ConsensusProofOfStakeDataEpochDataNextValueVersionedValueStableV1,
     ConsensusProofOfStakeDataEpochDataStakingValueVersionedValueStableV1, LedgerHash,
 };
 use serde::{Deserialize, Serialize};
-use vrf::VrfEvaluationOutput;
+use vrf::VrfWonSlot;
 
 use super::{DelegatorTable, VrfEvaluatorInput};
 
@@ -27,62 +29,115 @@ pub enum BlockProducerVrfEvaluatorAction {
         vrf_input: VrfEvaluatorInput,
     },
     EvaluationSuccess {
-        vrf_output: VrfEvaluationOutput,
+        producer: AccountPublicKey,
+        slots: RangeInclusive<u32>,
+        won_slots: Vec<VrfWonSlot>,
+        staking_ledger_hash: LedgerHash,
+    },
+    /// Service failed to evaluate the range, it will be retried.
+    EvaluationFailed {
+        producer: AccountPublicKey,
+        slots: RangeInclusive<u32>,
         staking_ledger_hash: LedgerHash,
+        error: AccountSignerError,
     },
+    /// Evaluate again ranges, which the service failed to evaluate.
+    EvaluationRetry,
     UpdateProducerAndDelegates {
         current_epoch_ledger_hash: LedgerHash,
         next_epoch_ledger_hash: LedgerHash,
-        producer: AccountPublicKey,
+        producers: BTreeSet<AccountPublicKey>,
     },
     UpdateProducerAndDelegatesSuccess {
-        current_epoch_producer_and_delegators: Arc<DelegatorTable>,
-        next_epoch_producer_and_delegators: Arc<DelegatorTable>,
+        current_epoch_producer_and_delegators: BTreeMap<AccountPublicKey, Arc<DelegatorTable>>,
+        next_epoch_producer_and_delegators: BTreeMap<AccountPublicKey, Arc<DelegatorTable>>,
         staking_ledger_hash: LedgerHash,
     },
 }
 
+impl BlockProducerVrfEvaluatorAction {
+    /// Producer whose vrf evaluator the action is for, `None` if it's
+    /// for all of them.
+    pub fn producer(&self) -> Option<&AccountPublicKey> {
+        match self {
+            Self::EvaluateVrf { vrf_input } => Some(&vrf_input.producer),
+            Self::EvaluationSuccess { producer, .. } | Self::EvaluationFailed { producer, .. } => {
+                Some(producer)
+            }
+            Self::EpochDataUpdate { .. }
+            | Self::EvaluationRetry
+            | Self::UpdateProducerAndDelegates { .. }
+            | Self::UpdateProducerAndDelegatesSuccess { .. } => None,
+        }
+    }
+}
+
 impl redux::EnablingCondition<crate::State> for BlockProducerVrfEvaluatorAction {
     fn is_enabled(&self, state: &crate::State) -> bool {
         match self {
             BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegates { .. } => {
                 state.block_producer.with(false, |this| {
-                    matches!(
-                        this.vrf_evaluator.status,
-                        BlockProducerVrfEvaluatorStatus::EpochChanged { .. }
-                    )
+                    this.vrf_evaluators.values().all(|vrf_evaluator| {
+                        matches!(
+                            vrf_evaluator.status,
+                            BlockProducerVrfEvaluatorStatus::EpochChanged { .. }
+                        )
+                    })
                 })
             }
             BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegatesSuccess {
                 staking_ledger_hash,
                 ..
             } => state.block_producer.with(false, |this| {
-                matches!(
-                    this.vrf_evaluator.status,
-                    BlockProducerVrfEvaluatorStatus::DataPending { .. }
-                ) && this
-                    .vrf_evaluator
-                    .current_epoch_data
-                    .as_ref()
-                    .is_some_and(|epoch_data| &epoch_data.ledger == staking_ledger_hash)
+                this.vrf_evaluators.values().all(|vrf_evaluator| {
+                    matches!(
+                        vrf_evaluator.status,
+                        BlockProducerVrfEvaluatorStatus::DataPending { .. }
+                    ) && vrf_evaluator
+                        .current_epoch_data
+                        .as_ref()
+                        .is_some_and(|epoch_data| &epoch_data.ledger == staking_ledger_hash)
+                })
             }),
-            BlockProducerVrfEvaluatorAction::EvaluateVrf { .. } => {
-                state.block_producer.with(false, |this| {
+            BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input } => state
+                .block_producer
+                .vrf_evaluator(&vrf_input.producer)
+                .map_or(false, |vrf_evaluator| {
                     matches!(
-                        this.vrf_evaluator.status,
-                        BlockProducerVrfEvaluatorStatus::SlotsReceived { .. }
+                        vrf_evaluator.status,
+                        BlockProducerVrfEvaluatorStatus::SlotsRequested { .. }
+                            | BlockProducerVrfEvaluatorStatus::SlotsReceived { .. }
                             | BlockProducerVrfEvaluatorStatus::DataSuccess { .. }
-                    )
-                })
-            }
+                    ) && vrf_evaluator
+                        .pending_evaluations
+                        .get(vrf_input.slots.start())
+                        .map_or(true, |pending| pending.should_retry(state.time()))
+                }),
             BlockProducerVrfEvaluatorAction::EvaluationSuccess {
-                vrf_output,
+                producer,
+                slots,
                 staking_ledger_hash,
-            } => state.block_producer.with(false, |this| {
-                this.vrf_evaluator
-                    .status
-                    .matches_requested_slot(vrf_output.global_slot(), staking_ledger_hash)
-            }),
+                ..
+            } => state
+                .block_producer
+                .vrf_evaluator(producer)
+                .map_or(false, |vrf_evaluator| {
+                    vrf_evaluator.is_evaluation_pending(slots, staking_ledger_hash)
+                }),
+            BlockProducerVrfEvaluatorAction::EvaluationFailed {
+                producer,
+                slots,
+                staking_ledger_hash,
+                ..
+            } => state
+                .block_producer
+                .vrf_evaluator(producer)
+                .map_or(false, |vrf_evaluator| {
+                    vrf_evaluator.is_evaluation_pending(slots, staking_ledger_hash)
+                }),
+            BlockProducerVrfEvaluatorAction::EvaluationRetry => {
+                state.block_producer.with(false, |_| true)
+            }
             BlockProducerVrfEvaluatorAction::EpochDataUpdate { .. } => true,
         }
     }
@@ -1,27 +1,31 @@
+use std::sync::Arc;
+
 use redux::ActionMeta;
 
-use crate::block_producer::vrf_evaluator::VrfEvaluatorInput;
 use crate::Service;
 use crate::Store;
 
 use super::BlockProducerVrfEvaluatorAction;
 
 impl BlockProducerVrfEvaluatorAction {
-    pub fn effects<S: Service>(self, _: &ActionMeta, store: &mut Store<S>) {
+    pub fn effects<S: Service>(self, meta: &ActionMeta, store: &mut Store<S>) {
         match self {
             BlockProducerVrfEvaluatorAction::EpochDataUpdate {
                 epoch_data,
                 next_epoch_data,
                 ..
             } => {
-                let vrf_evaluator_state_with_config =
-                    store.state().block_producer.vrf_evaluator_with_config();
-                if let Some((_, config)) = vrf_evaluator_state_with_config {
+                let producers = store
+                    .state()
+                    .block_producer
+                    .vrf_evaluators()
+                    .map(|vrf_evaluators| vrf_evaluators.keys().cloned().collect());
+                if let Some(producers) = producers {
                     store.dispatch(
                         BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegates {
                             current_epoch_ledger_hash: epoch_data.ledger.hash,
                             next_epoch_ledger_hash: next_epoch_data.ledger.hash,
-                            producer: config.pub_key.clone().into(),
+                            producers,
                         },
                     );
                 }
@@ -29,89 +33,80 @@ impl BlockProducerVrfEvaluatorAction {
             BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input } => {
                 store.service.evaluate(vrf_input);
             }
-            BlockProducerVrfEvaluatorAction::EvaluationSuccess { .. } => {
-                let Some((next_slot, current_epoch, current_epoch_data, next_epoch_data)) =
-                    store.state().block_producer.with(None, |block_producer| {
-                        let vrf_evaluator = &block_producer.vrf_evaluator;
-                        let next_slot = vrf_evaluator.latest_evaluated_slot + 1;
-                        let next_slot = next_slot.max(store.state().cur_global_slot()?);
-
-                        Some((
-                            next_slot,
-                            vrf_evaluator.current_epoch.as_ref()?,
-                            vrf_evaluator.current_epoch_data.as_ref()?,
-                            vrf_evaluator.next_epoch_data.as_ref()?,
-                        ))
-                    })
-                else {
-                    return;
-                };
-
-                // TODO(adonagy): Can we get this from somewhere?
-                const SLOTS_PER_EPOCH: u32 = 7140;
-                let current_epoch_end = current_epoch * SLOTS_PER_EPOCH + SLOTS_PER_EPOCH - 1;
-                let next_epoch_end = (current_epoch + 1) * SLOTS_PER_EPOCH + SLOTS_PER_EPOCH - 1;
-
-                // slot is in the current epoch
-                if next_slot <= current_epoch_end {
-                    let vrf_input = VrfEvaluatorInput::new(
-                        current_epoch_data.seed.clone(),
-                        current_epoch_data.delegator_table.clone(),
-                        next_slot,
-                        current_epoch_data.total_currency,
-                        current_epoch_data.ledger.clone(),
-                    );
+            BlockProducerVrfEvaluatorAction::EvaluationSuccess { producer, .. } => {
+                // next ranges are requested once all pending ones are evaluated.
+                let vrf_inputs = store.state().block_producer.with(vec![], |block_producer| {
+                    let Some(vrf_evaluator) = block_producer.vrf_evaluators.get(&producer) else {
+                        return vec![];
+                    };
+                    if !vrf_evaluator.pending_evaluations.is_empty() {
+                        return vec![];
+                    }
+                    let Some(cur_global_slot) = store.state().cur_global_slot() else {
+                        return vec![];
+                    };
+                    let next_slot = vrf_evaluator.latest_evaluated_slot + 1;
+                    vrf_evaluator.evaluation_inputs_from(&producer, next_slot.max(cur_global_slot))
+                });
+                for vrf_input in vrf_inputs {
                     store.dispatch(BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input });
-                // slot is in the next epoch
-                } else if next_slot > current_epoch_end && next_slot <= next_epoch_end {
-                    let vrf_input = VrfEvaluatorInput::new(
-                        next_epoch_data.seed.clone(),
-                        next_epoch_data.delegator_table.clone(),
-                        next_slot,
-                        next_epoch_data.total_currency,
-                        next_epoch_data.ledger.clone(),
-                    );
+                }
+            }
+            BlockProducerVrfEvaluatorAction::EvaluationFailed { .. } => {}
+            BlockProducerVrfEvaluatorAction::EvaluationRetry => {
+                let vrf_inputs = store.state().block_producer.with(vec![], |block_producer| {
+                    block_producer
+                        .vrf_evaluators
+                        .iter()
+                        .flat_map(|(producer, vrf_evaluator)| {
+                            vrf_evaluator.evaluation_retry_inputs(producer, meta.time())
+                        })
+                        .collect()
+                });
+                for vrf_input in vrf_inputs {
                     store.dispatch(BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input });
                 }
             }
             BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegates {
                 current_epoch_ledger_hash,
                 next_epoch_ledger_hash,
-                producer,
+                producers,
             } => {
-                let current_epoch_producer_and_delegators =
-                    store.service.get_producer_and_delegates(
-                        current_epoch_ledger_hash.clone(),
-                        producer.clone(),
-                    );
+                let current_epoch_producer_and_delegators = store
+                    .service
+                    .get_producers_and_delegates(current_epoch_ledger_hash.clone(), &producers);
                 let next_epoch_producer_and_delegators = store
                     .service
-                    .get_producer_and_delegates(next_epoch_ledger_hash, producer.clone());
+                    .get_producers_and_delegates(next_epoch_ledger_hash, &producers);
 
                 store.dispatch(
                     BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegatesSuccess {
                         current_epoch_producer_and_delegators:
-                            current_epoch_producer_and_delegators.into(),
+                            current_epoch_producer_and_delegators
+                                .into_iter()
+                                .map(|(producer, table)| (producer, Arc::new(table)))
+                                .collect(),
                         next_epoch_producer_and_delegators: next_epoch_producer_and_delegators
-                            .into(),
+                            .into_iter()
+                            .map(|(producer, table)| (producer, Arc::new(table)))
+                            .collect(),
                         staking_ledger_hash: current_epoch_ledger_hash,
                     },
                 );
             }
             BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegatesSuccess { .. } => {
-                let vrf_evaluator_state = store.state().block_producer.vrf_evaluator();
-
-                if let Some(vrf_evaluator_state) = vrf_evaluator_state {
-                    if let Some(current_epoch_data) = &vrf_evaluator_state.current_epoch_data {
-                        let vrf_input: VrfEvaluatorInput = VrfEvaluatorInput::new(
-                            current_epoch_data.seed.clone(),
-                            current_epoch_data.delegator_table.clone(),
-                            vrf_evaluator_state.current_best_tip_slot + 1,
-                            current_epoch_data.total_currency,
-                            current_epoch_data.ledger.clone(),
-                        );
-                        store.dispatch(BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input });
-                    }
+                let vrf_inputs = store.state().block_producer.with(vec![], |block_producer| {
+                    block_producer
+                        .vrf_evaluators
+                        .iter()
+                        .flat_map(|(producer, vrf_evaluator)| {
+                            let from_slot = vrf_evaluator.current_best_tip_slot + 1;
+                            vrf_evaluator.evaluation_inputs_from(producer, from_slot)
+                        })
+                        .collect()
+                });
+                for vrf_input in vrf_inputs {
+                    store.dispatch(BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input });
                 }
             }
         }
@@ -1,18 +1,38 @@
+use std::ops::RangeInclusive;
+
+use mina_p2p_messages::v2::LedgerHash;
 use serde::{Deserialize, Serialize};
 
-use super::VrfEvaluationOutputWithHash;
+use crate::account::{AccountPublicKey, AccountSignerError};
+
+use super::VrfEvaluationBatchWithHash;
 
 #[derive(derive_more::From, Serialize, Deserialize, Debug, Clone)]
 pub enum BlockProducerVrfEvaluatorEvent {
-    Evaluated(VrfEvaluationOutputWithHash),
+    Evaluated(VrfEvaluationBatchWithHash),
+    #[from(ignore)]
+    EvaluationFailed {
+        producer: AccountPublicKey,
+        slots: RangeInclusive<u32>,
+        staking_ledger_hash: LedgerHash,
+        error: AccountSignerError,
+    },
 }
 
 impl std::fmt::Display for BlockProducerVrfEvaluatorEvent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "VrfEvaluator, ")?;
         match self {
-            Self::Evaluated(vrf_output) => {
-                write!(f, "Evaluated, {}", vrf_output)
+            Self::Evaluated(batch) => {
+                write!(f, "Evaluated, {}", batch)
+            }
+            Self::EvaluationFailed {
+                producer,
+                slots,
+                error,
+                ..
+            } => {
+                write!(f, "EvaluationFailed, {producer}, {slots:?}, {error}")
             }
         }
     }
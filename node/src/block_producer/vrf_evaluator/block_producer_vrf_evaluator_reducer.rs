@@ -1,10 +1,17 @@
+use crate::account::AccountPublicKey;
+
 use super::{
     BlockProducerVrfEvaluatorAction, BlockProducerVrfEvaluatorActionWithMetaRef,
-    BlockProducerVrfEvaluatorState, BlockProducerVrfEvaluatorStatus, EpochData, VrfWonSlotWithHash,
+    BlockProducerVrfEvaluatorState, BlockProducerVrfEvaluatorStatus, EpochData,
+    PendingVrfEvaluation, VrfEvaluationFailure, VrfWonSlotWithHash,
 };
 
 impl BlockProducerVrfEvaluatorState {
-    pub fn reducer(&mut self, action: BlockProducerVrfEvaluatorActionWithMetaRef<'_>) {
+    pub fn reducer(
+        &mut self,
+        action: BlockProducerVrfEvaluatorActionWithMetaRef<'_>,
+        producer: &AccountPublicKey,
+    ) {
         let (action, meta) = action.split();
         match action {
             BlockProducerVrfEvaluatorAction::EpochDataUpdate {
@@ -26,59 +33,79 @@ impl BlockProducerVrfEvaluatorState {
                 self.current_epoch = Some(*new_epoch_number);
             }
             BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input } => {
+                self.pending_evaluations.insert(
+                    *vrf_input.slots.start(),
+                    PendingVrfEvaluation {
+                        time: meta.time(),
+                        slots: vrf_input.slots.clone(),
+                        staking_ledger_hash: vrf_input.staking_ledger_hash.clone(),
+                        failed: None,
+                    },
+                );
                 self.status = BlockProducerVrfEvaluatorStatus::SlotsRequested {
                     time: meta.time(),
-                    global_slot: vrf_input.global_slot,
                     staking_ledger_hash: vrf_input.staking_ledger_hash.clone(),
                 };
             }
             // BlockProducerVrfEvaluatorAction::EvaluationPending(_) => todo!(),
             BlockProducerVrfEvaluatorAction::EvaluationSuccess {
-                vrf_output,
+                slots,
+                won_slots,
                 staking_ledger_hash,
+                ..
             } => {
-                let global_slot_evaluated = match vrf_output {
-                    vrf::VrfEvaluationOutput::SlotWon(won_slot_data) => {
-                        self.won_slots.insert(
-                            won_slot_data.global_slot,
-                            VrfWonSlotWithHash::new(
-                                won_slot_data.clone(),
-                                staking_ledger_hash.clone(),
-                            ),
-                        );
-                        won_slot_data.global_slot
-                    }
-                    vrf::VrfEvaluationOutput::SlotLost(global_slot) => *global_slot,
-                };
-                self.status = BlockProducerVrfEvaluatorStatus::SlotsReceived {
-                    time: meta.time(),
-                    global_slot: global_slot_evaluated,
-                    staking_ledger_hash: staking_ledger_hash.clone(),
-                };
-                self.latest_evaluated_slot = global_slot_evaluated;
+                self.pending_evaluations.remove(slots.start());
+                for won_slot in won_slots {
+                    self.won_slots.insert(
+                        won_slot.global_slot,
+                        VrfWonSlotWithHash::new(won_slot.clone(), staking_ledger_hash.clone()),
+                    );
+                }
+                self.latest_evaluated_slot = self.latest_evaluated_slot.max(*slots.end());
+                if self.pending_evaluations.is_empty() {
+                    self.status = BlockProducerVrfEvaluatorStatus::SlotsReceived {
+                        time: meta.time(),
+                        staking_ledger_hash: staking_ledger_hash.clone(),
+                    };
+                }
             }
+            BlockProducerVrfEvaluatorAction::EvaluationFailed { slots, error, .. } => {
+                if let Some(pending) = self.pending_evaluations.get_mut(slots.start()) {
+                    pending.failed = Some(VrfEvaluationFailure {
+                        time: meta.time(),
+                        error: error.clone(),
+                    });
+                }
+            }
+            BlockProducerVrfEvaluatorAction::EvaluationRetry => {}
             BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegates { .. } => {
                 self.status = BlockProducerVrfEvaluatorStatus::DataPending { time: meta.time() };
+                // results for the old delegator table will be ignored.
+                self.pending_evaluations.clear();
             }
             BlockProducerVrfEvaluatorAction::UpdateProducerAndDelegatesSuccess {
                 current_epoch_producer_and_delegators,
+                next_epoch_producer_and_delegators,
                 ..
             } => {
                 self.status = BlockProducerVrfEvaluatorStatus::DataSuccess { time: meta.time() };
                 // TODO(adonagy): causes reevaluation of already evaluated slots.
                 // Needed since delegate table changed and we might miss slots
                 // in case of the fork.
-
-                // TODO(adonagy): we are also missing cleanup logic for won_slots.
-                // We might produce invalid blocks because of that.
                 self.latest_evaluated_slot = 0;
 
                 if let Some(epoch_data) = self.current_epoch_data.as_mut() {
-                    epoch_data.delegator_table = current_epoch_producer_and_delegators.clone();
+                    epoch_data.delegator_table = current_epoch_producer_and_delegators
+                        .get(producer)
+                        .cloned()
+                        .unwrap_or_default();
                 }
 
                 if let Some(epoch_data) = self.next_epoch_data.as_mut() {
-                    epoch_data.delegator_table = current_epoch_producer_and_delegators.clone();
+                    epoch_data.delegator_table = next_epoch_producer_and_delegators
+                        .get(producer)
+                        .cloned()
+                        .unwrap_or_default();
                 }
             }
         }
@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use mina_p2p_messages::v2::LedgerHash;
 
 use crate::account::AccountPublicKey;
@@ -9,9 +11,11 @@ pub trait BlockProducerVrfEvaluatorService: redux::Service {
 }
 
 pub trait BlockProducerVrfEvaluatorLedgerService: redux::Service {
-    fn get_producer_and_delegates(
+    /// Delegator table of each of the `producers`, empty if the producer
+    /// has no stake in the ledger.
+    fn get_producers_and_delegates(
         &mut self,
         ledger_hash: LedgerHash,
-        producer: AccountPublicKey,
-    ) -> DelegatorTable;
+        producers: &BTreeSet<AccountPublicKey>,
+    ) -> BTreeMap<AccountPublicKey, DelegatorTable>;
 }
@@ -1,13 +1,23 @@
 use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
+use std::time::Duration;
 
 use mina_p2p_messages::v2::LedgerHash;
 use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 
+use crate::account::{AccountPublicKey, AccountSignerError};
 use crate::block_producer::BlockProducerWonSlot;
 
-use super::{DelegatorTable, VrfWonSlotWithHash};
+use super::{DelegatorTable, VrfEvaluatorInput, VrfWonSlotWithHash};
+
+/// Number of slots sent to the service for evaluation at once.
+pub const VRF_EVALUATION_RANGE_LEN: u32 = 120;
+
+/// How long to wait before evaluating a slot range again, after the
+/// service failed to evaluate it.
+pub const VRF_EVALUATION_RETRY_DELAY: Duration = Duration::from_secs(10);
 
 // TODO(adonagy): consodilate types, make more clear
 // pub type AccountAddressAndBalance = (String, u64);
@@ -42,6 +52,9 @@ use super::{DelegatorTable, VrfWonSlotWithHash};
 pub struct BlockProducerVrfEvaluatorState {
     pub status: BlockProducerVrfEvaluatorStatus,
     pub won_slots: BTreeMap<u32, VrfWonSlotWithHash>,
+    /// Slot ranges being evaluated by the service, by the first slot
+    /// of the range.
+    pub pending_evaluations: BTreeMap<u32, PendingVrfEvaluation>,
     pub current_epoch_data: Option<EpochData>,
     pub next_epoch_data: Option<EpochData>,
     // TODO(adonagy): move to block producer state probably
@@ -50,6 +63,8 @@ pub struct BlockProducerVrfEvaluatorState {
     pub latest_evaluated_slot: u32,
     pub last_possible_evaluation_slot: u32,
     pub genesis_timestamp: redux::Timestamp,
+    /// Taken from the protocol constants of the best tip.
+    pub slots_per_epoch: u32,
 }
 
 impl BlockProducerVrfEvaluatorState {
@@ -57,6 +72,7 @@ impl BlockProducerVrfEvaluatorState {
         Self {
             status: BlockProducerVrfEvaluatorStatus::Idle { time: now },
             won_slots: Default::default(),
+            pending_evaluations: Default::default(),
             current_epoch_data: Default::default(),
             next_epoch_data: Default::default(),
             current_epoch: None,
@@ -64,9 +80,111 @@ impl BlockProducerVrfEvaluatorState {
             latest_evaluated_slot: Default::default(),
             last_possible_evaluation_slot: Default::default(),
             genesis_timestamp: redux::Timestamp::ZERO,
+            slots_per_epoch: 7140,
         }
     }
 
+    pub fn is_evaluation_pending(
+        &self,
+        slots: &RangeInclusive<u32>,
+        staking_ledger_hash: &LedgerHash,
+    ) -> bool {
+        self.pending_evaluations
+            .get(slots.start())
+            .map_or(false, |pending| {
+                &pending.slots == slots && &pending.staking_ledger_hash == staking_ledger_hash
+            })
+    }
+
+    /// Epoch data for the epoch which `slot` belongs to and the last slot
+    /// of that epoch.
+    ///
+    /// `None` if `slot` is past the next epoch, or epoch data isn't
+    /// available yet.
+    fn epoch_data_for(&self, slot: u32) -> Option<(&EpochData, u32)> {
+        let (Some(current_epoch), Some(current_epoch_data), Some(next_epoch_data)) = (
+            self.current_epoch,
+            self.current_epoch_data.as_ref(),
+            self.next_epoch_data.as_ref(),
+        ) else {
+            return None;
+        };
+        let slots_per_epoch = self.slots_per_epoch;
+        let current_epoch_end = current_epoch * slots_per_epoch + slots_per_epoch - 1;
+        let next_epoch_end = current_epoch_end + slots_per_epoch;
+
+        if slot <= current_epoch_end {
+            Some((current_epoch_data, current_epoch_end))
+        } else if slot <= next_epoch_end {
+            Some((next_epoch_data, next_epoch_end))
+        } else {
+            None
+        }
+    }
+
+    /// Inputs for evaluating the rest of the epoch which `from_slot`
+    /// belongs to, split into ranges of [`VRF_EVALUATION_RANGE_LEN`] slots.
+    ///
+    /// Empty if `from_slot` is past the next epoch, or epoch data isn't
+    /// available yet.
+    pub fn evaluation_inputs_from(
+        &self,
+        producer: &AccountPublicKey,
+        from_slot: u32,
+    ) -> Vec<VrfEvaluatorInput> {
+        let Some((epoch_data, epoch_end)) = self.epoch_data_for(from_slot) else {
+            return vec![];
+        };
+
+        (from_slot..=epoch_end)
+            .step_by(VRF_EVALUATION_RANGE_LEN as usize)
+            .map(|start| {
+                let end = start
+                    .saturating_add(VRF_EVALUATION_RANGE_LEN - 1)
+                    .min(epoch_end);
+                VrfEvaluatorInput::new(
+                    producer.clone(),
+                    epoch_data.seed.clone(),
+                    epoch_data.delegator_table.clone(),
+                    start..=end,
+                    epoch_data.total_currency,
+                    epoch_data.ledger.clone(),
+                )
+            })
+            .collect()
+    }
+
+    /// Inputs for evaluating again the slot ranges, which the service
+    /// failed to evaluate at least [`VRF_EVALUATION_RETRY_DELAY`] ago.
+    ///
+    /// Ranges evaluated with a staking ledger other than the current one
+    /// for their epoch are skipped, they will be replaced once delegator
+    /// tables are updated.
+    pub fn evaluation_retry_inputs(
+        &self,
+        producer: &AccountPublicKey,
+        now: redux::Timestamp,
+    ) -> Vec<VrfEvaluatorInput> {
+        self.pending_evaluations
+            .values()
+            .filter(|pending| pending.should_retry(now))
+            .filter_map(|pending| {
+                let (epoch_data, _) = self.epoch_data_for(*pending.slots.start())?;
+                if epoch_data.ledger != pending.staking_ledger_hash {
+                    return None;
+                }
+                Some(VrfEvaluatorInput::new(
+                    producer.clone(),
+                    epoch_data.seed.clone(),
+                    epoch_data.delegator_table.clone(),
+                    pending.slots.clone(),
+                    epoch_data.total_currency,
+                    epoch_data.ledger.clone(),
+                ))
+            })
+            .collect()
+    }
+
     pub fn next_won_slot(
         &self,
         cur_global_slot: u32,
@@ -75,13 +193,42 @@ impl BlockProducerVrfEvaluatorState {
         self.won_slots
             .range(cur_global_slot..)
             .map(|(_, won_slot)| {
-                BlockProducerWonSlot::from_vrf_won_slot(won_slot, best_tip.genesis_timestamp())
+                BlockProducerWonSlot::from_vrf_won_slot(
+                    won_slot,
+                    best_tip.genesis_timestamp(),
+                    self.slots_per_epoch,
+                )
             })
             .filter(|won_slot| won_slot > best_tip)
             .next()
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PendingVrfEvaluation {
+    pub time: redux::Timestamp,
+    pub slots: RangeInclusive<u32>,
+    pub staking_ledger_hash: LedgerHash,
+    /// Set if the service failed to evaluate the range. It is evaluated
+    /// again after [`VRF_EVALUATION_RETRY_DELAY`].
+    pub failed: Option<VrfEvaluationFailure>,
+}
+
+impl PendingVrfEvaluation {
+    pub fn should_retry(&self, now: redux::Timestamp) -> bool {
+        self.failed
+            .as_ref()
+            .and_then(|failed| now.checked_sub(failed.time))
+            .map_or(false, |waited| waited >= VRF_EVALUATION_RETRY_DELAY)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VrfEvaluationFailure {
+    pub time: redux::Timestamp,
+    pub error: AccountSignerError,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EpochData {
     pub seed: String,
@@ -118,34 +265,212 @@ pub enum BlockProducerVrfEvaluatorStatus {
     DataFail {
         time: redux::Timestamp,
     },
+    /// Some slot ranges are still being evaluated, see
+    /// [`BlockProducerVrfEvaluatorState::pending_evaluations`].
     SlotsRequested {
         time: redux::Timestamp,
-        global_slot: u32,
         staking_ledger_hash: LedgerHash,
     },
+    /// All requested slot ranges were evaluated.
     SlotsReceived {
         time: redux::Timestamp,
-        global_slot: u32,
         staking_ledger_hash: LedgerHash,
     },
 }
 
-impl BlockProducerVrfEvaluatorStatus {
-    pub fn matches_requested_slot(
-        &self,
-        expected_global_slot: u32,
-        expected_staking_ledger_hash: &LedgerHash,
-    ) -> bool {
-        match self {
-            Self::SlotsRequested {
-                global_slot,
-                staking_ledger_hash,
-                ..
-            } => {
-                &expected_global_slot == global_slot
-                    && expected_staking_ledger_hash == staking_ledger_hash
-            }
-            _ => false,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SLOTS_PER_EPOCH: u32 = 7140;
+
+    fn producer() -> AccountPublicKey {
+        "B62qrztYfPinaKqpXaYGY6QJ3SSW2NNKs7SajBLF1iFNXW9BoALN2Aq"
+            .parse()
+            .unwrap()
+    }
+
+    fn state() -> BlockProducerVrfEvaluatorState {
+        let mut state = BlockProducerVrfEvaluatorState::new(redux::Timestamp::ZERO);
+        state.current_epoch = Some(1);
+        state.current_epoch_data = Some(EpochData::new(
+            "current".to_owned(),
+            "jx5YAT36bv62M8mPcREYYfZWXaKqqMzDCP8wmc21uf4CfDKAHCr"
+                .parse()
+                .unwrap(),
+            1_000,
+        ));
+        state.next_epoch_data = Some(EpochData::new(
+            "next".to_owned(),
+            "jxo5pSyt16XGwA9UeuAdiFDzrwFH3smbNTJF7fxq98w1y9Jem2m"
+                .parse()
+                .unwrap(),
+            2_000,
+        ));
+        state
+    }
+
+    fn ranges(inputs: &[VrfEvaluatorInput]) -> Vec<(u32, u32)> {
+        inputs
+            .iter()
+            .map(|input| (*input.slots.start(), *input.slots.end()))
+            .collect()
+    }
+
+    #[test]
+    fn no_inputs_without_epoch_data() {
+        let mut state = state();
+        state.next_epoch_data = None;
+        assert!(state.evaluation_inputs_from(&producer(), 7140).is_empty());
+
+        let state = BlockProducerVrfEvaluatorState::new(redux::Timestamp::ZERO);
+        assert!(state.evaluation_inputs_from(&producer(), 0).is_empty());
+    }
+
+    #[test]
+    fn rest_of_current_epoch_in_ranges() {
+        let state = state();
+        let current_epoch_end = 2 * SLOTS_PER_EPOCH - 1;
+        let inputs = state.evaluation_inputs_from(&producer(), current_epoch_end - 279);
+
+        assert_eq!(
+            ranges(&inputs),
+            vec![
+                (current_epoch_end - 279, current_epoch_end - 160),
+                (current_epoch_end - 159, current_epoch_end - 40),
+                (current_epoch_end - 39, current_epoch_end),
+            ]
+        );
+        for input in &inputs {
+            assert_eq!(input.epoch_seed, "current");
+            assert_eq!(input.total_currency, 1_000);
+            assert_eq!(input.producer, producer());
+        }
+    }
+
+    #[test]
+    fn whole_epoch_is_covered() {
+        let state = state();
+        let inputs = state.evaluation_inputs_from(&producer(), SLOTS_PER_EPOCH);
+        let ranges = ranges(&inputs);
+
+        assert_eq!(ranges.first().unwrap().0, SLOTS_PER_EPOCH);
+        assert_eq!(ranges.last().unwrap().1, 2 * SLOTS_PER_EPOCH - 1);
+        assert!(ranges
+            .iter()
+            .all(|(start, end)| end - start < VRF_EVALUATION_RANGE_LEN));
+        assert!(ranges.windows(2).all(|w| w[0].1 + 1 == w[1].0));
+    }
+
+    #[test]
+    fn next_epoch_uses_next_epoch_data() {
+        let state = state();
+        let next_epoch_start = 2 * SLOTS_PER_EPOCH;
+        let inputs = state.evaluation_inputs_from(&producer(), next_epoch_start);
+
+        assert_eq!(inputs.first().unwrap().slots.start(), &next_epoch_start);
+        assert_eq!(
+            inputs.last().unwrap().slots.end(),
+            &(3 * SLOTS_PER_EPOCH - 1)
+        );
+        assert!(inputs.iter().all(|input| input.epoch_seed == "next"));
+        assert!(inputs.iter().all(|input| input.total_currency == 2_000));
+    }
+
+    #[test]
+    fn no_inputs_past_next_epoch() {
+        let state = state();
+        assert!(state
+            .evaluation_inputs_from(&producer(), 3 * SLOTS_PER_EPOCH)
+            .is_empty());
+    }
+
+    #[test]
+    fn epochs_follow_slots_per_epoch() {
+        let mut state = state();
+        state.slots_per_epoch = 576;
+        let inputs = state.evaluation_inputs_from(&producer(), 576);
+        assert_eq!(ranges(&inputs).last().unwrap().1, 2 * 576 - 1);
+        assert!(inputs.iter().all(|input| input.epoch_seed == "current"));
+
+        let inputs = state.evaluation_inputs_from(&producer(), 2 * 576);
+        assert_eq!(ranges(&inputs).last().unwrap().1, 3 * 576 - 1);
+        assert!(inputs.iter().all(|input| input.epoch_seed == "next"));
+
+        assert!(state
+            .evaluation_inputs_from(&producer(), 3 * 576)
+            .is_empty());
+    }
+
+    fn pending(
+        start: u32,
+        staking_ledger_hash: LedgerHash,
+        failed_at: Option<u64>,
+    ) -> PendingVrfEvaluation {
+        PendingVrfEvaluation {
+            time: redux::Timestamp::ZERO,
+            slots: start..=start + VRF_EVALUATION_RANGE_LEN - 1,
+            staking_ledger_hash,
+            failed: failed_at.map(|secs| VrfEvaluationFailure {
+                time: redux::Timestamp::ZERO + secs * 1_000_000_000,
+                error: AccountSignerError::IOError("connection refused".to_owned()),
+            }),
         }
     }
+
+    #[test]
+    fn retry_failed_after_delay() {
+        let delay = VRF_EVALUATION_RETRY_DELAY.as_secs();
+        let at = |secs: u64| redux::Timestamp::ZERO + secs * 1_000_000_000;
+        let pending = pending(
+            7140,
+            "jx5YAT36bv62M8mPcREYYfZWXaKqqMzDCP8wmc21uf4CfDKAHCr"
+                .parse()
+                .unwrap(),
+            Some(5),
+        );
+        assert!(!pending.should_retry(at(5 + delay - 1)));
+        assert!(pending.should_retry(at(5 + delay)));
+
+        let mut not_failed = pending.clone();
+        not_failed.failed = None;
+        assert!(!not_failed.should_retry(at(5 + delay)));
+    }
+
+    #[test]
+    fn retry_inputs_of_failed_ranges() {
+        let mut state = state();
+        let current = state.current_epoch_data.as_ref().unwrap().ledger.clone();
+        let next = state.next_epoch_data.as_ref().unwrap().ledger.clone();
+        let next_epoch_start = 2 * SLOTS_PER_EPOCH;
+        let now = redux::Timestamp::ZERO + VRF_EVALUATION_RETRY_DELAY.as_nanos() as u64;
+
+        for pending in [
+            pending(7140, current.clone(), Some(0)),
+            // still being evaluated.
+            pending(7260, current.clone(), None),
+            pending(next_epoch_start, next.clone(), Some(0)),
+            // evaluated with a staking ledger which is no longer current.
+            pending(next_epoch_start + 120, current.clone(), Some(0)),
+        ] {
+            state
+                .pending_evaluations
+                .insert(*pending.slots.start(), pending);
+        }
+
+        let inputs = state.evaluation_retry_inputs(&producer(), now);
+        assert_eq!(
+            ranges(&inputs),
+            vec![(7140, 7259), (next_epoch_start, next_epoch_start + 119)]
+        );
+        assert_eq!(inputs[0].epoch_seed, "current");
+        assert_eq!(inputs[0].staking_ledger_hash, current);
+        assert_eq!(inputs[1].epoch_seed, "next");
+        assert_eq!(inputs[1].staking_ledger_hash, next);
+
+        let too_early = redux::Timestamp::ZERO + VRF_EVALUATION_RETRY_DELAY.as_nanos() as u64 / 2;
+        assert!(state
+            .evaluation_retry_inputs(&producer(), too_early)
+            .is_empty());
+    }
 }
@@ -3,8 +3,9 @@ use crate::account::AccountPublicKey;
 use ledger::AccountIndex;
 use mina_p2p_messages::v2::LedgerHash;
 use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
 use std::sync::Arc;
-use vrf::{VrfEvaluationOutput, VrfWonSlot};
+use vrf::VrfWonSlot;
 
 pub use block_producer_vrf_evaluator_state::*;
 
@@ -28,9 +29,12 @@ pub type DelegatorTable = BTreeMap<AccountIndex, (AccountPublicKey, u64)>;
 
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct VrfEvaluatorInput {
+    /// Our key for which vrf is evaluated.
+    pub producer: AccountPublicKey,
     pub epoch_seed: String,
     pub delegator_table: Arc<DelegatorTable>,
-    pub global_slot: u32,
+    /// Slots to evaluate, all of them must be in the same epoch.
+    pub slots: RangeInclusive<u32>,
     pub total_currency: u64,
     pub staking_ledger_hash: LedgerHash,
 }
@@ -50,30 +54,44 @@ impl VrfWonSlotWithHash {
     }
 }
 
+/// Result of evaluating a range of slots. Slots in the range, which
+/// aren't in `won_slots`, were lost.
 #[derive(Debug, Deserialize, Clone, Serialize)]
-pub struct VrfEvaluationOutputWithHash {
-    pub evaluation_result: VrfEvaluationOutput,
+pub struct VrfEvaluationBatchWithHash {
+    pub producer: AccountPublicKey,
+    pub slots: RangeInclusive<u32>,
+    pub won_slots: Vec<VrfWonSlot>,
     pub staking_ledger_hash: LedgerHash,
 }
 
-impl std::fmt::Display for VrfEvaluationOutputWithHash {
+impl std::fmt::Display for VrfEvaluationBatchWithHash {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} ", self.staking_ledger_hash.to_string())?;
-        match &self.evaluation_result {
-            VrfEvaluationOutput::SlotWon(won_slot) => {
-                write!(f, "SlotWon {}", won_slot.global_slot)
-            }
-            VrfEvaluationOutput::SlotLost(global_slot) => {
-                write!(f, "SlotLost {}", global_slot)
-            }
-        }
+        write!(
+            f,
+            "{} {} slots {}..={}, won: {:?}",
+            self.producer,
+            self.staking_ledger_hash,
+            self.slots.start(),
+            self.slots.end(),
+            self.won_slots
+                .iter()
+                .map(|won_slot| won_slot.global_slot)
+                .collect::<Vec<_>>()
+        )
     }
 }
 
-impl VrfEvaluationOutputWithHash {
-    pub fn new(evaluation_result: VrfEvaluationOutput, staking_ledger_hash: LedgerHash) -> Self {
+impl VrfEvaluationBatchWithHash {
+    pub fn new(
+        producer: AccountPublicKey,
+        slots: RangeInclusive<u32>,
+        won_slots: Vec<VrfWonSlot>,
+        staking_ledger_hash: LedgerHash,
+    ) -> Self {
         Self {
-            evaluation_result,
+            producer,
+            slots,
+            won_slots,
             staking_ledger_hash,
         }
     }
@@ -81,16 +99,18 @@ impl VrfEvaluationOutputWithHash {
 
 impl VrfEvaluatorInput {
     pub fn new(
+        producer: AccountPublicKey,
         epoch_seed: String,
         delegator_table: Arc<DelegatorTable>,
-        global_slot: u32,
+        slots: RangeInclusive<u32>,
         total_currency: u64,
         staking_ledger_hash: LedgerHash,
     ) -> Self {
         Self {
+            producer,
             epoch_seed,
             delegator_table,
-            global_slot,
+            slots,
             total_currency,
             staking_ledger_hash,
         }
@@ -1,7 +1,9 @@
 use std::ffi::OsString;
 use std::str::FromStr;
 
-use mina_p2p_messages::v2::CurrencyFeeStableV1;
+use mina_p2p_messages::v2::{
+    CurrencyFeeStableV1, UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::account::AccountPublicKey;
@@ -29,6 +31,9 @@ pub struct Config {
 pub struct GlobalConfig {
     pub build: Box<BuildEnv>,
     pub snarker: Option<SnarkerConfig>,
+    /// Correct the time used for slot timing by the estimated skew of
+    /// our clock, when it's detected.
+    pub clock_skew_correction: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -37,14 +42,76 @@ pub struct SnarkerConfig {
     pub fee: CurrencyFeeStableV1,
     pub strategy: SnarkerStrategy,
     pub auto_commit: bool,
+    /// Minimum payout, in nanomina per second of estimated proving time,
+    /// for [`SnarkerStrategy::Profit`] to commit to a job.
+    #[serde(default)]
+    pub min_payout_per_sec: u64,
     /// External Mina snark worker executable path
     pub path: OsString,
+    /// Number of snark workers, which prove jobs in parallel.
+    #[serde(default = "default_snarker_workers")]
+    pub workers: usize,
+    #[serde(default)]
+    pub worker_kind: SnarkerWorkerKind,
+    /// If set, [`SnarkerConfig::fee`] is only the initial fee, which is
+    /// then adjusted based on the fees of competing snarkers.
+    #[serde(default)]
+    pub fee_policy: Option<SnarkerFeePolicy>,
+}
+
+fn default_snarker_workers() -> usize {
+    1
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
 pub enum SnarkerStrategy {
     Sequential,
     Random,
+    /// Only commit to jobs which we can win with our fee and which are
+    /// worth their estimated proving time, oldest jobs first.
+    Profit,
+}
+
+/// Keeps our fee just below most of the recent winning fees of other
+/// snarkers, within `[min, max]` bounds.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkerFeePolicy {
+    pub min: CurrencyFeeStableV1,
+    pub max: CurrencyFeeStableV1,
+}
+
+impl SnarkerFeePolicy {
+    /// Minimum number of observed fees, before adjusting our fee.
+    const MIN_SAMPLES: usize = 5;
+
+    /// Fee, which would beat 3/4 of the `winning_fees`, or `None` if
+    /// there are not enough of them.
+    pub fn fee<I>(&self, winning_fees: I) -> Option<CurrencyFeeStableV1>
+    where
+        I: IntoIterator<Item = u64>,
+    {
+        let mut fees = winning_fees.into_iter().collect::<Vec<_>>();
+        if fees.len() < Self::MIN_SAMPLES {
+            return None;
+        }
+        fees.sort_unstable();
+        let (min, max) = (self.min.0.as_u64(), self.max.0.as_u64());
+        let fee = fees[fees.len() / 4]
+            .saturating_sub(1)
+            .clamp(min, max.max(min));
+        Some(CurrencyFeeStableV1(
+            UnsignedExtendedUInt64Int64ForVersionTagsStableV1(fee.into()),
+        ))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SnarkerWorkerKind {
+    /// Mina snark worker process, spawned from [`SnarkerConfig::path`].
+    #[default]
+    External,
+    /// Prover running on a thread of the node's own process.
+    InProcess,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -109,7 +176,7 @@ impl BuildEnv {
 }
 
 #[derive(thiserror::Error, Debug)]
-#[error("invalid strategy: {0}! expected one of: seq/sequential/rand/random")]
+#[error("invalid strategy: {0}! expected one of: seq/sequential/rand/random/profit")]
 pub struct SnarkerStrategyParseError(String);
 
 impl FromStr for SnarkerStrategy {
@@ -119,7 +186,74 @@ impl FromStr for SnarkerStrategy {
         Ok(match s {
             "seq" | "sequential" => SnarkerStrategy::Sequential,
             "rand" | "random" => SnarkerStrategy::Random,
+            "profit" => SnarkerStrategy::Profit,
             other => return Err(SnarkerStrategyParseError(other.to_owned())),
         })
     }
 }
+
+#[derive(thiserror::Error, Debug)]
+#[error("invalid snark worker kind: {0}! expected one of: external/in-process")]
+pub struct SnarkerWorkerKindParseError(String);
+
+impl FromStr for SnarkerWorkerKind {
+    type Err = SnarkerWorkerKindParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "external" => SnarkerWorkerKind::External,
+            "in-process" | "inprocess" => SnarkerWorkerKind::InProcess,
+            other => return Err(SnarkerWorkerKindParseError(other.to_owned())),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::scan_state::currency::{Fee, Magnitude};
+
+    use super::*;
+
+    fn fee(nanomina: u64) -> CurrencyFeeStableV1 {
+        (&Fee::from_u64(nanomina)).into()
+    }
+
+    fn policy(min: u64, max: u64) -> SnarkerFeePolicy {
+        SnarkerFeePolicy {
+            min: fee(min),
+            max: fee(max),
+        }
+    }
+
+    fn policy_fee(policy: &SnarkerFeePolicy, winning_fees: &[u64]) -> Option<u64> {
+        policy
+            .fee(winning_fees.iter().copied())
+            .map(|fee| fee.0.as_u64())
+    }
+
+    #[test]
+    fn fee_policy_needs_enough_samples() {
+        let policy = policy(0, 1_000);
+        assert_eq!(policy_fee(&policy, &[]), None);
+        assert_eq!(policy_fee(&policy, &[100, 200, 300, 400]), None);
+        assert_eq!(policy_fee(&policy, &[100, 200, 300, 400, 500]), Some(199));
+    }
+
+    #[test]
+    fn fee_policy_beats_three_quarters_of_fees() {
+        let policy = policy(0, 1_000);
+        let fees = [800, 100, 700, 200, 600, 300, 500, 400];
+        assert_eq!(policy_fee(&policy, &fees), Some(299));
+        assert_eq!(policy_fee(&policy, &[0; 8]), Some(0));
+    }
+
+    #[test]
+    fn fee_policy_clamped() {
+        let fees = [800, 100, 700, 200, 600, 300, 500, 400];
+        assert_eq!(policy_fee(&policy(500, 1_000), &fees), Some(500));
+        assert_eq!(policy_fee(&policy(0, 200), &fees), Some(200));
+        assert_eq!(policy_fee(&policy(299, 299), &fees), Some(299));
+        // invalid bounds don't panic, min wins.
+        assert_eq!(policy_fee(&policy(600, 500), &fees), Some(600));
+    }
+}
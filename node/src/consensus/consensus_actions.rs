@@ -41,6 +41,12 @@ pub enum ConsensusAction {
         hash: StateHash,
     },
     Prune,
+    /// Estimated skew of our clock is past the threshold, or changed
+    /// while past it.
+    ClockSkewDetected {
+        skew_ms: i64,
+    },
+    ClockSkewResolved,
 }
 
 impl redux::EnablingCondition<crate::State> for ConsensusAction {
@@ -125,6 +131,15 @@ impl redux::EnablingCondition<crate::State> for ConsensusAction {
             ConsensusAction::Prune => {
                 state.consensus.best_tip().is_some()
             },
+            ConsensusAction::ClockSkewDetected { skew_ms } => {
+                let clock_skew = &state.consensus.clock_skew;
+                clock_skew.estimate_past_threshold_ms() == Some(*skew_ms)
+                    && clock_skew.detected.as_ref().map_or(true, |d| d.skew_ms != *skew_ms)
+            },
+            ConsensusAction::ClockSkewResolved => {
+                let clock_skew = &state.consensus.clock_skew;
+                clock_skew.is_detected() && clock_skew.estimate_past_threshold_ms().is_none()
+            },
         }
     }
 }
@@ -0,0 +1,217 @@
+use std::collections::VecDeque;
+
+use openmina_core::block::ArcBlockWithHash;
+use serde::{Deserialize, Serialize};
+
+/// Number of the latest received blocks the clock skew is estimated from.
+pub const CLOCK_SKEW_SAMPLES_MAX: usize = 32;
+/// Skew isn't estimated until this many blocks are received.
+pub const CLOCK_SKEW_SAMPLES_MIN: usize = 5;
+/// Estimated skew past which our clock is considered to be wrong.
+///
+/// Blocks normally arrive some seconds after the start of their slot,
+/// that delay is included in the estimate, so it has to be well above it.
+pub const CLOCK_SKEW_THRESHOLD_MS: i64 = 60_000;
+/// Maximum correction applied to the time used for slot timing, half
+/// of the slot duration.
+pub const CLOCK_SKEW_CORRECTION_MAX_MS: i64 = 90_000;
+
+/// Offset of our clock from the block producers' clocks, estimated by
+/// comparing arrival times of received blocks with start times of their
+/// slots.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ConsensusClockSkewState {
+    /// Arrival time minus slot start time of the latest received blocks,
+    /// in milliseconds.
+    pub samples: VecDeque<i64>,
+    /// Set while the estimated skew is past [`CLOCK_SKEW_THRESHOLD_MS`].
+    pub detected: Option<ConsensusClockSkewDetected>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConsensusClockSkewDetected {
+    pub time: redux::Timestamp,
+    pub skew_ms: i64,
+}
+
+impl ConsensusClockSkewState {
+    /// Samples the received block, if it's for a later slot than our
+    /// best tip and we are synced.
+    ///
+    /// Other blocks, like the ones received while syncing or blocks of
+    /// forks for older slots, were produced well before they arrived, so
+    /// they would skew the estimate.
+    pub fn block_sample_add(
+        &mut self,
+        arrival: redux::Timestamp,
+        block: &ArcBlockWithHash,
+        synced_best_tip: Option<&ArcBlockWithHash>,
+    ) {
+        let Some(best_tip) = synced_best_tip else {
+            return;
+        };
+        if block.global_slot() > best_tip.global_slot() {
+            self.sample_add(arrival, block.timestamp());
+        }
+    }
+
+    pub fn sample_add(&mut self, arrival: redux::Timestamp, slot_start: redux::Timestamp) {
+        let arrival_ms = (u64::from(arrival) / 1_000_000) as i64;
+        let slot_start_ms = (u64::from(slot_start) / 1_000_000) as i64;
+        if self.samples.len() >= CLOCK_SKEW_SAMPLES_MAX {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(arrival_ms - slot_start_ms);
+    }
+
+    /// Median of the samples, so that a few stale or early blocks don't
+    /// affect it.
+    pub fn estimate_ms(&self) -> Option<i64> {
+        if self.samples.len() < CLOCK_SKEW_SAMPLES_MIN {
+            return None;
+        }
+        let mut samples = self.samples.iter().copied().collect::<Vec<_>>();
+        samples.sort_unstable();
+        Some(samples[samples.len() / 2])
+    }
+
+    /// Estimated skew if it's past [`CLOCK_SKEW_THRESHOLD_MS`].
+    pub fn estimate_past_threshold_ms(&self) -> Option<i64> {
+        self.estimate_ms()
+            .filter(|skew| skew.abs() > CLOCK_SKEW_THRESHOLD_MS)
+    }
+
+    pub fn is_detected(&self) -> bool {
+        self.detected.is_some()
+    }
+
+    /// To be subtracted from the current time for slot timing, if
+    /// correction is enabled.
+    pub fn correction_ms(&self) -> i64 {
+        self.detected.as_ref().map_or(0, |detected| {
+            detected
+                .skew_ms
+                .clamp(-CLOCK_SKEW_CORRECTION_MAX_MS, CLOCK_SKEW_CORRECTION_MAX_MS)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::v2::MinaNumbersGlobalSlotSinceHardForkMStableV1;
+    use openmina_core::block::test_block_with;
+
+    use super::*;
+
+    fn ms(ms: u64) -> redux::Timestamp {
+        redux::Timestamp::new(ms * 1_000_000)
+    }
+
+    fn with_samples(samples: &[i64]) -> ConsensusClockSkewState {
+        ConsensusClockSkewState {
+            samples: samples.iter().copied().collect(),
+            detected: None,
+        }
+    }
+
+    fn detected(skew_ms: i64) -> ConsensusClockSkewState {
+        ConsensusClockSkewState {
+            samples: Default::default(),
+            detected: Some(ConsensusClockSkewDetected {
+                time: redux::Timestamp::ZERO,
+                skew_ms,
+            }),
+        }
+    }
+
+    fn block(slot_offset: i64) -> ArcBlockWithHash {
+        test_block_with(|block| {
+            let consensus_state = &mut block.header.protocol_state.body.consensus_state;
+            let slot = consensus_state.global_slot() as i64 + slot_offset;
+            consensus_state.curr_global_slot_since_hard_fork.slot_number =
+                MinaNumbersGlobalSlotSinceHardForkMStableV1::SinceHardFork((slot as u32).into());
+        })
+    }
+
+    #[test]
+    fn sample_add_keeps_latest() {
+        let mut state = ConsensusClockSkewState::default();
+        for i in 0..CLOCK_SKEW_SAMPLES_MAX as u64 + 3 {
+            state.sample_add(ms(10_000 + i), ms(10_000));
+        }
+        assert_eq!(state.samples.len(), CLOCK_SKEW_SAMPLES_MAX);
+        assert_eq!(state.samples.front(), Some(&3));
+        assert_eq!(
+            state.samples.back(),
+            Some(&(CLOCK_SKEW_SAMPLES_MAX as i64 + 2))
+        );
+
+        // blocks arriving before their slot starts are negative samples.
+        state.sample_add(ms(1_000), ms(5_000));
+        assert_eq!(state.samples.back(), Some(&-4_000));
+    }
+
+    #[test]
+    fn estimate_is_median() {
+        assert_eq!(with_samples(&[1, 2, 3, 4]).estimate_ms(), None);
+        assert_eq!(
+            with_samples(&[2_000, 3_000, -900_000, 1_000, 900_000]).estimate_ms(),
+            Some(2_000)
+        );
+        assert_eq!(
+            with_samples(&[-70_000, -80_000, -75_000, 0, -72_000, -71_000]).estimate_ms(),
+            Some(-71_000)
+        );
+    }
+
+    #[test]
+    fn estimate_past_threshold() {
+        let skewed = with_samples(&[CLOCK_SKEW_THRESHOLD_MS + 1; CLOCK_SKEW_SAMPLES_MIN]);
+        assert_eq!(
+            skewed.estimate_past_threshold_ms(),
+            Some(CLOCK_SKEW_THRESHOLD_MS + 1)
+        );
+        let skewed = with_samples(&[-CLOCK_SKEW_THRESHOLD_MS - 1; CLOCK_SKEW_SAMPLES_MIN]);
+        assert!(skewed.estimate_past_threshold_ms().is_some());
+
+        let fine = with_samples(&[CLOCK_SKEW_THRESHOLD_MS; CLOCK_SKEW_SAMPLES_MIN]);
+        assert_eq!(fine.estimate_past_threshold_ms(), None);
+    }
+
+    #[test]
+    fn correction_is_bounded() {
+        assert_eq!(ConsensusClockSkewState::default().correction_ms(), 0);
+        assert_eq!(detected(70_000).correction_ms(), 70_000);
+        assert_eq!(detected(-70_000).correction_ms(), -70_000);
+        assert_eq!(
+            detected(1_000_000).correction_ms(),
+            CLOCK_SKEW_CORRECTION_MAX_MS
+        );
+        assert_eq!(
+            detected(-1_000_000).correction_ms(),
+            -CLOCK_SKEW_CORRECTION_MAX_MS
+        );
+    }
+
+    #[test]
+    fn only_new_blocks_sampled_when_synced() {
+        let best_tip = block(0);
+        let arrival = ms(u64::from(best_tip.timestamp()) / 1_000_000);
+        let mut state = ConsensusClockSkewState::default();
+
+        // not synced.
+        state.block_sample_add(arrival, &block(1), None);
+        assert!(state.samples.is_empty());
+
+        // not later than our best tip.
+        state.block_sample_add(arrival, &block(0), Some(&best_tip));
+        state.block_sample_add(arrival, &block(-5), Some(&best_tip));
+        assert!(state.samples.is_empty());
+
+        let new_block = block(1);
+        state.block_sample_add(arrival, &new_block, Some(&best_tip));
+        let expected = (u64::from(arrival) / 1_000_000) as i64
+            - (u64::from(new_block.timestamp()) / 1_000_000) as i64;
+        assert_eq!(state.samples, [expected]);
+    }
+}
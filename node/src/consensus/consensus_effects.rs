@@ -1,3 +1,6 @@
+use mina_p2p_messages::v2::StateHash;
+use openmina_core::block::ArcBlockWithHash;
+
 use crate::snark::block_verify::SnarkBlockVerifyAction;
 use crate::transition_frontier::sync::TransitionFrontierSyncAction;
 use crate::watched_accounts::WatchedAccountsAction;
@@ -10,6 +13,16 @@ pub fn consensus_effects<S: crate::Service>(store: &mut Store<S>, action: Consen
 
     match action {
         ConsensusAction::BlockReceived { hash, block, .. } => {
+            match store
+                .state()
+                .consensus
+                .clock_skew
+                .estimate_past_threshold_ms()
+            {
+                Some(skew_ms) => store.dispatch(ConsensusAction::ClockSkewDetected { skew_ms }),
+                None => store.dispatch(ConsensusAction::ClockSkewResolved),
+            };
+
             let req_id = store.state().snark.block_verify.next_req_id();
             store.dispatch(SnarkBlockVerifyAction::Init {
                 req_id,
@@ -30,10 +43,11 @@ pub fn consensus_effects<S: crate::Service>(store: &mut Store<S>, action: Consen
             store.dispatch(ConsensusAction::ShortRangeForkResolve { hash: hash.clone() });
             store.dispatch(ConsensusAction::LongRangeForkResolve { hash });
         }
-        ConsensusAction::ShortRangeForkResolve { hash } => {
-            store.dispatch(ConsensusAction::BestTipUpdate { hash });
-        }
-        ConsensusAction::LongRangeForkResolve { hash } => {
+        ConsensusAction::ShortRangeForkResolve { hash }
+        | ConsensusAction::LongRangeForkResolve { hash } => {
+            if let Some(block) = store.state().consensus.rejected_fork_block_with_hash(&hash) {
+                store.service.archive_fork_block_add(&block);
+            }
             store.dispatch(ConsensusAction::BestTipUpdate { hash });
         }
         ConsensusAction::BestTipUpdate { .. } => {
@@ -53,6 +67,8 @@ pub fn consensus_effects<S: crate::Service>(store: &mut Store<S>, action: Consen
             transition_frontier_new_best_tip(store);
         }
         ConsensusAction::Prune => {}
+        ConsensusAction::ClockSkewDetected { .. } => {}
+        ConsensusAction::ClockSkewResolved => {}
     }
 }
 
@@ -68,10 +84,10 @@ fn transition_frontier_new_best_tip<S: crate::Service>(store: &mut Store<S>) {
             let old_best_tip = state.transition_frontier.best_tip()?;
             let mut iter = state.transition_frontier.best_chain.iter();
             if old_best_tip.hash() == pred_hash {
-                iter.next();
-                let root_block = iter.next()?.clone();
-                let hashes = iter.map(|b| b.hash.clone()).collect();
-                Some((hashes, root_block))
+                best_chain_extended(
+                    &state.transition_frontier.best_chain,
+                    state.transition_frontier.config.k(),
+                )
             } else if old_best_tip.pred_hash() == pred_hash {
                 let root_block = iter.next()?.clone();
                 let hashes = iter.rev().skip(1).rev().map(|b| b.hash.clone()).collect();
@@ -98,3 +114,78 @@ fn transition_frontier_new_best_tip<S: crate::Service>(store: &mut Store<S>) {
         });
     }
 }
+
+/// Root and blocks in between of the best chain, once it's extended by
+/// a new best tip.
+///
+/// Root only moves once the chain holds more than `k` blocks. Until
+/// then, e.g. in a network started from a genesis block, which we built
+/// locally, the chain grows from the genesis block. Otherwise the root
+/// would move past the only block of the chain and the chain could
+/// never be extended.
+fn best_chain_extended(
+    best_chain: &[ArcBlockWithHash],
+    k: usize,
+) -> Option<(Vec<StateHash>, ArcBlockWithHash)> {
+    let mut iter = best_chain.iter();
+    if best_chain.len() > k {
+        iter.next();
+    }
+    let root_block = iter.next()?.clone();
+    let hashes = iter.map(|b| b.hash.clone()).collect();
+    Some((hashes, root_block))
+}
+
+#[cfg(test)]
+mod tests {
+    use mina_p2p_messages::v2::UnsignedExtendedUInt32StableV1;
+    use openmina_core::block::{test_block, test_block_with};
+
+    use super::*;
+
+    fn chain(len: usize) -> Vec<ArcBlockWithHash> {
+        let mut chain = Vec::<ArcBlockWithHash>::new();
+        for _ in 0..len {
+            let block = match chain.last() {
+                None => test_block(),
+                Some(parent) => test_block_with(|block| {
+                    let protocol_state = &mut block.header.protocol_state;
+                    protocol_state.previous_state_hash = parent.hash.clone();
+                    protocol_state.body.consensus_state.blockchain_length =
+                        UnsignedExtendedUInt32StableV1((parent.height() + 1).into());
+                }),
+            };
+            chain.push(block);
+        }
+        chain
+    }
+
+    fn hashes(blocks: &[ArcBlockWithHash]) -> Vec<StateHash> {
+        blocks.iter().map(|b| b.hash.clone()).collect()
+    }
+
+    #[test]
+    fn root_stays_until_chain_holds_k_blocks() {
+        let (between, root) = best_chain_extended(&chain(1), 2).unwrap();
+        assert_eq!(root.hash, chain(1)[0].hash);
+        assert!(between.is_empty());
+
+        let chain = chain(2);
+        let (between, root) = best_chain_extended(&chain, 2).unwrap();
+        assert_eq!(root.hash, chain[0].hash);
+        assert_eq!(between, hashes(&chain[1..]));
+    }
+
+    #[test]
+    fn root_moves_once_chain_holds_more_than_k_blocks() {
+        let chain = chain(3);
+        let (between, root) = best_chain_extended(&chain, 2).unwrap();
+        assert_eq!(root.hash, chain[1].hash);
+        assert_eq!(between, hashes(&chain[2..]));
+    }
+
+    #[test]
+    fn empty_chain_has_no_root() {
+        assert!(best_chain_extended(&[], 2).is_none());
+    }
+}
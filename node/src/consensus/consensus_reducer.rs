@@ -1,12 +1,20 @@
+use openmina_core::block::{ArcBlockWithHash, BlockWithHash};
 use openmina_core::consensus::{is_short_range_fork, long_range_fork_take, short_range_fork_take};
 
 use super::{
     ConsensusAction, ConsensusActionWithMetaRef, ConsensusBlockState, ConsensusBlockStatus,
-    ConsensusLongRangeForkDecision, ConsensusShortRangeForkDecision, ConsensusState,
+    ConsensusClockSkewDetected, ConsensusLongRangeForkDecision, ConsensusShortRangeForkDecision,
+    ConsensusState,
 };
 
 impl ConsensusState {
-    pub fn reducer(&mut self, action: ConsensusActionWithMetaRef<'_>) {
+    /// `synced_best_tip` is our best tip, if the transition frontier is
+    /// synced.
+    pub fn reducer(
+        &mut self,
+        action: ConsensusActionWithMetaRef<'_>,
+        synced_best_tip: Option<&ArcBlockWithHash>,
+    ) {
         let (action, meta) = action.split();
         match action {
             ConsensusAction::BlockReceived {
@@ -14,6 +22,13 @@ impl ConsensusState {
                 block,
                 chain_proof,
             } => {
+                let block_with_hash = BlockWithHash {
+                    hash: hash.clone(),
+                    block: block.clone(),
+                };
+                self.clock_skew
+                    .block_sample_add(meta.time(), &block_with_hash, synced_best_tip);
+
                 self.blocks.insert(
                     hash.clone(),
                     ConsensusBlockState {
@@ -172,6 +187,15 @@ impl ConsensusState {
                     .collect();
                 *blocks = blocks_to_keep;
             }
+            ConsensusAction::ClockSkewDetected { skew_ms } => {
+                self.clock_skew.detected = Some(ConsensusClockSkewDetected {
+                    time: meta.time(),
+                    skew_ms: *skew_ms,
+                });
+            }
+            ConsensusAction::ClockSkewResolved => {
+                self.clock_skew.detected = None;
+            }
         }
     }
 }
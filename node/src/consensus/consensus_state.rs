@@ -13,6 +13,8 @@ use openmina_core::consensus::{
 
 use crate::snark::block_verify::SnarkBlockVerifyId;
 
+use super::ConsensusClockSkewState;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ConsensusShortRangeForkDecision {
     TakeNoBestTip,
@@ -118,6 +120,7 @@ pub struct ConsensusState {
     // TODO(binier): rename to best candidate. Best tip will be in transition_frontier state.
     pub best_tip: Option<StateHash>,
     pub best_tip_chain_proof: Option<(Vec<StateHash>, ArcBlockWithHash)>,
+    pub clock_skew: ConsensusClockSkewState,
 }
 
 impl ConsensusState {
@@ -126,9 +129,28 @@ impl ConsensusState {
             blocks: BTreeMap::new(),
             best_tip: None,
             best_tip_chain_proof: None,
+            clock_skew: Default::default(),
         }
     }
 
+    /// Block, which was resolved not to be used as the best tip.
+    pub fn rejected_fork_block_with_hash(&self, hash: &StateHash) -> Option<ArcBlockWithHash> {
+        let block = self.blocks.get(hash)?;
+        let rejected = match &block.status {
+            ConsensusBlockStatus::ShortRangeForkResolve { decision, .. } => {
+                !decision.use_as_best_tip()
+            }
+            ConsensusBlockStatus::LongRangeForkResolve { decision, .. } => {
+                !decision.use_as_best_tip()
+            }
+            _ => false,
+        };
+        rejected.then(|| BlockWithHash {
+            hash: hash.clone(),
+            block: block.block.clone(),
+        })
+    }
+
     pub fn best_tip_block_with_hash(&self) -> Option<BlockWithHash<Arc<MinaBlockBlockStableV2>>> {
         let hash = self.best_tip.as_ref()?;
         let block = self.blocks.get(hash)?;
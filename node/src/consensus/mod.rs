@@ -1,6 +1,9 @@
 mod consensus_state;
 pub use consensus_state::*;
 
+mod consensus_clock_skew;
+pub use consensus_clock_skew::*;
+
 mod consensus_actions;
 pub use consensus_actions::*;
 
@@ -1,6 +1,7 @@
 use p2p::channels::snark::P2pChannelsSnarkAction;
 use redux::ActionMeta;
 
+use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorAction;
 use crate::block_producer::{block_producer_effects, BlockProducerAction};
 use crate::consensus::consensus_effects;
 use crate::event_source::event_source_effects;
@@ -15,6 +16,7 @@ use crate::rpc::rpc_effects;
 use crate::snark::snark_effects;
 use crate::snark_pool::candidate::SnarkPoolCandidateAction;
 use crate::snark_pool::{snark_pool_effects, SnarkPoolAction};
+use crate::transition_frontier::genesis::TransitionFrontierGenesisAction;
 use crate::transition_frontier::sync::TransitionFrontierSyncAction;
 use crate::transition_frontier::transition_frontier_effects;
 use crate::watched_accounts::watched_accounts_effects;
@@ -37,7 +39,10 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
         // effect execution should be as light as possible.
         Action::CheckTimeouts(_) => {
             // TODO(binier): create init action and dispatch this there.
-            store.dispatch(ExternalSnarkWorkerAction::Start);
+            for worker_id in store.state().external_snark_worker.ids() {
+                store.dispatch(ExternalSnarkWorkerAction::Start { worker_id });
+            }
+            store.dispatch(TransitionFrontierGenesisAction::LedgerLoadInit);
 
             p2p_connection_timeouts(store, &meta);
 
@@ -68,10 +73,14 @@ pub fn effects<S: Service>(store: &mut Store<S>, action: ActionWithMeta) {
             // TODO(binier): remove once ledger communication is async.
             store.dispatch(TransitionFrontierSyncAction::BlocksNextApplyInit);
 
-            store.dispatch(ExternalSnarkWorkerAction::StartTimeout { now: meta.time() });
-            store.dispatch(ExternalSnarkWorkerAction::WorkTimeout { now: meta.time() });
+            for worker_id in store.state().external_snark_worker.ids() {
+                let now = meta.time();
+                store.dispatch(ExternalSnarkWorkerAction::StartTimeout { worker_id, now });
+                store.dispatch(ExternalSnarkWorkerAction::WorkTimeout { worker_id, now });
+            }
 
             store.dispatch(BlockProducerAction::WonSlotProduceInit);
+            store.dispatch(BlockProducerVrfEvaluatorAction::EvaluationRetry);
         }
         Action::EventSource(action) => {
             event_source_effects(store, meta.with_action(action));
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::block_producer::BlockProducerEvent;
-use crate::external_snark_worker::ExternalSnarkWorkerEvent;
+use crate::external_snark_worker::{ExternalSnarkWorkerEvent, ExternalSnarkWorkerId};
 pub use crate::p2p::{P2pConnectionEvent, P2pEvent};
 pub use crate::rpc::{RpcId, RpcRequest};
 pub use crate::snark::SnarkEvent;
@@ -11,7 +11,7 @@ pub enum Event {
     P2p(P2pEvent),
     Snark(SnarkEvent),
     Rpc(RpcId, RpcRequest),
-    ExternalSnarkWorker(ExternalSnarkWorkerEvent),
+    ExternalSnarkWorker(ExternalSnarkWorkerId, ExternalSnarkWorkerEvent),
     BlockProducerEvent(BlockProducerEvent),
 }
 
@@ -26,6 +26,9 @@ impl std::fmt::Display for Event {
                     RpcRequest::StateGet => write!(f, "StateGet"),
                     RpcRequest::ActionStatsGet(query) => write!(f, "ActionStatsGet, {query:?}"),
                     RpcRequest::SyncStatsGet(query) => write!(f, "SyncStatsGet, {query:?}"),
+                    RpcRequest::SnarkMarketStatsGet(query) => {
+                        write!(f, "SnarkMarketStatsGet, {query:?}")
+                    }
                     RpcRequest::PeersGet => write!(f, "PeersGet"),
                     RpcRequest::P2pConnectionOutgoing(opts) => {
                         write!(f, "P2pConnectionOutgoing, {opts}")
@@ -46,12 +49,37 @@ impl std::fmt::Display for Event {
                     }
                     RpcRequest::SnarkerJobSpec { job_id } => write!(f, "SnarkerJobSpec, {job_id}"),
                     RpcRequest::SnarkerWorkers => write!(f, "SnarkerWorkers"),
+                    RpcRequest::SnarkersReliability => write!(f, "SnarkersReliability"),
                     RpcRequest::HealthCheck => write!(f, "HealthCheck"),
                     RpcRequest::ReadinessCheck => write!(f, "ReadinessCheck"),
+                    RpcRequest::WatchedAccountsAdd { pub_key } => {
+                        write!(f, "WatchedAccountsAdd, {pub_key}")
+                    }
+                    RpcRequest::WatchedAccountsRemove { pub_key } => {
+                        write!(f, "WatchedAccountsRemove, {pub_key}")
+                    }
+                    RpcRequest::WatchedAccountsGet => write!(f, "WatchedAccountsGet"),
+                    RpcRequest::WatchedAccountBlocksGet(query) => {
+                        write!(f, "WatchedAccountBlocksGet, {}", query.pub_key)
+                    }
+                    RpcRequest::AccountProofGet(query) => {
+                        write!(f, "AccountProofGet, {}", query.public_key)
+                    }
+                    RpcRequest::BlockGet(query) => write!(f, "BlockGet, {query:?}"),
+                    RpcRequest::TransitionFrontierStatusGet => {
+                        write!(f, "TransitionFrontierStatusGet")
+                    }
+                    RpcRequest::BlockProducerScheduleGet => write!(f, "BlockProducerScheduleGet"),
+                    RpcRequest::BlockProducerHistoryGet => write!(f, "BlockProducerHistoryGet"),
+                    RpcRequest::SnarkerWorkGet => write!(f, "SnarkerWorkGet"),
+                    RpcRequest::SnarkerWorkSubmit(_) => write!(f, "SnarkerWorkSubmit"),
+                    RpcRequest::BlockProducerEpochDataGet => {
+                        write!(f, "BlockProducerEpochDataGet")
+                    }
                 }
             }
-            Self::ExternalSnarkWorker(event) => {
-                write!(f, "ExternalSnarkWorker, ")?;
+            Self::ExternalSnarkWorker(worker_id, event) => {
+                write!(f, "ExternalSnarkWorker, {worker_id}, ")?;
 
                 match event {
                     ExternalSnarkWorkerEvent::Started => write!(f, "Started"),
@@ -231,6 +231,9 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::SyncStatsGet(query) => {
                     store.dispatch(RpcAction::SyncStatsGet { rpc_id, query });
                 }
+                RpcRequest::SnarkMarketStatsGet(query) => {
+                    store.dispatch(RpcAction::SnarkMarketStatsGet { rpc_id, query });
+                }
                 RpcRequest::PeersGet => {
                     store.dispatch(RpcAction::PeersGet { rpc_id });
                 }
@@ -264,31 +267,71 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
                 RpcRequest::SnarkerWorkers => {
                     store.dispatch(RpcAction::SnarkerWorkersGet { rpc_id });
                 }
+                RpcRequest::SnarkersReliability => {
+                    store.dispatch(RpcAction::SnarkersReliabilityGet { rpc_id });
+                }
                 RpcRequest::HealthCheck => {
                     store.dispatch(RpcAction::HealthCheck { rpc_id });
                 }
                 RpcRequest::ReadinessCheck => {
                     store.dispatch(RpcAction::ReadinessCheck { rpc_id });
                 }
+                RpcRequest::WatchedAccountsAdd { pub_key } => {
+                    store.dispatch(RpcAction::WatchedAccountsAdd { rpc_id, pub_key });
+                }
+                RpcRequest::WatchedAccountsRemove { pub_key } => {
+                    store.dispatch(RpcAction::WatchedAccountsRemove { rpc_id, pub_key });
+                }
+                RpcRequest::WatchedAccountsGet => {
+                    store.dispatch(RpcAction::WatchedAccountsGet { rpc_id });
+                }
+                RpcRequest::WatchedAccountBlocksGet(query) => {
+                    store.dispatch(RpcAction::WatchedAccountBlocksGet { rpc_id, query });
+                }
+                RpcRequest::AccountProofGet(query) => {
+                    store.dispatch(RpcAction::AccountProofGet { rpc_id, query });
+                }
+                RpcRequest::BlockGet(query) => {
+                    store.dispatch(RpcAction::BlockGet { rpc_id, query });
+                }
+                RpcRequest::TransitionFrontierStatusGet => {
+                    store.dispatch(RpcAction::TransitionFrontierStatusGet { rpc_id });
+                }
+                RpcRequest::BlockProducerScheduleGet => {
+                    store.dispatch(RpcAction::BlockProducerScheduleGet { rpc_id });
+                }
+                RpcRequest::BlockProducerHistoryGet => {
+                    store.dispatch(RpcAction::BlockProducerHistoryGet { rpc_id });
+                }
+                RpcRequest::SnarkerWorkGet => {
+                    store.dispatch(RpcAction::SnarkerWorkGet { rpc_id });
+                }
+                RpcRequest::SnarkerWorkSubmit(work) => {
+                    store.dispatch(RpcAction::SnarkerWorkSubmit { rpc_id, work });
+                }
+                RpcRequest::BlockProducerEpochDataGet => {
+                    store.dispatch(RpcAction::BlockProducerEpochDataGet { rpc_id });
+                }
             },
-            Event::ExternalSnarkWorker(e) => match e {
+            Event::ExternalSnarkWorker(worker_id, e) => match e {
                 ExternalSnarkWorkerEvent::Started => {
-                    store.dispatch(ExternalSnarkWorkerAction::Started);
+                    store.dispatch(ExternalSnarkWorkerAction::Started { worker_id });
                 }
                 ExternalSnarkWorkerEvent::Killed => {
-                    store.dispatch(ExternalSnarkWorkerAction::Killed);
+                    store.dispatch(ExternalSnarkWorkerAction::Killed { worker_id });
                 }
                 ExternalSnarkWorkerEvent::WorkResult(result) => {
-                    store.dispatch(ExternalSnarkWorkerAction::WorkResult { result });
+                    store.dispatch(ExternalSnarkWorkerAction::WorkResult { worker_id, result });
                 }
                 ExternalSnarkWorkerEvent::WorkError(error) => {
-                    store.dispatch(ExternalSnarkWorkerAction::WorkError { error });
+                    store.dispatch(ExternalSnarkWorkerAction::WorkError { worker_id, error });
                 }
                 ExternalSnarkWorkerEvent::WorkCancelled => {
-                    store.dispatch(ExternalSnarkWorkerAction::WorkCancelled);
+                    store.dispatch(ExternalSnarkWorkerAction::WorkCancelled { worker_id });
                 }
                 ExternalSnarkWorkerEvent::Error(error) => {
                     store.dispatch(ExternalSnarkWorkerAction::Error {
+                        worker_id,
                         error,
                         permanent: false,
                     });
@@ -297,11 +340,26 @@ pub fn event_source_effects<S: Service>(store: &mut Store<S>, action: EventSourc
             Event::BlockProducerEvent(e) => match e {
                 crate::block_producer::BlockProducerEvent::VrfEvaluator(vrf_e) => match vrf_e {
                     crate::block_producer::BlockProducerVrfEvaluatorEvent::Evaluated(
-                        vrf_output_with_hash,
+                        vrf_batch_with_hash,
                     ) => {
                         store.dispatch(BlockProducerVrfEvaluatorAction::EvaluationSuccess {
-                            vrf_output: vrf_output_with_hash.evaluation_result,
-                            staking_ledger_hash: vrf_output_with_hash.staking_ledger_hash,
+                            producer: vrf_batch_with_hash.producer,
+                            slots: vrf_batch_with_hash.slots,
+                            won_slots: vrf_batch_with_hash.won_slots,
+                            staking_ledger_hash: vrf_batch_with_hash.staking_ledger_hash,
+                        });
+                    }
+                    crate::block_producer::BlockProducerVrfEvaluatorEvent::EvaluationFailed {
+                        producer,
+                        slots,
+                        staking_ledger_hash,
+                        error,
+                    } => {
+                        store.dispatch(BlockProducerVrfEvaluatorAction::EvaluationFailed {
+                            producer,
+                            slots,
+                            staking_ledger_hash,
+                            error,
                         });
                     }
                 },
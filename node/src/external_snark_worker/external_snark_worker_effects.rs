@@ -13,41 +13,52 @@ pub fn external_snark_worker_effects<S: crate::Service>(
 ) {
     let (action, _) = action.split();
     match action {
-        ExternalSnarkWorkerAction::Start => {
+        ExternalSnarkWorkerAction::Start { worker_id } => {
             let Some(config) = &store.state.get().config.snarker else {
                 return;
             };
             let public_key = config.public_key.clone().into();
-            let fee = config.fee.clone();
-            if let Err(err) = store.service.start(&config.path, public_key, fee) {
+            if let Err(err) =
+                store
+                    .service
+                    .start(worker_id, config.worker_kind, &config.path, public_key)
+            {
                 store.dispatch(ExternalSnarkWorkerAction::Error {
+                    worker_id,
                     error: err,
                     permanent: true,
                 });
             }
         }
-        ExternalSnarkWorkerAction::Started => {
+        ExternalSnarkWorkerAction::Started { .. } => {
             store.dispatch(SnarkPoolAction::AutoCreateCommitment);
         }
-        ExternalSnarkWorkerAction::StartTimeout { .. } => {
+        ExternalSnarkWorkerAction::StartTimeout { worker_id, .. } => {
             store.dispatch(ExternalSnarkWorkerAction::Error {
+                worker_id,
                 error: super::ExternalSnarkWorkerError::StartTimeout,
                 permanent: true,
             });
         }
-        ExternalSnarkWorkerAction::Kill => {
-            if let Err(err) = store.service().kill() {
+        ExternalSnarkWorkerAction::Kill { worker_id } => {
+            if let Err(err) = store.service().kill(worker_id) {
                 store.dispatch(ExternalSnarkWorkerAction::Error {
+                    worker_id,
                     error: err,
                     permanent: true,
                 });
             }
         }
-        ExternalSnarkWorkerAction::Killed => {}
-        ExternalSnarkWorkerAction::Error { .. } => {
-            store.dispatch(ExternalSnarkWorkerAction::Kill);
+        ExternalSnarkWorkerAction::Killed { .. } => {}
+        ExternalSnarkWorkerAction::Error { worker_id, .. } => {
+            store.dispatch(ExternalSnarkWorkerAction::Kill { worker_id });
         }
-        ExternalSnarkWorkerAction::SubmitWork { job_id, .. } => {
+        ExternalSnarkWorkerAction::SubmitWork {
+            worker_id,
+            job_id,
+            fee,
+            ..
+        } => {
             let Some(job) = store.state().snark_pool.get(&job_id) else {
                 return;
             };
@@ -57,21 +68,35 @@ pub fn external_snark_worker_effects<S: crate::Service>(
             ) {
                 Ok(v) => v,
                 Err(err) => {
-                    store.dispatch(ExternalSnarkWorkerAction::WorkError { error: err.into() });
+                    store.dispatch(ExternalSnarkWorkerAction::WorkError {
+                        worker_id,
+                        error: err.into(),
+                    });
                     return;
                 }
             };
-            if let Err(err) = store.service().submit(input) {
-                store.dispatch(ExternalSnarkWorkerAction::WorkError { error: err.into() });
+            if let Err(err) = store.service().submit(worker_id, input, fee) {
+                store.dispatch(ExternalSnarkWorkerAction::WorkError {
+                    worker_id,
+                    error: err.into(),
+                });
                 return;
             }
         }
-        ExternalSnarkWorkerAction::WorkResult { result } => {
+        ExternalSnarkWorkerAction::WorkResult { worker_id, result } => {
             let Some(config) = &store.state().config.snarker else {
                 return;
             };
+            // snark must have the fee it was proven with.
+            let Some(fee) = store
+                .state()
+                .external_snark_worker
+                .get(worker_id)
+                .and_then(|worker| worker.fee.clone())
+            else {
+                return;
+            };
             let snarker = config.public_key.clone().into();
-            let fee = config.fee.clone();
             let snark = Snark {
                 snarker,
                 fee,
@@ -80,27 +105,28 @@ pub fn external_snark_worker_effects<S: crate::Service>(
             let sender = store.state().p2p.my_id();
             // Directly add snark to the snark pool as it's produced by us.
             store.dispatch(SnarkPoolAction::WorkAdd { snark, sender });
-            store.dispatch(ExternalSnarkWorkerAction::PruneWork);
+            store.dispatch(ExternalSnarkWorkerAction::PruneWork { worker_id });
         }
-        ExternalSnarkWorkerAction::WorkError { .. } => {
-            store.dispatch(ExternalSnarkWorkerAction::PruneWork);
+        ExternalSnarkWorkerAction::WorkError { worker_id, .. } => {
+            store.dispatch(ExternalSnarkWorkerAction::PruneWork { worker_id });
         }
-        ExternalSnarkWorkerAction::WorkTimeout { .. } => {
-            store.dispatch(ExternalSnarkWorkerAction::CancelWork);
+        ExternalSnarkWorkerAction::WorkTimeout { worker_id, .. } => {
+            store.dispatch(ExternalSnarkWorkerAction::CancelWork { worker_id });
         }
-        ExternalSnarkWorkerAction::CancelWork => {
-            if let Err(err) = store.service().cancel() {
+        ExternalSnarkWorkerAction::CancelWork { worker_id } => {
+            if let Err(err) = store.service().cancel(worker_id) {
                 store.dispatch(ExternalSnarkWorkerAction::Error {
+                    worker_id,
                     error: err.into(),
                     permanent: true,
                 });
                 return;
             }
         }
-        ExternalSnarkWorkerAction::WorkCancelled => {
-            store.dispatch(ExternalSnarkWorkerAction::PruneWork);
+        ExternalSnarkWorkerAction::WorkCancelled { worker_id } => {
+            store.dispatch(ExternalSnarkWorkerAction::PruneWork { worker_id });
         }
-        ExternalSnarkWorkerAction::PruneWork => {
+        ExternalSnarkWorkerAction::PruneWork { .. } => {
             store.dispatch(SnarkPoolAction::AutoCreateCommitment);
         }
     }
@@ -5,7 +5,20 @@ use super::{
 
 impl ExternalSnarkWorkers {
     pub fn reducer(&mut self, action: ExternalSnarkWorkerActionWithMetaRef<'_>) {
-        self.0.reducer(action)
+        let Some(worker) = self.workers.get_mut(action.action().worker_id()) else {
+            return;
+        };
+        if let (
+            ExternalSnarkWorkerAction::WorkResult { .. },
+            ExternalSnarkWorkerState::Working(_, summary),
+        ) = (*action.action(), &worker.state)
+        {
+            // `timestamp` is the time when the work was submitted.
+            if let Some(duration) = action.meta().time().checked_sub(worker.timestamp) {
+                self.proving_times.add(summary, duration);
+            }
+        }
+        worker.reducer(action)
     }
 }
 
@@ -13,63 +26,278 @@ impl ExternalSnarkWorker {
     pub fn reducer(&mut self, action: ExternalSnarkWorkerActionWithMetaRef<'_>) {
         let (action, meta) = action.split();
         match action {
-            ExternalSnarkWorkerAction::Start => {
+            ExternalSnarkWorkerAction::Start { .. } => {
                 self.state = ExternalSnarkWorkerState::Starting;
             }
-            ExternalSnarkWorkerAction::Started => {
+            ExternalSnarkWorkerAction::Started { .. } => {
                 self.state = ExternalSnarkWorkerState::Idle;
             }
             ExternalSnarkWorkerAction::StartTimeout { .. } => {
                 return;
             }
-            ExternalSnarkWorkerAction::Kill => {
+            ExternalSnarkWorkerAction::Kill { .. } => {
                 self.state = ExternalSnarkWorkerState::Killing;
             }
-            ExternalSnarkWorkerAction::Killed => {
+            ExternalSnarkWorkerAction::Killed { .. } => {
                 self.state = ExternalSnarkWorkerState::None;
             }
-            ExternalSnarkWorkerAction::Error { error, permanent } => {
+            ExternalSnarkWorkerAction::Error {
+                error, permanent, ..
+            } => {
                 self.state = ExternalSnarkWorkerState::Error(error.clone(), *permanent);
             }
-            ExternalSnarkWorkerAction::SubmitWork { job_id, summary } => {
+            ExternalSnarkWorkerAction::SubmitWork {
+                job_id,
+                summary,
+                fee,
+                ..
+            } => {
                 self.state = ExternalSnarkWorkerState::Working(
                     job_id.clone(),
                     summary.clone(),
                 );
+                self.fee = Some(fee.clone());
             }
-            ExternalSnarkWorkerAction::WorkResult { result } => {
-                let ExternalSnarkWorkerState::Working(job_id, _) = &self.state else {
+            ExternalSnarkWorkerAction::WorkResult { result, .. } => {
+                let (ExternalSnarkWorkerState::Working(job_id, _)
+                | ExternalSnarkWorkerState::Cancelling(job_id)) = &self.state
+                else {
                     return;
                 };
+                self.stats.work_results += 1;
+                self.stats.last_proving_time = meta.time().checked_sub(self.timestamp);
                 self.state =
                     ExternalSnarkWorkerState::WorkReady(job_id.clone(), result.clone());
             }
-            ExternalSnarkWorkerAction::WorkError { error } => {
+            ExternalSnarkWorkerAction::WorkError { error, .. } => {
                 let ExternalSnarkWorkerState::Working(job_id, _) = &self.state else {
                     return;
                 };
+                self.stats.work_errors += 1;
                 self.state =
                     ExternalSnarkWorkerState::WorkError(job_id.clone(), error.clone());
             }
             ExternalSnarkWorkerAction::WorkTimeout { .. } => {
                 return;
             }
-            ExternalSnarkWorkerAction::CancelWork => {
+            ExternalSnarkWorkerAction::CancelWork { .. } => {
                 let ExternalSnarkWorkerState::Working(job_id, _) = &self.state else {
                     return;
                 };
                 self.state = ExternalSnarkWorkerState::Cancelling(job_id.clone());
             }
-            ExternalSnarkWorkerAction::WorkCancelled => {
+            ExternalSnarkWorkerAction::WorkCancelled { .. } => {
                 let ExternalSnarkWorkerState::Cancelling(job_id) = &self.state else {
                     return;
                 };
+                self.stats.work_cancelled += 1;
                 self.state = ExternalSnarkWorkerState::Cancelled(job_id.clone());
             }
-            ExternalSnarkWorkerAction::PruneWork => {
+            ExternalSnarkWorkerAction::PruneWork { .. } => {
                 self.state = ExternalSnarkWorkerState::Idle;
             }
         }
         self.timestamp = meta.time();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use ledger::scan_state::currency::{Fee, Magnitude};
+    use mina_p2p_messages::binprot::BinProtRead;
+    use mina_p2p_messages::gossip::GossipNetMessageV2;
+    use mina_p2p_messages::v2::NetworkPoolSnarkPoolDiffVersionedStableV2;
+    use redux::Timestamp;
+
+    use crate::external_snark_worker::{ExternalSnarkWorkerWorkError, SnarkWorkResult};
+    use crate::snark_pool::JobSummary;
+
+    use super::*;
+
+    fn result() -> SnarkWorkResult {
+        const SNARK_POOL_DIFF: &[u8] =
+            include_bytes!("../../../mina-p2p-messages/tests/files/v2/gossip/snark_pool_diff.bin");
+        let GossipNetMessageV2::SnarkPoolDiff {
+            message: NetworkPoolSnarkPoolDiffVersionedStableV2::AddSolvedWork(work),
+            ..
+        } = GossipNetMessageV2::binprot_read(&mut &SNARK_POOL_DIFF[..]).unwrap()
+        else {
+            unreachable!()
+        };
+        Arc::new(work.1.proof)
+    }
+
+    fn secs(secs: u64) -> Timestamp {
+        Timestamp::ZERO + secs * 1_000_000_000
+    }
+
+    fn reduce(
+        workers: &mut ExternalSnarkWorkers,
+        time: Timestamp,
+        action: ExternalSnarkWorkerAction,
+    ) {
+        workers.reducer(redux::ActionMeta::zero_custom(time).with_action(&action));
+    }
+
+    /// Pool of `count` started workers.
+    fn started(count: usize) -> ExternalSnarkWorkers {
+        let mut workers = ExternalSnarkWorkers::new(Timestamp::ZERO, count);
+        for worker_id in workers.ids() {
+            reduce(
+                &mut workers,
+                Timestamp::ZERO,
+                ExternalSnarkWorkerAction::Start { worker_id },
+            );
+            reduce(
+                &mut workers,
+                Timestamp::ZERO,
+                ExternalSnarkWorkerAction::Started { worker_id },
+            );
+        }
+        workers
+    }
+
+    fn submit(workers: &mut ExternalSnarkWorkers, time: Timestamp, worker_id: usize) {
+        let action = ExternalSnarkWorkerAction::SubmitWork {
+            worker_id,
+            job_id: result().as_ref().into(),
+            summary: JobSummary::Merge(1),
+            fee: (&Fee::from_u64(1_000)).into(),
+        };
+        reduce(workers, time, action);
+    }
+
+    #[test]
+    fn jobs_dispatched_to_idle_workers() {
+        let mut workers = ExternalSnarkWorkers::new(Timestamp::ZERO, 3);
+        assert_eq!(workers.available(), 0);
+        assert_eq!(workers.first_idle(), None);
+
+        let mut workers = started(3);
+        assert_eq!(workers.available(), 3);
+        assert_eq!(workers.first_idle(), Some(0));
+
+        submit(&mut workers, Timestamp::ZERO, 0);
+        assert_eq!(workers.available(), 2);
+        assert_eq!(workers.first_idle(), Some(1));
+        let job_id = result().as_ref().into();
+        assert!(workers.is_working_on(&job_id));
+        assert_eq!(
+            workers
+                .working_job_ids()
+                .map(|(id, _)| id)
+                .collect::<Vec<_>>(),
+            vec![0]
+        );
+
+        reduce(
+            &mut workers,
+            secs(1),
+            ExternalSnarkWorkerAction::WorkResult {
+                worker_id: 0,
+                result: result(),
+            },
+        );
+        assert!(!workers.is_working_on(&job_id));
+        assert_eq!(workers.first_idle(), Some(1));
+        reduce(
+            &mut workers,
+            secs(1),
+            ExternalSnarkWorkerAction::PruneWork { worker_id: 0 },
+        );
+        assert_eq!(workers.available(), 3);
+        assert_eq!(workers.first_idle(), Some(0));
+    }
+
+    #[test]
+    fn stats_per_worker() {
+        let mut workers = started(2);
+
+        submit(&mut workers, secs(10), 0);
+        submit(&mut workers, secs(10), 1);
+        reduce(
+            &mut workers,
+            secs(15),
+            ExternalSnarkWorkerAction::WorkResult {
+                worker_id: 0,
+                result: result(),
+            },
+        );
+        reduce(
+            &mut workers,
+            secs(16),
+            ExternalSnarkWorkerAction::WorkError {
+                worker_id: 1,
+                error: ExternalSnarkWorkerWorkError::Error("failed".to_owned()),
+            },
+        );
+
+        let stats = |id| workers.get(id).unwrap().stats.clone();
+        assert_eq!(stats(0).work_results, 1);
+        assert_eq!(stats(0).work_errors, 0);
+        assert_eq!(stats(0).last_proving_time, Some(Duration::from_secs(5)));
+        assert_eq!(stats(1).work_results, 0);
+        assert_eq!(stats(1).work_errors, 1);
+        assert_eq!(stats(1).last_proving_time, None);
+        // proving times are shared by the pool.
+        assert_eq!(
+            workers.proving_times().estimate(&JobSummary::Merge(1)),
+            Duration::from_secs(5)
+        );
+
+        for worker_id in workers.ids() {
+            reduce(
+                &mut workers,
+                secs(20),
+                ExternalSnarkWorkerAction::PruneWork { worker_id },
+            );
+        }
+        submit(&mut workers, secs(20), 1);
+        reduce(
+            &mut workers,
+            secs(21),
+            ExternalSnarkWorkerAction::CancelWork { worker_id: 1 },
+        );
+        reduce(
+            &mut workers,
+            secs(22),
+            ExternalSnarkWorkerAction::WorkCancelled { worker_id: 1 },
+        );
+        assert_eq!(workers.get(1).unwrap().stats.work_cancelled, 1);
+        assert_eq!(workers.get(0).unwrap().stats.work_cancelled, 0);
+    }
+
+    #[test]
+    fn result_accepted_while_cancelling() {
+        let mut workers = started(1);
+        submit(&mut workers, secs(0), 0);
+        reduce(
+            &mut workers,
+            secs(1),
+            ExternalSnarkWorkerAction::CancelWork { worker_id: 0 },
+        );
+        assert!(matches!(
+            workers.get(0).unwrap().state,
+            ExternalSnarkWorkerState::Cancelling(_)
+        ));
+
+        reduce(
+            &mut workers,
+            secs(2),
+            ExternalSnarkWorkerAction::WorkResult {
+                worker_id: 0,
+                result: result(),
+            },
+        );
+        let worker = workers.get(0).unwrap();
+        assert!(matches!(
+            worker.state,
+            ExternalSnarkWorkerState::WorkReady(..)
+        ));
+        assert_eq!(worker.stats.work_results, 1);
+        assert_eq!(worker.stats.work_cancelled, 0);
+    }
+}
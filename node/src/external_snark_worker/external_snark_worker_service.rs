@@ -3,8 +3,11 @@ use std::ffi::OsStr;
 use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
 use serde::{Deserialize, Serialize};
 
+use crate::SnarkerWorkerKind;
+
 use super::{
-    ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkResult, SnarkWorkSpec,
+    ExternalSnarkWorkerError, ExternalSnarkWorkerId, ExternalSnarkWorkerWorkError, SnarkWorkResult,
+    SnarkWorkSpec,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone, derive_more::From)]
@@ -18,20 +21,27 @@ pub enum ExternalSnarkWorkerEvent {
 }
 
 pub trait ExternalSnarkWorkerService {
-    /// Starts external process.
+    /// Starts the worker, either as an external process at `path` or
+    /// as an in-process prover, depending on `kind`.
     fn start<P: AsRef<OsStr>>(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
+        kind: SnarkerWorkerKind,
         path: P,
         public_key: NonZeroCurvePoint,
-        fee: CurrencyFeeStableV1,
     ) -> Result<(), ExternalSnarkWorkerError>;
 
-    /// Submits snark work
-    fn submit(&mut self, spec: SnarkWorkSpec) -> Result<(), ExternalSnarkWorkerError>;
+    /// Submits snark work, to be proven with the `fee`.
+    fn submit(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+        spec: SnarkWorkSpec,
+        fee: CurrencyFeeStableV1,
+    ) -> Result<(), ExternalSnarkWorkerError>;
 
     /// Cancel current work
-    fn cancel(&mut self) -> Result<(), ExternalSnarkWorkerError>;
+    fn cancel(&mut self, worker_id: ExternalSnarkWorkerId) -> Result<(), ExternalSnarkWorkerError>;
 
-    /// Kills external process.
-    fn kill(&mut self) -> Result<(), ExternalSnarkWorkerError>;
+    /// Kills the worker.
+    fn kill(&mut self, worker_id: ExternalSnarkWorkerId) -> Result<(), ExternalSnarkWorkerError>;
 }
@@ -1,3 +1,7 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use mina_p2p_messages::v2::CurrencyFeeStableV1;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
@@ -5,13 +9,46 @@ use crate::snark_pool::JobSummary;
 
 use super::{ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkId, SnarkWorkResult};
 
+/// Index of the worker in the pool.
+pub type ExternalSnarkWorkerId = usize;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExternalSnarkWorkers(pub(crate) ExternalSnarkWorker);
+pub struct ExternalSnarkWorkers {
+    pub(crate) workers: Vec<ExternalSnarkWorker>,
+    /// Proving times of the jobs completed by any of the workers.
+    #[serde(default)]
+    pub(crate) proving_times: ExternalSnarkWorkerProvingTimes,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalSnarkWorker {
     pub(crate) state: ExternalSnarkWorkerState,
     pub(crate) timestamp: Timestamp,
+    #[serde(default)]
+    pub(crate) stats: ExternalSnarkWorkerStats,
+    /// Fee of the last job submitted to the worker.
+    #[serde(default)]
+    pub(crate) fee: Option<CurrencyFeeStableV1>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExternalSnarkWorkerStats {
+    pub work_results: u64,
+    pub work_errors: u64,
+    pub work_cancelled: u64,
+    /// Proving time of the last job completed by the worker.
+    pub last_proving_time: Option<Duration>,
+}
+
+/// Number of recently completed jobs to estimate proving times from.
+const PROVING_TIMES_SAMPLES: usize = 16;
+
+/// Measured proving times of recently completed jobs, normalized per
+/// account update, so that they can be used to estimate any job.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExternalSnarkWorkerProvingTimes {
+    tx: VecDeque<Duration>,
+    merge: VecDeque<Duration>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,18 +69,33 @@ pub enum ExternalSnarkWorkerState {
 }
 
 impl ExternalSnarkWorkers {
-    pub fn new(now: Timestamp) -> Self {
-        ExternalSnarkWorkers(ExternalSnarkWorker {
+    pub fn new(now: Timestamp, count: usize) -> Self {
+        let worker = ExternalSnarkWorker {
             state: ExternalSnarkWorkerState::None,
             timestamp: now,
-        })
+            stats: Default::default(),
+            fee: None,
+        };
+        ExternalSnarkWorkers {
+            workers: vec![worker; count],
+            proving_times: Default::default(),
+        }
     }
 
-    pub fn is_idle(&self) -> bool {
-        match self.0.state {
-            ExternalSnarkWorkerState::Idle => true,
-            _ => false,
-        }
+    pub fn get(&self, worker_id: ExternalSnarkWorkerId) -> Option<&ExternalSnarkWorker> {
+        self.workers.get(worker_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ExternalSnarkWorkerId, &ExternalSnarkWorker)> {
+        self.workers.iter().enumerate()
+    }
+
+    pub fn ids(&self) -> std::ops::Range<ExternalSnarkWorkerId> {
+        0..self.workers.len()
+    }
+
+    pub fn proving_times(&self) -> &ExternalSnarkWorkerProvingTimes {
+        &self.proving_times
     }
 
     pub fn has_idle(&self) -> bool {
@@ -51,17 +103,61 @@ impl ExternalSnarkWorkers {
     }
 
     pub fn available(&self) -> usize {
-        if matches!(self.0.state, ExternalSnarkWorkerState::Idle) {
-            1
-        } else {
-            0
-        }
+        self.workers.iter().filter(|w| w.is_idle()).count()
+    }
+
+    pub fn first_idle(&self) -> Option<ExternalSnarkWorkerId> {
+        self.workers.iter().position(|w| w.is_idle())
+    }
+
+    /// Ids of the workers and jobs, which they are working on.
+    pub fn working_job_ids(&self) -> impl Iterator<Item = (ExternalSnarkWorkerId, &SnarkWorkId)> {
+        self.iter()
+            .filter_map(|(id, w)| w.working_job_id().map(|job_id| (id, job_id)))
+    }
+
+    pub fn is_working_on(&self, job_id: &SnarkWorkId) -> bool {
+        self.working_job_ids().any(|(_, id)| id == job_id)
+    }
+}
+
+impl ExternalSnarkWorker {
+    pub fn is_idle(&self) -> bool {
+        matches!(self.state, ExternalSnarkWorkerState::Idle)
     }
 
     pub fn working_job_id(&self) -> Option<&SnarkWorkId> {
-        match &self.0.state {
+        match &self.state {
             ExternalSnarkWorkerState::Working(job_id, _) => Some(job_id),
             _ => None,
         }
     }
 }
+
+impl ExternalSnarkWorkerProvingTimes {
+    pub fn add(&mut self, summary: &JobSummary, duration: Duration) {
+        let (samples, n) = match summary {
+            JobSummary::Tx(n) => (&mut self.tx, n),
+            JobSummary::Merge(n) => (&mut self.merge, n),
+        };
+        if samples.len() >= PROVING_TIMES_SAMPLES {
+            samples.pop_front();
+        }
+        samples.push_back(duration / (*n).max(1) as u32);
+    }
+
+    /// Estimated duration of the job based on the recently measured
+    /// proving times, or on [`JobSummary::estimated_duration`] if no
+    /// such job was done yet.
+    pub fn estimate(&self, summary: &JobSummary) -> Duration {
+        let (samples, n) = match summary {
+            JobSummary::Tx(n) => (&self.tx, n),
+            JobSummary::Merge(n) => (&self.merge, n),
+        };
+        if samples.is_empty() {
+            return summary.estimated_duration();
+        }
+        let avg = samples.iter().sum::<Duration>() / samples.len() as u32;
+        avg * (*n).max(1) as u32
+    }
+}
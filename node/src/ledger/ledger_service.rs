@@ -7,7 +7,7 @@ use std::{
 
 use ledger::{
     scan_state::{
-        currency::{Amount, Fee, Slot},
+        currency::{Amount, Balance, Fee, Slot},
         scan_state::{
             AvailableJobMessage, ConstraintConstants, JobValueBase, JobValueMerge,
             JobValueWithIndex, Pass,
@@ -16,7 +16,8 @@ use ledger::{
             local_state::LocalState,
             protocol_state::{protocol_state_view, ProtocolStateView},
             transaction_partially_applied::TransactionPartiallyApplied,
-            Transaction,
+            zkapp_command::AccessedOrNot,
+            Transaction, WithStatus,
         },
     },
     sparse_ledger::SparseLedger,
@@ -26,18 +27,20 @@ use ledger::{
         validate_block::block_body_hash,
     },
     verifier::Verifier,
-    Account, AccountIndex, BaseLedger, Database, Mask, TreeVersion, UnregisterBehavior,
+    Account, AccountId, AccountIndex, BaseLedger, Database, Mask, MerklePath, TokenId, TreeVersion,
+    UnregisterBehavior,
 };
 use mina_hasher::Fp;
 use mina_p2p_messages::{
     binprot::BinProtRead,
     v2::{
-        self, DataHashLibStateHashStableV1, LedgerHash, MinaBaseAccountBinableArgStableV2,
-        MinaBaseLedgerHash0StableV1, MinaBaseSokMessageStableV1, MinaBaseStagedLedgerHashStableV1,
-        MinaLedgerSyncLedgerAnswerStableV2, MinaLedgerSyncLedgerQueryStableV1,
+        self, DataHashLibStateHashStableV1, LedgerHash, MerkleTreeNode,
+        MinaBaseAccountBinableArgStableV2, MinaBaseLedgerHash0StableV1, MinaBaseSokMessageStableV1,
+        MinaBaseStagedLedgerHashStableV1, MinaLedgerSyncLedgerAnswerStableV2,
+        MinaLedgerSyncLedgerQueryStableV1,
         MinaStateBlockchainStateValueStableV2LedgerProofStatement,
         MinaStateProtocolStateValueStableV2, MinaTransactionTransactionStableV2, NonZeroCurvePoint,
-        StateHash,
+        StateHash, TokenIdKeyHash,
     },
 };
 use openmina_core::snark::{Snark, SnarkJobId};
@@ -49,6 +52,9 @@ use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorLedgerService
 use crate::block_producer::{
     BlockProducerService, BlockProducerWonSlot, StagedLedgerDiffCreateOutput,
 };
+use crate::transition_frontier::genesis::{
+    GenesisConfig, GenesisLedgerLoaded, TransitionFrontierGenesisService,
+};
 use crate::transition_frontier::sync::ledger::staged::TransitionFrontierSyncLedgerStagedService;
 use crate::transition_frontier::sync::{
     ledger::staged::StagedLedgerAuxAndPendingCoinbasesValid,
@@ -61,6 +67,7 @@ use crate::{
 };
 use crate::{
     rpc::{
+        RpcAccountProof, RpcAccountProofGetError, RpcAccountProofGetResponse, RpcBlockTransaction,
         RpcLedgerService, RpcScanStateSummaryBlockTransaction, RpcScanStateSummaryScanStateJob,
         RpcScanStateSummaryScanStateJobKind, RpcSnarkPoolJobSnarkWorkDone,
     },
@@ -354,6 +361,58 @@ impl LedgerCtx {
         );
         Some(producers)
     }
+
+    /// Returns transactions (including fee transfers and coinbases) applied
+    /// by the `block`, in the order of application.
+    pub fn block_transactions(
+        &self,
+        block: &ArcBlockWithHash,
+    ) -> Result<Vec<WithStatus<Transaction>>, String> {
+        Self::block_transactions_of(block)
+    }
+
+    fn block_transactions_of(
+        block: &ArcBlockWithHash,
+    ) -> Result<Vec<WithStatus<Transaction>>, String> {
+        let consensus_state = &block.header().protocol_state.body.consensus_state;
+        let coinbase_receiver: CompressedPubKey = (&consensus_state.coinbase_receiver).into();
+        // FIXME: same as in `block_apply`, `supercharge_coinbase` from the
+        // block does not work.
+        let supercharge_coinbase = false;
+
+        let diff: Diff = (&block.block.body.staged_ledger_diff).into();
+        diff.get_transactions(
+            &CONSTRAINT_CONSTANTS,
+            coinbase_receiver,
+            supercharge_coinbase,
+        )
+        .map_err(|err| format!("{err:?}"))
+    }
+
+    /// Returns [Self::block_transactions], together with the accounts they
+    /// accessed, as they are in the staged ledger of the applied `block`.
+    pub fn block_transactions_and_accounts(
+        &self,
+        block: &ArcBlockWithHash,
+    ) -> Result<(Vec<WithStatus<Transaction>>, Vec<Account>), String> {
+        let (mask, _) = self
+            .mask(block.staged_ledger_hash())
+            .ok_or_else(|| "block staged ledger missing".to_owned())?;
+        let transactions = Self::block_transactions_of(block)?;
+
+        let accounts = transactions
+            .iter()
+            .flat_map(|tx| tx.data.account_access_statuses(&tx.status))
+            .filter(|(_, status)| matches!(status, AccessedOrNot::Accessed))
+            .map(|(id, _)| id)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter_map(|id| mask.get(mask.location_of_account(&id)?))
+            .map(|account| *account)
+            .collect();
+
+        Ok((transactions, accounts))
+    }
 }
 
 impl LedgerSyncState {
@@ -499,6 +558,44 @@ impl<T: LedgerService> TransitionFrontierSyncLedgerStagedService for T {
     }
 }
 
+impl<T: LedgerService> TransitionFrontierGenesisService for T {
+    fn load_genesis(&mut self, config: &GenesisConfig) -> GenesisLedgerLoaded {
+        let mut mask = Mask::new_root(Database::create(35));
+        let mut total_currency = 0u64;
+        for genesis_account in &config.accounts {
+            let public_key: CompressedPubKey = genesis_account.public_key.clone().into();
+            let account_id = AccountId::new(public_key, TokenId::default());
+            let mut account = Account::create_with(
+                account_id.clone(),
+                Balance::from_u64(genesis_account.balance),
+            );
+            if let Some(delegate) = &genesis_account.delegate {
+                account.delegate = Some(delegate.clone().into());
+            }
+            total_currency = total_currency.saturating_add(genesis_account.balance);
+            mask.get_or_create_account(account_id, account).unwrap();
+        }
+
+        let genesis_ledger_hash =
+            v2::LedgerHash::from(v2::MinaBaseLedgerHash0StableV1(mask.merkle_root().into()));
+        let mut staged_ledger = StagedLedger::create_exn(CONSTRAINT_CONSTANTS.clone(), mask.copy())
+            .expect("empty staged ledger on top of genesis ledger");
+        let staged_ledger_hashes = (&staged_ledger.hash()).into();
+
+        let ctx = self.ctx_mut();
+        ctx.snarked_ledgers
+            .insert(genesis_ledger_hash.clone(), mask);
+        ctx.staged_ledgers
+            .insert(genesis_ledger_hash.clone(), staged_ledger);
+
+        GenesisLedgerLoaded {
+            genesis_ledger_hash,
+            staged_ledger_hashes,
+            total_currency: (&Amount::from_u64(total_currency)).into(),
+        }
+    }
+}
+
 impl<T: LedgerService> TransitionFrontierService for T {
     fn block_apply(
         &mut self,
@@ -969,6 +1066,7 @@ impl<T: LedgerService> RpcLedgerService for T {
                             bundle_job_id,
                             job: job_kind,
                             seq_no,
+                            statement: (&stmt).into(),
                             snark: RpcSnarkPoolJobSnarkWorkDone {
                                 snarker: sok_message.prover,
                                 fee: sok_message.fee,
@@ -980,6 +1078,7 @@ impl<T: LedgerService> RpcLedgerService for T {
                             bundle_job_id,
                             job: job_kind,
                             seq_no,
+                            statement: (&stmt).into(),
                         }
                     })
                 }
@@ -987,28 +1086,92 @@ impl<T: LedgerService> RpcLedgerService for T {
             })
             .collect()
     }
+
+    fn account_proof(
+        &self,
+        ledger_hash: LedgerHash,
+        public_key: NonZeroCurvePoint,
+        token: Option<TokenIdKeyHash>,
+    ) -> RpcAccountProofGetResponse {
+        let ctx = self.ctx();
+        let Some(mut mask) = ctx
+            .mask(&ledger_hash)
+            .filter(|(_, is_synced)| *is_synced)
+            .map(|(mask, _)| mask)
+            .or_else(|| ctx.additional_snarked_ledgers.get(&ledger_hash).cloned())
+        else {
+            return Err(RpcAccountProofGetError::LedgerNotFound { ledger_hash });
+        };
+
+        let token_id = token.map_or_else(TokenId::default, |token| (&*token).into());
+        let account_id = AccountId::new((&public_key).into(), token_id);
+        let Some((addr, account)) = mask
+            .location_of_account(&account_id)
+            .and_then(|addr| Some((addr.clone(), mask.get(addr)?)))
+        else {
+            return Err(RpcAccountProofGetError::AccountNotFound { ledger_hash });
+        };
+
+        let merkle_path = mask
+            .merkle_path(addr.clone())
+            .into_iter()
+            .map(|node| match node {
+                MerklePath::Left(right) => MerkleTreeNode::Left(right.into()),
+                MerklePath::Right(left) => MerkleTreeNode::Right(left.into()),
+            })
+            .collect();
+
+        Ok(RpcAccountProof {
+            ledger_hash,
+            account: (&*account).into(),
+            index: addr.to_index().0,
+            merkle_path,
+            block: None,
+        })
+    }
+
+    fn block_transactions(
+        &self,
+        block: &ArcBlockWithHash,
+    ) -> Result<Vec<RpcBlockTransaction>, String> {
+        let transactions = self.ctx().block_transactions(block)?;
+        Ok(transactions
+            .iter()
+            .map(|tx| RpcBlockTransaction {
+                transaction: (&tx.data).into(),
+                status: (&tx.status).into(),
+            })
+            .collect())
+    }
 }
 
 impl<T: LedgerService> BlockProducerVrfEvaluatorLedgerService for T {
-    fn get_producer_and_delegates(
+    fn get_producers_and_delegates(
         &mut self,
         ledger_hash: LedgerHash,
-        producer: AccountPublicKey,
-    ) -> DelegatorTable {
+        producers: &BTreeSet<AccountPublicKey>,
+    ) -> BTreeMap<AccountPublicKey, DelegatorTable> {
         // TODO(adonagy): Error handling
-        let delegate_table = self
+        let mut delegate_tables = self
             .ctx()
             .producers_with_delegates(&ledger_hash, |pub_key| {
-                AccountPublicKey::from(pub_key.clone()) == producer
+                producers.contains(&AccountPublicKey::from(pub_key.clone()))
             })
-            .unwrap()
-            .into_values()
-            .next()
             .unwrap();
 
-        delegate_table
-            .into_iter()
-            .map(|(index, pub_key, balance)| (index, (pub_key, balance)))
+        // accounts of our keys, which delegate elsewhere, are also grouped
+        // under their delegate, so only keep our keys.
+        producers
+            .iter()
+            .map(|producer| {
+                let delegate_table = delegate_tables
+                    .remove(producer)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(index, pub_key, balance)| (index, (pub_key, balance)))
+                    .collect();
+                (producer.clone(), delegate_table)
+            })
             .collect()
     }
 }
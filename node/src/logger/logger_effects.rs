@@ -1,4 +1,5 @@
 use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorAction;
+use crate::consensus::ConsensusAction;
 use crate::p2p::channels::best_tip::P2pChannelsBestTipAction;
 use crate::p2p::channels::rpc::P2pChannelsRpcAction;
 use crate::p2p::channels::snark::P2pChannelsSnarkAction;
@@ -12,6 +13,7 @@ use crate::p2p::discovery::P2pDiscoveryAction;
 use crate::p2p::P2pAction;
 use crate::snark::work_verify::SnarkWorkVerifyAction;
 use crate::snark::SnarkAction;
+use crate::transition_frontier::genesis::TransitionFrontierGenesisAction;
 use crate::transition_frontier::sync::TransitionFrontierSyncAction;
 use crate::transition_frontier::TransitionFrontierAction;
 use crate::{Action, ActionWithMetaRef, BlockProducerAction, Service, Store};
@@ -475,61 +477,60 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
         Action::ExternalSnarkWorker(a) => {
             use crate::external_snark_worker::ExternalSnarkWorkerAction;
             match a {
-                ExternalSnarkWorkerAction::Start
-                | ExternalSnarkWorkerAction::Started
-                | ExternalSnarkWorkerAction::Kill
-                | ExternalSnarkWorkerAction::Killed
-                | ExternalSnarkWorkerAction::WorkCancelled
-                | ExternalSnarkWorkerAction::PruneWork => {
+                ExternalSnarkWorkerAction::Start { .. }
+                | ExternalSnarkWorkerAction::Started { .. }
+                | ExternalSnarkWorkerAction::Kill { .. }
+                | ExternalSnarkWorkerAction::Killed { .. }
+                | ExternalSnarkWorkerAction::WorkCancelled { .. }
+                | ExternalSnarkWorkerAction::PruneWork { .. } => {
                     openmina_core::log::debug!(
                         meta.time();
                         kind = kind.to_string(),
                         trace_action = serde_json::to_string(&a).ok()
                     )
                 }
-                ExternalSnarkWorkerAction::SubmitWork { job_id, .. } => {
+                ExternalSnarkWorkerAction::SubmitWork {
+                    worker_id, job_id, ..
+                } => {
                     openmina_core::log::info!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = *worker_id,
                         work_id = job_id.to_string(),
                     )
                 }
-                ExternalSnarkWorkerAction::WorkResult { .. } => {
-                    openmina_core::log::info!(
-                        meta.time();
-                        kind = kind.to_string(),
-                    )
-                }
-                ExternalSnarkWorkerAction::CancelWork => {
+                ExternalSnarkWorkerAction::WorkResult { worker_id, .. }
+                | ExternalSnarkWorkerAction::CancelWork { worker_id } => {
                     openmina_core::log::info!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = *worker_id,
                     )
                 }
-                ExternalSnarkWorkerAction::WorkError { error, .. } => {
+                ExternalSnarkWorkerAction::WorkError { worker_id, error } => {
                     openmina_core::log::warn!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = *worker_id,
                         error = error.to_string(),
                     )
                 }
-                ExternalSnarkWorkerAction::Error { error, .. } => {
+                ExternalSnarkWorkerAction::Error {
+                    worker_id, error, ..
+                } => {
                     openmina_core::log::info!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = *worker_id,
                         error = error.to_string(),
                     )
                 }
-                ExternalSnarkWorkerAction::StartTimeout { .. } => {
-                    openmina_core::log::warn!(
-                        meta.time();
-                        kind = kind.to_string(),
-                    )
-                }
-                ExternalSnarkWorkerAction::WorkTimeout { .. } => {
+                ExternalSnarkWorkerAction::StartTimeout { worker_id, .. }
+                | ExternalSnarkWorkerAction::WorkTimeout { worker_id, .. } => {
                     openmina_core::log::warn!(
                         meta.time();
                         kind = kind.to_string(),
+                        worker_id = *worker_id,
                     )
                 }
             }
@@ -581,6 +582,25 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
             _ => {}
         },
         Action::TransitionFrontier(a) => match a {
+            TransitionFrontierAction::Genesis(action) => match action {
+                TransitionFrontierGenesisAction::LedgerLoadSuccess { data } => {
+                    openmina_core::log::info!(
+                        meta.time();
+                        kind = kind.to_string(),
+                        summary = "Genesis ledger loaded".to_string(),
+                        genesis_ledger_hash = data.genesis_ledger_hash.to_string(),
+                    )
+                }
+                TransitionFrontierGenesisAction::Inject => openmina_core::log::info!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = "Genesis block injected".to_string(),
+                ),
+                _other => openmina_core::log::debug!(
+                    meta.time();
+                    kind = kind.to_string(),
+                ),
+            },
             TransitionFrontierAction::Sync(action) => match action {
                 TransitionFrontierSyncAction::Init {
                     best_tip,
@@ -668,27 +688,34 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                         meta.time();
                         kind = kind.to_string(),
                         summary = format!("Current epoch accounts: {:?}, Next epoch accounts: {:?}",
-                            current_epoch_producer_and_delegators.values().map(| a | a.0.clone()).collect::<Vec<_>>(),
-                            next_epoch_producer_and_delegators.values().map(| a | a.0.clone()).collect::<Vec<_>>()
+                            current_epoch_producer_and_delegators.iter().map(|(producer, table)| (producer, table.values().map(| a | a.0.clone()).collect::<Vec<_>>())).collect::<Vec<_>>(),
+                            next_epoch_producer_and_delegators.iter().map(|(producer, table)| (producer, table.values().map(| a | a.0.clone()).collect::<Vec<_>>())).collect::<Vec<_>>()
                         ),
                     );
                 }
-                BlockProducerVrfEvaluatorAction::EvaluationSuccess { vrf_output, .. } => {
-                    match vrf_output {
-                        vrf::VrfEvaluationOutput::SlotWon(_) => {
-                            openmina_core::log::info!(
-                                meta.time();
-                                kind = kind.to_string(),
-                                summary = format!("Slot evaluation result - won slot: {:?}", vrf_output),
-                            )
-                        }
-                        vrf::VrfEvaluationOutput::SlotLost(_) => {
-                            openmina_core::log::debug!(
-                                meta.time();
-                                kind = kind.to_string(),
-                                summary = format!("Slot evaluation result - lost slot: {:?}", vrf_output),
-                            )
-                        }
+                BlockProducerVrfEvaluatorAction::EvaluationSuccess {
+                    producer,
+                    slots,
+                    won_slots,
+                    ..
+                } => {
+                    if won_slots.is_empty() {
+                        openmina_core::log::debug!(
+                            meta.time();
+                            kind = kind.to_string(),
+                            producer = producer.to_string(),
+                            summary = format!("Slot evaluation result - no slots won in {:?}", slots),
+                        )
+                    } else {
+                        openmina_core::log::info!(
+                            meta.time();
+                            kind = kind.to_string(),
+                            producer = producer.to_string(),
+                            summary = format!("Slot evaluation result - won slots in {:?}: {:?}",
+                                slots,
+                                won_slots.iter().map(|s| s.global_slot).collect::<Vec<_>>()
+                            ),
+                        )
                     }
                 }
                 BlockProducerVrfEvaluatorAction::EvaluateVrf { vrf_input } => {
@@ -698,8 +725,93 @@ pub fn logger_effects<S: Service>(store: &Store<S>, action: ActionWithMetaRef<'_
                         summary = format!("Vrf Evaluation requested: {:?}", vrf_input),
                     )
                 }
+                BlockProducerVrfEvaluatorAction::EvaluationFailed {
+                    producer,
+                    slots,
+                    error,
+                    ..
+                } => {
+                    openmina_core::log::warn!(
+                        meta.time();
+                        kind = kind.to_string(),
+                        producer = producer.to_string(),
+                        summary = format!("Vrf evaluation of {:?} failed, will be retried", slots),
+                        error = error.to_string(),
+                    )
+                }
+                BlockProducerVrfEvaluatorAction::EvaluationRetry => {}
             },
             BlockProducerAction::BestTipUpdate { .. } => {}
+            BlockProducerAction::WonSlotProduceInit
+            | BlockProducerAction::StagedLedgerDiffCreateSuccess { .. }
+            | BlockProducerAction::BlockUnprovenBuild
+            | BlockProducerAction::BlockProduced => {
+                let block_producer = &store.state().block_producer;
+                let Some(won_slot) = block_producer
+                    .current_won_slot()
+                    .filter(|_| block_producer.is_dry_run())
+                else {
+                    return;
+                };
+                let since_slot_start = meta
+                    .time()
+                    .checked_sub(won_slot.slot_time)
+                    .unwrap_or_default();
+                openmina_core::log::info!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = format!(
+                        "dry run, slot: {}, {}ms since slot start",
+                        won_slot.global_slot(),
+                        since_slot_start.as_millis()
+                    ),
+                    global_slot = won_slot.global_slot(),
+                    since_slot_start_ms = since_slot_start.as_millis() as u64,
+                )
+            }
+            BlockProducerAction::BlockDryRunFinished => {
+                let Some(block) = store.state().block_producer.dry_run_block() else {
+                    return;
+                };
+                openmina_core::log::info!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = format!(
+                        "dry run block: {}, height: {}, commands: {}, completed works: {}, coinbases: {}",
+                        block.hash(),
+                        block.height(),
+                        block.commands_iter().count(),
+                        block.completed_works_iter().count(),
+                        block.coinbases_iter().count()
+                    ),
+                    block_hash = block.hash().to_string(),
+                );
+                openmina_core::log::debug!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = format!("dry run block: {}, staged ledger diff", block.hash()),
+                    staged_ledger_diff = serde_json::to_string(block.staged_ledger_diff())
+                        .unwrap_or_default(),
+                )
+            }
+            _ => {}
+        },
+        Action::Consensus(a) => match a {
+            ConsensusAction::ClockSkewDetected { skew_ms } => {
+                openmina_core::log::warn!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = format!("system clock is skewed by {skew_ms}ms from block producers"),
+                    skew_ms = skew_ms,
+                )
+            }
+            ConsensusAction::ClockSkewResolved => {
+                openmina_core::log::info!(
+                    meta.time();
+                    kind = kind.to_string(),
+                    summary = "system clock is no longer skewed",
+                )
+            }
             _ => {}
         },
         _ => {}
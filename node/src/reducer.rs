@@ -38,13 +38,20 @@ pub fn reducer(state: &mut State, action: &ActionWithMeta) {
             state.snark.reducer(meta.with_action(a));
         }
         Action::Consensus(a) => {
-            state.consensus.reducer(meta.with_action(a));
+            let transition_frontier = &state.transition_frontier;
+            let synced_best_tip = transition_frontier
+                .best_tip()
+                .filter(|_| transition_frontier.sync.is_synced());
+            state
+                .consensus
+                .reducer(meta.with_action(a), synced_best_tip);
         }
         Action::TransitionFrontier(a) => {
             state.transition_frontier.reducer(meta.with_action(a));
         }
         Action::SnarkPool(a) => {
-            state.snark_pool.reducer(meta.with_action(a));
+            let my_id = state.p2p.my_id();
+            state.snark_pool.reducer(meta.with_action(a), my_id);
         }
         Action::BlockProducer(a) => {
             state
@@ -1,8 +1,13 @@
 mod rpc_state;
 use mina_p2p_messages::v2::{
-    MinaBaseSignedCommandPayloadBodyStableV2, MinaBaseTransactionStatusStableV2,
-    MinaBaseUserCommandStableV2, MinaTransactionTransactionStableV2,
-    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse, StateHash, TransactionHash,
+    CurrencyBalanceStableV1, LedgerHash, MerkleTreePath, MinaBaseAccountBinableArgStableV2,
+    MinaBaseFeeExcessStableV1, MinaBaseSignedCommandPayloadBodyStableV2,
+    MinaBaseTransactionStatusStableV2, MinaBaseUserCommandStableV2,
+    MinaStateBlockchainStateValueStableV2LedgerProofStatement,
+    MinaStateBlockchainStateValueStableV2SignedAmount, MinaTransactionTransactionStableV2,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
+    SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery, StateHash, TokenIdKeyHash, TransactionHash,
+    UnsignedExtendedUInt32StableV1,
 };
 pub use rpc_state::*;
 
@@ -24,27 +29,40 @@ pub use openmina_core::requests::{RpcId, RpcIdType};
 
 use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
 use ledger::scan_state::scan_state::AvailableJobMessage;
+use ledger::AccountIndex;
 use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
 use openmina_core::snark::SnarkJobId;
+use openmina_core::block::ArcBlockWithHash;
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
+use crate::account::AccountPublicKey;
+use crate::block_producer::BlockProducerHistory;
 use crate::external_snark_worker::{
-    ExternalSnarkWorkerError, ExternalSnarkWorkerWorkError, SnarkWorkSpecError,
+    ExternalSnarkWorkerError, ExternalSnarkWorkerStats, ExternalSnarkWorkerWorkError,
+    SnarkWorkSpecError,
 };
 use crate::p2p::connection::incoming::P2pConnectionIncomingInitOpts;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
 use crate::p2p::PeerId;
 use crate::snark_pool::{JobCommitment, JobSummary};
 use crate::stats::actions::{ActionStatsForBlock, ActionStatsSnapshot};
+use crate::stats::snark_market::{
+    SnarkMarketJobStats, SnarkMarketSeriesEntry, SnarkMarketSeriesError, SnarkMarketStatsSnapshot,
+};
 use crate::stats::sync::SyncStatsSnapshot;
-use crate::State;
+use crate::watched_accounts::{
+    Transaction as WatchedAccountTransaction, WatchedAccountBlockInfo,
+    WatchedAccountLedgerInitialState,
+};
+use crate::{SnarkerFeePolicy, State};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RpcRequest {
     StateGet,
     ActionStatsGet(ActionStatsQuery),
     SyncStatsGet(SyncStatsQuery),
+    SnarkMarketStatsGet(SnarkMarketStatsQuery),
     PeersGet,
     P2pConnectionOutgoing(P2pConnectionOutgoingInitOpts),
     P2pConnectionIncoming(P2pConnectionIncomingInitOpts),
@@ -55,8 +73,89 @@ pub enum RpcRequest {
     SnarkerJobCommit { job_id: SnarkJobId },
     SnarkerJobSpec { job_id: SnarkJobId },
     SnarkerWorkers,
+    SnarkersReliability,
+    /// Remote snark worker asks for a job to work on.
+    SnarkerWorkGet,
+    /// Remote snark worker submits the work done.
+    SnarkerWorkSubmit(Box<SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery>),
     HealthCheck,
     ReadinessCheck,
+    WatchedAccountsAdd { pub_key: NonZeroCurvePoint },
+    WatchedAccountsRemove { pub_key: NonZeroCurvePoint },
+    WatchedAccountsGet,
+    WatchedAccountBlocksGet(RpcWatchedAccountBlocksGetQuery),
+    AccountProofGet(RpcAccountProofGetQuery),
+    BlockGet(RpcBlockGetQuery),
+    TransitionFrontierStatusGet,
+    BlockProducerScheduleGet,
+    BlockProducerHistoryGet,
+    BlockProducerEpochDataGet,
+}
+
+/// Access level required to make an rpc request.
+///
+/// Roles are ordered, so a role grants access to requests of all lower
+/// roles as well.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum RpcRole {
+    /// Requests needed by peers and orchestrators: webrtc signaling
+    /// and health probes.
+    Public,
+    ReadOnly,
+    /// Requests which modify the node's behavior or expose its whole state.
+    Admin,
+}
+
+impl std::str::FromStr for RpcRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "public" => Ok(Self::Public),
+            "read-only" => Ok(Self::ReadOnly),
+            "admin" => Ok(Self::Admin),
+            _ => Err(format!(
+                "invalid rpc role `{s}`, expected `public`, `read-only` or `admin`"
+            )),
+        }
+    }
+}
+
+impl RpcRequest {
+    pub fn role(&self) -> RpcRole {
+        match self {
+            Self::P2pConnectionIncoming(_) | Self::HealthCheck | Self::ReadinessCheck => {
+                RpcRole::Public
+            }
+            Self::ActionStatsGet(_)
+            | Self::SyncStatsGet(_)
+            | Self::SnarkMarketStatsGet(_)
+            | Self::PeersGet
+            | Self::ScanStateSummaryGet(_)
+            | Self::SnarkPoolGet
+            | Self::SnarkPoolJobGet { .. }
+            | Self::SnarkerConfig
+            | Self::SnarkerJobSpec { .. }
+            | Self::SnarkerWorkers
+            | Self::SnarkersReliability
+            | Self::WatchedAccountsGet
+            | Self::WatchedAccountBlocksGet(_)
+            | Self::AccountProofGet(_)
+            | Self::BlockGet(_)
+            | Self::TransitionFrontierStatusGet
+            | Self::BlockProducerScheduleGet
+            | Self::BlockProducerHistoryGet
+            | Self::BlockProducerEpochDataGet => RpcRole::ReadOnly,
+            Self::StateGet
+            | Self::P2pConnectionOutgoing(_)
+            | Self::SnarkerJobCommit { .. }
+            | Self::SnarkerWorkGet
+            | Self::SnarkerWorkSubmit(_)
+            | Self::WatchedAccountsAdd { .. }
+            | Self::WatchedAccountsRemove { .. } => RpcRole::Admin,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -71,6 +170,25 @@ pub struct SyncStatsQuery {
     pub limit: Option<usize>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SnarkMarketStatsQuery {
+    /// Length of the time series interval, 10 minutes by default.
+    pub interval_secs: Option<u64>,
+    /// Number of time series intervals.
+    pub limit: Option<usize>,
+    /// Job to include the market view of.
+    pub job_id: Option<SnarkJobId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcSnarkMarketStats {
+    pub summary: SnarkMarketStatsSnapshot,
+    /// Latest interval first.
+    pub series: Vec<SnarkMarketSeriesEntry>,
+    /// Requested job, if it is known.
+    pub job: Option<SnarkMarketJobStats>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum RpcScanStateSummaryGetQuery {
     ForBestTip,
@@ -78,6 +196,31 @@ pub enum RpcScanStateSummaryGetQuery {
     ForBlockWithHeight(u32),
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcWatchedAccountBlocksGetQuery {
+    pub pub_key: NonZeroCurvePoint,
+    /// Only return blocks with height greater or equal to this one.
+    ///
+    /// Lets clients poll for new blocks incrementally.
+    pub from_height: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcAccountProofGetQuery {
+    pub public_key: NonZeroCurvePoint,
+    /// Default token if `None`.
+    pub token: Option<TokenIdKeyHash>,
+    /// Staged ledger of the best tip if `None`.
+    pub ledger_hash: Option<LedgerHash>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum RpcBlockGetQuery {
+    ForBestTip,
+    ForBlockWithHash(StateHash),
+    ForBlockWithHeight(u32),
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "kind")]
 pub enum ActionStatsResponse {
@@ -107,6 +250,12 @@ pub struct RpcPeerInfo {
 #[derive(Serialize, Debug, Clone)]
 pub struct RpcScanStateSummary {
     pub block: RpcScanStateSummaryBlock,
+    /// Trees of the parallel scan state, each one laid out as an array
+    /// in breadth-first order, so the children of the job at index `i`
+    /// are at `2 * i + 1` and `2 * i + 2`.
+    ///
+    /// Jobs keep their `job_id` between blocks, which can be used to
+    /// match them in responses for consecutive blocks.
     pub scan_state: Vec<Vec<RpcScanStateSummaryScanStateJob>>,
 }
 
@@ -145,12 +294,14 @@ pub enum RpcScanStateSummaryScanStateJob {
         bundle_job_id: SnarkJobId,
         job: RpcScanStateSummaryScanStateJobKind,
         seq_no: u64,
+        statement: RpcScanStateSummaryScanStateJobStatement,
     },
     Pending {
         job_id: SnarkJobId,
         bundle_job_id: SnarkJobId,
         job: RpcScanStateSummaryScanStateJobKind,
         seq_no: u64,
+        statement: RpcScanStateSummaryScanStateJobStatement,
         commitment: Option<JobCommitment>,
         snark: Option<RpcSnarkPoolJobSnarkWork>,
     },
@@ -159,10 +310,24 @@ pub enum RpcScanStateSummaryScanStateJob {
         bundle_job_id: SnarkJobId,
         job: RpcScanStateSummaryScanStateJobKind,
         seq_no: u64,
+        statement: RpcScanStateSummaryScanStateJobStatement,
         snark: RpcSnarkPoolJobSnarkWorkDone,
     },
 }
 
+/// Ledger hashes and fee excess of the job's statement.
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcScanStateSummaryScanStateJobStatement {
+    pub source_first_pass_ledger: LedgerHash,
+    pub source_second_pass_ledger: LedgerHash,
+    pub target_first_pass_ledger: LedgerHash,
+    pub target_second_pass_ledger: LedgerHash,
+    pub connecting_ledger_left: LedgerHash,
+    pub connecting_ledger_right: LedgerHash,
+    pub fee_excess: MinaBaseFeeExcessStableV1,
+    pub supply_increase: MinaStateBlockchainStateValueStableV2SignedAmount,
+}
+
 #[derive(Serialize, Debug, Clone)]
 #[serde(tag = "kind")]
 pub enum RpcScanStateSummaryScanStateJobKind {
@@ -224,9 +389,41 @@ pub enum RpcSnarkerJobSpecResponse {
     JobNotFound,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RpcSnarkerWorkGetResponse {
+    /// Spec of the job, which the node committed to on behalf of the
+    /// worker, or `None` if there are no jobs available.
+    Ok(SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse),
+    Err(SnarkWorkSpecError),
+    SnarkerDisabled,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RpcSnarkerWorkSubmitResponse {
+    /// Work is accepted for verification, after which it is added to
+    /// the snark pool.
+    Ok,
+    JobNotFound,
+    /// Same or better work for the job was already submitted.
+    AlreadySubmitted,
+    /// Fee or prover of the work doesn't match the commitment made
+    /// when the job was handed out, or the commitment was outbid.
+    CommitmentMismatch,
+    SnarkerDisabled,
+}
+
+/// Sender of the work submitted by remote snark workers, used in place
+/// of the peer id when the work is verified.
+pub const SNARKER_REMOTE_WORKER_SENDER: &str = "remote_snark_worker";
+
 pub type RpcStateGetResponse = Box<State>;
 pub type RpcActionStatsGetResponse = Option<ActionStatsResponse>;
 pub type RpcSyncStatsGetResponse = Option<Vec<SyncStatsSnapshot>>;
+/// `None` if the stats are disabled.
+pub type RpcSnarkMarketStatsGetResponse =
+    Result<Option<RpcSnarkMarketStats>, SnarkMarketSeriesError>;
 pub type RpcPeersGetResponse = Vec<RpcPeerInfo>;
 pub type RpcP2pConnectionOutgoingResponse = Result<(), String>;
 pub type RpcScanStateSummaryGetResponse = Option<RpcScanStateSummary>;
@@ -237,7 +434,10 @@ pub type RpcSnarkerConfigGetResponse = Option<RpcSnarkerConfig>;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RpcSnarkerConfig {
     public_key: NonZeroCurvePoint,
+    /// Fee currently in effect, adjusted by the `fee_policy`, if set.
     fee: CurrencyFeeStableV1,
+    #[serde(default)]
+    fee_policy: Option<SnarkerFeePolicy>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -245,6 +445,7 @@ pub struct RpcSnarkWorker {
     pub time: Option<Timestamp>,
     pub id: Option<String>,
     pub status: RpcSnarkWorkerStatus,
+    pub stats: ExternalSnarkWorkerStats,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -279,6 +480,37 @@ pub enum RpcSnarkWorkerStatus {
 
 pub type RpcSnarkerWorkersResponse = Vec<RpcSnarkWorker>;
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RpcSnarkerReliability {
+    /// Peer, from which the snarker's commitments were received.
+    pub peer_id: PeerId,
+    pub snarker: NonZeroCurvePoint,
+    pub delivered: u32,
+    pub timed_out: u32,
+    pub score: f64,
+    /// Whether commitments from the snarker are refused.
+    pub unreliable: bool,
+}
+
+pub type RpcSnarkersReliabilityResponse = Vec<RpcSnarkerReliability>;
+
+impl From<&MinaStateBlockchainStateValueStableV2LedgerProofStatement>
+    for RpcScanStateSummaryScanStateJobStatement
+{
+    fn from(value: &MinaStateBlockchainStateValueStableV2LedgerProofStatement) -> Self {
+        Self {
+            source_first_pass_ledger: value.source.first_pass_ledger.clone(),
+            source_second_pass_ledger: value.source.second_pass_ledger.clone(),
+            target_first_pass_ledger: value.target.first_pass_ledger.clone(),
+            target_second_pass_ledger: value.target.second_pass_ledger.clone(),
+            connecting_ledger_left: value.connecting_ledger_left.clone(),
+            connecting_ledger_right: value.connecting_ledger_right.clone(),
+            fee_excess: value.fee_excess.clone(),
+            supply_increase: value.supply_increase.clone(),
+        }
+    }
+}
+
 impl From<&MinaTransactionTransactionStableV2> for RpcScanStateSummaryBlockTransactionKind {
     fn from(value: &MinaTransactionTransactionStableV2) -> Self {
         match value {
@@ -305,3 +537,177 @@ impl From<&MinaBaseUserCommandStableV2> for RpcScanStateSummaryBlockTransactionK
 
 pub type RpcHealthCheckResponse = Result<(), String>;
 pub type RpcReadinessCheckResponse = Result<(), String>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RpcWatchedAccountsAddResponse {
+    Ok,
+    AlreadyWatched,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RpcWatchedAccountsRemoveResponse {
+    Ok,
+    NotWatched,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcWatchedAccount {
+    pub public_key: NonZeroCurvePoint,
+    pub initial_state: WatchedAccountLedgerInitialState,
+    pub blocks_count: usize,
+    pub last_block: Option<WatchedAccountBlockInfo>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcWatchedAccountBlock {
+    pub block: WatchedAccountBlockInfo,
+    /// Transactions included in the block ordered by nonce from low to high.
+    pub transactions: Vec<WatchedAccountTransaction>,
+    /// None until the account is fetched from the block's ledger.
+    pub balance: Option<CurrencyBalanceStableV1>,
+    /// None until the account is fetched from the block's ledger.
+    pub nonce: Option<UnsignedExtendedUInt32StableV1>,
+}
+
+pub type RpcWatchedAccountsGetResponse = Vec<RpcWatchedAccount>;
+pub type RpcWatchedAccountBlocksGetResponse = Option<Vec<RpcWatchedAccountBlock>>;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcAccountProof {
+    pub ledger_hash: LedgerHash,
+    pub account: MinaBaseAccountBinableArgStableV2,
+    pub index: u64,
+    /// Sibling hashes ordered from the account's leaf up to the root.
+    ///
+    /// Can be checked with `snark::merkle_path::calc_merkle_root_hash`.
+    pub merkle_path: MerkleTreePath,
+    /// Block in our best chain which commits to `ledger_hash`.
+    pub block: Option<RpcAccountProofBlock>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcAccountProofBlock {
+    pub hash: StateHash,
+    pub height: u32,
+    pub global_slot: u32,
+    pub ledger: RpcAccountProofLedgerKind,
+}
+
+#[derive(Serialize, Debug, Clone, Copy)]
+pub enum RpcAccountProofLedgerKind {
+    Staged,
+    Snarked,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RpcAccountProofGetError {
+    NoBestTip,
+    LedgerNotFound { ledger_hash: LedgerHash },
+    AccountNotFound { ledger_hash: LedgerHash },
+}
+
+pub type RpcAccountProofGetResponse = Result<RpcAccountProof, RpcAccountProofGetError>;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcBlockWithTransactions {
+    pub block: ArcBlockWithHash,
+    /// Transactions (including fee transfers and coinbases) applied by the
+    /// block, in the order of application.
+    pub transactions: Vec<RpcBlockTransaction>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcBlockTransaction {
+    pub transaction: MinaTransactionTransactionStableV2,
+    pub status: MinaBaseTransactionStatusStableV2,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "kind")]
+pub enum RpcBlockGetError {
+    NoBestTip,
+    /// Block isn't in our best chain.
+    BlockNotFound,
+    TransactionsUnavailable { error: String },
+}
+
+pub type RpcBlockGetResponse = Result<RpcBlockWithTransactions, RpcBlockGetError>;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcTransitionFrontierStatus {
+    pub genesis_block_hash: StateHash,
+    pub root: RpcTransitionFrontierBlock,
+    pub best_tip: RpcTransitionFrontierBlock,
+    pub is_synced: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcTransitionFrontierBlock {
+    pub hash: StateHash,
+    pub height: u32,
+    /// Block timestamp in milliseconds since unix epoch.
+    pub timestamp: u64,
+}
+
+pub type RpcTransitionFrontierStatusGetResponse = Option<RpcTransitionFrontierStatus>;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcBlockProducerScheduledSlot {
+    pub producer: NonZeroCurvePoint,
+    pub delegator: NonZeroCurvePoint,
+    pub global_slot: u32,
+    pub slot_time: Timestamp,
+    pub staking_ledger_hash: LedgerHash,
+    /// If several of our keys won the same slot, only the best one is
+    /// selected for production.
+    ///
+    /// Selected slot might still be skipped, if a better block for the
+    /// slot is received from the network.
+    pub selected: bool,
+}
+
+/// `None` if block production isn't enabled.
+pub type RpcBlockProducerScheduleGetResponse = Option<Vec<RpcBlockProducerScheduledSlot>>;
+/// `None` if block production isn't enabled.
+pub type RpcBlockProducerHistoryGetResponse = Option<BlockProducerHistory>;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcBlockProducerEpochData {
+    pub current: Option<RpcBlockProducerEpoch>,
+    pub next: Option<RpcBlockProducerEpoch>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcBlockProducerEpoch {
+    pub epoch: u32,
+    pub seed: String,
+    pub ledger_hash: LedgerHash,
+    pub total_currency: u64,
+    pub producers: Vec<RpcBlockProducerEpochProducer>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcBlockProducerEpochProducer {
+    pub producer: AccountPublicKey,
+    pub total_stake: u64,
+    /// Probability that at least one of the delegators wins a slot.
+    pub slot_win_probability: f64,
+    pub expected_won_slots: f64,
+    /// Empty if the producer has no stake in the epoch ledger, or the
+    /// delegator table isn't built yet.
+    pub delegators: Vec<RpcBlockProducerEpochDelegator>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct RpcBlockProducerEpochDelegator {
+    pub index: AccountIndex,
+    pub public_key: AccountPublicKey,
+    pub stake: u64,
+    pub slot_win_probability: f64,
+}
+
+/// `None` if block production isn't enabled.
+pub type RpcBlockProducerEpochDataGetResponse = Option<RpcBlockProducerEpochData>;
@@ -1,3 +1,4 @@
+use mina_p2p_messages::v2::{NonZeroCurvePoint, SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery};
 use openmina_core::snark::SnarkJobId;
 use serde::{Deserialize, Serialize};
 
@@ -6,7 +7,11 @@ use crate::p2p::connection::incoming::P2pConnectionIncomingInitOpts;
 use crate::p2p::connection::outgoing::{P2pConnectionOutgoingError, P2pConnectionOutgoingInitOpts};
 use crate::p2p::connection::P2pConnectionResponse;
 
-use super::{ActionStatsQuery, RpcId, RpcScanStateSummaryGetQuery, SyncStatsQuery};
+use super::{
+    ActionStatsQuery, RpcAccountProofGetQuery, RpcBlockGetQuery, RpcId,
+    RpcScanStateSummaryGetQuery, RpcWatchedAccountBlocksGetQuery, SnarkMarketStatsQuery,
+    SyncStatsQuery,
+};
 
 pub type RpcActionWithMeta = redux::ActionWithMeta<RpcAction>;
 pub type RpcActionWithMetaRef<'a> = redux::ActionWithMeta<&'a RpcAction>;
@@ -26,6 +31,10 @@ pub enum RpcAction {
         rpc_id: RpcId,
         query: SyncStatsQuery,
     },
+    SnarkMarketStatsGet {
+        rpc_id: RpcId,
+        query: SnarkMarketStatsQuery,
+    },
 
     PeersGet {
         rpc_id: RpcId,
@@ -93,6 +102,16 @@ pub enum RpcAction {
     SnarkerWorkersGet {
         rpc_id: RpcId,
     },
+    SnarkersReliabilityGet {
+        rpc_id: RpcId,
+    },
+    SnarkerWorkGet {
+        rpc_id: RpcId,
+    },
+    SnarkerWorkSubmit {
+        rpc_id: RpcId,
+        work: Box<SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery>,
+    },
 
     HealthCheck {
         rpc_id: RpcId,
@@ -101,6 +120,43 @@ pub enum RpcAction {
         rpc_id: RpcId,
     },
 
+    WatchedAccountsAdd {
+        rpc_id: RpcId,
+        pub_key: NonZeroCurvePoint,
+    },
+    WatchedAccountsRemove {
+        rpc_id: RpcId,
+        pub_key: NonZeroCurvePoint,
+    },
+    WatchedAccountsGet {
+        rpc_id: RpcId,
+    },
+    WatchedAccountBlocksGet {
+        rpc_id: RpcId,
+        query: RpcWatchedAccountBlocksGetQuery,
+    },
+
+    AccountProofGet {
+        rpc_id: RpcId,
+        query: RpcAccountProofGetQuery,
+    },
+    BlockGet {
+        rpc_id: RpcId,
+        query: RpcBlockGetQuery,
+    },
+    TransitionFrontierStatusGet {
+        rpc_id: RpcId,
+    },
+    BlockProducerScheduleGet {
+        rpc_id: RpcId,
+    },
+    BlockProducerHistoryGet {
+        rpc_id: RpcId,
+    },
+    BlockProducerEpochDataGet {
+        rpc_id: RpcId,
+    },
+
     Finish {
         rpc_id: RpcId,
     },
@@ -112,6 +168,7 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
             RpcAction::GlobalStateGet { .. } => true,
             RpcAction::ActionStatsGet { .. } => true,
             RpcAction::SyncStatsGet { .. } => true,
+            RpcAction::SnarkMarketStatsGet { .. } => true,
             RpcAction::PeersGet { .. } => true,
             RpcAction::P2pConnectionOutgoingInit { rpc_id, .. } => {
                 !state.rpc.requests.contains_key(rpc_id)
@@ -161,8 +218,21 @@ impl redux::EnablingCondition<crate::State> for RpcAction {
             RpcAction::SnarkerJobCommit { .. } => true,
             RpcAction::SnarkerJobSpec { .. } => true,
             RpcAction::SnarkerWorkersGet { .. } => true,
+            RpcAction::SnarkersReliabilityGet { .. } => true,
+            RpcAction::SnarkerWorkGet { .. } => true,
+            RpcAction::SnarkerWorkSubmit { .. } => true,
             RpcAction::HealthCheck { .. } => true,
             RpcAction::ReadinessCheck { .. } => true,
+            RpcAction::WatchedAccountsAdd { .. } => true,
+            RpcAction::WatchedAccountsRemove { .. } => true,
+            RpcAction::WatchedAccountsGet { .. } => true,
+            RpcAction::WatchedAccountBlocksGet { .. } => true,
+            RpcAction::AccountProofGet { .. } => true,
+            RpcAction::BlockGet { .. } => true,
+            RpcAction::TransitionFrontierStatusGet { .. } => true,
+            RpcAction::BlockProducerScheduleGet { .. } => true,
+            RpcAction::BlockProducerHistoryGet { .. } => true,
+            RpcAction::BlockProducerEpochDataGet { .. } => true,
             RpcAction::Finish { rpc_id } => state
                 .rpc
                 .requests
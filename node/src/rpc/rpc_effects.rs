@@ -1,21 +1,42 @@
+use std::sync::Arc;
 use std::time::Duration;
 
-use mina_p2p_messages::v2::MinaBaseTransactionStatusStableV2;
+use mina_p2p_messages::v2::{
+    MinaBaseTransactionStatusStableV2, NonZeroCurvePoint,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse,
+    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0,
+    SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery,
+};
+
+use openmina_core::block::ArcBlockWithHash;
+use openmina_core::snark::{Snark, SnarkJobCommitment};
 
+use crate::account::AccountPublicKey;
+use crate::block_producer::vrf_evaluator::EpochData;
+use crate::block_producer::BlockProducerWonSlot;
 use crate::external_snark_worker::available_job_to_snark_worker_spec;
 use crate::p2p::connection::incoming::P2pConnectionIncomingAction;
 use crate::p2p::connection::outgoing::P2pConnectionOutgoingAction;
 use crate::p2p::connection::P2pConnectionResponse;
 use crate::rpc::{PeerConnectionStatus, RpcPeerInfo};
-use crate::snark_pool::SnarkPoolAction;
+use crate::snark::work_verify::SnarkWorkVerifyAction;
+use crate::snark_pool::{JobCommitment, SnarkPoolAction, SnarkWork};
+use crate::watched_accounts::WatchedAccountsAction;
 use crate::{Service, Store};
 
 use super::{
-    ActionStatsQuery, ActionStatsResponse, RpcAction, RpcActionWithMeta, RpcScanStateSummary,
-    RpcScanStateSummaryBlock, RpcScanStateSummaryBlockTransaction,
-    RpcScanStateSummaryBlockTransactionKind, RpcScanStateSummaryGetQuery,
-    RpcScanStateSummaryScanStateJob, RpcSnarkPoolJobFull, RpcSnarkPoolJobSnarkWork,
-    RpcSnarkPoolJobSummary, RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse,
+    ActionStatsQuery, ActionStatsResponse, RpcAccountProof, RpcAccountProofBlock,
+    RpcAccountProofGetError, RpcAccountProofLedgerKind, RpcAction, RpcActionWithMeta,
+    RpcBlockGetError, RpcBlockGetQuery, RpcBlockProducerEpoch, RpcBlockProducerEpochData,
+    RpcBlockProducerEpochDelegator, RpcBlockProducerEpochProducer, RpcBlockProducerScheduledSlot,
+    RpcBlockWithTransactions, RpcScanStateSummary, RpcScanStateSummaryBlock,
+    RpcScanStateSummaryBlockTransaction, RpcScanStateSummaryBlockTransactionKind,
+    RpcScanStateSummaryGetQuery, RpcScanStateSummaryScanStateJob, RpcSnarkMarketStats,
+    RpcSnarkPoolJobFull, RpcSnarkPoolJobSnarkWork, RpcSnarkPoolJobSummary,
+    RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcSnarkerReliability,
+    RpcSnarkerWorkGetResponse, RpcSnarkerWorkSubmitResponse, RpcTransitionFrontierBlock,
+    RpcTransitionFrontierStatus, RpcWatchedAccount, RpcWatchedAccountBlock,
+    RpcWatchedAccountsAddResponse, RpcWatchedAccountsRemoveResponse, SNARKER_REMOTE_WORKER_SENDER,
 };
 
 macro_rules! respond_or_log {
@@ -66,6 +87,28 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                 .map(|s| s.collect_sync_stats(query.limit));
             let _ = store.service.respond_sync_stats_get(rpc_id, resp);
         }
+        RpcAction::SnarkMarketStatsGet { rpc_id, query } => {
+            let interval = query.interval_secs.map(Duration::from_secs);
+            let resp = store
+                .service
+                .stats()
+                .map(|s| {
+                    Ok(RpcSnarkMarketStats {
+                        summary: s.collect_snark_market_stats(meta.time()),
+                        series: s.collect_snark_market_series(
+                            meta.time(),
+                            interval,
+                            query.limit,
+                        )?,
+                        job: query
+                            .job_id
+                            .as_ref()
+                            .and_then(|job_id| s.collect_snark_market_job(job_id)),
+                    })
+                })
+                .transpose();
+            let _ = store.service.respond_snark_market_stats_get(rpc_id, resp);
+        }
         RpcAction::PeersGet { rpc_id } => {
             let peers = store
                 .state()
@@ -232,6 +275,7 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                         bundle_job_id,
                         job: kind,
                         seq_no,
+                        statement,
                     } => {
                         let Some(data) = snark_pool.get(bundle_job_id) else {
                             return;
@@ -252,6 +296,7 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                             bundle_job_id: bundle_job_id.clone(),
                             job: kind.clone(),
                             seq_no: *seq_no,
+                            statement: statement.clone(),
                             commitment,
                             snark,
                         };
@@ -306,16 +351,17 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
             let _ = store.service().respond_snark_pool_job_get(rpc_id, resp);
         }
         RpcAction::SnarkerConfigGet { rpc_id } => {
+            let state = store.state.get();
             let config =
-                store
-                    .state
-                    .get()
+                state
                     .config
                     .snarker
                     .as_ref()
-                    .map(|config| super::RpcSnarkerConfig {
+                    .zip(state.snarker_fee())
+                    .map(|(config, fee)| super::RpcSnarkerConfig {
                         public_key: config.public_key.as_ref().clone(),
-                        fee: config.fee.clone(),
+                        fee: fee.clone(),
+                        fee_policy: config.fee_policy.clone(),
                     });
             let _ = store.service().respond_snarker_config_get(rpc_id, config);
         }
@@ -359,11 +405,12 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                 &store.state().transition_frontier,
             );
             // TODO(binier): maybe don't require snarker to be enabled here.
-            let Some(config) = store.state.get().config.snarker.as_ref() else {
+            let state = store.state.get();
+            let Some((config, fee)) = state.config.snarker.as_ref().zip(state.snarker_fee()) else {
                 return;
             };
             let public_key = config.public_key.clone().into();
-            let fee = config.fee.clone();
+            let fee = fee.clone();
             let input = match input {
                 Ok(instances) => RpcSnarkerJobSpecResponse::Ok(
                     mina_p2p_messages::v2::SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(Some((
@@ -385,15 +432,140 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
             }
         }
         RpcAction::SnarkerWorkersGet { rpc_id } => {
-            let the_only = store.state().external_snark_worker.0.clone();
+            let workers = store
+                .state()
+                .external_snark_worker
+                .iter()
+                .map(|(worker_id, worker)| (worker_id, worker.clone()))
+                .map(super::RpcSnarkWorker::from)
+                .collect();
             if store
                 .service()
-                .respond_snarker_workers(rpc_id, vec![the_only.into()])
+                .respond_snarker_workers(rpc_id, workers)
                 .is_err()
             {
                 return;
             }
         }
+        RpcAction::SnarkersReliabilityGet { rpc_id } => {
+            let mut snarkers = store
+                .state()
+                .snark_pool
+                .snarkers_reliability_iter()
+                .map(|(peer_id, snarker, reliability)| RpcSnarkerReliability {
+                    peer_id: *peer_id,
+                    snarker: snarker.clone(),
+                    delivered: reliability.delivered,
+                    timed_out: reliability.timed_out,
+                    score: reliability.score(),
+                    unreliable: reliability.is_unreliable(),
+                })
+                .collect::<Vec<_>>();
+            snarkers.sort_by(|a, b| a.score.total_cmp(&b.score));
+            let _ = store
+                .service()
+                .respond_snarkers_reliability(rpc_id, snarkers);
+        }
+        RpcAction::SnarkerWorkGet { rpc_id } => {
+            let state = store.state();
+            let Some((config, fee)) = state.config.snarker.as_ref().zip(state.snarker_fee()) else {
+                let _ = store
+                    .service()
+                    .respond_snarker_work_get(rpc_id, RpcSnarkerWorkGetResponse::SnarkerDisabled);
+                return;
+            };
+            let public_key: NonZeroCurvePoint = config.public_key.clone().into();
+            let fee = fee.clone();
+            let Some(job) = state
+                .snark_pool
+                .available_jobs_with_highest_priority(1)
+                .into_iter()
+                .next()
+            else {
+                let resp = RpcSnarkerWorkGetResponse::Ok(
+                    SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(None),
+                );
+                let _ = store.service().respond_snarker_work_get(rpc_id, resp);
+                return;
+            };
+            let job_id = job.id.clone();
+            let instances = match available_job_to_snark_worker_spec(
+                job.job.clone(),
+                &state.transition_frontier,
+            ) {
+                Ok(instances) => instances,
+                Err(err) => {
+                    let resp = RpcSnarkerWorkGetResponse::Err(err);
+                    let _ = store.service().respond_snarker_work_get(rpc_id, resp);
+                    return;
+                }
+            };
+            // Commit to the job on behalf of the remote worker, so that
+            // other snarkers won't work on it in the meantime. Job is only
+            // handed out if the commitment was accepted.
+            let timestamp_ms = meta.time_as_nanos() / 1_000_000;
+            let commitment =
+                SnarkJobCommitment::new(timestamp_ms, job_id, fee.clone(), public_key.clone());
+            let committed = store.dispatch(SnarkPoolAction::CommitmentAdd {
+                commitment,
+                sender: store.state().p2p.my_id(),
+            });
+            let resp = RpcSnarkerWorkGetResponse::Ok(
+                SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponse(committed.then(|| {
+                    (
+                        SnarkWorkerWorkerRpcsVersionedGetWorkV2TResponseA0 { instances, fee },
+                        public_key,
+                    )
+                })),
+            );
+            let _ = store.service().respond_snarker_work_get(rpc_id, resp);
+        }
+        RpcAction::SnarkerWorkSubmit { rpc_id, work } => {
+            let SnarkWorkerWorkerRpcsVersionedSubmitWorkV2TQuery {
+                proofs,
+                spec,
+                prover,
+                ..
+            } = *work;
+            let snark = Snark {
+                snarker: prover,
+                fee: spec.fee,
+                proofs: Arc::new(proofs),
+            };
+            let state = store.state();
+            let Some(config) = state.config.snarker.as_ref() else {
+                let _ = store.service().respond_snarker_work_submit(
+                    rpc_id,
+                    RpcSnarkerWorkSubmitResponse::SnarkerDisabled,
+                );
+                return;
+            };
+            let public_key = config.public_key.clone().into();
+            let check = match state.snark_pool.get(&snark.job_id()) {
+                None => Err(RpcSnarkerWorkSubmitResponse::JobNotFound),
+                Some(job) => snarker_work_submit_check(
+                    job.commitment.as_ref(),
+                    job.snark.as_ref(),
+                    &snark,
+                    &public_key,
+                ),
+            };
+            if let Err(resp) = check {
+                let _ = store.service().respond_snarker_work_submit(rpc_id, resp);
+                return;
+            }
+            // Work from remote workers isn't trusted, so it is verified
+            // before it is added to the snark pool.
+            let req_id = state.snark.work_verify.next_req_id();
+            store.dispatch(SnarkWorkVerifyAction::Init {
+                req_id,
+                batch: vec![snark],
+                sender: SNARKER_REMOTE_WORKER_SENDER.to_owned(),
+            });
+            let _ = store
+                .service()
+                .respond_snarker_work_submit(rpc_id, RpcSnarkerWorkSubmitResponse::Ok);
+        }
         RpcAction::HealthCheck { rpc_id } => {
             let some_peers = store
                 .state()
@@ -406,8 +578,17 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                 .ok_or_else(|| {
                     openmina_core::log::warn!(openmina_core::log::system_time(); "no ready peers");
                     String::from("no ready peers") });
+            let clock_skew = match &store.state().consensus.clock_skew.detected {
+                Some(detected) => Err(format!(
+                    "system clock is skewed by {}ms from block producers",
+                    detected.skew_ms
+                )),
+                None => Ok(()),
+            };
             respond_or_log!(
-                store.service().respond_health_check(rpc_id, some_peers),
+                store
+                    .service()
+                    .respond_health_check(rpc_id, some_peers.and(clock_skew)),
                 meta.time()
             );
         }
@@ -439,6 +620,471 @@ pub fn rpc_effects<S: Service>(store: &mut Store<S>, action: RpcActionWithMeta)
                 meta.time()
             );
         }
+        RpcAction::WatchedAccountsAdd { rpc_id, pub_key } => {
+            if store.state().watched_accounts.contains(&pub_key) {
+                respond_or_log!(
+                    store.service().respond_watched_accounts_add(
+                        rpc_id,
+                        RpcWatchedAccountsAddResponse::AlreadyWatched
+                    ),
+                    meta.time()
+                );
+                return;
+            }
+            store.dispatch(WatchedAccountsAction::Add { pub_key });
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_watched_accounts_add(rpc_id, RpcWatchedAccountsAddResponse::Ok),
+                meta.time()
+            );
+        }
+        RpcAction::WatchedAccountsRemove { rpc_id, pub_key } => {
+            let resp = if store.dispatch(WatchedAccountsAction::Remove { pub_key }) {
+                RpcWatchedAccountsRemoveResponse::Ok
+            } else {
+                RpcWatchedAccountsRemoveResponse::NotWatched
+            };
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_watched_accounts_remove(rpc_id, resp),
+                meta.time()
+            );
+        }
+        RpcAction::WatchedAccountsGet { rpc_id } => {
+            let accounts = store
+                .state()
+                .watched_accounts
+                .iter()
+                .map(|(pub_key, account)| RpcWatchedAccount {
+                    public_key: pub_key.clone(),
+                    initial_state: account.initial_state.clone(),
+                    blocks_count: account.blocks.len(),
+                    last_block: account.blocks.back().map(|b| b.block().clone()),
+                })
+                .collect();
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_watched_accounts_get(rpc_id, accounts),
+                meta.time()
+            );
+        }
+        RpcAction::WatchedAccountBlocksGet { rpc_id, query } => {
+            let from_height = query.from_height.unwrap_or(0);
+            let blocks = store
+                .state()
+                .watched_accounts
+                .get(&query.pub_key)
+                .map(|account| {
+                    account
+                        .blocks
+                        .iter()
+                        .filter(|b| b.block().level >= from_height)
+                        .map(|b| RpcWatchedAccountBlock {
+                            block: b.block().clone(),
+                            transactions: b.transactions().to_vec(),
+                            balance: b.ledger_account().map(|a| a.balance.clone()),
+                            nonce: b.ledger_account().map(|a| a.nonce.clone()),
+                        })
+                        .collect()
+                });
+            respond_or_log!(
+                store
+                    .service()
+                    .respond_watched_account_blocks_get(rpc_id, blocks),
+                meta.time()
+            );
+        }
+        RpcAction::AccountProofGet { rpc_id, query } => {
+            let transition_frontier = &store.state.get().transition_frontier;
+            let ledger_hash = query.ledger_hash.or_else(|| {
+                let best_tip = transition_frontier.best_tip()?;
+                Some(best_tip.staged_ledger_hash().clone())
+            });
+            let resp = match ledger_hash {
+                None => Err(RpcAccountProofGetError::NoBestTip),
+                Some(ledger_hash) => {
+                    let block = transition_frontier.best_chain.iter().rev().find_map(|b| {
+                        let ledger = if b.staged_ledger_hash() == &ledger_hash {
+                            RpcAccountProofLedgerKind::Staged
+                        } else if b.snarked_ledger_hash() == &ledger_hash {
+                            RpcAccountProofLedgerKind::Snarked
+                        } else {
+                            return None;
+                        };
+                        Some(RpcAccountProofBlock {
+                            hash: b.hash().clone(),
+                            height: b.height(),
+                            global_slot: b.global_slot_since_genesis(),
+                            ledger,
+                        })
+                    });
+                    store
+                        .service
+                        .account_proof(ledger_hash, query.public_key, query.token)
+                        .map(|proof| RpcAccountProof { block, ..proof })
+                }
+            };
+            respond_or_log!(
+                store.service.respond_account_proof_get(rpc_id, resp),
+                meta.time()
+            );
+        }
+        RpcAction::BlockGet { rpc_id, query } => {
+            let transition_frontier = &store.state.get().transition_frontier;
+            let block = match query {
+                RpcBlockGetQuery::ForBestTip => transition_frontier.best_tip(),
+                RpcBlockGetQuery::ForBlockWithHash(hash) => transition_frontier
+                    .best_chain
+                    .iter()
+                    .rev()
+                    .find(|b| b.hash == hash),
+                RpcBlockGetQuery::ForBlockWithHeight(height) => transition_frontier
+                    .best_chain
+                    .iter()
+                    .rev()
+                    .find(|b| b.height() == height),
+            };
+            let resp = match block {
+                None if transition_frontier.best_tip().is_none() => {
+                    Err(RpcBlockGetError::NoBestTip)
+                }
+                None => Err(RpcBlockGetError::BlockNotFound),
+                Some(block) => store
+                    .service
+                    .block_transactions(block)
+                    .map(|transactions| RpcBlockWithTransactions {
+                        block: block.clone(),
+                        transactions,
+                    })
+                    .map_err(|error| RpcBlockGetError::TransactionsUnavailable { error }),
+            };
+            respond_or_log!(store.service.respond_block_get(rpc_id, resp), meta.time());
+        }
+        RpcAction::TransitionFrontierStatusGet { rpc_id } => {
+            let transition_frontier = &store.state.get().transition_frontier;
+            let summary = |block: &ArcBlockWithHash| RpcTransitionFrontierBlock {
+                hash: block.hash().clone(),
+                height: block.height(),
+                timestamp: u64::from(block.timestamp()) / 1_000_000,
+            };
+            let resp = None.or_else(|| {
+                let root = transition_frontier.best_chain.first()?;
+                let best_tip = transition_frontier.best_tip()?;
+                Some(RpcTransitionFrontierStatus {
+                    genesis_block_hash: best_tip
+                        .header()
+                        .protocol_state
+                        .body
+                        .genesis_state_hash
+                        .clone(),
+                    root: summary(root),
+                    best_tip: summary(best_tip),
+                    is_synced: transition_frontier.sync.is_synced(),
+                })
+            });
+            respond_or_log!(
+                store
+                    .service
+                    .respond_transition_frontier_status_get(rpc_id, resp),
+                meta.time()
+            );
+        }
+        RpcAction::BlockProducerScheduleGet { rpc_id } => {
+            let state = store.state.get();
+            let resp = state.block_producer.vrf_evaluators().map(|vrf_evaluators| {
+                let cur_global_slot = state.cur_global_slot().unwrap_or(0);
+                let mut won_slots = vrf_evaluators
+                    .values()
+                    .flat_map(|vrf_evaluator| {
+                        vrf_evaluator
+                            .won_slots
+                            .range(cur_global_slot..)
+                            .map(|(_, won_slot)| {
+                                BlockProducerWonSlot::from_vrf_won_slot(
+                                    won_slot,
+                                    vrf_evaluator.genesis_timestamp,
+                                    vrf_evaluator.slots_per_epoch,
+                                )
+                            })
+                    })
+                    .collect::<Vec<_>>();
+                // best candidate of each slot first.
+                won_slots.sort_by(|a, b| {
+                    a.global_slot()
+                        .cmp(&b.global_slot())
+                        .then_with(|| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal))
+                });
+
+                let mut prev_slot = None;
+                won_slots
+                    .into_iter()
+                    .map(|won_slot| {
+                        let global_slot = won_slot.global_slot();
+                        let selected = prev_slot != Some(global_slot);
+                        prev_slot = Some(global_slot);
+                        RpcBlockProducerScheduledSlot {
+                            producer: won_slot.producer,
+                            delegator: won_slot.delegator.0,
+                            global_slot,
+                            slot_time: won_slot.slot_time,
+                            staking_ledger_hash: won_slot.staking_ledger_hash,
+                            selected,
+                        }
+                    })
+                    .collect()
+            });
+            respond_or_log!(
+                store
+                    .service
+                    .respond_block_producer_schedule_get(rpc_id, resp),
+                meta.time()
+            );
+        }
+        RpcAction::BlockProducerHistoryGet { rpc_id } => {
+            let resp = store.state().block_producer.history().cloned();
+            respond_or_log!(
+                store
+                    .service
+                    .respond_block_producer_history_get(rpc_id, resp),
+                meta.time()
+            );
+        }
+        RpcAction::BlockProducerEpochDataGet { rpc_id } => {
+            let state = store.state.get();
+            let slots_per_epoch = state
+                .transition_frontier
+                .best_tip()
+                .map(|best_tip| best_tip.constants().slots_per_epoch.as_u32());
+            let resp = state.block_producer.vrf_evaluators().map(|vrf_evaluators| {
+                let current_epoch = vrf_evaluators
+                    .values()
+                    .find_map(|vrf_evaluator| vrf_evaluator.current_epoch);
+                let epoch = |epoch: u32, next: bool| {
+                    let epoch_data =
+                        vrf_evaluators
+                            .iter()
+                            .filter_map(|(producer, vrf_evaluator)| {
+                                let epoch_data = if next {
+                                    vrf_evaluator.next_epoch_data.as_ref()
+                                } else {
+                                    vrf_evaluator.current_epoch_data.as_ref()
+                                };
+                                Some((producer, epoch_data?))
+                            });
+                    block_producer_epoch(epoch, epoch_data, slots_per_epoch?)
+                };
+                RpcBlockProducerEpochData {
+                    current: current_epoch.and_then(|e| epoch(e, false)),
+                    next: current_epoch.and_then(|e| epoch(e + 1, true)),
+                }
+            });
+            respond_or_log!(
+                store
+                    .service
+                    .respond_block_producer_epoch_data_get(rpc_id, resp),
+                meta.time()
+            );
+        }
         RpcAction::Finish { .. } => {}
     }
 }
+
+/// Checks the work submitted by a remote snark worker against the
+/// commitment, which was made for it when the job was handed out.
+fn snarker_work_submit_check(
+    commitment: Option<&JobCommitment>,
+    snark_work: Option<&SnarkWork>,
+    snark: &Snark,
+    public_key: &NonZeroCurvePoint,
+) -> Result<(), RpcSnarkerWorkSubmitResponse> {
+    if snark_work.map_or(false, |v| snark <= &v.work) {
+        return Err(RpcSnarkerWorkSubmitResponse::AlreadySubmitted);
+    }
+    let Some(JobCommitment { commitment, .. }) = commitment else {
+        return Err(RpcSnarkerWorkSubmitResponse::CommitmentMismatch);
+    };
+    if &commitment.snarker != public_key
+        || snark.snarker != commitment.snarker
+        || snark.fee != commitment.fee
+    {
+        return Err(RpcSnarkerWorkSubmitResponse::CommitmentMismatch);
+    }
+    Ok(())
+}
+
+/// Epoch data is the same for all of our keys, except for the delegator
+/// tables.
+fn block_producer_epoch<'a>(
+    epoch: u32,
+    epoch_data: impl Iterator<Item = (&'a AccountPublicKey, &'a EpochData)>,
+    slots_per_epoch: u32,
+) -> Option<RpcBlockProducerEpoch> {
+    let mut epoch_data = epoch_data.peekable();
+    let (_, first) = epoch_data.peek()?;
+    let (seed, ledger_hash, total_currency) = (
+        first.seed.clone(),
+        first.ledger.clone(),
+        first.total_currency,
+    );
+    let producers = epoch_data
+        .map(|(producer, epoch_data)| {
+            let delegators = epoch_data
+                .delegator_table
+                .iter()
+                .map(
+                    |(index, (public_key, stake))| RpcBlockProducerEpochDelegator {
+                        index: index.clone(),
+                        public_key: public_key.clone(),
+                        stake: *stake,
+                        slot_win_probability: Some(total_currency)
+                            .filter(|total_currency| *total_currency > 0)
+                            .and_then(|total_currency| {
+                                vrf::slot_win_probability(*stake, total_currency).ok()
+                            })
+                            .unwrap_or(0.0),
+                    },
+                )
+                .collect::<Vec<_>>();
+            // vrf is evaluated for each delegator independently.
+            let slot_win_probability = 1.0
+                - delegators
+                    .iter()
+                    .map(|delegator| 1.0 - delegator.slot_win_probability)
+                    .product::<f64>();
+            RpcBlockProducerEpochProducer {
+                producer: producer.clone(),
+                total_stake: delegators.iter().map(|delegator| delegator.stake).sum(),
+                slot_win_probability,
+                expected_won_slots: slot_win_probability * slots_per_epoch as f64,
+                delegators,
+            }
+        })
+        .collect();
+    Some(RpcBlockProducerEpoch {
+        epoch,
+        seed,
+        ledger_hash,
+        total_currency,
+        producers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::scan_state::currency::{Fee, Magnitude};
+    use mina_p2p_messages::binprot::BinProtRead;
+    use mina_p2p_messages::gossip::GossipNetMessageV2;
+    use mina_p2p_messages::v2::{CurrencyFeeStableV1, NetworkPoolSnarkPoolDiffVersionedStableV2};
+    use redux::Timestamp;
+
+    use crate::p2p::PeerId;
+
+    use super::*;
+
+    const OTHER_SNARKER: &str = "B62qrztYfPinaKqpXaYGY6QJ3SSW2NNKs7SajBLF1iFNXW9BoALN2Aq";
+
+    fn fee(nanomina: u64) -> CurrencyFeeStableV1 {
+        (&Fee::from_u64(nanomina)).into()
+    }
+
+    /// Snark with the given fee, proven by the key returned with it.
+    fn snark(nanomina: u64) -> (Snark, NonZeroCurvePoint) {
+        const SNARK_POOL_DIFF: &[u8] =
+            include_bytes!("../../../mina-p2p-messages/tests/files/v2/gossip/snark_pool_diff.bin");
+        let GossipNetMessageV2::SnarkPoolDiff {
+            message: NetworkPoolSnarkPoolDiffVersionedStableV2::AddSolvedWork(work),
+            ..
+        } = GossipNetMessageV2::binprot_read(&mut &SNARK_POOL_DIFF[..]).unwrap()
+        else {
+            unreachable!()
+        };
+        let mut snark = Snark::from(work.1);
+        snark.fee = fee(nanomina);
+        let public_key = snark.snarker.clone();
+        (snark, public_key)
+    }
+
+    fn commitment(snark: &Snark, snarker: NonZeroCurvePoint, nanomina: u64) -> JobCommitment {
+        JobCommitment {
+            commitment: SnarkJobCommitment::new(0, snark.job_id(), fee(nanomina), snarker),
+            received_t: Timestamp::ZERO,
+            sender: PeerId::from_bytes([1; 32]),
+        }
+    }
+
+    fn snark_work(snark: Snark) -> SnarkWork {
+        SnarkWork {
+            work: snark,
+            received_t: Timestamp::ZERO,
+            sender: PeerId::from_bytes([2; 32]),
+        }
+    }
+
+    #[test]
+    fn submitted_work_matches_commitment() {
+        let (snark, public_key) = snark(100);
+        let commitment = commitment(&snark, public_key.clone(), 100);
+        assert!(snarker_work_submit_check(Some(&commitment), None, &snark, &public_key).is_ok());
+
+        // worse snark in the pool is replaced.
+        let (worse, _) = self::snark(200);
+        let worse = snark_work(worse);
+        assert!(
+            snarker_work_submit_check(Some(&commitment), Some(&worse), &snark, &public_key).is_ok()
+        );
+    }
+
+    #[test]
+    fn submitted_work_commitment_mismatch() {
+        let (snark, public_key) = snark(100);
+        let other: NonZeroCurvePoint = OTHER_SNARKER.parse().unwrap();
+        let check = |commitment: Option<&JobCommitment>, snark: &Snark| {
+            snarker_work_submit_check(commitment, None, snark, &public_key)
+        };
+        let is_mismatch = |res: Result<(), RpcSnarkerWorkSubmitResponse>| {
+            matches!(res, Err(RpcSnarkerWorkSubmitResponse::CommitmentMismatch))
+        };
+
+        // not committed or commitment outbid by another snarker.
+        assert!(is_mismatch(check(None, &snark)));
+        let outbid = commitment(&snark, other.clone(), 50);
+        assert!(is_mismatch(check(Some(&outbid), &snark)));
+
+        let handed_out = commitment(&snark, public_key.clone(), 100);
+        // fee differs from the one handed out.
+        let (cheaper, _) = self::snark(50);
+        assert!(is_mismatch(check(Some(&handed_out), &cheaper)));
+        // proven with another key.
+        let mut other_prover = snark.clone();
+        other_prover.snarker = other;
+        assert!(is_mismatch(check(Some(&handed_out), &other_prover)));
+    }
+
+    #[test]
+    fn submitted_work_already_submitted() {
+        let (snark, public_key) = snark(100);
+        let commitment = commitment(&snark, public_key.clone(), 100);
+        let is_already_submitted = |res: Result<(), RpcSnarkerWorkSubmitResponse>| {
+            matches!(res, Err(RpcSnarkerWorkSubmitResponse::AlreadySubmitted))
+        };
+
+        let same = snark_work(snark.clone());
+        assert!(is_already_submitted(snarker_work_submit_check(
+            Some(&commitment),
+            Some(&same),
+            &snark,
+            &public_key
+        )));
+        let (better, _) = self::snark(50);
+        let better = snark_work(better);
+        assert!(is_already_submitted(snarker_work_submit_check(
+            Some(&commitment),
+            Some(&better),
+            &snark,
+            &public_key
+        )));
+    }
+}
@@ -9,6 +9,7 @@ impl RpcState {
             RpcAction::GlobalStateGet { .. } => {}
             RpcAction::ActionStatsGet { .. } => {}
             RpcAction::SyncStatsGet { .. } => {}
+            RpcAction::SnarkMarketStatsGet { .. } => {}
             RpcAction::PeersGet { .. } => {}
             RpcAction::P2pConnectionOutgoingInit { rpc_id, opts } => {
                 let rpc_state = RpcRequestState {
@@ -74,8 +75,21 @@ impl RpcState {
             RpcAction::SnarkerJobCommit { .. } => {}
             RpcAction::SnarkerJobSpec { .. } => {}
             RpcAction::SnarkerWorkersGet { .. } => {}
+            RpcAction::SnarkersReliabilityGet { .. } => {}
+            RpcAction::SnarkerWorkGet { .. } => {}
+            RpcAction::SnarkerWorkSubmit { .. } => {}
             RpcAction::HealthCheck { .. } => {}
             RpcAction::ReadinessCheck { .. } => {}
+            RpcAction::WatchedAccountsAdd { .. } => {}
+            RpcAction::WatchedAccountsRemove { .. } => {}
+            RpcAction::WatchedAccountsGet { .. } => {}
+            RpcAction::WatchedAccountBlocksGet { .. } => {}
+            RpcAction::AccountProofGet { .. } => {}
+            RpcAction::BlockGet { .. } => {}
+            RpcAction::TransitionFrontierStatusGet { .. } => {}
+            RpcAction::BlockProducerScheduleGet { .. } => {}
+            RpcAction::BlockProducerHistoryGet { .. } => {}
+            RpcAction::BlockProducerEpochDataGet { .. } => {}
             RpcAction::Finish { rpc_id } => {
                 self.requests.remove(rpc_id);
             }
@@ -1,4 +1,5 @@
-use mina_p2p_messages::v2::LedgerHash;
+use mina_p2p_messages::v2::{LedgerHash, NonZeroCurvePoint, TokenIdKeyHash};
+use openmina_core::block::ArcBlockWithHash;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -6,11 +7,17 @@ use crate::p2p::connection::P2pConnectionResponse;
 use crate::State;
 
 use super::{
-    RpcActionStatsGetResponse, RpcHealthCheckResponse, RpcId, RpcP2pConnectionOutgoingResponse,
-    RpcPeersGetResponse, RpcReadinessCheckResponse, RpcScanStateSummaryGetResponse,
-    RpcScanStateSummaryScanStateJob, RpcSnarkPoolGetResponse, RpcSnarkPoolJobGetResponse,
-    RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcSnarkerWorkersResponse,
-    RpcSyncStatsGetResponse,
+    RpcAccountProofGetResponse, RpcActionStatsGetResponse, RpcBlockGetResponse,
+    RpcBlockProducerEpochDataGetResponse, RpcBlockProducerHistoryGetResponse,
+    RpcBlockProducerScheduleGetResponse, RpcBlockTransaction, RpcHealthCheckResponse, RpcId,
+    RpcP2pConnectionOutgoingResponse, RpcPeersGetResponse, RpcReadinessCheckResponse,
+    RpcScanStateSummaryGetResponse, RpcScanStateSummaryScanStateJob,
+    RpcSnarkMarketStatsGetResponse, RpcSnarkPoolGetResponse, RpcSnarkPoolJobGetResponse,
+    RpcSnarkerJobCommitResponse, RpcSnarkerJobSpecResponse, RpcSnarkerWorkGetResponse,
+    RpcSnarkerWorkSubmitResponse, RpcSnarkerWorkersResponse, RpcSnarkersReliabilityResponse,
+    RpcSyncStatsGetResponse, RpcTransitionFrontierStatusGetResponse,
+    RpcWatchedAccountBlocksGetResponse, RpcWatchedAccountsAddResponse,
+    RpcWatchedAccountsGetResponse, RpcWatchedAccountsRemoveResponse,
 };
 
 #[derive(Error, Serialize, Deserialize, Debug, Clone)]
@@ -28,6 +35,23 @@ pub trait RpcLedgerService: redux::Service {
         &self,
         staged_ledger_hash: LedgerHash,
     ) -> Vec<Vec<RpcScanStateSummaryScanStateJob>>;
+
+    /// Account with its index and merkle path in the ledger with `ledger_hash`.
+    ///
+    /// `block` of the response is left empty, it's up to the caller to fill it.
+    fn account_proof(
+        &self,
+        ledger_hash: LedgerHash,
+        public_key: NonZeroCurvePoint,
+        token: Option<TokenIdKeyHash>,
+    ) -> RpcAccountProofGetResponse;
+
+    /// Transactions (including fee transfers and coinbases) applied by the
+    /// `block`, in the order of application.
+    fn block_transactions(
+        &self,
+        block: &ArcBlockWithHash,
+    ) -> Result<Vec<RpcBlockTransaction>, String>;
 }
 
 pub trait RpcService: RpcLedgerService {
@@ -42,6 +66,11 @@ pub trait RpcService: RpcLedgerService {
         rpc_id: RpcId,
         response: RpcSyncStatsGetResponse,
     ) -> Result<(), RespondError>;
+    fn respond_snark_market_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkMarketStatsGetResponse,
+    ) -> Result<(), RespondError>;
     fn respond_peers_get(
         &mut self,
         rpc_id: RpcId,
@@ -97,6 +126,21 @@ pub trait RpcService: RpcLedgerService {
         rpc_id: RpcId,
         response: RpcSnarkerWorkersResponse,
     ) -> Result<(), RespondError>;
+    fn respond_snarkers_reliability(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkersReliabilityResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_snarker_work_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkerWorkGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_snarker_work_submit(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcSnarkerWorkSubmitResponse,
+    ) -> Result<(), RespondError>;
     fn respond_health_check(
         &mut self,
         rpc_id: RpcId,
@@ -107,4 +151,54 @@ pub trait RpcService: RpcLedgerService {
         rpc_id: RpcId,
         response: RpcReadinessCheckResponse,
     ) -> Result<(), RespondError>;
+    fn respond_watched_accounts_add(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcWatchedAccountsAddResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_watched_accounts_remove(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcWatchedAccountsRemoveResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_watched_accounts_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcWatchedAccountsGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_watched_account_blocks_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcWatchedAccountBlocksGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_account_proof_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcAccountProofGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_block_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_transition_frontier_status_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcTransitionFrontierStatusGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_block_producer_schedule_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockProducerScheduleGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_block_producer_history_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockProducerHistoryGetResponse,
+    ) -> Result<(), RespondError>;
+    fn respond_block_producer_epoch_data_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: RpcBlockProducerEpochDataGetResponse,
+    ) -> Result<(), RespondError>;
 }
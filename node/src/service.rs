@@ -1,6 +1,6 @@
 use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorLedgerService;
 pub use crate::block_producer::vrf_evaluator::BlockProducerVrfEvaluatorService;
-pub use crate::block_producer::BlockProducerService;
+pub use crate::block_producer::{BlockProducerHistoryPersistService, BlockProducerService};
 pub use crate::event_source::EventSourceService;
 use crate::external_snark_worker::ExternalSnarkWorkerService;
 pub use crate::p2p::channels::P2pChannelsService;
@@ -10,10 +10,11 @@ pub use crate::recorder::Recorder;
 pub use crate::rpc::RpcService;
 pub use crate::snark::block_verify::SnarkBlockVerifyService;
 pub use crate::snark::work_verify::SnarkWorkVerifyService;
-pub use crate::snark_pool::SnarkPoolService;
+pub use crate::snark_pool::{SnarkPoolPersistService, SnarkPoolService};
+pub use crate::transition_frontier::genesis::TransitionFrontierGenesisService;
 pub use crate::transition_frontier::sync::ledger::snarked::TransitionFrontierSyncLedgerSnarkedService;
 pub use crate::transition_frontier::sync::ledger::staged::TransitionFrontierSyncLedgerStagedService;
-pub use crate::transition_frontier::TransitionFrontierService;
+pub use crate::transition_frontier::{TransitionFrontierArchiveService, TransitionFrontierService};
 pub use redux::TimeService;
 
 use crate::stats::Stats;
@@ -26,12 +27,16 @@ pub trait Service:
     + P2pConnectionService
     + P2pDisconnectionService
     + P2pChannelsService
+    + TransitionFrontierGenesisService
     + TransitionFrontierSyncLedgerSnarkedService
     + TransitionFrontierSyncLedgerStagedService
     + TransitionFrontierService
+    + TransitionFrontierArchiveService
     + SnarkPoolService
+    + SnarkPoolPersistService
     + BlockProducerVrfEvaluatorService
     + BlockProducerService
+    + BlockProducerHistoryPersistService
     + BlockProducerVrfEvaluatorLedgerService
     + ExternalSnarkWorkerService
     + RpcService
@@ -32,7 +32,17 @@ pub fn snark_effects<S: Service>(store: &mut Store<S>, action: SnarkActionWithMe
                 SnarkWorkVerifyAction::Error { req_id, .. } => {
                     let req = store.state().snark.work_verify.jobs.get(req_id);
                     let Some(req) = req else { return };
-                    let sender = req.sender().parse().unwrap();
+                    // work submitted by a remote snark worker isn't
+                    // from a peer, so there is no one to disconnect.
+                    let Ok(sender) = req.sender().parse() else {
+                        openmina_core::log::warn!(
+                            meta.time();
+                            kind = "SnarkWorkVerifyError",
+                            summary = "invalid work from remote snark worker",
+                            sender = req.sender(),
+                        );
+                        return;
+                    };
 
                     store.dispatch(SnarkPoolCandidateAction::WorkVerifyError {
                         peer_id: sender,
@@ -42,13 +52,20 @@ pub fn snark_effects<S: Service>(store: &mut Store<S>, action: SnarkActionWithMe
                 SnarkWorkVerifyAction::Success { req_id } => {
                     let req = store.state().snark.work_verify.jobs.get(req_id);
                     let Some(req) = req else { return };
-                    let sender = req.sender().parse().unwrap();
+                    let sender = req.sender().parse().ok();
                     let batch = req.batch().to_vec();
 
-                    store.dispatch(SnarkPoolCandidateAction::WorkVerifySuccess {
-                        peer_id: sender,
-                        verify_id: req_id,
-                    });
+                    let sender = match sender {
+                        Some(sender) => {
+                            store.dispatch(SnarkPoolCandidateAction::WorkVerifySuccess {
+                                peer_id: sender,
+                                verify_id: req_id,
+                            });
+                            sender
+                        }
+                        // work submitted by a remote snark worker.
+                        None => store.state().p2p.my_id(),
+                    };
                     for snark in batch {
                         store.dispatch(SnarkPoolAction::WorkAdd { snark, sender });
                     }
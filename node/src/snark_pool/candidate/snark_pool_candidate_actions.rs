@@ -9,6 +9,10 @@ use crate::snark::work_verify::SnarkWorkVerifyId;
 
 use super::SnarkPoolCandidateState;
 
+/// Maximum number of snark work verification requests in flight, so
+/// that snarks received from different peers can be verified together.
+pub const SNARK_WORK_VERIFY_MAX_PENDING: usize = 8;
+
 pub type SnarkPoolCandidateActionWithMeta = redux::ActionWithMeta<SnarkPoolCandidateAction>;
 pub type SnarkPoolCandidateActionWithMetaRef<'a> =
     redux::ActionWithMeta<&'a SnarkPoolCandidateAction>;
@@ -102,7 +106,9 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolCandidateAction {
                             }
                         })
             }
-            SnarkPoolCandidateAction::WorkVerifyNext => state.snark.work_verify.jobs.is_empty(),
+            SnarkPoolCandidateAction::WorkVerifyNext => {
+                state.snark.work_verify.jobs.len() < SNARK_WORK_VERIFY_MAX_PENDING
+            }
             SnarkPoolCandidateAction::WorkVerifyPending {
                 peer_id, job_ids, ..
             } => {
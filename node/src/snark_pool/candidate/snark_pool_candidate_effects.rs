@@ -8,13 +8,17 @@ use crate::Store;
 
 use super::{SnarkPoolCandidateAction, SnarkPoolCandidateActionWithMeta};
 
-pub fn snark_pool_candidate_effects<S: redux::Service>(
+pub fn snark_pool_candidate_effects<S: crate::Service>(
     store: &mut Store<S>,
     action: SnarkPoolCandidateActionWithMeta,
 ) {
     let (action, _) = action.split();
     match action {
-        SnarkPoolCandidateAction::InfoReceived { .. } => {}
+        SnarkPoolCandidateAction::InfoReceived { info, .. } => {
+            if let Some(stats) = store.service.stats() {
+                stats.snark_pool_snark_offered(&info.job_id, &info.prover);
+            }
+        }
         SnarkPoolCandidateAction::WorkFetchAll => {
             let state = store.state();
             let peers = state.p2p.ready_peers_iter().map(|(id, _)| *id);
@@ -71,16 +75,21 @@ pub fn snark_pool_candidate_effects<S: redux::Service>(
             let req_id = state.snark.work_verify.next_req_id();
             let job_ids = batch.iter().map(|v| v.job_id()).collect::<Vec<_>>();
             let sender = peer_id.to_string();
-            store.dispatch(SnarkWorkVerifyAction::Init {
+            if !store.dispatch(SnarkWorkVerifyAction::Init {
                 req_id,
                 batch,
                 sender,
-            });
+            }) {
+                return;
+            }
             store.dispatch(SnarkPoolCandidateAction::WorkVerifyPending {
                 peer_id,
                 job_ids,
                 verify_id: req_id,
             });
+            // Start verification of snarks from other peers as well, so
+            // that they are verified in the same batch.
+            store.dispatch(SnarkPoolCandidateAction::WorkVerifyNext);
         }
         SnarkPoolCandidateAction::WorkVerifyPending { .. } => {}
         SnarkPoolCandidateAction::WorkVerifyError { peer_id, .. } => {
@@ -184,7 +184,10 @@ impl SnarkPoolCandidatesState {
             if let Some(res) = None.or_else(|| {
                 for peer_id in self.by_job_id.get(job_id)? {
                     let peer_jobs = self.by_peer.get(peer_id)?;
-                    if peer_jobs.get(job_id)?.work().is_some() {
+                    if matches!(
+                        peer_jobs.get(job_id)?,
+                        SnarkPoolCandidateState::WorkReceived { .. }
+                    ) {
                         let jobs = peer_jobs
                             .iter()
                             .filter_map(|(_, v)| match v {
@@ -1,9 +1,12 @@
 use ledger::scan_state::scan_state::transaction_snark::OneOrTwo;
 use ledger::scan_state::scan_state::AvailableJobMessage;
+use mina_p2p_messages::v2::CurrencyFeeStableV1;
 use openmina_core::snark::{Snark, SnarkJobCommitment, SnarkJobId};
+use redux::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use crate::p2p::PeerId;
+use crate::SnarkerStrategy;
 
 use super::candidate::SnarkPoolCandidateAction;
 use super::SnarkWork;
@@ -19,6 +22,19 @@ pub enum SnarkPoolAction {
         jobs: Vec<OneOrTwo<AvailableJobMessage>>,
         orphaned_snarks: Vec<SnarkWork>,
     },
+    /// Restore snarks and our own commitments persisted by the previous
+    /// run of the node, for jobs which are still in the pool, with the
+    /// time they were originally added to it.
+    Restore {
+        snarks: Vec<(Snark, Timestamp)>,
+        /// Only commitments, which we resume working on.
+        commitments: Vec<(SnarkJobCommitment, Timestamp)>,
+        sender: PeerId,
+    },
+    /// Adjust our fee according to the snarker's fee policy.
+    SnarkerFeeUpdate {
+        fee: CurrencyFeeStableV1,
+    },
     AutoCreateCommitment,
     CommitmentCreate {
         job_id: SnarkJobId,
@@ -50,16 +66,37 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolAction {
                 .snarker
                 .as_ref()
                 .map_or(false, |v| v.auto_commit),
+            SnarkPoolAction::SnarkerFeeUpdate { fee } => state
+                .config
+                .snarker
+                .as_ref()
+                .filter(|config| config.fee_policy.is_some())
+                .map_or(false, |_| state.snarker_fee() != Some(fee)),
             SnarkPoolAction::CommitmentCreate { job_id } => {
-                state.config.snarker.is_some() && state.snark_pool.should_create_commitment(job_id)
+                let Some(config) = state.config.snarker.as_ref() else {
+                    return false;
+                };
+                match config.strategy {
+                    // only profit strategy competes for jobs, which are
+                    // already committed to by others.
+                    SnarkerStrategy::Profit => state
+                        .snarker_fee()
+                        .map_or(false, |fee| state.snark_pool.can_outbid(job_id, fee)),
+                    SnarkerStrategy::Sequential | SnarkerStrategy::Random => {
+                        state.snark_pool.should_create_commitment(job_id)
+                    }
+                }
+            }
+            SnarkPoolAction::CommitmentAdd { commitment, sender } => {
+                let is_ours = sender == &state.p2p.my_id();
+                // refuse commitments of snarkers, which keep committing to
+                // jobs without delivering snarks for them.
+                (is_ours
+                    || !state
+                        .snark_pool
+                        .is_snarker_unreliable(sender, &commitment.snarker))
+                    && state.snark_pool.should_take_commitment(commitment, sender)
             }
-            SnarkPoolAction::CommitmentAdd { commitment, .. } => state
-                .snark_pool
-                .get(&commitment.job_id)
-                .map_or(false, |s| match s.commitment.as_ref() {
-                    Some(cur) => commitment > &cur.commitment,
-                    None => true,
-                }),
             SnarkPoolAction::WorkAdd { snark, .. } => {
                 state
                     .snark_pool
@@ -106,6 +143,7 @@ impl redux::EnablingCondition<crate::State> for SnarkPoolAction {
                 .snark_pool
                 .is_commitment_timed_out(job_id, state.time()),
             SnarkPoolAction::JobsUpdate { .. } => true,
+            SnarkPoolAction::Restore { .. } => !state.snark_pool.is_restored(),
             SnarkPoolAction::P2pSendAll => true,
         }
     }
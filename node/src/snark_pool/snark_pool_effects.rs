@@ -1,3 +1,6 @@
+use std::collections::BTreeSet;
+
+use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
 use openmina_core::snark::SnarkJobCommitment;
 use p2p::channels::snark::P2pChannelsSnarkAction;
 
@@ -15,29 +18,99 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
             snark_pool_candidate_effects(store, meta.with_action(action))
         }
         SnarkPoolAction::JobsUpdate { .. } => {
+            if !store.state().snark_pool.is_restored() {
+                let (snarks, commitments) = store.service.snark_pool_persisted_take();
+                let state = store.state();
+                let commitments = match state.config.snarker.as_ref() {
+                    Some(config) => state.snark_pool.resumable_commitments(
+                        commitments,
+                        &snarks,
+                        config.public_key.as_ref(),
+                        state.external_snark_worker.available(),
+                    ),
+                    None => vec![],
+                };
+                store.dispatch(SnarkPoolAction::Restore {
+                    snarks,
+                    commitments,
+                    sender: store.state().p2p.my_id(),
+                });
+            }
+            let job_ids = store
+                .state()
+                .snark_pool
+                .range(..)
+                .map(|(_, job)| job.id.clone())
+                .collect::<BTreeSet<_>>();
+            if let Some(stats) = store.service.stats() {
+                stats.snark_pool_jobs_update(meta.time(), &job_ids);
+            }
+            store.service.snark_pool_persist_retain(job_ids);
+
+            if let Some(fee) = snarker_competitive_fee(store.state()) {
+                store.dispatch(SnarkPoolAction::SnarkerFeeUpdate { fee });
+            }
+
             let state = store.state();
-            if let Some(job_id) = state.external_snark_worker.working_job_id() {
-                if !state.snark_pool.contains(job_id) {
-                    // job is no longer needed.
-                    store.dispatch(ExternalSnarkWorkerAction::CancelWork);
+            // jobs which are no longer needed.
+            let cancel_worker_ids = state
+                .external_snark_worker
+                .working_job_ids()
+                .filter(|(_, job_id)| !state.snark_pool.contains(job_id))
+                .map(|(worker_id, _)| worker_id)
+                .collect::<Vec<_>>();
+            for worker_id in cancel_worker_ids {
+                store.dispatch(ExternalSnarkWorkerAction::CancelWork { worker_id });
+            }
+            store.dispatch(SnarkPoolAction::AutoCreateCommitment);
+        }
+        SnarkPoolAction::Restore { commitments, .. } => {
+            // Resume work on jobs, which we have committed to before the
+            // restart, but haven't produced snarks for.
+            for (commitment, _) in commitments {
+                let state = store.state();
+                let Some(job) = state.snark_pool.get(&commitment.job_id) else {
+                    continue;
+                };
+                // better commitment was received before ours was restored.
+                if job
+                    .commitment
+                    .as_ref()
+                    .map_or(true, |cur| cur.commitment != commitment)
+                {
+                    continue;
                 }
-            } else {
-                store.dispatch(SnarkPoolAction::AutoCreateCommitment);
+                let summary = job.summary();
+                // restored commitments are limited to available workers.
+                let Some(worker_id) = state.external_snark_worker.first_idle() else {
+                    break;
+                };
+                let SnarkJobCommitment { job_id, fee, .. } = commitment;
+                store.dispatch(ExternalSnarkWorkerAction::SubmitWork {
+                    worker_id,
+                    job_id,
+                    summary,
+                    fee,
+                });
             }
         }
+        SnarkPoolAction::SnarkerFeeUpdate { .. } => {}
         SnarkPoolAction::AutoCreateCommitment { .. } => {
             let state = store.state.get();
             let Some(snarker_config) = &state.config.snarker else {
                 return;
             };
+            let Some(fee) = state.snarker_fee() else {
+                return;
+            };
             let available_workers = state.external_snark_worker.available();
 
             if available_workers > 0 {
-                let jobs = state
-                    .snark_pool
-                    .available_jobs_with_highest_priority(available_workers);
                 let job_ids: Vec<_> = match snarker_config.strategy {
                     SnarkerStrategy::Sequential => {
+                        let jobs = state
+                            .snark_pool
+                            .available_jobs_with_highest_priority(available_workers);
                         jobs.into_iter()
                             .map(|job| job.id.clone())
                             .take(available_workers) // just in case
@@ -47,6 +120,20 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
                         let jobs = state.snark_pool.available_jobs_iter();
                         store.service.random_choose(jobs, available_workers)
                     }
+                    SnarkerStrategy::Profit => {
+                        let proving_times = state.external_snark_worker.proving_times();
+                        state
+                            .snark_pool
+                            .profitable_jobs(
+                                fee,
+                                snarker_config.min_payout_per_sec,
+                                |summary| proving_times.estimate(summary),
+                                available_workers,
+                            )
+                            .into_iter()
+                            .map(|job| job.id.clone())
+                            .collect()
+                    }
                 };
 
                 for job_id in job_ids {
@@ -58,9 +145,17 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
             let Some(summary) = store.state().snark_pool.job_summary(&job_id) else {
                 return;
             };
+            let Some(worker_id) = store.state().external_snark_worker.first_idle() else {
+                return;
+            };
+            let Some(fee) = store.state().snarker_fee().cloned() else {
+                return;
+            };
             if store.dispatch(ExternalSnarkWorkerAction::SubmitWork {
+                worker_id,
                 job_id: job_id.clone(),
                 summary,
+                fee: fee.clone(),
             }) {
                 let timestamp_ms = meta.time_as_nanos() / 1_000_000;
                 let Some(config) = store.state.get().config.snarker.as_ref() else {
@@ -70,7 +165,7 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
                     commitment: SnarkJobCommitment::new(
                         timestamp_ms,
                         job_id,
-                        config.fee.clone(),
+                        fee,
                         config.public_key.clone().into(),
                     ),
                     sender: store.state().p2p.my_id(),
@@ -78,33 +173,58 @@ pub fn snark_pool_effects<S: Service>(store: &mut Store<S>, action: SnarkPoolAct
             }
         }
         SnarkPoolAction::CommitmentAdd { commitment, .. } => {
+            if let Some(stats) = store.service.stats() {
+                stats.snark_pool_commitment_add(meta.time(), &commitment);
+            }
             let state = store.state();
-            if let Some(job_id) = state.external_snark_worker.working_job_id() {
-                let Some(config) = store.state.get().config.snarker.as_ref() else {
-                    return;
-                };
-                if &commitment.job_id == job_id && &commitment.snarker != config.public_key.as_ref()
-                {
-                    store.dispatch(ExternalSnarkWorkerAction::CancelWork);
-                }
+            let Some(config) = state.config.snarker.as_ref() else {
+                return;
+            };
+            if &commitment.snarker == config.public_key.as_ref() {
+                store
+                    .service
+                    .snark_pool_persist_commitment(&commitment, meta.time());
+                return;
+            }
+            let cancel_worker_ids = state
+                .external_snark_worker
+                .working_job_ids()
+                .filter(|(_, job_id)| *job_id == &commitment.job_id)
+                .map(|(worker_id, _)| worker_id)
+                .collect::<Vec<_>>();
+            for worker_id in cancel_worker_ids {
+                store.dispatch(ExternalSnarkWorkerAction::CancelWork { worker_id });
             }
         }
         SnarkPoolAction::WorkAdd { snark, .. } => {
+            if let Some(stats) = store.service.stats() {
+                stats.snark_pool_snark_add(
+                    meta.time(),
+                    &snark.job_id(),
+                    &snark.snarker,
+                    &snark.fee,
+                );
+            }
+            store.service.snark_pool_persist_snark(&snark, meta.time());
+
             let state = store.state();
-            if let Some(job_id) = state
-                .external_snark_worker
-                .working_job_id()
-                .filter(|job_id| *job_id == &snark.job_id())
-            {
-                if let Some(commitment) = state
-                    .snark_pool
-                    .get(job_id)
-                    .and_then(|job| job.commitment.as_ref())
-                {
-                    if snark > commitment.commitment {
-                        store.dispatch(ExternalSnarkWorkerAction::CancelWork);
-                    }
-                }
+            let job_id = snark.job_id();
+            let cancel_worker_ids = state
+                .snark_pool
+                .get(&job_id)
+                .and_then(|job| job.commitment.as_ref())
+                .filter(|commitment| snark > commitment.commitment)
+                .map(|_| {
+                    state
+                        .external_snark_worker
+                        .working_job_ids()
+                        .filter(|(_, id)| *id == &job_id)
+                        .map(|(worker_id, _)| worker_id)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            for worker_id in cancel_worker_ids {
+                store.dispatch(ExternalSnarkWorkerAction::CancelWork { worker_id });
             }
 
             store.dispatch(P2pChannelsSnarkAction::Libp2pBroadcast { snark, nonce: 0 });
@@ -203,3 +323,30 @@ where
         // Loop preemptively ended.
         .unwrap_or_else(|v| v)
 }
+
+/// Number of latest blocks to take winning fees from.
+const SNARKER_FEE_POLICY_BLOCKS: usize = 10;
+
+/// Our fee according to the snarker's fee policy, based on the fees of
+/// snarks by other snarkers in the pool and in the latest blocks.
+fn snarker_competitive_fee(state: &State) -> Option<CurrencyFeeStableV1> {
+    let config = state.config.snarker.as_ref()?;
+    let policy = config.fee_policy.as_ref()?;
+    let our_key: &NonZeroCurvePoint = config.public_key.as_ref();
+
+    let pool_fees = state
+        .snark_pool
+        .completed_snarks_iter()
+        .filter(|snark| &snark.snarker != our_key)
+        .map(|snark| snark.fee.0.as_u64());
+    let block_fees = state
+        .transition_frontier
+        .best_chain
+        .iter()
+        .rev()
+        .take(SNARKER_FEE_POLICY_BLOCKS)
+        .flat_map(|block| block.completed_works_iter())
+        .filter(|work| &work.prover != our_key)
+        .map(|work| work.fee.0.as_u64());
+    policy.fee(pool_fees.chain(block_fees))
+}
@@ -2,12 +2,15 @@ use std::collections::BTreeMap;
 
 use openmina_core::snark::SnarkJobId;
 
+use crate::p2p::PeerId;
 use crate::snark_pool::JobCommitment;
 
 use super::{JobState, SnarkPoolAction, SnarkPoolActionWithMetaRef, SnarkPoolState, SnarkWork};
 
 impl SnarkPoolState {
-    pub fn reducer(&mut self, action: SnarkPoolActionWithMetaRef<'_>) {
+    /// `my_id` is our peer id, used to tell apart our own commitments,
+    /// which aren't taken into account for the reliability of snarkers.
+    pub fn reducer(&mut self, action: SnarkPoolActionWithMetaRef<'_>, my_id: PeerId) {
         let (action, meta) = action.split();
         match action {
             SnarkPoolAction::Candidate(action) => {
@@ -54,6 +57,50 @@ impl SnarkPoolState {
 
                 self.candidates_prune();
             }
+            SnarkPoolAction::Restore {
+                snarks,
+                commitments,
+                sender,
+            } => {
+                for (snark, received_t) in snarks {
+                    let take = self.get(&snark.job_id()).map_or(false, |job| {
+                        job.snark.as_ref().map_or(true, |cur| snark > &cur.work)
+                    });
+                    if !take {
+                        continue;
+                    }
+                    if let Some(mut job) = self.remove(&snark.job_id()) {
+                        job.snark = Some(SnarkWork {
+                            work: snark.clone(),
+                            received_t: *received_t,
+                            sender: *sender,
+                        });
+                        self.insert(job);
+                    }
+                }
+                for (commitment, received_t) in commitments {
+                    let take = self.get(&commitment.job_id).map_or(false, |job| {
+                        job.commitment
+                            .as_ref()
+                            .map_or(true, |cur| commitment > &cur.commitment)
+                    });
+                    if !take {
+                        continue;
+                    }
+                    if let Some(mut job) = self.remove(&commitment.job_id) {
+                        job.commitment = Some(JobCommitment {
+                            commitment: commitment.clone(),
+                            received_t: *received_t,
+                            sender: *sender,
+                        });
+                        self.insert(job);
+                    }
+                }
+                self.restored = true;
+            }
+            SnarkPoolAction::SnarkerFeeUpdate { fee } => {
+                self.snarker_fee = Some(fee.clone());
+            }
             SnarkPoolAction::AutoCreateCommitment => {}
             SnarkPoolAction::CommitmentCreate { .. } => {}
             SnarkPoolAction::CommitmentAdd { commitment, sender } => {
@@ -77,8 +124,17 @@ impl SnarkPoolState {
                     received_t: meta.time(),
                     sender: *sender,
                 });
+                let commitment = job
+                    .commitment
+                    .as_ref()
+                    .filter(|c| c.sender != my_id && c.commitment.snarker == snark.snarker)
+                    .map(|c| (c.sender, c.commitment.snarker.clone()));
                 self.insert(job);
                 self.candidates.remove_inferior_snarks(snark);
+                if let Some((sender, snarker)) = commitment {
+                    self.snarker_reliability_mut(meta.time(), sender, snarker)
+                        .delivered += 1;
+                }
             }
             SnarkPoolAction::P2pSendAll { .. } => {}
             SnarkPoolAction::P2pSend { .. } => {}
@@ -86,7 +142,14 @@ impl SnarkPoolState {
                 self.last_check_timeouts = meta.time();
             }
             SnarkPoolAction::JobCommitmentTimeout { job_id } => {
-                self.remove_commitment(&job_id);
+                let Some(commitment) = self.remove_commitment(job_id) else {
+                    return;
+                };
+                if commitment.sender != my_id {
+                    let snarker = commitment.commitment.snarker;
+                    self.snarker_reliability_mut(meta.time(), commitment.sender, snarker)
+                        .timed_out += 1;
+                }
             }
         }
     }
@@ -1,4 +1,8 @@
-use crate::core::snark::SnarkJobId;
+use std::collections::BTreeSet;
+
+use redux::Timestamp;
+
+use crate::core::snark::{Snark, SnarkJobCommitment, SnarkJobId};
 
 use super::JobState;
 
@@ -9,3 +13,32 @@ pub trait SnarkPoolService: redux::Service {
         n: usize,
     ) -> Vec<SnarkJobId>;
 }
+
+/// Persisted snarks and commitments, with the time they were added to
+/// the pool.
+pub type SnarkPoolPersisted = (
+    Vec<(Snark, Timestamp)>,
+    Vec<(SnarkJobCommitment, Timestamp)>,
+);
+
+pub trait SnarkPoolPersistService: redux::Service {
+    /// Takes snarks and our own commitments persisted by the previous run
+    /// of the node, with the time they were added to the pool.
+    /// Subsequent calls return nothing.
+    fn snark_pool_persisted_take(&mut self) -> SnarkPoolPersisted;
+
+    /// Persist verified snark, which was added to the pool at `received_t`.
+    fn snark_pool_persist_snark(&mut self, snark: &Snark, received_t: Timestamp);
+
+    /// Persist our own commitment, which was added to the pool at
+    /// `received_t`.
+    fn snark_pool_persist_commitment(
+        &mut self,
+        commitment: &SnarkJobCommitment,
+        received_t: Timestamp,
+    );
+
+    /// Drop persisted snarks and commitments for jobs, which are no
+    /// longer in the pool.
+    fn snark_pool_persist_retain(&mut self, job_ids: BTreeSet<SnarkJobId>);
+}
@@ -2,6 +2,7 @@ use std::time::Duration;
 use std::{collections::BTreeMap, fmt, ops::RangeBounds};
 
 use ledger::scan_state::scan_state::{transaction_snark::OneOrTwo, AvailableJobMessage};
+use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
 use openmina_core::snark::{Snark, SnarkInfo, SnarkJobCommitment, SnarkJobId};
 use redux::Timestamp;
 use serde::{Deserialize, Serialize};
@@ -11,6 +12,14 @@ use crate::p2p::PeerId;
 use super::candidate::SnarkPoolCandidatesState;
 use super::SnarkPoolConfig;
 
+/// Snarker must have at least this many timed out commitments, before
+/// it can be considered unreliable.
+const UNRELIABLE_SNARKER_MIN_TIMEOUTS: u32 = 3;
+const UNRELIABLE_SNARKER_MAX_SCORE: f64 = 0.5;
+/// Max number of tracked snarkers, after which the least recently
+/// updated one is forgotten.
+const MAX_TRACKED_SNARKERS: usize = 2048;
+
 #[derive(Clone)]
 pub struct SnarkPoolState {
     config: SnarkPoolConfig,
@@ -19,6 +28,15 @@ pub struct SnarkPoolState {
     by_ledger_hash_index: BTreeMap<SnarkJobId, u64>,
     pub candidates: SnarkPoolCandidatesState,
     pub(super) last_check_timeouts: Timestamp,
+    /// Whether snarks and commitments persisted by the previous run of
+    /// the node were restored.
+    pub(super) restored: bool,
+    /// Delivered vs timed out commitments per snarker key and the peer,
+    /// from which the commitments came. Keyed on both, so that a peer
+    /// can't make another snarker unreliable by committing in its name.
+    pub(super) reliability: BTreeMap<(PeerId, NonZeroCurvePoint), SnarkerReliability>,
+    /// Our fee, as adjusted by [`crate::config::SnarkerFeePolicy`].
+    pub(super) snarker_fee: Option<CurrencyFeeStableV1>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -46,6 +64,16 @@ pub struct SnarkWork {
     pub sender: PeerId,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkerReliability {
+    /// Number of committed snarks added to the pool.
+    pub delivered: u32,
+    /// Number of commitments, which timed out without the snark being
+    /// delivered.
+    pub timed_out: u32,
+    pub last_update: Timestamp,
+}
+
 /// Whether the job is a merge proof job, or a transaction proof job, with particular number of account updates.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum JobSummary {
@@ -62,9 +90,45 @@ impl SnarkPoolState {
             by_ledger_hash_index: Default::default(),
             candidates: SnarkPoolCandidatesState::new(),
             last_check_timeouts: Timestamp::ZERO,
+            restored: false,
+            reliability: Default::default(),
+            snarker_fee: None,
         }
     }
 
+    pub fn is_restored(&self) -> bool {
+        self.restored
+    }
+
+    /// Our persisted commitments, which can be resumed by one of the
+    /// `available_workers`. Others are dropped, so that they don't keep
+    /// other snarkers from the jobs, while nobody works on them.
+    pub fn resumable_commitments<T>(
+        &self,
+        commitments: Vec<(SnarkJobCommitment, T)>,
+        snarks: &[(Snark, T)],
+        public_key: &NonZeroCurvePoint,
+        available_workers: usize,
+    ) -> Vec<(SnarkJobCommitment, T)> {
+        commitments
+            .into_iter()
+            .filter(|(commitment, _)| &commitment.snarker == public_key)
+            .filter(|(commitment, _)| {
+                self.get(&commitment.job_id)
+                    .map_or(false, |job| job.snark.is_none())
+                    && !snarks
+                        .iter()
+                        .any(|(snark, _)| snark.job_id() == commitment.job_id)
+            })
+            .take(available_workers)
+            .collect()
+    }
+
+    /// Our fee, if it was adjusted by the fee policy.
+    pub fn snarker_fee(&self) -> Option<&CurrencyFeeStableV1> {
+        self.snarker_fee.as_ref()
+    }
+
     pub fn last_index(&self) -> u64 {
         self.list.last_key_value().map_or(0, |(k, _)| *k)
     }
@@ -141,6 +205,98 @@ impl SnarkPoolState {
         self.get(job_id).map_or(false, |s| s.is_available())
     }
 
+    /// Whether our commitment or snark with the `fee` would beat the
+    /// existing ones for the job. Commitments of unreliable snarkers
+    /// can be outbid regardless of the fee.
+    pub fn can_outbid(&self, job_id: &SnarkJobId, fee: &CurrencyFeeStableV1) -> bool {
+        self.get(job_id).map_or(false, |job| {
+            let is_commitment_unreliable = job.commitment.as_ref().map_or(false, |c| {
+                self.is_snarker_unreliable(&c.sender, &c.commitment.snarker)
+            });
+            if !is_commitment_unreliable {
+                return job.can_outbid(fee);
+            }
+            let fee = fee.0.as_u64();
+            job.snark
+                .as_ref()
+                .map_or(true, |s| s.work.fee.0.as_u64() > fee)
+        })
+    }
+
+    /// Whether the `commitment` received from the `sender` should
+    /// replace the existing one for the job. Commitments of reliable
+    /// snarkers are preferred over those of unreliable ones, otherwise
+    /// the better commitment wins.
+    pub fn should_take_commitment(&self, commitment: &SnarkJobCommitment, sender: &PeerId) -> bool {
+        let Some(job) = self.get(&commitment.job_id) else {
+            return false;
+        };
+        let Some(cur) = job.commitment.as_ref() else {
+            return true;
+        };
+        match (
+            self.is_snarker_unreliable(sender, &commitment.snarker),
+            self.is_snarker_unreliable(&cur.sender, &cur.commitment.snarker),
+        ) {
+            (false, true) => true,
+            (true, false) => false,
+            _ => commitment > &cur.commitment,
+        }
+    }
+
+    pub fn snarker_reliability(
+        &self,
+        sender: &PeerId,
+        snarker: &NonZeroCurvePoint,
+    ) -> Option<&SnarkerReliability> {
+        self.reliability.get(&(*sender, snarker.clone()))
+    }
+
+    pub fn snarkers_reliability_iter(
+        &self,
+    ) -> impl Iterator<Item = (&PeerId, &NonZeroCurvePoint, &SnarkerReliability)> {
+        self.reliability
+            .iter()
+            .map(|((sender, snarker), v)| (sender, snarker, v))
+    }
+
+    pub fn is_snarker_unreliable(&self, sender: &PeerId, snarker: &NonZeroCurvePoint) -> bool {
+        self.snarker_reliability(sender, snarker)
+            .map_or(false, |v| v.is_unreliable())
+    }
+
+    /// Reliability of the `snarker`, whose commitments come from the
+    /// `sender`, for an update at `time`. If too many snarkers are
+    /// tracked, the least recently updated one is forgotten.
+    pub(super) fn snarker_reliability_mut(
+        &mut self,
+        time: Timestamp,
+        sender: PeerId,
+        snarker: NonZeroCurvePoint,
+    ) -> &mut SnarkerReliability {
+        let key = (sender, snarker);
+        if !self.reliability.contains_key(&key) && self.reliability.len() >= MAX_TRACKED_SNARKERS {
+            let oldest = self
+                .reliability
+                .iter()
+                .min_by_key(|(_, v)| v.last_update)
+                .map(|(k, _)| k.clone());
+            if let Some(oldest) = oldest {
+                self.reliability.remove(&oldest);
+            }
+        }
+        let reliability = self
+            .reliability
+            .entry(key)
+            .or_insert_with(|| SnarkerReliability {
+                delivered: 0,
+                timed_out: 0,
+                last_update: time,
+            });
+        reliability.last_update = time;
+        reliability
+    }
+
     pub fn is_commitment_timed_out(&self, id: &SnarkJobId, time_now: Timestamp) -> bool {
         self.by_ledger_hash_index.get(id).map_or(false, |i| {
             self.is_commitment_timed_out_by_index(i, time_now)
@@ -196,6 +352,35 @@ impl SnarkPoolState {
             })
     }
 
+    /// Find up to `n` jobs, which we can win with the `fee` and for which
+    /// the fee pays at least `min_payout_per_sec` for each second of the
+    /// `estimated_duration`. Older jobs are preferred, as they block the
+    /// scan state from making progress.
+    pub fn profitable_jobs<F>(
+        &self,
+        fee: &CurrencyFeeStableV1,
+        min_payout_per_sec: u64,
+        estimated_duration: F,
+        n: usize,
+    ) -> Vec<&JobState>
+    where
+        F: Fn(&JobSummary) -> Duration,
+    {
+        let fee_nanomina = fee.0.as_u64() as u128;
+        let mut jobs = self
+            .list
+            .values()
+            .filter(|job| job.can_outbid(fee))
+            .filter(|job| {
+                let millis = estimated_duration(&job.summary()).as_millis().max(1);
+                fee_nanomina * 1000 / millis >= min_payout_per_sec as u128
+            })
+            .collect::<Vec<_>>();
+        jobs.sort_by_key(|job| job.order);
+        jobs.truncate(n);
+        jobs
+    }
+
     pub fn completed_snarks_iter<'a>(&'a self) -> impl 'a + Iterator<Item = &'a Snark> {
         self.list
             .iter()
@@ -230,11 +415,35 @@ impl fmt::Debug for SnarkPoolState {
     }
 }
 
+impl SnarkerReliability {
+    /// Estimated probability of the snarker delivering committed work.
+    /// Snarkers without history start at 0.5.
+    pub fn score(&self) -> f64 {
+        let delivered = self.delivered as f64;
+        let timed_out = self.timed_out as f64;
+        (delivered + 1.0) / (delivered + timed_out + 2.0)
+    }
+
+    pub fn is_unreliable(&self) -> bool {
+        self.timed_out >= UNRELIABLE_SNARKER_MIN_TIMEOUTS
+            && self.score() < UNRELIABLE_SNARKER_MAX_SCORE
+    }
+}
+
 impl JobState {
     pub fn is_available(&self) -> bool {
         self.commitment.is_none() && self.snark.is_none()
     }
 
+    /// Whether our commitment or snark with the `fee` would beat the
+    /// existing ones for this job.
+    pub fn can_outbid(&self, fee: &CurrencyFeeStableV1) -> bool {
+        let fee = fee.0.as_u64();
+        let commitment_fee = self.commitment_msg().map(|c| c.fee.0.as_u64());
+        let snark_fee = self.snark.as_ref().map(|s| s.work.fee.0.as_u64());
+        commitment_fee.map_or(true, |v| v > fee) && snark_fee.map_or(true, |v| v > fee)
+    }
+
     pub fn commitment_msg(&self) -> Option<&SnarkJobCommitment> {
         self.commitment.as_ref().map(|v| &v.commitment)
     }
@@ -299,6 +508,12 @@ mod ser {
         list: BTreeMap<u64, JobState>,
         candidates: SnarkPoolCandidatesState,
         last_check_timeouts: Timestamp,
+        #[serde(default)]
+        restored: bool,
+        #[serde(default)]
+        reliability: Vec<((PeerId, NonZeroCurvePoint), SnarkerReliability)>,
+        #[serde(default)]
+        snarker_fee: Option<CurrencyFeeStableV1>,
     }
 
     impl Serialize for super::SnarkPoolState {
@@ -306,12 +521,16 @@ mod ser {
         where
             S: serde::Serializer,
         {
-            let mut s = serializer.serialize_struct("SnarkPool", 5)?;
+            let mut s = serializer.serialize_struct("SnarkPool", 8)?;
             s.serialize_field("config", &self.config)?;
             s.serialize_field("counter", &self.counter)?;
             s.serialize_field("list", &self.list)?;
             s.serialize_field("candidates", &self.candidates)?;
             s.serialize_field("last_check_timeouts", &self.last_check_timeouts)?;
+            s.serialize_field("restored", &self.restored)?;
+            let reliability = self.reliability.iter().collect::<Vec<_>>();
+            s.serialize_field("reliability", &reliability)?;
+            s.serialize_field("snarker_fee", &self.snarker_fee)?;
             s.end()
         }
     }
@@ -329,7 +548,384 @@ mod ser {
                 by_ledger_hash_index,
                 candidates: v.candidates,
                 last_check_timeouts: v.last_check_timeouts,
+                restored: v.restored,
+                reliability: v.reliability.into_iter().collect(),
+                snarker_fee: v.snarker_fee,
             })
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ledger::dummy::dummy_transaction_proof;
+    use ledger::scan_state::scan_state::transaction_snark::{
+        LedgerProof, LedgerProofWithSokMessage, Registers, SokDigest, SokMessage, Statement,
+    };
+    use ledger::scan_state::{
+        currency::{Amount, Fee, Magnitude, Signed},
+        fee_excess::FeeExcess,
+        pending_coinbase::Stack,
+        transaction_logic::local_state::LocalState,
+    };
+    use mina_hasher::Fp;
+    use mina_p2p_messages::v2::TransactionSnarkWorkTStableV2Proofs;
+
+    use crate::snark_pool::SnarkPoolAction;
+
+    use super::*;
+
+    const SNARKER_A: &str = "B62qrztYfPinaKqpXaYGY6QJ3SSW2NNKs7SajBLF1iFNXW9BoALN2Aq";
+    const SNARKER_B: &str = "B62qmkso2Knz9pxo5V9YEZFJ9Frq57GZfKgem1DVTKiYH9D5H3n2DGS";
+
+    fn fee(nanomina: u64) -> CurrencyFeeStableV1 {
+        (&Fee::from_u64(nanomina)).into()
+    }
+
+    fn snarker(address: &str) -> NonZeroCurvePoint {
+        address.parse().unwrap()
+    }
+
+    fn ledger_proof(n: u64) -> LedgerProof {
+        let registers = |ledger: u64| Registers {
+            first_pass_ledger: Fp::from(ledger),
+            second_pass_ledger: Fp::from(ledger),
+            pending_coinbase_stack: Stack::empty(),
+            local_state: LocalState::empty(),
+        };
+        let statement = Statement {
+            source: registers(2 * n),
+            target: registers(2 * n + 1),
+            connecting_ledger_left: Fp::from(2 * n),
+            connecting_ledger_right: Fp::from(2 * n + 1),
+            supply_increase: Signed::<Amount>::zero(),
+            fee_excess: FeeExcess::empty(),
+            sok_digest: (),
+        };
+        LedgerProof::create(statement, SokDigest::default(), dummy_transaction_proof())
+    }
+
+    /// Merge job, which is distinct for each `n`.
+    fn job(n: u64, order: usize) -> JobState {
+        let message = |proof: LedgerProof| {
+            let sok_message = SokMessage::create(Fee::zero(), (&snarker(SNARKER_A)).into());
+            (&LedgerProofWithSokMessage { proof, sok_message }).into()
+        };
+        let job = OneOrTwo::One(AvailableJobMessage::Merge {
+            left: message(ledger_proof(n)),
+            right: message(ledger_proof(n)),
+        });
+        JobState {
+            time: Timestamp::ZERO,
+            id: (&job).into(),
+            job,
+            commitment: None,
+            snark: None,
+            order,
+        }
+    }
+
+    fn with_commitment(mut job: JobState, snarker_address: &str, nanomina: u64) -> JobState {
+        job.commitment = Some(JobCommitment {
+            commitment: SnarkJobCommitment::new(
+                0,
+                job.id.clone(),
+                fee(nanomina),
+                snarker(snarker_address),
+            ),
+            received_t: Timestamp::ZERO,
+            sender: PeerId::from_bytes([1; 32]),
+        });
+        job
+    }
+
+    fn with_snark(mut job: JobState, nanomina: u64) -> JobState {
+        job.snark = Some(SnarkWork {
+            work: Snark {
+                snarker: snarker(SNARKER_B),
+                fee: fee(nanomina),
+                proofs: Arc::new(TransactionSnarkWorkTStableV2Proofs::One(
+                    (&ledger_proof(0)).into(),
+                )),
+            },
+            received_t: Timestamp::ZERO,
+            sender: PeerId::from_bytes([2; 32]),
+        });
+        job
+    }
+
+    fn pool(jobs: impl IntoIterator<Item = JobState>) -> SnarkPoolState {
+        let mut pool = SnarkPoolState::new();
+        jobs.into_iter().for_each(|job| pool.insert(job));
+        pool
+    }
+
+    fn ids(jobs: &[&JobState]) -> Vec<SnarkJobId> {
+        jobs.iter().map(|job| job.id.clone()).collect()
+    }
+
+    #[test]
+    fn job_can_outbid_only_higher_fees() {
+        let available = job(0, 0);
+        assert!(available.can_outbid(&fee(1_000)));
+
+        let committed = with_commitment(job(0, 0), SNARKER_B, 1_000);
+        assert!(committed.can_outbid(&fee(999)));
+        assert!(!committed.can_outbid(&fee(1_000)));
+        assert!(!committed.can_outbid(&fee(1_001)));
+
+        let done = with_snark(committed, 500);
+        assert!(done.can_outbid(&fee(499)));
+        assert!(!done.can_outbid(&fee(500)));
+        assert!(!done.can_outbid(&fee(999)));
+    }
+
+    #[test]
+    fn pool_can_outbid_unknown_job() {
+        let pool = pool([job(0, 0)]);
+        assert!(pool.can_outbid(&job(0, 0).id, &fee(1)));
+        assert!(!pool.can_outbid(&job(1, 0).id, &fee(1)));
+    }
+
+    #[test]
+    fn should_create_commitment_only_for_available_jobs() {
+        let pool = pool([job(0, 0), with_commitment(job(1, 1), SNARKER_B, 1_000)]);
+        assert!(pool.should_create_commitment(&job(0, 0).id));
+        assert!(!pool.should_create_commitment(&job(1, 1).id));
+        // profit strategy could still outbid it.
+        assert!(pool.can_outbid(&job(1, 1).id, &fee(999)));
+    }
+
+    #[test]
+    fn profitable_jobs_oldest_first() {
+        let pool = pool([
+            job(0, 2),
+            with_commitment(job(1, 0), SNARKER_B, 1_000),
+            job(2, 1),
+            with_commitment(job(3, 3), SNARKER_B, 10),
+        ]);
+        let estimate = |_: &JobSummary| Duration::from_secs(1);
+
+        let jobs = pool.profitable_jobs(&fee(100), 0, estimate, 10);
+        assert_eq!(ids(&jobs), vec![job(1, 0).id, job(2, 1).id, job(0, 2).id]);
+
+        let jobs = pool.profitable_jobs(&fee(100), 0, estimate, 2);
+        assert_eq!(ids(&jobs), vec![job(1, 0).id, job(2, 1).id]);
+    }
+
+    #[test]
+    fn profitable_jobs_min_payout_per_sec() {
+        let pool = pool([job(0, 0), job(1, 1)]);
+        let estimate = |summary: &JobSummary| match summary {
+            JobSummary::Merge(_) => Duration::from_secs(10),
+            JobSummary::Tx(_) => unreachable!(),
+        };
+
+        // 1_000 nanomina for 10s is 100 nanomina per second.
+        assert_eq!(
+            pool.profitable_jobs(&fee(1_000), 100, estimate, 10).len(),
+            2
+        );
+        assert!(pool
+            .profitable_jobs(&fee(1_000), 101, estimate, 10)
+            .is_empty());
+        assert!(pool
+            .profitable_jobs(&fee(999), 100, estimate, 10)
+            .is_empty());
+    }
+
+    #[test]
+    fn resumable_commitments() {
+        let commitment = |job: &JobState, address: &str| {
+            let commitment = SnarkJobCommitment::new(0, job.id.clone(), fee(100), snarker(address));
+            (commitment, ())
+        };
+        let pool = pool([job(0, 0), job(1, 1), with_snark(job(2, 2), 100)]);
+        let commitments = vec![
+            commitment(&job(0, 0), SNARKER_A),
+            // not ours.
+            commitment(&job(1, 1), SNARKER_B),
+            // already has a snark.
+            commitment(&job(2, 2), SNARKER_A),
+            // no longer in the pool.
+            commitment(&job(3, 3), SNARKER_A),
+            commitment(&job(1, 1), SNARKER_A),
+        ];
+        let resumable = |available_workers| {
+            pool.resumable_commitments(
+                commitments.clone(),
+                &[],
+                &snarker(SNARKER_A),
+                available_workers,
+            )
+            .into_iter()
+            .map(|(commitment, _)| commitment.job_id)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(resumable(4), vec![job(0, 0).id, job(1, 1).id]);
+        assert_eq!(resumable(1), vec![job(0, 0).id]);
+        assert!(resumable(0).is_empty());
+    }
+
+    fn peer(n: u8) -> PeerId {
+        PeerId::from_bytes([n; 32])
+    }
+
+    fn reliability(delivered: u32, timed_out: u32) -> SnarkerReliability {
+        SnarkerReliability {
+            delivered,
+            timed_out,
+            last_update: Timestamp::ZERO,
+        }
+    }
+
+    fn reduce(pool: &mut SnarkPoolState, action: SnarkPoolAction) {
+        let meta = redux::ActionMeta::zero_custom(Timestamp::ZERO);
+        pool.reducer(meta.with_action(&action), peer(0));
+    }
+
+    #[test]
+    fn snarker_reliability_score() {
+        assert_eq!(reliability(0, 0).score(), 0.5);
+        assert_eq!(reliability(3, 0).score(), 0.8);
+        assert_eq!(reliability(0, 3).score(), 0.2);
+
+        // too few timeouts to judge.
+        assert!(!reliability(0, 2).is_unreliable());
+        assert!(reliability(0, 3).is_unreliable());
+        assert!(!reliability(3, 3).is_unreliable());
+        assert!(reliability(1, 4).is_unreliable());
+    }
+
+    #[test]
+    fn unreliable_commitment_replaced() {
+        let id = job(0, 0).id;
+        let commitment = |address: &str, nanomina: u64| {
+            SnarkJobCommitment::new(0, id.clone(), fee(nanomina), snarker(address))
+        };
+        // committed by snarker A from peer 1.
+        let mut pool = pool([with_commitment(job(0, 0), SNARKER_A, 1_000)]);
+        assert!(pool.should_take_commitment(&commitment(SNARKER_B, 999), &peer(2)));
+        assert!(!pool.should_take_commitment(&commitment(SNARKER_B, 1_001), &peer(2)));
+        assert!(!pool.can_outbid(&id, &fee(1_001)));
+
+        pool.snarker_reliability_mut(Timestamp::ZERO, peer(1), snarker(SNARKER_A))
+            .timed_out = 3;
+        assert!(pool.is_snarker_unreliable(&peer(1), &snarker(SNARKER_A)));
+        // same key from another peer isn't affected.
+        assert!(!pool.is_snarker_unreliable(&peer(2), &snarker(SNARKER_A)));
+        assert!(pool.should_take_commitment(&commitment(SNARKER_A, 1_001), &peer(2)));
+        assert!(pool.should_take_commitment(&commitment(SNARKER_B, 1_001), &peer(2)));
+        assert!(pool.can_outbid(&id, &fee(1_001)));
+
+        // both unreliable, so the better commitment wins.
+        pool.snarker_reliability_mut(Timestamp::ZERO, peer(2), snarker(SNARKER_B))
+            .timed_out = 3;
+        assert!(!pool.should_take_commitment(&commitment(SNARKER_B, 1_001), &peer(2)));
+        assert!(pool.should_take_commitment(&commitment(SNARKER_B, 999), &peer(2)));
+    }
+
+    #[test]
+    fn reliable_commitment_kept() {
+        let id = job(0, 0).id;
+        let mut pool = pool([with_commitment(job(0, 0), SNARKER_A, 1_000)]);
+        pool.snarker_reliability_mut(Timestamp::ZERO, peer(2), snarker(SNARKER_B))
+            .timed_out = 3;
+        let cheaper = SnarkJobCommitment::new(0, id.clone(), fee(1), snarker(SNARKER_B));
+        assert!(!pool.should_take_commitment(&cheaper, &peer(2)));
+        assert!(pool.should_take_commitment(&cheaper, &peer(3)));
+
+        let unknown = SnarkJobCommitment::new(0, job(1, 1).id, fee(1), snarker(SNARKER_B));
+        assert!(!pool.should_take_commitment(&unknown, &peer(3)));
+    }
+
+    #[test]
+    fn snarker_reliability_updated() {
+        let job = job(0, 0);
+        let snark = with_snark(job.clone(), 100).snark.unwrap().work;
+        let commitment = |address: &str| {
+            SnarkJobCommitment::new(0, job.id.clone(), fee(1_000), snarker(address))
+        };
+        let counts = |pool: &SnarkPoolState, sender: u8, address: &str| {
+            pool.snarker_reliability(&peer(sender), &snarker(address))
+                .map(|v| (v.delivered, v.timed_out))
+        };
+
+        // snark delivered by the committed snarker B, from peer 1.
+        let mut pool = pool([with_commitment(job.clone(), SNARKER_B, 1_000)]);
+        reduce(
+            &mut pool,
+            SnarkPoolAction::WorkAdd {
+                snark,
+                sender: peer(3),
+            },
+        );
+        assert_eq!(counts(&pool, 1, SNARKER_B), Some((1, 0)));
+        assert_eq!(counts(&pool, 3, SNARKER_B), None);
+
+        reduce(
+            &mut pool,
+            SnarkPoolAction::CommitmentAdd {
+                commitment: commitment(SNARKER_A),
+                sender: peer(1),
+            },
+        );
+        reduce(
+            &mut pool,
+            SnarkPoolAction::JobCommitmentTimeout {
+                job_id: job.id.clone(),
+            },
+        );
+        assert_eq!(counts(&pool, 1, SNARKER_A), Some((0, 1)));
+
+        // our own commitments aren't counted.
+        reduce(
+            &mut pool,
+            SnarkPoolAction::CommitmentAdd {
+                commitment: commitment(SNARKER_A),
+                sender: peer(0),
+            },
+        );
+        reduce(
+            &mut pool,
+            SnarkPoolAction::JobCommitmentTimeout {
+                job_id: job.id.clone(),
+            },
+        );
+        assert_eq!(counts(&pool, 0, SNARKER_A), None);
+    }
+
+    #[test]
+    fn snarker_reliability_bounded() {
+        let mut pool = pool([]);
+        let peer = |i: usize| {
+            let mut bytes = [0; 32];
+            bytes[..8].copy_from_slice(&(i as u64).to_le_bytes());
+            PeerId::from_bytes(bytes)
+        };
+        for i in 0..MAX_TRACKED_SNARKERS {
+            let time = Timestamp::new(i as u64 + 1);
+            pool.snarker_reliability_mut(time, peer(i), snarker(SNARKER_A));
+        }
+        // least recently updated is forgotten.
+        pool.snarker_reliability_mut(Timestamp::new(u64::MAX), peer(1), snarker(SNARKER_A));
+        pool.snarker_reliability_mut(
+            Timestamp::new(u64::MAX),
+            peer(usize::MAX),
+            snarker(SNARKER_A),
+        );
+        assert_eq!(pool.reliability.len(), MAX_TRACKED_SNARKERS);
+        assert!(pool
+            .snarker_reliability(&peer(0), &snarker(SNARKER_A))
+            .is_none());
+        assert!(pool
+            .snarker_reliability(&peer(1), &snarker(SNARKER_A))
+            .is_some());
+        assert!(pool
+            .snarker_reliability(&peer(usize::MAX), &snarker(SNARKER_A))
+            .is_some());
+    }
+}
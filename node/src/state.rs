@@ -1,4 +1,5 @@
 use ledger::proofs::transaction::transaction_snark::CONSTRAINT_CONSTANTS;
+use mina_p2p_messages::v2::CurrencyFeeStableV1;
 use redux::{ActionMeta, Timestamp};
 use serde::{Deserialize, Serialize};
 
@@ -44,7 +45,10 @@ impl State {
             snark: SnarkState::new(config.snark),
             consensus: ConsensusState::new(),
             transition_frontier: TransitionFrontierState::new(config.transition_frontier),
-            external_snark_worker: ExternalSnarkWorkers::new(now),
+            external_snark_worker: ExternalSnarkWorkers::new(
+                now,
+                config.global.snarker.as_ref().map_or(0, |c| c.workers),
+            ),
             block_producer: BlockProducerState::new(now, config.block_producer),
             rpc: RpcState::new(),
 
@@ -71,13 +75,33 @@ impl State {
         self.applied_actions_count += 1;
     }
 
+    /// Our snarker fee currently in effect, which differs from the
+    /// configured one, if it was adjusted by the fee policy.
+    pub fn snarker_fee(&self) -> Option<&CurrencyFeeStableV1> {
+        let config = self.config.snarker.as_ref()?;
+        Some(self.snark_pool.snarker_fee().unwrap_or(&config.fee))
+    }
+
+    /// Current time to be used for slot timing.
+    ///
+    /// If clock skew correction is enabled and our clock is detected
+    /// to be skewed, it's corrected by the (bounded) estimated skew.
+    pub fn slot_timing_time(&self) -> Timestamp {
+        let now = self.time();
+        if !self.config.clock_skew_correction {
+            return now;
+        }
+        let correction_ns = self.consensus.clock_skew.correction_ms() * 1_000_000;
+        Timestamp::new(u64::from(now).saturating_add_signed(-correction_ns))
+    }
+
     /// Current global slot based on constants and current time.
     ///
     /// It's not equal to global slot of the best tip.
     pub fn cur_global_slot(&self) -> Option<u32> {
         let best_tip = self.transition_frontier.best_tip()?;
         let best_tip_ms = u64::from(best_tip.timestamp()) / 1_000_000;
-        let now_ms = u64::from(self.time()) / 1_000_000;
+        let now_ms = u64::from(self.slot_timing_time()) / 1_000_000;
         let ms = now_ms.saturating_sub(best_tip_ms) as u64;
         let slots = ms / CONSTRAINT_CONSTANTS.block_window_duration_ms;
 
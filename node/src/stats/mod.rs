@@ -10,9 +10,21 @@ pub mod sync {
 }
 use sync::{SyncStats, SyncStatsSnapshot, SyncingLedger};
 
+mod stats_snark_market;
+pub mod snark_market {
+    pub use super::stats_snark_market::*;
+}
+use snark_market::{
+    SnarkMarketJobStats, SnarkMarketSeriesEntry, SnarkMarketSeriesError, SnarkMarketStats,
+    SnarkMarketStatsSnapshot,
+};
+
 use std::collections::VecDeque;
+use std::time::Duration;
 
+use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
 use openmina_core::block::{ArcBlockWithHash, Block, BlockWithHash};
+use openmina_core::snark::{SnarkJobCommitment, SnarkJobId};
 use redux::{ActionMeta, ActionWithMeta, Timestamp};
 
 use crate::transition_frontier::sync::ledger::SyncLedgerTargetKind;
@@ -25,6 +37,7 @@ pub struct Stats {
     last_action: ActionKindWithMeta,
     action_stats: ActionStats,
     sync_stats: SyncStats,
+    snark_market_stats: SnarkMarketStats,
 }
 
 impl Stats {
@@ -49,6 +62,7 @@ impl Stats {
                 per_block: action_stats_per_block,
             },
             sync_stats: Default::default(),
+            snark_market_stats: Default::default(),
         }
     }
 
@@ -97,6 +111,43 @@ impl Stats {
         self
     }
 
+    pub fn snark_pool_jobs_update<'a, I>(&mut self, time: Timestamp, job_ids: I) -> &mut Self
+    where
+        I: IntoIterator<Item = &'a SnarkJobId>,
+    {
+        self.snark_market_stats.jobs_update(time, job_ids);
+        self
+    }
+
+    pub fn snark_pool_commitment_add(
+        &mut self,
+        time: Timestamp,
+        commitment: &SnarkJobCommitment,
+    ) -> &mut Self {
+        self.snark_market_stats.commitment_add(time, commitment);
+        self
+    }
+
+    pub fn snark_pool_snark_offered(
+        &mut self,
+        job_id: &SnarkJobId,
+        prover: &NonZeroCurvePoint,
+    ) -> &mut Self {
+        self.snark_market_stats.snark_offered(job_id, prover);
+        self
+    }
+
+    pub fn snark_pool_snark_add(
+        &mut self,
+        time: Timestamp,
+        job_id: &SnarkJobId,
+        prover: &NonZeroCurvePoint,
+        fee: &CurrencyFeeStableV1,
+    ) -> &mut Self {
+        self.snark_market_stats.snark_add(time, job_id, prover, fee);
+        self
+    }
+
     pub fn collect_action_stats_since_start(&self) -> ActionStatsSnapshot {
         self.action_stats.since_start.clone()
     }
@@ -112,6 +163,23 @@ impl Stats {
         self.sync_stats.collect_stats(limit)
     }
 
+    pub fn collect_snark_market_stats(&self, now: Timestamp) -> SnarkMarketStatsSnapshot {
+        self.snark_market_stats.collect_stats(now)
+    }
+
+    pub fn collect_snark_market_series(
+        &self,
+        now: Timestamp,
+        interval: Option<Duration>,
+        limit: Option<usize>,
+    ) -> Result<Vec<SnarkMarketSeriesEntry>, SnarkMarketSeriesError> {
+        self.snark_market_stats.collect_series(now, interval, limit)
+    }
+
+    pub fn collect_snark_market_job(&self, job_id: &SnarkJobId) -> Option<SnarkMarketJobStats> {
+        self.snark_market_stats.collect_job(job_id)
+    }
+
     pub fn get_sync_time(&self) -> Option<Timestamp> {
         self.sync_stats
             .collect_stats(Some(1))
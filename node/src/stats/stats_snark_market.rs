@@ -0,0 +1,584 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::time::Duration;
+
+use mina_p2p_messages::v2::{CurrencyFeeStableV1, NonZeroCurvePoint};
+use openmina_core::snark::{SnarkJobCommitment, SnarkJobId};
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+const MAX_FINISHED_JOBS_LEN: usize = 4096;
+const TOP_PROVERS_LEN: usize = 10;
+/// Job is considered stalled, if it was committed to, but the snark
+/// wasn't delivered within this duration.
+const STALLED_JOB_AFTER: Duration = Duration::from_secs(10 * 60);
+const DEFAULT_SERIES_INTERVAL: Duration = Duration::from_secs(10 * 60);
+pub const MIN_SERIES_INTERVAL: Duration = Duration::from_secs(1);
+pub const MAX_SERIES_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const DEFAULT_SERIES_LEN: usize = 24;
+pub const MAX_SERIES_LEN: usize = 1024;
+
+#[derive(Default)]
+pub struct SnarkMarketStats {
+    jobs: BTreeMap<SnarkJobId, SnarkMarketJob>,
+    finished: VecDeque<SnarkMarketJob>,
+}
+
+#[derive(Debug, Clone)]
+struct SnarkMarketJob {
+    id: SnarkJobId,
+    added: Timestamp,
+    removed: Option<Timestamp>,
+    /// Time of the first commitment from each prover.
+    commitments: BTreeMap<NonZeroCurvePoint, Timestamp>,
+    /// Provers that offered a snark for the job.
+    competing: BTreeSet<NonZeroCurvePoint>,
+    best: Option<SnarkMarketSnark>,
+}
+
+#[derive(Debug, Clone)]
+struct SnarkMarketSnark {
+    prover: NonZeroCurvePoint,
+    fee: u64,
+    received: Timestamp,
+}
+
+impl SnarkMarketJob {
+    fn new(id: SnarkJobId, time: Timestamp) -> Self {
+        Self {
+            id,
+            added: time,
+            removed: None,
+            commitments: Default::default(),
+            competing: Default::default(),
+            best: None,
+        }
+    }
+
+    /// Time from the winning prover's commitment (or from the job being
+    /// added, if there was none) to the delivery of its snark.
+    fn delivery_latency(&self) -> Option<Duration> {
+        let best = self.best.as_ref()?;
+        let committed = self
+            .commitments
+            .get(&best.prover)
+            .copied()
+            .unwrap_or(self.added);
+        best.received.checked_sub(committed)
+    }
+
+    fn first_commitment(&self) -> Option<Timestamp> {
+        self.commitments.values().min().copied()
+    }
+
+    fn is_stalled(&self, now: Timestamp) -> bool {
+        self.best.is_none()
+            && self
+                .first_commitment()
+                .and_then(|t| now.checked_sub(t))
+                .map_or(false, |waited| waited >= STALLED_JOB_AFTER)
+    }
+}
+
+impl SnarkMarketStats {
+    /// Updates the set of jobs in the snark pool. Jobs, which are no
+    /// longer in the pool are considered finished.
+    pub fn jobs_update<'a, I>(&mut self, time: Timestamp, job_ids: I)
+    where
+        I: IntoIterator<Item = &'a SnarkJobId>,
+    {
+        let mut jobs = std::mem::take(&mut self.jobs);
+        for job_id in job_ids {
+            let job = jobs
+                .remove(job_id)
+                .unwrap_or_else(|| SnarkMarketJob::new(job_id.clone(), time));
+            self.jobs.insert(job_id.clone(), job);
+        }
+        for (_, mut job) in jobs {
+            job.removed = Some(time);
+            if self.finished.len() >= MAX_FINISHED_JOBS_LEN {
+                self.finished.pop_front();
+            }
+            self.finished.push_back(job);
+        }
+    }
+
+    pub fn commitment_add(&mut self, time: Timestamp, commitment: &SnarkJobCommitment) {
+        if let Some(job) = self.jobs.get_mut(&commitment.job_id) {
+            job.commitments
+                .entry(commitment.snarker.clone())
+                .or_insert(time);
+        }
+    }
+
+    /// Snark for the job was offered by a prover.
+    pub fn snark_offered(&mut self, job_id: &SnarkJobId, prover: &NonZeroCurvePoint) {
+        if let Some(job) = self.jobs.get_mut(job_id) {
+            job.competing.insert(prover.clone());
+        }
+    }
+
+    /// Snark was added to the pool, as the best one for the job.
+    pub fn snark_add(
+        &mut self,
+        time: Timestamp,
+        job_id: &SnarkJobId,
+        prover: &NonZeroCurvePoint,
+        fee: &CurrencyFeeStableV1,
+    ) {
+        if let Some(job) = self.jobs.get_mut(job_id) {
+            job.competing.insert(prover.clone());
+            job.best = Some(SnarkMarketSnark {
+                prover: prover.clone(),
+                fee: fee.0.as_u64(),
+                received: time,
+            });
+        }
+    }
+
+    pub fn collect_stats(&self, now: Timestamp) -> SnarkMarketStatsSnapshot {
+        let finished = self.finished.iter().filter(|job| job.best.is_some());
+
+        let mut fees = finished
+            .clone()
+            .filter_map(|job| job.best.as_ref())
+            .map(|snark| snark.fee)
+            .collect::<Vec<_>>();
+        let mut latencies = finished
+            .clone()
+            .filter_map(|job| job.delivery_latency())
+            .map(|latency| latency.as_millis() as u64)
+            .collect::<Vec<_>>();
+        let competing = finished
+            .clone()
+            .map(|job| job.competing.len() as u64)
+            .collect::<Vec<_>>();
+
+        let mut won = BTreeMap::<&NonZeroCurvePoint, SnarkMarketProverStats>::new();
+        for job in self.finished.iter() {
+            for prover in &job.competing {
+                won.entry(prover).or_default();
+            }
+            for prover in job.commitments.keys() {
+                won.entry(prover).or_default().commitments += 1;
+            }
+            if let Some(best) = job.best.as_ref() {
+                let prover = won.entry(&best.prover).or_default();
+                prover.jobs_won += 1;
+                prover.fees_earned += best.fee;
+            }
+        }
+        let provers_count = won.len();
+        let mut top_provers = won
+            .into_iter()
+            .map(|(prover, stats)| SnarkMarketProverStats {
+                prover: Some(prover.clone()),
+                ..stats
+            })
+            .collect::<Vec<_>>();
+        top_provers.sort_by(|a, b| b.jobs_won.cmp(&a.jobs_won));
+        top_provers.truncate(TOP_PROVERS_LEN);
+
+        let stalled_jobs = self
+            .jobs
+            .values()
+            .filter(|job| job.is_stalled(now))
+            .map(|job| job.id.clone())
+            .collect();
+
+        SnarkMarketStatsSnapshot {
+            time: now,
+            jobs_in_pool: self.jobs.len(),
+            jobs_with_snark: self.jobs.values().filter(|j| j.best.is_some()).count(),
+            jobs_without_commitment: self
+                .jobs
+                .values()
+                .filter(|job| job.commitments.is_empty() && job.best.is_none())
+                .count(),
+            stalled_jobs,
+            finished_jobs: self.finished.len(),
+            fee: SnarkMarketValueStats::new(&mut fees),
+            delivery_latency_ms: SnarkMarketValueStats::new(&mut latencies),
+            competing_snarks_avg: average(&competing),
+            provers_count,
+            top_provers,
+        }
+    }
+
+    /// Statistics of finished jobs, grouped by the time they left the
+    /// pool, latest interval first.
+    ///
+    /// Interval must be within [`MIN_SERIES_INTERVAL`] and
+    /// [`MAX_SERIES_INTERVAL`] and is truncated to whole seconds. At most
+    /// [`MAX_SERIES_LEN`] intervals are returned.
+    pub fn collect_series(
+        &self,
+        now: Timestamp,
+        interval: Option<Duration>,
+        limit: Option<usize>,
+    ) -> Result<Vec<SnarkMarketSeriesEntry>, SnarkMarketSeriesError> {
+        let interval = interval.unwrap_or(DEFAULT_SERIES_INTERVAL);
+        if interval < MIN_SERIES_INTERVAL {
+            return Err(SnarkMarketSeriesError::IntervalTooShort);
+        }
+        if interval > MAX_SERIES_INTERVAL {
+            return Err(SnarkMarketSeriesError::IntervalTooLong);
+        }
+        let interval = interval.as_secs();
+        let secs = |t: Timestamp| Duration::from_nanos(u64::from(t)).as_secs();
+        let now = secs(now);
+        let current_start = now - now % interval;
+        // no intervals before the start of the clock.
+        let len = limit
+            .unwrap_or(DEFAULT_SERIES_LEN)
+            .min(MAX_SERIES_LEN)
+            .min((current_start / interval).saturating_add(1) as usize);
+
+        let mut intervals = vec![vec![]; len];
+        for job in &self.finished {
+            let Some(removed) = job.removed.map(secs) else {
+                continue;
+            };
+            let start = removed - removed % interval;
+            let i = current_start
+                .checked_sub(start)
+                .map(|d| d / interval)
+                .and_then(|i| usize::try_from(i).ok());
+            if let Some(jobs) = i.and_then(|i| intervals.get_mut(i)) {
+                jobs.push(job);
+            }
+        }
+
+        Ok(intervals
+            .into_iter()
+            .enumerate()
+            .map(|(i, jobs)| {
+                let start = current_start - i as u64 * interval;
+                let mut fees = jobs
+                    .iter()
+                    .filter_map(|job| job.best.as_ref())
+                    .map(|snark| snark.fee)
+                    .collect::<Vec<_>>();
+                let mut latencies = jobs
+                    .iter()
+                    .filter_map(|job| job.delivery_latency())
+                    .map(|latency| latency.as_millis() as u64)
+                    .collect::<Vec<_>>();
+                let competing = jobs
+                    .iter()
+                    .filter(|job| job.best.is_some())
+                    .map(|job| job.competing.len() as u64)
+                    .collect::<Vec<_>>();
+                SnarkMarketSeriesEntry {
+                    start: Timestamp::new(Duration::from_secs(start).as_nanos() as u64),
+                    jobs: jobs.len(),
+                    fee: SnarkMarketValueStats::new(&mut fees),
+                    delivery_latency_ms: SnarkMarketValueStats::new(&mut latencies),
+                    competing_snarks_avg: average(&competing),
+                }
+            })
+            .collect())
+    }
+
+    /// Market view of a single job, either in the pool or recently
+    /// finished.
+    pub fn collect_job(&self, job_id: &SnarkJobId) -> Option<SnarkMarketJobStats> {
+        let job = self
+            .jobs
+            .get(job_id)
+            .or_else(|| self.finished.iter().rev().find(|job| &job.id == job_id))?;
+        Some(SnarkMarketJobStats {
+            job_id: job.id.clone(),
+            added: job.added,
+            removed: job.removed,
+            committed: job.commitments.keys().cloned().collect(),
+            competing: job.competing.iter().cloned().collect(),
+            winner: job.best.as_ref().map(|best| best.prover.clone()),
+            fee: job.best.as_ref().map(|best| best.fee),
+            delivery_latency_ms: job
+                .delivery_latency()
+                .map(|latency| latency.as_millis() as u64),
+        })
+    }
+}
+
+fn average(values: &[u64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<u64>() as f64 / values.len() as f64)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkMarketStatsSnapshot {
+    pub time: Timestamp,
+    pub jobs_in_pool: usize,
+    pub jobs_with_snark: usize,
+    pub jobs_without_commitment: usize,
+    /// Jobs in the pool, which were committed to, but no snark was
+    /// delivered for them in time.
+    pub stalled_jobs: Vec<SnarkJobId>,
+    pub finished_jobs: usize,
+    /// Winning fees (in nanomina) of finished jobs.
+    pub fee: Option<SnarkMarketValueStats>,
+    /// Time from commitment to the delivery of the winning snark.
+    pub delivery_latency_ms: Option<SnarkMarketValueStats>,
+    pub competing_snarks_avg: Option<f64>,
+    pub provers_count: usize,
+    /// Provers with the most won jobs.
+    pub top_provers: Vec<SnarkMarketProverStats>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SnarkMarketProverStats {
+    pub prover: Option<NonZeroCurvePoint>,
+    pub commitments: u64,
+    pub jobs_won: u64,
+    pub fees_earned: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkMarketValueStats {
+    pub min: u64,
+    pub median: u64,
+    pub mean: u64,
+    pub max: u64,
+}
+
+impl SnarkMarketValueStats {
+    fn new(values: &mut [u64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_unstable();
+        let sum = values.iter().map(|v| *v as u128).sum::<u128>();
+        Some(Self {
+            min: values[0],
+            median: values[values.len() / 2],
+            mean: (sum / values.len() as u128) as u64,
+            max: values[values.len() - 1],
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, thiserror::Error)]
+pub enum SnarkMarketSeriesError {
+    #[error("series interval is shorter than {MIN_SERIES_INTERVAL:?}")]
+    IntervalTooShort,
+    #[error("series interval is longer than {MAX_SERIES_INTERVAL:?}")]
+    IntervalTooLong,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkMarketSeriesEntry {
+    pub start: Timestamp,
+    pub jobs: usize,
+    pub fee: Option<SnarkMarketValueStats>,
+    pub delivery_latency_ms: Option<SnarkMarketValueStats>,
+    pub competing_snarks_avg: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnarkMarketJobStats {
+    pub job_id: SnarkJobId,
+    pub added: Timestamp,
+    /// When the job left the pool, if it did.
+    pub removed: Option<Timestamp>,
+    /// Provers that committed to the job.
+    pub committed: Vec<NonZeroCurvePoint>,
+    /// Provers that offered a snark for the job.
+    pub competing: Vec<NonZeroCurvePoint>,
+    /// Prover of the best snark for the job.
+    pub winner: Option<NonZeroCurvePoint>,
+    /// Winning fee in nanomina.
+    pub fee: Option<u64>,
+    pub delivery_latency_ms: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::scan_state::currency::{Fee, Magnitude};
+    use mina_p2p_messages::v2::LedgerHash;
+
+    use super::*;
+
+    const PROVER_A: &str = "B62qiTKpEPjGTSHZrtM8uXiKgn8So916pLmNJKDhKeyBQL9TDb3nvBG";
+    const PROVER_B: &str = "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg";
+
+    fn t(secs: u64) -> Timestamp {
+        Timestamp::new(secs * 1_000_000_000)
+    }
+
+    fn job_id(n: u64) -> SnarkJobId {
+        let hash = |n: u64| LedgerHash::from_fp(mina_hasher::Fp::from(n)).to_string();
+        format!("{}_{}-{}_{}", hash(n), hash(n), hash(n + 1), hash(n + 1))
+            .parse()
+            .unwrap()
+    }
+
+    fn prover(key: &str) -> NonZeroCurvePoint {
+        key.parse().unwrap()
+    }
+
+    fn fee(nanomina: u64) -> CurrencyFeeStableV1 {
+        (&Fee::from_u64(nanomina)).into()
+    }
+
+    fn commit(stats: &mut SnarkMarketStats, time: Timestamp, job: u64, key: &str) {
+        let commitment = SnarkJobCommitment::new(0, job_id(job), fee(1), prover(key));
+        stats.commitment_add(time, &commitment);
+    }
+
+    /// Market with 3 jobs, of which the first 2 got a snark and finished.
+    fn market() -> SnarkMarketStats {
+        let mut stats = SnarkMarketStats::default();
+        stats.jobs_update(t(0), &[job_id(1), job_id(2), job_id(3)]);
+
+        commit(&mut stats, t(10), 1, PROVER_A);
+        commit(&mut stats, t(20), 1, PROVER_B);
+        stats.snark_offered(&job_id(1), &prover(PROVER_B));
+        stats.snark_add(t(40), &job_id(1), &prover(PROVER_A), &fee(100));
+
+        commit(&mut stats, t(0), 2, PROVER_B);
+        stats.snark_add(t(30), &job_id(2), &prover(PROVER_B), &fee(300));
+
+        commit(&mut stats, t(0), 3, PROVER_A);
+        stats.jobs_update(t(60), &[job_id(3)]);
+        stats
+    }
+
+    #[test]
+    fn jobs_update_finishes_removed_jobs() {
+        let mut stats = SnarkMarketStats::default();
+        stats.jobs_update(t(0), &[job_id(1), job_id(2)]);
+        commit(&mut stats, t(5), 2, PROVER_A);
+        commit(&mut stats, t(6), 2, PROVER_A);
+        // not in the pool.
+        commit(&mut stats, t(5), 4, PROVER_A);
+
+        stats.jobs_update(t(10), &[job_id(2), job_id(3)]);
+        assert_eq!(stats.jobs.len(), 2);
+        assert_eq!(stats.finished.len(), 1);
+        assert!(stats.collect_job(&job_id(4)).is_none());
+
+        let finished = stats.collect_job(&job_id(1)).unwrap();
+        assert_eq!(finished.removed, Some(t(10)));
+        let kept = &stats.jobs[&job_id(2)];
+        assert_eq!(kept.added, t(0));
+        assert_eq!(kept.commitments[&prover(PROVER_A)], t(5));
+        let new = stats.collect_job(&job_id(3)).unwrap();
+        assert_eq!((new.added, new.removed), (t(10), None));
+    }
+
+    #[test]
+    fn collect_stats_of_finished_jobs() {
+        let stats = market().collect_stats(t(11 * 60));
+        assert_eq!(stats.jobs_in_pool, 1);
+        assert_eq!(stats.jobs_with_snark, 0);
+        assert_eq!(stats.jobs_without_commitment, 0);
+        assert_eq!(stats.stalled_jobs, vec![job_id(3)]);
+        assert_eq!(stats.finished_jobs, 2);
+
+        let fee = stats.fee.unwrap();
+        assert_eq!((fee.min, fee.mean, fee.max), (100, 200, 300));
+        let latency = stats.delivery_latency_ms.unwrap();
+        assert_eq!((latency.min, latency.max), (30_000, 30_000));
+        assert_eq!(stats.competing_snarks_avg, Some(1.5));
+
+        assert_eq!(stats.provers_count, 2);
+        let prover_stats = |key: &str| {
+            let prover = prover(key);
+            stats
+                .top_provers
+                .iter()
+                .find(|stats| stats.prover.as_ref() == Some(&prover))
+                .map(|stats| (stats.commitments, stats.jobs_won, stats.fees_earned))
+        };
+        assert_eq!(prover_stats(PROVER_A), Some((1, 1, 100)));
+        assert_eq!(prover_stats(PROVER_B), Some((2, 1, 300)));
+    }
+
+    #[test]
+    fn job_not_stalled_before_timeout() {
+        let stats = market().collect_stats(t(5 * 60));
+        assert!(stats.stalled_jobs.is_empty());
+    }
+
+    #[test]
+    fn collect_job_view() {
+        let job = market().collect_job(&job_id(1)).unwrap();
+        assert_eq!(job.removed, Some(t(60)));
+        let mut provers = vec![prover(PROVER_A), prover(PROVER_B)];
+        provers.sort();
+        assert_eq!(job.committed, provers);
+        assert_eq!(job.competing, provers);
+        assert_eq!(job.winner, Some(prover(PROVER_A)));
+        assert_eq!(job.fee, Some(100));
+        assert_eq!(job.delivery_latency_ms, Some(30_000));
+    }
+
+    #[test]
+    fn collect_series_grouped_by_removal() {
+        let mut stats = SnarkMarketStats::default();
+        stats.jobs_update(t(0), &[job_id(1), job_id(2), job_id(3)]);
+        stats.snark_add(t(10), &job_id(1), &prover(PROVER_A), &fee(100));
+        stats.jobs_update(t(30), &[job_id(2), job_id(3)]);
+        stats.jobs_update(t(130), &[job_id(3)]);
+
+        let interval = Some(Duration::from_secs(60));
+        let series = stats.collect_series(t(150), interval, Some(3)).unwrap();
+        let summary = series
+            .iter()
+            .map(|entry| {
+                (
+                    entry.start,
+                    entry.jobs,
+                    entry.fee.as_ref().map(|fee| fee.max),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![(t(120), 1, None), (t(60), 0, None), (t(0), 1, Some(100))]
+        );
+
+        // no intervals before the start of the clock.
+        let series = stats.collect_series(t(150), interval, Some(10)).unwrap();
+        assert_eq!(series.len(), 3);
+        let series = stats.collect_series(t(150), interval, Some(1)).unwrap();
+        assert_eq!(series.len(), 1);
+    }
+
+    #[test]
+    fn collect_series_bounds() {
+        let stats = market();
+        let now = Timestamp::new(u64::MAX);
+        let series = |interval| stats.collect_series(now, interval, Some(usize::MAX));
+
+        assert!(matches!(
+            series(Some(Duration::ZERO)),
+            Err(SnarkMarketSeriesError::IntervalTooShort)
+        ));
+        assert!(matches!(
+            series(Some(Duration::from_millis(500))),
+            Err(SnarkMarketSeriesError::IntervalTooShort)
+        ));
+        assert!(matches!(
+            series(Some(MAX_SERIES_INTERVAL + Duration::from_secs(1))),
+            Err(SnarkMarketSeriesError::IntervalTooLong)
+        ));
+        assert!(matches!(
+            series(Some(Duration::from_secs(u64::MAX))),
+            Err(SnarkMarketSeriesError::IntervalTooLong)
+        ));
+
+        assert_eq!(
+            series(Some(MIN_SERIES_INTERVAL)).unwrap().len(),
+            MAX_SERIES_LEN
+        );
+        assert_eq!(
+            series(Some(MAX_SERIES_INTERVAL)).unwrap().len(),
+            MAX_SERIES_LEN
+        );
+        assert_eq!(series(None).unwrap().len(), MAX_SERIES_LEN);
+    }
+}
@@ -0,0 +1,15 @@
+mod transition_frontier_genesis_config;
+pub use transition_frontier_genesis_config::*;
+
+mod transition_frontier_genesis_state;
+pub use transition_frontier_genesis_state::*;
+
+mod transition_frontier_genesis_actions;
+pub use transition_frontier_genesis_actions::*;
+
+mod transition_frontier_genesis_reducer;
+
+mod transition_frontier_genesis_effects;
+
+mod transition_frontier_genesis_service;
+pub use transition_frontier_genesis_service::*;
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use crate::transition_frontier::sync::TransitionFrontierSyncState;
+
+use super::{GenesisLedgerLoaded, TransitionFrontierGenesisState};
+
+pub type TransitionFrontierGenesisActionWithMeta =
+    redux::ActionWithMeta<TransitionFrontierGenesisAction>;
+pub type TransitionFrontierGenesisActionWithMetaRef<'a> =
+    redux::ActionWithMeta<&'a TransitionFrontierGenesisAction>;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum TransitionFrontierGenesisAction {
+    LedgerLoadInit,
+    LedgerLoadPending,
+    LedgerLoadSuccess {
+        data: GenesisLedgerLoaded,
+    },
+    Produce,
+    /// Make the genesis block the initial best tip.
+    Inject,
+}
+
+impl redux::EnablingCondition<crate::State> for TransitionFrontierGenesisAction {
+    fn is_enabled(&self, state: &crate::State) -> bool {
+        let genesis_state = &state.transition_frontier.genesis;
+        match self {
+            TransitionFrontierGenesisAction::LedgerLoadInit => {
+                state.transition_frontier.config.genesis.is_some()
+                    && matches!(genesis_state, TransitionFrontierGenesisState::Idle)
+                    && state.transition_frontier.best_chain.is_empty()
+                    && matches!(
+                        state.transition_frontier.sync,
+                        TransitionFrontierSyncState::Idle
+                    )
+            }
+            TransitionFrontierGenesisAction::LedgerLoadPending => {
+                matches!(genesis_state, TransitionFrontierGenesisState::Idle)
+            }
+            TransitionFrontierGenesisAction::LedgerLoadSuccess { .. } => {
+                matches!(
+                    genesis_state,
+                    TransitionFrontierGenesisState::LedgerLoadPending { .. }
+                )
+            }
+            TransitionFrontierGenesisAction::Produce => {
+                matches!(
+                    genesis_state,
+                    TransitionFrontierGenesisState::LedgerLoadSuccess { .. }
+                )
+            }
+            TransitionFrontierGenesisAction::Inject => {
+                genesis_state.block().is_some()
+                    && state.transition_frontier.best_chain.is_empty()
+                    && matches!(
+                        state.transition_frontier.sync,
+                        TransitionFrontierSyncState::Idle
+                    )
+            }
+        }
+    }
+}
+
+use crate::transition_frontier::TransitionFrontierAction;
+
+impl From<TransitionFrontierGenesisAction> for crate::Action {
+    fn from(value: TransitionFrontierGenesisAction) -> Self {
+        Self::TransitionFrontier(TransitionFrontierAction::Genesis(value))
+    }
+}
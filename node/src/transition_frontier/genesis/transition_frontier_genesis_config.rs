@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use ledger::{
+    proofs::transaction::transaction_snark::CONSTRAINT_CONSTANTS,
+    scan_state::{
+        currency::{Amount, Signed},
+        fee_excess::FeeExcess,
+        pending_coinbase::Stack,
+        scan_state::transaction_snark::{Registers, Statement},
+        transaction_logic::local_state::LocalState,
+    },
+    staged_ledger::{
+        diff::with_valid_signatures_and_proofs::Diff, validate_block::block_body_hash,
+    },
+};
+use mina_hasher::Fp;
+use mina_p2p_messages::{
+    bigint::BigInt,
+    list::List,
+    v2::{
+        ConsensusGlobalSlotStableV1, ConsensusProofOfStakeDataConsensusStateValueStableV2,
+        ConsensusProofOfStakeDataEpochDataNextValueVersionedValueStableV1,
+        ConsensusProofOfStakeDataEpochDataStakingValueVersionedValueStableV1,
+        ConsensusVrfOutputTruncatedStableV1, CurrencyAmountStableV1, DataHashLibStateHashStableV1,
+        LedgerHash, MinaBaseEpochLedgerValueStableV1, MinaBaseEpochSeedStableV1,
+        MinaBaseStagedLedgerHashStableV1, MinaBlockBlockStableV2, MinaBlockHeaderStableV2,
+        MinaNumbersGlobalSlotSinceGenesisMStableV1, MinaNumbersGlobalSlotSinceHardForkMStableV1,
+        MinaStateBlockchainStateValueStableV2, MinaStateProtocolStateBodyValueStableV2,
+        MinaStateProtocolStateValueStableV2, NonZeroCurvePoint, ProtocolVersionStableV2,
+        StagedLedgerDiffBodyStableV1, StagedLedgerDiffDiffStableV2, StateHash,
+        UnsignedExtendedUInt32StableV1,
+    },
+};
+use mina_signer::CompressedPubKey;
+use openmina_core::block::{ArcBlockWithHash, BlockWithHash};
+use serde::{Deserialize, Serialize};
+
+use crate::account::AccountPublicKey;
+use crate::ProtocolConstants;
+
+/// Winner of the genesis block, same as in the OCaml node.
+const GENESIS_WINNER: &str = "B62qiy32p8kAKnny8ZFwoMhYpBppM1DWVCqAPBYNcXnsAHhnfAAuXgg";
+
+/// Genesis of a network which isn't bootstrapped from peers, but starts
+/// from a genesis block built locally. E.g. a testing network made only
+/// of our nodes.
+///
+/// Protocol constants of the genesis block are the ones from
+/// `TransitionFrontierConfig::protocol_constants`, with overrides from
+/// [`GenesisConfig::protocol_constants`] applied.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GenesisConfig {
+    pub accounts: Vec<GenesisConfigAccount>,
+    /// Overrides `k`, e.g. so that the root of a testing network moves
+    /// sooner.
+    #[serde(default)]
+    pub k: Option<u32>,
+    /// Overrides `slots_per_epoch`, e.g. so that a testing network
+    /// reaches epoch boundaries sooner. Must be divisible by 3.
+    #[serde(default)]
+    pub slots_per_epoch: Option<u32>,
+}
+
+impl GenesisConfig {
+    /// `defaults` with the overrides of this config applied.
+    pub fn protocol_constants(&self, defaults: &ProtocolConstants) -> ProtocolConstants {
+        let mut constants = defaults.clone();
+        if let Some(k) = self.k {
+            constants.k = k.into();
+        }
+        if let Some(slots_per_epoch) = self.slots_per_epoch {
+            constants.slots_per_epoch = slots_per_epoch.into();
+        }
+        constants
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GenesisConfigAccount {
+    pub public_key: AccountPublicKey,
+    /// Balance in nanomina.
+    pub balance: u64,
+    /// Delegates to itself if `None`.
+    pub delegate: Option<AccountPublicKey>,
+}
+
+/// Genesis ledger, as loaded by the ledger service.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GenesisLedgerLoaded {
+    pub genesis_ledger_hash: LedgerHash,
+    /// Hashes of the empty staged ledger on top of the genesis ledger.
+    pub staged_ledger_hashes: MinaBaseStagedLedgerHashStableV1,
+    pub total_currency: CurrencyAmountStableV1,
+}
+
+/// Builds the genesis block on top of the `ledger`.
+///
+/// Mirrors `Consensus.Data.Consensus_state.create_genesis` of the
+/// OCaml node, except that the block's proof is a dummy.
+pub fn genesis_block(
+    constants: &ProtocolConstants,
+    ledger: &GenesisLedgerLoaded,
+) -> ArcBlockWithHash {
+    let empty_state_hash: StateHash = DataHashLibStateHashStableV1(BigInt::zero()).into();
+    let genesis_ledger_hash = &ledger.genesis_ledger_hash;
+    let genesis_winner: NonZeroCurvePoint =
+        AccountPublicKey::from(CompressedPubKey::from_address(GENESIS_WINNER).unwrap()).into();
+
+    let epoch_ledger = MinaBaseEpochLedgerValueStableV1 {
+        hash: genesis_ledger_hash.clone(),
+        total_currency: ledger.total_currency.clone(),
+    };
+    let epoch_seed = MinaBaseEpochSeedStableV1(BigInt::zero());
+    let epoch_length = UnsignedExtendedUInt32StableV1(1.into());
+
+    let slots_per_sub_window = constants.slots_per_sub_window.as_u32();
+    let sub_windows_per_window = CONSTRAINT_CONSTANTS.sub_windows_per_window as u32;
+    let sub_window_densities = std::iter::once(0)
+        .chain(std::iter::repeat(slots_per_sub_window))
+        .take(sub_windows_per_window as usize)
+        .map(|v| UnsignedExtendedUInt32StableV1(v.into()))
+        .collect();
+
+    let consensus_state = ConsensusProofOfStakeDataConsensusStateValueStableV2 {
+        blockchain_length: UnsignedExtendedUInt32StableV1(1.into()),
+        epoch_count: UnsignedExtendedUInt32StableV1(0.into()),
+        min_window_density: UnsignedExtendedUInt32StableV1(
+            (slots_per_sub_window * sub_windows_per_window).into(),
+        ),
+        sub_window_densities,
+        last_vrf_output: ConsensusVrfOutputTruncatedStableV1((&[0u8; 32][..]).into()),
+        total_currency: ledger.total_currency.clone(),
+        curr_global_slot_since_hard_fork: ConsensusGlobalSlotStableV1 {
+            slot_number: MinaNumbersGlobalSlotSinceHardForkMStableV1::SinceHardFork(0.into()),
+            slots_per_epoch: constants.slots_per_epoch.clone(),
+        },
+        global_slot_since_genesis: MinaNumbersGlobalSlotSinceGenesisMStableV1::SinceGenesis(
+            0.into(),
+        ),
+        staking_epoch_data: ConsensusProofOfStakeDataEpochDataStakingValueVersionedValueStableV1 {
+            ledger: epoch_ledger.clone(),
+            seed: epoch_seed.clone().into(),
+            start_checkpoint: empty_state_hash.clone(),
+            lock_checkpoint: empty_state_hash.clone(),
+            epoch_length: epoch_length.clone(),
+        },
+        next_epoch_data: ConsensusProofOfStakeDataEpochDataNextValueVersionedValueStableV1 {
+            ledger: epoch_ledger,
+            seed: epoch_seed.into(),
+            start_checkpoint: empty_state_hash.clone(),
+            lock_checkpoint: empty_state_hash.clone(),
+            epoch_length,
+        },
+        has_ancestor_in_same_checkpoint_window: true,
+        block_stake_winner: genesis_winner.clone(),
+        block_creator: genesis_winner.clone(),
+        coinbase_receiver: genesis_winner,
+        supercharge_coinbase: true,
+    };
+
+    let genesis_ledger_fp: Fp = genesis_ledger_hash.to_field();
+    let registers = Registers {
+        first_pass_ledger: genesis_ledger_fp,
+        second_pass_ledger: genesis_ledger_fp,
+        pending_coinbase_stack: Stack::empty(),
+        local_state: LocalState::empty(),
+    };
+    let ledger_proof_statement = Statement {
+        source: registers.clone(),
+        target: registers,
+        connecting_ledger_left: genesis_ledger_fp,
+        connecting_ledger_right: genesis_ledger_fp,
+        supply_increase: Signed::<Amount>::zero(),
+        fee_excess: FeeExcess::empty(),
+        sok_digest: (),
+    };
+
+    let diff = StagedLedgerDiffDiffStableV2::from(&Diff::empty());
+    let body_reference = block_body_hash(&diff).expect("empty diff must be hashable");
+
+    let protocol_state = MinaStateProtocolStateValueStableV2 {
+        previous_state_hash: empty_state_hash.clone(),
+        body: MinaStateProtocolStateBodyValueStableV2 {
+            genesis_state_hash: empty_state_hash,
+            blockchain_state: MinaStateBlockchainStateValueStableV2 {
+                staged_ledger_hash: ledger.staged_ledger_hashes.clone(),
+                genesis_ledger_hash: genesis_ledger_hash.clone(),
+                ledger_proof_statement: (&ledger_proof_statement).into(),
+                timestamp: constants.genesis_state_timestamp.clone(),
+                body_reference,
+            },
+            consensus_state,
+            constants: constants.clone(),
+        },
+    };
+    let body_hash = protocol_state.body.hash();
+    let hash = StateHash::from_hashes(&protocol_state.previous_state_hash, &body_hash);
+
+    let block = MinaBlockBlockStableV2 {
+        header: MinaBlockHeaderStableV2 {
+            protocol_state,
+            protocol_state_proof: (*ledger::dummy::dummy_blockchain_proof()).clone(),
+            delta_block_chain_proof: (hash.clone(), List::new()),
+            current_protocol_version: ProtocolVersionStableV2 {
+                transaction: 3.into(),
+                network: 0.into(),
+                patch: 0.into(),
+            },
+            proposed_protocol_version_opt: None,
+        },
+        body: StagedLedgerDiffBodyStableV1 {
+            staged_ledger_diff: diff,
+        },
+    };
+
+    BlockWithHash {
+        hash,
+        block: Arc::new(block),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transition_frontier::TransitionFrontierConfig;
+
+    use super::*;
+
+    #[test]
+    fn protocol_constants_overrides() {
+        let defaults = TransitionFrontierConfig::default().protocol_constants;
+        let mut config = GenesisConfig {
+            accounts: vec![],
+            k: None,
+            slots_per_epoch: None,
+        };
+        assert_eq!(config.protocol_constants(&defaults), defaults);
+
+        config.k = Some(24);
+        config.slots_per_epoch = Some(576);
+        let constants = config.protocol_constants(&defaults);
+        assert_eq!(constants.k.as_u32(), 24);
+        assert_eq!(constants.slots_per_epoch.as_u32(), 576);
+        assert_eq!(
+            constants.slots_per_sub_window,
+            defaults.slots_per_sub_window
+        );
+        assert_eq!(
+            constants.genesis_state_timestamp,
+            defaults.genesis_state_timestamp
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use redux::ActionMeta;
+
+use crate::transition_frontier::TransitionFrontierSyncedAction;
+use crate::Store;
+
+use super::{TransitionFrontierGenesisAction, TransitionFrontierGenesisService};
+
+impl TransitionFrontierGenesisAction {
+    pub fn effects<S: redux::Service>(&self, _: &ActionMeta, store: &mut Store<S>)
+    where
+        S: TransitionFrontierGenesisService,
+    {
+        match self {
+            TransitionFrontierGenesisAction::LedgerLoadInit => {
+                let Some(config) = store.state().transition_frontier.config.genesis.clone() else {
+                    return;
+                };
+                store.dispatch(TransitionFrontierGenesisAction::LedgerLoadPending);
+                let data = store.service.load_genesis(&config);
+                store.dispatch(TransitionFrontierGenesisAction::LedgerLoadSuccess { data });
+            }
+            TransitionFrontierGenesisAction::LedgerLoadPending => {}
+            TransitionFrontierGenesisAction::LedgerLoadSuccess { .. } => {
+                store.dispatch(TransitionFrontierGenesisAction::Produce);
+            }
+            TransitionFrontierGenesisAction::Produce => {
+                store.dispatch(TransitionFrontierGenesisAction::Inject);
+            }
+            TransitionFrontierGenesisAction::Inject => {
+                store.dispatch(TransitionFrontierSyncedAction {
+                    needed_protocol_states: Default::default(),
+                });
+            }
+        }
+    }
+}
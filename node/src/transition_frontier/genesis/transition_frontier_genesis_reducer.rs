@@ -0,0 +1,40 @@
+use crate::ProtocolConstants;
+
+use super::{
+    genesis_block, TransitionFrontierGenesisAction, TransitionFrontierGenesisActionWithMetaRef,
+    TransitionFrontierGenesisState,
+};
+
+impl TransitionFrontierGenesisState {
+    pub fn reducer(
+        &mut self,
+        action: TransitionFrontierGenesisActionWithMetaRef<'_>,
+        constants: &ProtocolConstants,
+    ) {
+        let (action, meta) = action.split();
+        match action {
+            TransitionFrontierGenesisAction::LedgerLoadInit => {}
+            TransitionFrontierGenesisAction::LedgerLoadPending => {
+                *self = Self::LedgerLoadPending { time: meta.time() };
+            }
+            TransitionFrontierGenesisAction::LedgerLoadSuccess { data } => {
+                *self = Self::LedgerLoadSuccess {
+                    time: meta.time(),
+                    data: data.clone(),
+                };
+            }
+            TransitionFrontierGenesisAction::Produce => {
+                let Self::LedgerLoadSuccess { data, .. } = self else {
+                    return;
+                };
+                *self = Self::Produced {
+                    time: meta.time(),
+                    block: genesis_block(constants, data),
+                };
+            }
+            TransitionFrontierGenesisAction::Inject => {
+                // handled in parent.
+            }
+        }
+    }
+}
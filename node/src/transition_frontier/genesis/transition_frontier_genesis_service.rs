@@ -0,0 +1,7 @@
+use super::{GenesisConfig, GenesisLedgerLoaded};
+
+pub trait TransitionFrontierGenesisService: redux::Service {
+    /// Creates the genesis ledger and the empty staged ledger on top of
+    /// it, so that blocks can be applied on top of the genesis block.
+    fn load_genesis(&mut self, config: &GenesisConfig) -> GenesisLedgerLoaded;
+}
@@ -0,0 +1,36 @@
+use openmina_core::block::ArcBlockWithHash;
+use redux::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use super::GenesisLedgerLoaded;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum TransitionFrontierGenesisState {
+    Idle,
+    LedgerLoadPending {
+        time: Timestamp,
+    },
+    LedgerLoadSuccess {
+        time: Timestamp,
+        data: GenesisLedgerLoaded,
+    },
+    Produced {
+        time: Timestamp,
+        block: ArcBlockWithHash,
+    },
+}
+
+impl TransitionFrontierGenesisState {
+    pub fn block(&self) -> Option<&ArcBlockWithHash> {
+        match self {
+            Self::Produced { block, .. } => Some(block),
+            _ => None,
+        }
+    }
+}
+
+impl Default for TransitionFrontierGenesisState {
+    fn default() -> Self {
+        Self::Idle
+    }
+}
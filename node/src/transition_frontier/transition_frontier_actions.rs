@@ -3,6 +3,7 @@ use std::collections::BTreeSet;
 use mina_p2p_messages::v2::StateHash;
 use serde::{Deserialize, Serialize};
 
+use super::genesis::TransitionFrontierGenesisAction;
 use super::sync::{TransitionFrontierSyncAction, TransitionFrontierSyncState};
 
 pub type TransitionFrontierActionWithMeta = redux::ActionWithMeta<TransitionFrontierAction>;
@@ -11,6 +12,7 @@ pub type TransitionFrontierActionWithMetaRef<'a> =
 
 #[derive(derive_more::From, Serialize, Deserialize, Debug, Clone)]
 pub enum TransitionFrontierAction {
+    Genesis(TransitionFrontierGenesisAction),
     Sync(TransitionFrontierSyncAction),
     Synced(TransitionFrontierSyncedAction),
 }
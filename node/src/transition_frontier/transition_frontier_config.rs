@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use mina_p2p_messages::v2::{
     BlockTimeTimeStableV1, UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
 };
@@ -5,9 +7,15 @@ use serde::{Deserialize, Serialize};
 
 use crate::ProtocolConstants;
 
+use super::genesis::GenesisConfig;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransitionFrontierConfig {
     pub protocol_constants: ProtocolConstants,
+    /// Build the genesis block locally instead of syncing to peers' best
+    /// tip, in a network which we bootstrap ourselves.
+    #[serde(default)]
+    pub genesis: Option<Arc<GenesisConfig>>,
 }
 
 impl TransitionFrontierConfig {
@@ -30,6 +38,7 @@ impl Default for TransitionFrontierConfig {
                     UnsignedExtendedUInt64Int64ForVersionTagsStableV1(0.into()),
                 ),
             },
+            genesis: None,
         }
     }
 }
@@ -27,6 +27,9 @@ pub fn transition_frontier_effects<S: crate::Service>(
     let (action, meta) = action.split();
 
     match action {
+        TransitionFrontierAction::Genesis(a) => {
+            a.effects(&meta, store);
+        }
         TransitionFrontierAction::Sync(a) => {
             match a {
                 TransitionFrontierSyncAction::Init { ref best_tip, .. } => {
@@ -283,6 +286,9 @@ pub fn transition_frontier_effects<S: crate::Service>(
             if let Some(stats) = store.service.stats() {
                 stats.new_best_tip(meta.time(), best_tip);
             }
+            store
+                .service
+                .archive_best_chain_update(&store.state.get().transition_frontier.best_chain);
 
             // publish new best tip.
             let best_tip = best_tip.clone();
@@ -1,3 +1,4 @@
+use super::genesis::TransitionFrontierGenesisAction;
 use super::sync::TransitionFrontierSyncState;
 use super::{
     TransitionFrontierAction, TransitionFrontierActionWithMetaRef, TransitionFrontierState,
@@ -7,6 +8,21 @@ impl TransitionFrontierState {
     pub fn reducer(&mut self, action: TransitionFrontierActionWithMetaRef<'_>) {
         let (action, meta) = action.split();
         match action {
+            TransitionFrontierAction::Genesis(a) => {
+                self.genesis
+                    .reducer(meta.with_action(a), &self.config.protocol_constants);
+                if let TransitionFrontierGenesisAction::Inject = a {
+                    let Some(block) = self.genesis.block() else {
+                        return;
+                    };
+                    self.sync = TransitionFrontierSyncState::BlocksSuccess {
+                        time: meta.time(),
+                        chain: vec![block.clone()],
+                        root_snarked_ledger_updates: Default::default(),
+                        needed_protocol_states: Default::default(),
+                    };
+                }
+            }
             TransitionFrontierAction::Sync(a) => {
                 self.sync
                     .reducer(meta.with_action(a), &self.config, &self.best_chain);
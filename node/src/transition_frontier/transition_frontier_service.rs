@@ -45,3 +45,14 @@ pub trait TransitionFrontierService: redux::Service {
         protocol_states: BTreeMap<StateHash, MinaStateProtocolStateValueStableV2>,
     ) -> Option<Arc<StagedLedgerAuxAndPendingCoinbases>>;
 }
+
+pub trait TransitionFrontierArchiveService: redux::Service {
+    /// Called each time the transition frontier switches to a new best
+    /// chain. `best_chain` starts with the root block and ends with the
+    /// best tip.
+    fn archive_best_chain_update(&mut self, best_chain: &[ArcBlockWithHash]);
+
+    /// Called for a block, which consensus decided not to use as the best
+    /// tip. Such block is never applied, so only its header is archived.
+    fn archive_fork_block_add(&mut self, block: &ArcBlockWithHash);
+}
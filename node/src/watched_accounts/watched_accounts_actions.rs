@@ -21,6 +21,9 @@ pub enum WatchedAccountsAction {
     Add {
         pub_key: NonZeroCurvePoint,
     },
+    Remove {
+        pub_key: NonZeroCurvePoint,
+    },
     LedgerInitialStateGetInit {
         pub_key: NonZeroCurvePoint,
     },
@@ -87,6 +90,7 @@ impl redux::EnablingCondition<crate::State> for WatchedAccountsAction {
     fn is_enabled(&self, state: &crate::State) -> bool {
         match self {
             WatchedAccountsAction::Add { pub_key } => state.watched_accounts.get(pub_key).is_none(),
+            WatchedAccountsAction::Remove { pub_key } => state.watched_accounts.contains(pub_key),
             WatchedAccountsAction::LedgerInitialStateGetInit { pub_key } => {
                 should_request_ledger_initial_state(state, pub_key)
             }
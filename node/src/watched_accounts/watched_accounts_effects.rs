@@ -19,6 +19,7 @@ pub fn watched_accounts_effects<S: redux::Service>(
         WatchedAccountsAction::Add { pub_key } => {
             store.dispatch(WatchedAccountsAction::LedgerInitialStateGetInit { pub_key });
         }
+        WatchedAccountsAction::Remove { .. } => {}
         WatchedAccountsAction::TransactionsIncludedInBlock { pub_key, block } => {
             store.dispatch(WatchedAccountsAction::BlockLedgerQueryInit {
                 pub_key,
@@ -17,6 +17,9 @@ impl WatchedAccountsState {
                     },
                 );
             },
+            WatchedAccountsAction::Remove { pub_key } => {
+                self.remove(pub_key);
+            },
             WatchedAccountsAction::LedgerInitialStateGetInit { .. } => {},
             WatchedAccountsAction::LedgerInitialStateGetPending { pub_key, block, peer_id } => {
                 let Some(account) = self.get_mut(pub_key) else {
@@ -177,6 +177,10 @@ impl WatchedAccountsState {
         self.list.insert(key, value);
     }
 
+    pub fn remove(&mut self, key: &NonZeroCurvePoint) -> Option<WatchedAccountState> {
+        self.list.remove(key)
+    }
+
     pub fn iter<'a>(
         &'a self,
     ) -> impl 'a + Iterator<Item = (&'a NonZeroCurvePoint, &'a WatchedAccountState)> {
@@ -13,13 +13,16 @@ use std::time::Duration;
 use std::{collections::VecDeque, sync::Arc};
 
 use libp2p::futures::{stream::FuturesUnordered, StreamExt};
+use mina_p2p_messages::v2::{
+    BlockTimeTimeStableV1, UnsignedExtendedUInt64Int64ForVersionTagsStableV1,
+};
 use node::snark::{VerifierIndex, VerifierSRS};
 use node::core::channels::mpsc;
 use node::core::requests::RpcId;
 use node::p2p::connection::outgoing::P2pConnectionOutgoingInitOpts;
 use node::p2p::{P2pConnectionEvent, P2pDiscoveryEvent, PeerId};
 use node::{
-    account::{AccountPublicKey, AccountSecretKey},
+    account::{AccountLocalSigner, AccountPublicKey, AccountSecretKey},
     event_source::Event,
     ledger::LedgerCtx,
     p2p::{
@@ -37,7 +40,8 @@ use node::{
     TransitionFrontierConfig,
 };
 use openmina_node_invariants::{InvariantResult, Invariants};
-use openmina_node_native::{http_server, rpc::RpcService, NodeService, RpcSender};
+use openmina_node_native::http_server::{self, HttpServerConfig};
+use openmina_node_native::{rpc::RpcService, NodeService, RpcSender};
 use rand::{rngs::StdRng, SeedableRng};
 use serde::Serialize;
 
@@ -181,6 +185,20 @@ impl Cluster {
             })
             .collect();
 
+        let transition_frontier = {
+            let mut config = TransitionFrontierConfig::default();
+            if let Some(genesis) = testing_config.genesis {
+                config.protocol_constants = genesis.protocol_constants(&config.protocol_constants);
+                let genesis_ms = u64::from(testing_config.initial_time) / 1_000_000;
+                config.protocol_constants.genesis_state_timestamp =
+                    BlockTimeTimeStableV1(UnsignedExtendedUInt64Int64ForVersionTagsStableV1(
+                        genesis_ms.into(),
+                    ));
+                config.genesis = Some(genesis);
+            }
+            config
+        };
+
         let config = Config {
             ledger: LedgerConfig {},
             snark: SnarkConfig {
@@ -193,6 +211,7 @@ impl Cluster {
             global: GlobalConfig {
                 build: BuildEnv::get().into(),
                 snarker: testing_config.snark_worker,
+                clock_skew_correction: false,
             },
             p2p: P2pConfig {
                 libp2p_port: Some(libp2p_port),
@@ -203,7 +222,7 @@ impl Cluster {
                 ask_initial_peers_interval: testing_config.ask_initial_peers_interval,
                 enabled_channels: ChannelId::iter_all().collect(),
             },
-            transition_frontier: TransitionFrontierConfig::default(),
+            transition_frontier,
             block_producer: block_producer_config,
         };
 
@@ -211,6 +230,7 @@ impl Cluster {
 
         let (p2p_event_sender, mut rx) = mpsc::unbounded_channel::<P2pEvent>();
 
+        let http_config = HttpServerConfig::new(http_port, testing_config.chain_id.clone());
         let webrtc_with_libp2p::P2pServiceCtx {
             libp2p,
             webrtc: P2pServiceCtx { cmd_sender, peers },
@@ -248,7 +268,7 @@ impl Cluster {
                 let task = async {
                     tokio::select! {
                         _ = shutdown.closed() => {}
-                        _ = http_server::run(http_port, rpc_sender) => {}
+                        _ = http_server::run(http_config, rpc_sender) => {}
                     }
                 };
                 local_set.block_on(&runtime, task);
@@ -266,7 +286,11 @@ impl Cluster {
             peers,
             libp2p,
             block_producer: None,
-            snark_worker_sender: None,
+            block_producer_history_persist: None,
+            snark_workers: Default::default(),
+            archive: None,
+            snark_pool_persist: None,
+            snark_verifier: Default::default(),
             rpc: rpc_service,
             stats: node::stats::Stats::new(),
             recorder: Recorder::None,
@@ -274,7 +298,7 @@ impl Cluster {
             invariants_state: Default::default(),
         };
         if let Some(producer_key) = block_producer_sec_key {
-            real_service.block_producer_start(producer_key.into());
+            real_service.block_producer_start(Arc::new(AccountLocalSigner::new([producer_key])));
         }
         let mut service = NodeTestingService::new(real_service, node_id, shutdown_rx);
         if self.config.all_rust_to_rust_use_webrtc() {
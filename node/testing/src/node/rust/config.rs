@@ -1,5 +1,7 @@
+use std::sync::Arc;
 use std::time::Duration;
 
+use node::transition_frontier::genesis::GenesisConfig;
 use node::{account::AccountSecretKey, BlockProducerConfig, SnarkerConfig};
 use serde::{Deserialize, Serialize};
 
@@ -26,6 +28,10 @@ pub struct RustNodeTestingConfig {
     pub peer_id: TestPeerId,
     pub snark_worker: Option<SnarkerConfig>,
     pub block_producer: Option<RustNodeBlockProducerTestingConfig>,
+    /// Build genesis block locally from this config, instead of syncing
+    /// to the best tip of peers. `initial_time` is used as genesis timestamp.
+    #[serde(default)]
+    pub genesis: Option<Arc<GenesisConfig>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -45,6 +51,7 @@ impl RustNodeTestingConfig {
             peer_id: TestPeerId::default(),
             block_producer: None,
             snark_worker: None,
+            genesis: None,
         }
     }
 
@@ -72,4 +79,9 @@ impl RustNodeTestingConfig {
         self.peer_id = TestPeerId::Bytes(bytes);
         self
     }
+
+    pub fn genesis(mut self, config: Arc<GenesisConfig>) -> Self {
+        self.genesis = Some(config);
+        self
+    }
 }
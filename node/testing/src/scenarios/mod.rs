@@ -9,6 +9,7 @@
 //! Dynamic IP Handling: Nodes with frequently changing IP addresses should maintain stable connections.
 
 pub mod multi_node;
+pub mod rust_only;
 pub mod simulation;
 pub mod solo_node;
 
@@ -27,6 +28,7 @@ use crate::scenario::{Scenario, ScenarioId, ScenarioStep};
 use self::multi_node::basic_connectivity_initial_joining::MultiNodeBasicConnectivityInitialJoining;
 use self::multi_node::basic_connectivity_peer_discovery::MultiNodeBasicConnectivityPeerDiscovery;
 use self::multi_node::sync_4_block_producers::MultiNodeSync4BlockProducers;
+use self::rust_only::{delegation::RustOnlyDelegation, small::RustOnlySmall};
 use self::simulation::small::SimulationSmall;
 use self::solo_node::sync_to_genesis::SoloNodeSyncToGenesis;
 use self::solo_node::{
@@ -46,6 +48,8 @@ pub enum Scenarios {
     MultiNodeBasicConnectivityInitialJoining(MultiNodeBasicConnectivityInitialJoining),
     MultiNodeBasicConnectivityPeerDiscovery(MultiNodeBasicConnectivityPeerDiscovery),
     SimulationSmall(SimulationSmall),
+    RustOnlySmall(RustOnlySmall),
+    RustOnlyDelegation(RustOnlyDelegation),
 }
 
 impl Scenarios {
@@ -64,6 +68,8 @@ impl Scenarios {
             Self::MultiNodeBasicConnectivityInitialJoining(_) => false,
             Self::MultiNodeBasicConnectivityPeerDiscovery(_) => cfg!(feature = "p2p-webrtc"),
             Self::SimulationSmall(_) => false,
+            Self::RustOnlySmall(_) => false,
+            Self::RustOnlyDelegation(_) => false,
         }
     }
 
@@ -85,6 +91,8 @@ impl Scenarios {
             Self::MultiNodeBasicConnectivityInitialJoining(_) => None,
             Self::MultiNodeBasicConnectivityPeerDiscovery(_) => None,
             Self::SimulationSmall(_) => None,
+            Self::RustOnlySmall(_) => None,
+            Self::RustOnlyDelegation(_) => None,
         }
     }
 
@@ -111,6 +119,8 @@ impl Scenarios {
                 MultiNodeBasicConnectivityPeerDiscovery::DOCS
             }
             Self::SimulationSmall(_) => SimulationSmall::DOCS,
+            Self::RustOnlySmall(_) => RustOnlySmall::DOCS,
+            Self::RustOnlyDelegation(_) => RustOnlyDelegation::DOCS,
         }
     }
 
@@ -136,6 +146,8 @@ impl Scenarios {
             Self::MultiNodeBasicConnectivityInitialJoining(v) => v.run(runner).await,
             Self::MultiNodeBasicConnectivityPeerDiscovery(v) => v.run(runner).await,
             Self::SimulationSmall(v) => v.run(runner).await,
+            Self::RustOnlySmall(v) => v.run(runner).await,
+            Self::RustOnlyDelegation(v) => v.run(runner).await,
         }
     }
 
@@ -31,9 +31,10 @@ impl MultiNodeSync4BlockProducers {
             runner.add_rust_node(RustNodeTestingConfig {
                 block_producer: Some(RustNodeBlockProducerTestingConfig {
                     config: BlockProducerConfig {
-                        pub_key: sec_key.public_key().into(),
+                        pub_keys: vec![sec_key.public_key().into()],
                         custom_coinbase_receiver: None,
                         proposed_protocol_version: None,
+                        dry_run: false,
                     },
                     sec_key,
                 }),
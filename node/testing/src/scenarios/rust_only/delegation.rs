@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use crate::scenarios::ClusterRunner;
+
+use super::{RustOnlyNetwork, RustOnlyNetworkConfig};
+
+/// Network of Rust block producers only, whose stake is mostly delegated
+/// to them, starting from a locally built genesis block.
+///
+/// Run until `epoch_count >= 2` and then until all nodes agree on the best tip.
+/// Asserts that staking epoch ledger and seed rotate at epoch boundaries.
+///
+/// - block producers: **4**.
+/// - delegators: **5** per block producer.
+/// - slots per epoch: **576**, k: **24**.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct RustOnlyDelegation;
+
+impl RustOnlyDelegation {
+    pub async fn run(self, runner: ClusterRunner<'_>) {
+        let mut network = RustOnlyNetwork::new(RustOnlyNetworkConfig {
+            block_producers: 4,
+            delegators_per_producer: 5,
+            run_until_epoch: 2,
+            run_until_timeout: Duration::from_secs(60 * 60),
+        });
+        network.run(runner).await;
+    }
+}
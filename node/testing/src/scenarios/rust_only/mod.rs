@@ -0,0 +1,247 @@
+//! Networks made only of Rust nodes, which start from a genesis block
+//! built locally, so no OCaml node is needed to bootstrap them.
+
+pub mod delegation;
+pub mod small;
+
+use std::{sync::Arc, time::Duration};
+
+use node::{
+    account::AccountSecretKey,
+    transition_frontier::genesis::{GenesisConfig, GenesisConfigAccount},
+    BlockProducerConfig, State,
+};
+use rand::{Rng, SeedableRng};
+
+use crate::{
+    cluster::ClusterNodeId,
+    node::{RustNodeBlockProducerTestingConfig, RustNodeTestingConfig},
+    scenario::{ListenerNode, ScenarioStep},
+    scenarios::{ClusterRunner, RunDecision},
+};
+
+/// 2024-01-01T00:00:00Z
+const GENESIS_TIME_NANOS: u64 = 1704067200000_000_000;
+
+const MINA: u64 = 1_000_000_000;
+
+/// Much shorter than on mainnet (290), so that the root moves and epoch
+/// ledgers rotate within the scenario's timeout.
+const K: u32 = 24;
+/// Much shorter than on mainnet (7140), 1 epoch is 28.8 hours of slots.
+const SLOTS_PER_EPOCH: u32 = 576;
+
+#[derive(Debug, Clone)]
+pub struct RustOnlyNetworkConfig {
+    pub block_producers: usize,
+    /// Number of accounts, which delegate to each block producer.
+    pub delegators_per_producer: usize,
+    pub run_until_epoch: u32,
+    pub run_until_timeout: Duration,
+}
+
+pub struct RustOnlyNetwork {
+    config: RustOnlyNetworkConfig,
+}
+
+impl RustOnlyNetwork {
+    pub fn new(config: RustOnlyNetworkConfig) -> Self {
+        Self { config }
+    }
+
+    fn genesis_and_producers(&self) -> (GenesisConfig, Vec<AccountSecretKey>) {
+        let producers_count = self.config.block_producers as u32;
+        let delegators_per_producer = self.config.delegators_per_producer as u32;
+        let producers = (0..producers_count)
+            .map(|i| secret_key(1 + i))
+            .collect::<Vec<_>>();
+
+        let accounts = producers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, sec_key)| {
+                let producer = sec_key.public_key();
+                // producers have different stakes, so that they aren't
+                // winning slots equally often.
+                let producer_account = GenesisConfigAccount {
+                    public_key: producer.clone(),
+                    balance: (i as u64 + 1) * 1_000_000 * MINA,
+                    delegate: None,
+                };
+                let first_delegator = 1 + producers_count + i as u32 * delegators_per_producer;
+                let delegators = (0..delegators_per_producer).map(move |j| GenesisConfigAccount {
+                    public_key: secret_key(first_delegator + j).public_key(),
+                    balance: 100_000 * MINA,
+                    delegate: Some(producer.clone()),
+                });
+                std::iter::once(producer_account).chain(delegators)
+            })
+            .collect();
+
+        let genesis = GenesisConfig {
+            accounts,
+            k: Some(K),
+            slots_per_epoch: Some(SLOTS_PER_EPOCH),
+        };
+        (genesis, producers)
+    }
+
+    fn set_up_block_producer_nodes(&self, runner: &mut ClusterRunner<'_>) -> Vec<ClusterNodeId> {
+        let (genesis, producers) = self.genesis_and_producers();
+        eprintln!(
+            "setting up rust block producer nodes: {}; genesis accounts: {}",
+            producers.len(),
+            genesis.accounts.len()
+        );
+
+        let node_config = RustNodeTestingConfig {
+            initial_time: redux::Timestamp::new(GENESIS_TIME_NANOS),
+            max_peers: 100,
+            ask_initial_peers_interval: Duration::from_secs(60),
+            ..RustNodeTestingConfig::berkeley_default()
+        }
+        .genesis(Arc::new(genesis));
+
+        let mut nodes: Vec<ClusterNodeId> = Vec::new();
+        for sec_key in producers {
+            eprintln!("block producer({})", sec_key.public_key());
+            let config = RustNodeTestingConfig {
+                block_producer: Some(RustNodeBlockProducerTestingConfig {
+                    config: BlockProducerConfig {
+                        pub_keys: vec![sec_key.public_key().into()],
+                        custom_coinbase_receiver: None,
+                        proposed_protocol_version: None,
+                        dry_run: false,
+                    },
+                    sec_key,
+                }),
+                // first node is the seed node for the rest.
+                initial_peers: nodes
+                    .first()
+                    .map(|id| ListenerNode::Rust(*id))
+                    .into_iter()
+                    .collect(),
+                ..node_config.clone()
+            };
+            nodes.push(runner.add_rust_node(config));
+        }
+        nodes
+    }
+
+    /// Checks that whenever the best chain crosses an epoch boundary,
+    /// the staking epoch data of the new epoch is the next epoch data
+    /// of the previous one.
+    fn assert_epoch_ledgers_rotation(node_id: ClusterNodeId, state: &State) {
+        for pair in state.transition_frontier.best_chain.windows(2) {
+            let pred = &pair[0].header().protocol_state.body.consensus_state;
+            let block = &pair[1].header().protocol_state.body.consensus_state;
+            if block.epoch_count.as_u32() == pred.epoch_count.as_u32() {
+                continue;
+            }
+            assert_eq!(
+                block.staking_epoch_data.ledger.hash,
+                pred.next_epoch_data.ledger.hash,
+                "node_{node_id}: staking epoch ledger not rotated at block {}",
+                pair[1].hash()
+            );
+            assert_eq!(
+                block.staking_epoch_data.seed,
+                pred.next_epoch_data.seed,
+                "node_{node_id}: staking epoch seed not rotated at block {}",
+                pair[1].hash()
+            );
+        }
+    }
+
+    async fn run_until_consensus(&self, runner: &mut ClusterRunner<'_>, nodes: &[ClusterNodeId]) {
+        eprintln!("waiting for all rust nodes to agree on the best tip");
+        let best_tip_hash = |runner: &ClusterRunner<'_>, node_id: ClusterNodeId| {
+            let node = runner.node(node_id).unwrap();
+            let best_tip = node.state().transition_frontier.best_tip()?;
+            Some(best_tip.hash().clone())
+        };
+        let mut timeout = Duration::from_secs(5 * 60);
+        while !timeout.is_zero() {
+            let t = redux::Instant::now();
+            let _ = runner
+                .run(
+                    Duration::from_secs(1),
+                    |_, _, _| RunDecision::ContinueExec,
+                    |_, _, _, _| false,
+                )
+                .await;
+
+            let first = best_tip_hash(runner, nodes[0]);
+            if first.is_some() && nodes.iter().all(|id| best_tip_hash(runner, *id) == first) {
+                eprintln!("all rust nodes agree on the best tip: {}", first.unwrap());
+                return;
+            }
+            timeout = timeout.saturating_sub(t.elapsed());
+        }
+
+        panic!("rust nodes didn't reach consensus on the best tip");
+    }
+
+    pub async fn run<'a>(&mut self, mut runner: ClusterRunner<'a>) {
+        let nodes = self.set_up_block_producer_nodes(&mut runner);
+
+        let run_until_epoch = self.config.run_until_epoch;
+        let mut timeout = self.config.run_until_timeout;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+
+        while !timeout.is_zero() {
+            let t = redux::Instant::now();
+            tokio::task::yield_now().await;
+            let _ = runner
+                .run(
+                    Duration::ZERO,
+                    |_, _, _| RunDecision::ContinueExec,
+                    |_, _, _, _| false,
+                )
+                .await;
+
+            let mut reached_epoch = true;
+            for (node_id, node) in runner.nodes_iter() {
+                Self::assert_epoch_ledgers_rotation(node_id, node.state());
+                let Some(best_tip) = node.state().transition_frontier.best_tip() else {
+                    reached_epoch = false;
+                    continue;
+                };
+                let consensus_state = &best_tip.header().protocol_state.body.consensus_state;
+                let cur_epoch = consensus_state.epoch_count.as_u32();
+                eprintln!(
+                    "[node_status] node_{node_id} {} - {} [{}]; epoch: {cur_epoch}",
+                    best_tip.height(),
+                    best_tip.hash(),
+                    best_tip.producer(),
+                );
+                reached_epoch &= cur_epoch >= run_until_epoch;
+            }
+            if reached_epoch {
+                self.run_until_consensus(&mut runner, &nodes).await;
+                return;
+            }
+
+            // warp global time by a fraction of the slot, so that nodes
+            // have a chance to exchange blocks produced in the slot.
+            let advance_time = Duration::from_secs(rng.gen_range(10..60));
+            let elapsed = t.elapsed();
+            let by_nanos = advance_time.as_nanos() as u64;
+            runner
+                .exec_step(ScenarioStep::AdvanceTime { by_nanos })
+                .await
+                .unwrap();
+
+            timeout = timeout.saturating_sub(elapsed);
+        }
+
+        panic!("rust only network simulation timed out");
+    }
+}
+
+/// Deterministic keys, so that runs of the scenarios are reproducible.
+fn secret_key(n: u32) -> AccountSecretKey {
+    let mut bytes = [0; 32];
+    bytes[28..].copy_from_slice(&n.to_be_bytes());
+    AccountSecretKey::from_bytes(&bytes).expect("small scalar is a valid secret key")
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+use crate::scenarios::ClusterRunner;
+
+use super::{RustOnlyNetwork, RustOnlyNetworkConfig};
+
+/// Small network of Rust block producers only, starting from a locally
+/// built genesis block.
+///
+/// Run until `epoch_count >= 3` and then until all nodes agree on the best tip.
+/// Asserts that staking epoch ledger and seed rotate at epoch boundaries.
+///
+/// - block producers: **3**.
+/// - delegators: **0**.
+/// - slots per epoch: **576**, k: **24**.
+#[derive(documented::Documented, Default, Clone, Copy)]
+pub struct RustOnlySmall;
+
+impl RustOnlySmall {
+    pub async fn run(self, runner: ClusterRunner<'_>) {
+        let mut network = RustOnlyNetwork::new(RustOnlyNetworkConfig {
+            block_producers: 3,
+            delegators_per_producer: 0,
+            run_until_epoch: 3,
+            run_until_timeout: Duration::from_secs(60 * 60),
+        });
+        network.run(runner).await;
+    }
+}
@@ -2,7 +2,7 @@ mod rpc_service;
 
 use std::sync::Mutex;
 use std::time::Duration;
-use std::{collections::BTreeMap, ffi::OsStr, sync::Arc};
+use std::{collections::{BTreeMap, BTreeSet}, ffi::OsStr, sync::Arc};
 
 use ledger::dummy::dummy_transaction_proof;
 use ledger::scan_state::scan_state::transaction_snark::SokMessage;
@@ -15,11 +15,13 @@ use mina_p2p_messages::v2::{
     TransactionSnarkWorkTStableV2Proofs,
 };
 use node::account::AccountPublicKey;
+use node::block_producer::{BlockProducerHistory, BlockProducerHistoryPersistService};
 use node::block_producer::vrf_evaluator::VrfEvaluatorInput;
+use node::core::block::ArcBlockWithHash;
 use node::core::channels::mpsc;
 use node::core::requests::{PendingRequests, RequestId};
-use node::core::snark::{Snark, SnarkJobId};
-use node::external_snark_worker::ExternalSnarkWorkerEvent;
+use node::core::snark::{Snark, SnarkJobCommitment, SnarkJobId};
+use node::external_snark_worker::{ExternalSnarkWorkerEvent, ExternalSnarkWorkerId};
 use node::recorder::Recorder;
 use node::service::BlockProducerVrfEvaluatorService;
 use node::snark::block_verify::{
@@ -27,8 +29,9 @@ use node::snark::block_verify::{
 };
 use node::snark::work_verify::{SnarkWorkVerifyId, SnarkWorkVerifyService};
 use node::snark::{SnarkEvent, VerifierIndex, VerifierSRS};
-use node::snark_pool::{JobState, SnarkPoolService};
+use node::snark_pool::{JobState, SnarkPoolPersistService, SnarkPoolPersisted, SnarkPoolService};
 use node::stats::Stats;
+use node::transition_frontier::TransitionFrontierArchiveService;
 use node::{
     event_source::Event,
     external_snark_worker::{ExternalSnarkWorkerService, SnarkWorkSpec},
@@ -43,7 +46,7 @@ use node::{
         webrtc, P2pEvent, PeerId,
     },
 };
-use node::{ActionWithMeta, State};
+use node::{ActionWithMeta, SnarkerWorkerKind, State};
 use openmina_node_native::NodeService;
 use redux::Instant;
 
@@ -73,6 +76,7 @@ pub struct NodeTestingService {
     pending_events: PendingRequests<PendingEventIdType, Event>,
     dyn_effects: Option<DynEffects>,
 
+    snarker_public_key: Option<NonZeroCurvePoint>,
     snarker_sok_digest: Option<ByteString>,
     /// Once dropped, it will cause all threads associated to shutdown.
     _shutdown: mpsc::Receiver<()>,
@@ -88,6 +92,7 @@ impl NodeTestingService {
             monotonic_time: Instant::now(),
             pending_events: PendingRequests::new(),
             dyn_effects: None,
+            snarker_public_key: None,
             snarker_sok_digest: None,
             _shutdown,
         }
@@ -344,36 +349,84 @@ impl SnarkPoolService for NodeTestingService {
     }
 }
 
+impl SnarkPoolPersistService for NodeTestingService {
+    fn snark_pool_persisted_take(&mut self) -> SnarkPoolPersisted {
+        self.real.snark_pool_persisted_take()
+    }
+
+    fn snark_pool_persist_snark(&mut self, snark: &Snark, received_t: redux::Timestamp) {
+        self.real.snark_pool_persist_snark(snark, received_t)
+    }
+
+    fn snark_pool_persist_commitment(
+        &mut self,
+        commitment: &SnarkJobCommitment,
+        received_t: redux::Timestamp,
+    ) {
+        self.real.snark_pool_persist_commitment(commitment, received_t)
+    }
+
+    fn snark_pool_persist_retain(&mut self, job_ids: BTreeSet<SnarkJobId>) {
+        self.real.snark_pool_persist_retain(job_ids)
+    }
+}
+
+impl BlockProducerHistoryPersistService for NodeTestingService {
+    fn block_producer_history_persisted_take(&mut self) -> Option<BlockProducerHistory> {
+        self.real.block_producer_history_persisted_take()
+    }
+
+    fn block_producer_history_persist(&mut self, history: &BlockProducerHistory) {
+        self.real.block_producer_history_persist(history)
+    }
+}
+
 impl BlockProducerVrfEvaluatorService for NodeTestingService {
     fn evaluate(&mut self, data: VrfEvaluatorInput) {
         BlockProducerVrfEvaluatorService::evaluate(&mut self.real, data)
     }
 }
 
+impl TransitionFrontierArchiveService for NodeTestingService {
+    fn archive_best_chain_update(&mut self, best_chain: &[ArcBlockWithHash]) {
+        self.real.archive_best_chain_update(best_chain)
+    }
+
+    fn archive_fork_block_add(&mut self, block: &ArcBlockWithHash) {
+        self.real.archive_fork_block_add(block)
+    }
+}
+
 impl ExternalSnarkWorkerService for NodeTestingService {
     fn start<P: AsRef<OsStr>>(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
+        kind: SnarkerWorkerKind,
         path: P,
         public_key: NonZeroCurvePoint,
-        fee: CurrencyFeeStableV1,
     ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
-        let _ = path;
+        let _ = (kind, path);
 
-        let pub_key = AccountPublicKey::from(public_key);
-        let sok_message = SokMessage::create((&fee).into(), pub_key.into());
-        self.set_snarker_sok_digest((&sok_message.digest()).into());
+        self.snarker_public_key = Some(public_key);
         let _ = self
             .real
             .event_sender
-            .send(ExternalSnarkWorkerEvent::Started.into());
+            .send((worker_id, ExternalSnarkWorkerEvent::Started).into());
         Ok(())
-        // self.real.start(path, public_key, fee)
+        // self.real.start(worker_id, kind, path, public_key)
     }
 
     fn submit(
         &mut self,
+        worker_id: ExternalSnarkWorkerId,
         spec: SnarkWorkSpec,
+        fee: CurrencyFeeStableV1,
     ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
+        if let Some(public_key) = self.snarker_public_key.clone() {
+            let pub_key = AccountPublicKey::from(public_key);
+            let sok_message = SokMessage::create((&fee).into(), pub_key.into());
+            self.set_snarker_sok_digest((&sok_message.digest()).into());
+        }
         let sok_digest = self.snarker_sok_digest.clone().unwrap();
         let make_dummy_proof = |spec| {
             let statement = match spec {
@@ -401,30 +454,34 @@ impl ExternalSnarkWorkerService for NodeTestingService {
                 make_dummy_proof(v2),
             )),
         };
-        let _ = self
-            .real
-            .event_sender
-            .send(ExternalSnarkWorkerEvent::WorkResult(Arc::new(res)).into());
+        let event = ExternalSnarkWorkerEvent::WorkResult(Arc::new(res));
+        let _ = self.real.event_sender.send((worker_id, event).into());
         Ok(())
-        // self.real.submit(spec)
+        // self.real.submit(worker_id, spec, fee)
     }
 
-    fn cancel(&mut self) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
+    fn cancel(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+    ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         let _ = self
             .real
             .event_sender
-            .send(ExternalSnarkWorkerEvent::WorkCancelled.into());
+            .send((worker_id, ExternalSnarkWorkerEvent::WorkCancelled).into());
         Ok(())
-        // self.real.cancel()
+        // self.real.cancel(worker_id)
     }
 
-    fn kill(&mut self) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
+    fn kill(
+        &mut self,
+        worker_id: ExternalSnarkWorkerId,
+    ) -> Result<(), node::external_snark_worker::ExternalSnarkWorkerError> {
         let _ = self
             .real
             .event_sender
-            .send(ExternalSnarkWorkerEvent::Killed.into());
+            .send((worker_id, ExternalSnarkWorkerEvent::Killed).into());
         Ok(())
-        // self.real.kill()
+        // self.real.kill(worker_id)
     }
 }
 
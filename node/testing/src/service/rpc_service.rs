@@ -15,6 +15,14 @@ impl RpcService for super::NodeTestingService {
         self.real.respond_sync_stats_get(rpc_id, response)
     }
 
+    fn respond_snark_market_stats_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcSnarkMarketStatsGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_snark_market_stats_get(rpc_id, response)
+    }
+
     fn respond_action_stats_get(
         &mut self,
         rpc_id: RpcId,
@@ -104,6 +112,30 @@ impl RpcService for super::NodeTestingService {
         self.real.respond_snarker_workers(rpc_id, response)
     }
 
+    fn respond_snarkers_reliability(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcSnarkersReliabilityResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_snarkers_reliability(rpc_id, response)
+    }
+
+    fn respond_snarker_work_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcSnarkerWorkGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_snarker_work_get(rpc_id, response)
+    }
+
+    fn respond_snarker_work_submit(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcSnarkerWorkSubmitResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_snarker_work_submit(rpc_id, response)
+    }
+
     fn respond_snarker_config_get(
         &mut self,
         rpc_id: RpcId,
@@ -127,4 +159,89 @@ impl RpcService for super::NodeTestingService {
     ) -> Result<(), RespondError> {
         self.real.respond_readiness_check(rpc_id, response)
     }
+
+    fn respond_watched_accounts_add(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcWatchedAccountsAddResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_watched_accounts_add(rpc_id, response)
+    }
+
+    fn respond_watched_accounts_remove(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcWatchedAccountsRemoveResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_watched_accounts_remove(rpc_id, response)
+    }
+
+    fn respond_watched_accounts_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcWatchedAccountsGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_watched_accounts_get(rpc_id, response)
+    }
+
+    fn respond_watched_account_blocks_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcWatchedAccountBlocksGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real
+            .respond_watched_account_blocks_get(rpc_id, response)
+    }
+
+    fn respond_account_proof_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcAccountProofGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_account_proof_get(rpc_id, response)
+    }
+
+    fn respond_block_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcBlockGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real.respond_block_get(rpc_id, response)
+    }
+
+    fn respond_transition_frontier_status_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcTransitionFrontierStatusGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real
+            .respond_transition_frontier_status_get(rpc_id, response)
+    }
+
+    fn respond_block_producer_schedule_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcBlockProducerScheduleGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real
+            .respond_block_producer_schedule_get(rpc_id, response)
+    }
+
+    fn respond_block_producer_history_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcBlockProducerHistoryGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real
+            .respond_block_producer_history_get(rpc_id, response)
+    }
+
+    fn respond_block_producer_epoch_data_get(
+        &mut self,
+        rpc_id: RpcId,
+        response: node::rpc::RpcBlockProducerEpochDataGetResponse,
+    ) -> Result<(), RespondError> {
+        self.real
+            .respond_block_producer_epoch_data_get(rpc_id, response)
+    }
 }
@@ -6,7 +6,9 @@ use mina_p2p_messages::v2::{
 
 use std::{collections::BTreeSet, time::Duration};
 
-use node::{ActionKind, BlockProducerConfig, SnarkerConfig, SnarkerStrategy, State};
+use node::{
+    ActionKind, BlockProducerConfig, SnarkerConfig, SnarkerStrategy, SnarkerWorkerKind, State,
+};
 use rand::{Rng, SeedableRng};
 
 use crate::{
@@ -52,6 +54,7 @@ impl Simulator {
             peer_id: Default::default(),
             block_producer: None,
             snark_worker: None,
+            genesis: None,
         }
     }
 
@@ -191,8 +194,12 @@ impl Simulator {
                     )),
                     strategy: SnarkerStrategy::Sequential,
                     auto_commit: true,
+                    min_payout_per_sec: 0,
                     // TODO(binier): fix if we want to use real snarker.
                     path: "".into(),
+                    workers: 1,
+                    worker_kind: SnarkerWorkerKind::External,
+                    fee_policy: None,
                 }),
                 ..node_config.clone()
             };
@@ -229,9 +236,10 @@ impl Simulator {
             let config = RustNodeTestingConfig {
                 block_producer: Some(RustNodeBlockProducerTestingConfig {
                     config: BlockProducerConfig {
-                        pub_key: sec_key.public_key().into(),
+                        pub_keys: vec![sec_key.public_key().into()],
                         custom_coinbase_receiver: None,
                         proposed_protocol_version: None,
+                        dry_run: false,
                     },
                     sec_key,
                 }),
@@ -0,0 +1,5 @@
+use openmina_node_testing::scenarios::rust_only::small::RustOnlySmall;
+
+mod common;
+
+scenario_test!(small, RustOnlySmall, RustOnlySmall);
@@ -7,6 +7,9 @@ use crate::{VerifierIndex, VerifierSRS};
 use super::SnarkWorkVerifyId;
 
 pub trait SnarkWorkVerifyService: redux::Service {
+    /// Verify the `work`. Implementations may verify work from multiple
+    /// requests, received within a short window, in one batch, as long
+    /// as the result is reported for each request separately.
     fn verify_init(
         &mut self,
         req_id: SnarkWorkVerifyId,
@@ -1,3 +1,5 @@
+mod rewards;
+
 use std::{borrow::Cow, fs::File, path::PathBuf};
 
 use reqwest::Url;
@@ -14,7 +16,15 @@ use mina_signer::CompressedPubKey;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
-struct Args {
+enum Command {
+    /// Converts a json ledger to binprot format.
+    Convert(ConvertArgs),
+    /// Computes payouts to delegators of a block producer.
+    Rewards(rewards::RewardsArgs),
+}
+
+#[derive(StructOpt)]
+struct ConvertArgs {
     #[structopt(short, long)]
     input: Option<PathBuf>,
     #[structopt(long)]
@@ -23,6 +33,23 @@ struct Args {
     output: PathBuf,
 }
 
+/// Slot in a json ledger, either `["Since_genesis", "<slot>"]` as in
+/// genesis ledgers or just `"<slot>"` as in exported ledgers.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum SlotJson {
+    Tagged([String; 2]),
+    Plain(String),
+}
+
+impl SlotJson {
+    fn parse(&self) -> anyhow::Result<u32> {
+        match self {
+            Self::Tagged([_, slot]) | Self::Plain(slot) => Ok(slot.parse()?),
+        }
+    }
+}
+
 fn parse_account(mut a: serde_json::Value) -> anyhow::Result<Account> {
     let account_value = a
         .as_object_mut()
@@ -60,9 +87,9 @@ fn parse_account(mut a: serde_json::Value) -> anyhow::Result<Account> {
         #[derive(Deserialize, Debug)]
         struct Timed {
             initial_minimum_balance: String,
-            cliff_time: [String; 2],
+            cliff_time: SlotJson,
             cliff_amount: String,
-            vesting_period: [String; 2],
+            vesting_period: SlotJson,
             vesting_increment: String,
         }
 
@@ -86,9 +113,9 @@ fn parse_account(mut a: serde_json::Value) -> anyhow::Result<Account> {
 
         account.timing = Timing::Timed {
             initial_minimum_balance: Balance::of_mina_string_exn(&initial_minimum_balance),
-            cliff_time: Slot::from_u32(cliff_time[1].parse()?),
+            cliff_time: Slot::from_u32(cliff_time.parse()?),
             cliff_amount: Balance::of_mina_string_exn(&cliff_amount).to_amount(),
-            vesting_period: SlotSpan::from_u32(vesting_period[1].parse()?),
+            vesting_period: SlotSpan::from_u32(vesting_period.parse()?),
             vesting_increment: Balance::of_mina_string_exn(&vesting_increment).to_amount(),
         };
     }
@@ -102,7 +129,26 @@ fn parse_account(mut a: serde_json::Value) -> anyhow::Result<Account> {
 }
 
 fn main() -> anyhow::Result<()> {
-    let Args { input, url, output } = Args::from_args();
+    let mut args = std::env::args_os().collect::<Vec<_>>();
+    // `convert` used to be the only command, keep accepting its flags
+    // without the subcommand.
+    let is_flag = args
+        .get(1)
+        .and_then(|arg| arg.to_str())
+        .map_or(false, |arg| {
+            arg.starts_with('-') && !matches!(arg, "-h" | "--help" | "-V" | "--version")
+        });
+    if is_flag {
+        args.insert(1, "convert".into());
+    }
+    match Command::from_iter(args) {
+        Command::Convert(args) => convert(args),
+        Command::Rewards(args) => rewards::run(args),
+    }
+}
+
+fn convert(args: ConvertArgs) -> anyhow::Result<()> {
+    let ConvertArgs { input, url, output } = args;
 
     let value = if let Some(input) = input {
         let ledger_file = File::open(&input)?;
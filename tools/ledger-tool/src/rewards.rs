@@ -0,0 +1,513 @@
+use std::{collections::BTreeSet, fmt::Write as _, path::PathBuf};
+
+use reqwest::{StatusCode, Url};
+use serde::Deserialize;
+
+use ledger::{
+    scan_state::{
+        currency::{Magnitude, Slot},
+        transaction_logic::account_min_balance_at_slot,
+    },
+    Account, Timing,
+};
+
+use mina_p2p_messages::{binprot::BinProtRead, v2};
+use mina_signer::CompressedPubKey;
+
+use structopt::StructOpt;
+
+const NANOMINA: u128 = 1_000_000_000;
+
+#[derive(StructOpt)]
+pub struct RewardsArgs {
+    /// Staking epoch ledger of the blocks, binprot as produced by
+    /// `convert`, or json (`.json` extension).
+    #[structopt(long)]
+    ledger: PathBuf,
+    /// Block producer public key, whose delegators are paid.
+    #[structopt(long)]
+    producer: String,
+    /// Json files with blocks as returned by the node's `/blocks/{height}`
+    /// endpoint, either one block or an array of them.
+    #[structopt(long)]
+    blocks: Vec<PathBuf>,
+    /// Fetch blocks from the node's http rpc, e.g. `http://localhost:3000`.
+    #[structopt(long, requires_all = &["from-height", "to-height"])]
+    node: Option<Url>,
+    #[structopt(long)]
+    from_height: Option<u32>,
+    /// Inclusive.
+    #[structopt(long)]
+    to_height: Option<u32>,
+    /// Percentage of rewards kept by the pool operator.
+    #[structopt(long, default_value = "0")]
+    pool_fee: f64,
+    /// Factor by which the coinbase is multiplied, if the block's
+    /// coinbase is supercharged.
+    #[structopt(long, default_value = "2")]
+    supercharged_coinbase_factor: u64,
+    /// Output csv file.
+    #[structopt(short, long)]
+    output: PathBuf,
+}
+
+/// `node::rpc::RpcBlockWithTransactions`.
+#[derive(Deserialize)]
+struct BlockWithTransactions {
+    block: BlockWithHash,
+    transactions: Vec<BlockTransaction>,
+}
+
+#[derive(Deserialize)]
+struct BlockWithHash {
+    hash: v2::StateHash,
+    block: v2::MinaBlockBlockStableV2,
+}
+
+#[derive(Deserialize)]
+struct BlockTransaction {
+    transaction: v2::MinaTransactionTransactionStableV2,
+    status: v2::MinaBaseTransactionStatusStableV2,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BlocksFile {
+    Many(Vec<BlockWithTransactions>),
+    One(Box<BlockWithTransactions>),
+}
+
+struct Delegator {
+    account: Account,
+    coinbase: u128,
+    fees: u128,
+}
+
+pub fn run(args: RewardsArgs) -> anyhow::Result<()> {
+    let producer = CompressedPubKey::from_address(&args.producer)?;
+    if !(0.0..=100.0).contains(&args.pool_fee) {
+        anyhow::bail!("`--pool-fee` must be a percentage");
+    }
+    if args.supercharged_coinbase_factor == 0 {
+        anyhow::bail!("`--supercharged-coinbase-factor` must not be zero");
+    }
+
+    let (ledger_hash, accounts) = load_ledger(&args.ledger)?;
+    let mut delegators = accounts
+        .into_iter()
+        .filter(|account| account.delegate.as_ref() == Some(&producer))
+        .map(|account| Delegator {
+            account,
+            coinbase: 0,
+            fees: 0,
+        })
+        .collect::<Vec<_>>();
+    let total_stake = delegators
+        .iter()
+        .map(|d| d.account.balance.as_u64() as u128)
+        .sum::<u128>();
+    if total_stake == 0 {
+        anyhow::bail!("producer has no stake in the ledger");
+    }
+
+    let mut blocks = Vec::new();
+    for path in &args.blocks {
+        let file = std::fs::File::open(path)?;
+        match serde_json::from_reader(file)? {
+            BlocksFile::Many(list) => blocks.extend(list),
+            BlocksFile::One(block) => blocks.push(*block),
+        }
+    }
+    if let (Some(node), Some(from), Some(to)) = (&args.node, args.from_height, args.to_height) {
+        blocks.extend(fetch_blocks(node, from, to)?);
+    }
+    // same block may be in several files or also fetched from the node.
+    let mut seen = BTreeSet::new();
+    blocks.retain(|block| seen.insert(block.block.hash.clone()));
+
+    let mut blocks_count = 0;
+    for block in &blocks {
+        let consensus_state = &block.block.block.header.protocol_state.body.consensus_state;
+        if CompressedPubKey::from(&consensus_state.block_creator) != producer {
+            continue;
+        }
+        if let Some(ledger_hash) = &ledger_hash {
+            if &consensus_state.staking_epoch_data.ledger.hash != ledger_hash {
+                eprintln!(
+                    "skipping block {}, staking ledger is {}",
+                    block.block.hash, consensus_state.staking_epoch_data.ledger.hash
+                );
+                continue;
+            }
+        }
+        blocks_count += 1;
+
+        let slot = Slot::from_u32(consensus_state.global_slot_since_genesis.as_u32());
+        let (coinbase, fees) = block_revenue(block, &consensus_state.coinbase_receiver);
+
+        // locked stake doesn't earn the supercharged part of the coinbase.
+        let (base_coinbase, supercharged) = if consensus_state.supercharge_coinbase {
+            let base = coinbase / args.supercharged_coinbase_factor as u128;
+            (base, coinbase - base)
+        } else {
+            (coinbase, 0)
+        };
+        let unlocked_stake = |d: &Delegator| {
+            let balance = d.account.balance.as_u64() as u128;
+            balance.saturating_sub(locked_balance(&d.account, slot))
+        };
+        let total_unlocked_stake = delegators.iter().map(unlocked_stake).sum::<u128>();
+
+        for delegator in delegators.iter_mut() {
+            let stake = delegator.account.balance.as_u64() as u128;
+            delegator.coinbase += base_coinbase * stake / total_stake;
+            if total_unlocked_stake > 0 {
+                delegator.coinbase +=
+                    supercharged * unlocked_stake(delegator) / total_unlocked_stake;
+            }
+            delegator.fees += fees * stake / total_stake;
+        }
+    }
+
+    let pool_fee_ppm = pool_fee_ppm(args.pool_fee);
+    let mut csv = String::from("public_key,stake,stake_share,coinbase,fees,pool_fee,payout\n");
+    for delegator in &delegators {
+        let stake = delegator.account.balance.as_u64() as u128;
+        let revenue = delegator.coinbase + delegator.fees;
+        let (pool_fee, payout) = pool_fee_split(revenue, pool_fee_ppm);
+        writeln!(
+            csv,
+            "{},{},{:.6},{},{},{},{}",
+            delegator.account.public_key.into_address(),
+            mina(stake),
+            stake as f64 / total_stake as f64,
+            mina(delegator.coinbase),
+            mina(delegator.fees),
+            mina(pool_fee),
+            mina(payout),
+        )?;
+    }
+    std::fs::write(&args.output, csv)?;
+
+    println!(
+        "{blocks_count} blocks produced by {}, {} delegators",
+        args.producer,
+        delegators.len()
+    );
+    Ok(())
+}
+
+fn load_ledger(path: &PathBuf) -> anyhow::Result<(Option<v2::LedgerHash>, Vec<Account>)> {
+    let bytes = std::fs::read(path)?;
+    if path.extension().map_or(false, |ext| ext == "json") {
+        let value = serde_json::from_slice::<serde_json::Value>(&bytes)?;
+        // exported ledger is a list of accounts, genesis ledger an object.
+        let list = match value
+            .get("ledger")
+            .and_then(|ledger| ledger.get("accounts"))
+        {
+            Some(accounts) => accounts.clone(),
+            None => value,
+        };
+        let list = list
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("expected array of accounts"))?;
+        let accounts = list
+            .iter()
+            .enumerate()
+            .map(|(n, item)| {
+                parse_staking_account(item)
+                    .map_err(|err| anyhow::anyhow!("account: {n}, err: {err}"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok((None, accounts))
+    } else {
+        let mut slice = bytes.as_slice();
+        let hash = Option::<v2::LedgerHash>::binprot_read(&mut slice)?;
+        let accounts = Vec::<Account>::binprot_read(&mut slice)?;
+        Ok((hash, accounts))
+    }
+}
+
+/// Only the fields relevant for staking are kept, exported ledgers
+/// contain many more.
+fn parse_staking_account(value: &serde_json::Value) -> anyhow::Result<Account> {
+    let object = value
+        .as_object()
+        .ok_or_else(|| anyhow::anyhow!("expected object"))?;
+    let staking_fields = object
+        .iter()
+        .filter(|(key, _)| matches!(key.as_str(), "pk" | "balance" | "delegate" | "timing"))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    super::parse_account(serde_json::Value::Object(staking_fields))
+}
+
+fn fetch_blocks(node: &Url, from: u32, to: u32) -> anyhow::Result<Vec<BlockWithTransactions>> {
+    let client = reqwest::blocking::Client::new();
+    let mut blocks = Vec::new();
+    for height in from..=to {
+        let url = node.join(&format!("blocks/{height}"))?;
+        let resp = client.get(url).send()?;
+        if resp.status() == StatusCode::NOT_FOUND {
+            eprintln!("block at height {height} not found");
+            continue;
+        }
+        blocks.push(resp.error_for_status()?.json()?);
+    }
+    Ok(blocks)
+}
+
+/// Coinbase and fees received by the coinbase receiver in the block,
+/// after paying snark workers.
+fn block_revenue(block: &BlockWithTransactions, receiver: &v2::NonZeroCurvePoint) -> (u128, u128) {
+    let (mut coinbase, mut fees) = (0, 0);
+    let applied = block
+        .transactions
+        .iter()
+        .filter(|tx| matches!(tx.status, v2::MinaBaseTransactionStatusStableV2::Applied))
+        .map(|tx| &tx.transaction);
+    for transaction in applied {
+        match transaction {
+            v2::MinaTransactionTransactionStableV2::Coinbase(cb) if &cb.receiver == receiver => {
+                let fee_transfer = cb
+                    .fee_transfer
+                    .as_ref()
+                    .filter(|ft| &ft.receiver_pk != receiver)
+                    .map_or(0, |ft| ft.fee.0.as_u64());
+                coinbase += cb.amount.0.as_u64().saturating_sub(fee_transfer) as u128;
+            }
+            v2::MinaTransactionTransactionStableV2::FeeTransfer(ft) => {
+                let transfers = match ft {
+                    v2::MinaBaseFeeTransferStableV2::One(a) => vec![a],
+                    v2::MinaBaseFeeTransferStableV2::Two((a, b)) => vec![a, b],
+                };
+                fees += transfers
+                    .into_iter()
+                    .filter(|ft| &ft.receiver_pk == receiver)
+                    .map(|ft| ft.fee.0.as_u64() as u128)
+                    .sum::<u128>();
+            }
+            _ => {}
+        }
+    }
+    (coinbase, fees)
+}
+
+/// Part of the balance which is still locked at `slot`.
+fn locked_balance(account: &Account, slot: Slot) -> u128 {
+    match &account.timing {
+        Timing::Untimed => 0,
+        Timing::Timed {
+            initial_minimum_balance,
+            cliff_time,
+            cliff_amount,
+            vesting_period,
+            vesting_increment,
+        } => {
+            let min_balance = account_min_balance_at_slot(
+                slot,
+                *cliff_time,
+                *cliff_amount,
+                *vesting_period,
+                *vesting_increment,
+                *initial_minimum_balance,
+            );
+            min_balance.min(account.balance).as_u64() as u128
+        }
+    }
+}
+
+/// Pool fee percentage in parts per million.
+fn pool_fee_ppm(percent: f64) -> u128 {
+    (percent * 10_000.0).round() as u128
+}
+
+/// Splits delegator's revenue into the pool fee and the payout.
+fn pool_fee_split(revenue: u128, pool_fee_ppm: u128) -> (u128, u128) {
+    let pool_fee = revenue * pool_fee_ppm / 1_000_000;
+    (pool_fee, revenue - pool_fee)
+}
+
+fn mina(nanomina: u128) -> String {
+    format!("{}.{:09}", nanomina / NANOMINA, nanomina % NANOMINA)
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger::{
+        scan_state::currency::{Amount, Balance, SlotSpan},
+        TokenId,
+    };
+    use openmina_core::block::test_block;
+
+    use super::*;
+
+    const PRODUCER: &str = "B62qrztYfPinaKqpXaYGY6QJ3SSW2NNKs7SajBLF1iFNXW9BoALN2Aq";
+    const SNARKER: &str = "B62qmkso2Knz9pxo5V9YEZFJ9Frq57GZfKgem1DVTKiYH9D5H3n2DGS";
+
+    fn key(address: &str) -> v2::NonZeroCurvePoint {
+        address.parse().unwrap()
+    }
+
+    fn fee(nanomina: u64) -> v2::CurrencyFeeStableV1 {
+        v2::CurrencyFeeStableV1(v2::UnsignedExtendedUInt64Int64ForVersionTagsStableV1(
+            nanomina.into(),
+        ))
+    }
+
+    fn coinbase(
+        receiver: &str,
+        amount: u64,
+        fee_transfer: Option<(&str, u64)>,
+    ) -> v2::MinaTransactionTransactionStableV2 {
+        v2::MinaTransactionTransactionStableV2::Coinbase(v2::MinaBaseCoinbaseStableV1 {
+            receiver: key(receiver),
+            amount: v2::CurrencyAmountStableV1(
+                v2::UnsignedExtendedUInt64Int64ForVersionTagsStableV1(amount.into()),
+            ),
+            fee_transfer: fee_transfer.map(|(receiver, nanomina)| {
+                v2::MinaBaseCoinbaseFeeTransferStableV1 {
+                    receiver_pk: key(receiver),
+                    fee: fee(nanomina),
+                }
+            }),
+        })
+    }
+
+    fn fee_transfer_single(receiver: &str, nanomina: u64) -> v2::MinaBaseFeeTransferSingleStableV2 {
+        v2::MinaBaseFeeTransferSingleStableV2 {
+            receiver_pk: key(receiver),
+            fee: fee(nanomina),
+            fee_token: TokenId::default().into(),
+        }
+    }
+
+    fn block(
+        transactions: Vec<(v2::MinaTransactionTransactionStableV2, bool)>,
+    ) -> BlockWithTransactions {
+        let block = test_block();
+        let transactions = transactions
+            .into_iter()
+            .map(|(transaction, applied)| BlockTransaction {
+                transaction,
+                status: if applied {
+                    v2::MinaBaseTransactionStatusStableV2::Applied
+                } else {
+                    v2::MinaBaseTransactionStatusStableV2::Failed(
+                        v2::MinaBaseTransactionStatusFailureCollectionStableV1(Default::default()),
+                    )
+                },
+            })
+            .collect();
+        BlockWithTransactions {
+            block: BlockWithHash {
+                hash: block.hash.clone(),
+                block: (*block.block).clone(),
+            },
+            transactions,
+        }
+    }
+
+    #[test]
+    fn block_revenue_subtracts_snark_fee_from_coinbase() {
+        let block = block(vec![
+            (coinbase(PRODUCER, 720_000, Some((SNARKER, 20_000))), true),
+            (
+                v2::MinaTransactionTransactionStableV2::FeeTransfer(
+                    v2::MinaBaseFeeTransferStableV2::Two((
+                        fee_transfer_single(PRODUCER, 3_000),
+                        fee_transfer_single(SNARKER, 1_000),
+                    )),
+                ),
+                true,
+            ),
+        ]);
+        assert_eq!(block_revenue(&block, &key(PRODUCER)), (700_000, 3_000));
+        assert_eq!(block_revenue(&block, &key(SNARKER)), (0, 1_000));
+    }
+
+    #[test]
+    fn block_revenue_keeps_coinbase_fee_transfer_to_itself() {
+        let block = block(vec![(
+            coinbase(PRODUCER, 720_000, Some((PRODUCER, 20_000))),
+            true,
+        )]);
+        assert_eq!(block_revenue(&block, &key(PRODUCER)), (720_000, 0));
+    }
+
+    #[test]
+    fn block_revenue_ignores_failed_transactions() {
+        let block = block(vec![
+            (coinbase(PRODUCER, 720_000, None), false),
+            (
+                v2::MinaTransactionTransactionStableV2::FeeTransfer(
+                    v2::MinaBaseFeeTransferStableV2::One(fee_transfer_single(PRODUCER, 3_000)),
+                ),
+                false,
+            ),
+            (
+                v2::MinaTransactionTransactionStableV2::FeeTransfer(
+                    v2::MinaBaseFeeTransferStableV2::One(fee_transfer_single(PRODUCER, 5_000)),
+                ),
+                true,
+            ),
+        ]);
+        assert_eq!(block_revenue(&block, &key(PRODUCER)), (0, 5_000));
+    }
+
+    fn account(balance: u64, timing: Timing) -> Account {
+        let mut account = Account::empty();
+        account.balance = Balance::from_u64(balance);
+        account.timing = timing;
+        account
+    }
+
+    fn timed() -> Timing {
+        Timing::Timed {
+            initial_minimum_balance: Balance::from_u64(1_000),
+            cliff_time: Slot::from_u32(10),
+            cliff_amount: Amount::from_u64(200),
+            vesting_period: SlotSpan::from_u32(5),
+            vesting_increment: Amount::from_u64(100),
+        }
+    }
+
+    #[test]
+    fn locked_balance_vests_after_cliff() {
+        let account = account(1_500, timed());
+        let locked = |slot| locked_balance(&account, Slot::from_u32(slot));
+        assert_eq!(locked(0), 1_000);
+        assert_eq!(locked(9), 1_000);
+        assert_eq!(locked(10), 800);
+        assert_eq!(locked(14), 800);
+        assert_eq!(locked(15), 700);
+        assert_eq!(locked(50), 0);
+    }
+
+    #[test]
+    fn locked_balance_is_at_most_balance() {
+        assert_eq!(
+            locked_balance(&account(500, timed()), Slot::from_u32(0)),
+            500
+        );
+        assert_eq!(
+            locked_balance(&account(500, Timing::Untimed), Slot::from_u32(0)),
+            0
+        );
+    }
+
+    #[test]
+    fn pool_fee_split_keeps_revenue() {
+        assert_eq!(pool_fee_ppm(0.0), 0);
+        assert_eq!(pool_fee_ppm(5.0), 50_000);
+        assert_eq!(pool_fee_ppm(2.5), 25_000);
+        assert_eq!(pool_fee_ppm(100.0), 1_000_000);
+
+        assert_eq!(pool_fee_split(1_000_000, 0), (0, 1_000_000));
+        assert_eq!(pool_fee_split(1_000_000, 50_000), (50_000, 950_000));
+        assert_eq!(pool_fee_split(1_000_000, 1_000_000), (1_000_000, 0));
+        // pool fee is rounded down, remainder goes to the delegator.
+        assert_eq!(pool_fee_split(999, 25_000), (24, 975));
+    }
+}
@@ -0,0 +1,41 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::Context;
+use clap::Parser;
+
+use node::account::{AccountLocalSigner, AccountSecretKey, AccountSigner};
+use openmina_node_native::remote_signer::{self, RemoteSignerAddr};
+
+/// Reference remote signer, holds producer keys for nodes started with
+/// a remote signer.
+#[derive(Parser)]
+struct Args {
+    /// Socket to listen on, `unix:<path>`. Only the owner can connect to it.
+    #[arg(long, short, env = "OPENMINA_SIGNER_LISTEN")]
+    listen: RemoteSignerAddr,
+    /// File with base58 encoded private keys, one per line.
+    #[arg(long, short, env = "OPENMINA_SIGNER_KEYS")]
+    keys: PathBuf,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let keys = std::fs::read_to_string(&args.keys)
+        .with_context(|| format!("reading keys from {}", args.keys.display()))?;
+    let keys = keys
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.parse::<AccountSecretKey>())
+        .collect::<Result<Vec<_>, _>>()
+        .context("parsing keys")?;
+
+    let signer = AccountLocalSigner::new(keys);
+    for public_key in signer.public_keys()? {
+        println!("serving key {public_key}");
+    }
+    println!("listening on {}", args.listen);
+    remote_signer::serve(&args.listen, Arc::new(signer))?;
+    Ok(())
+}
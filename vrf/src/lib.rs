@@ -15,7 +15,7 @@ use thiserror::Error;
 
 use mina_curves::pasta::curves::pallas::Pallas as CurvePoint;
 use mina_hasher::{create_kimchi, Hasher};
-use mina_signer::Keypair;
+use mina_signer::{CompressedPubKey, Keypair};
 use o1_utils::FieldHelpers;
 use threshold::Threshold;
 
@@ -26,6 +26,9 @@ mod threshold;
 // TODO: remove after finalization, and `hidden_glob_reexports` lint attribute at beginning of file
 pub use num::*;
 
+/// Number of bits of the vrf output hash kept in the consensus state.
+const VRF_OUTPUT_NBITS: u32 = 253;
+
 type VrfResult<T> = std::result::Result<T, VrfError>;
 type BaseField = <CurvePoint as AffineCurve>::BaseField;
 type ScalarField = <CurvePoint as AffineCurve>::ScalarField;
@@ -199,6 +202,97 @@ pub fn evaluate_vrf(vrf_input: VrfEvaluationInput) -> VrfResult<VrfEvaluationOut
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VrfThresholdCheckOutput {
+    pub producer: String,
+    pub global_slot: u32,
+    pub account_index: AccountIndex,
+    pub vrf_fractional: f64,
+    /// The slot is won if `vrf_fractional` doesn't exceed it.
+    pub threshold: f64,
+    pub threshold_met: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VrfThresholdCheckInput {
+    producer_public_key: CompressedPubKey,
+    global_slot: u32,
+    delegator_index: AccountIndex,
+    delegated_stake: BigInt,
+    total_currency: BigInt,
+    /// Truncated vrf output, `last_vrf_output` of the block's consensus state.
+    vrf_output_bytes: Vec<u8>,
+}
+
+impl VrfThresholdCheckInput {
+    pub fn new(
+        producer_public_key: CompressedPubKey,
+        global_slot: u32,
+        delegator_index: AccountIndex,
+        delegated_stake: BigInt,
+        total_currency: BigInt,
+        vrf_output_bytes: Vec<u8>,
+    ) -> Self {
+        Self {
+            producer_public_key,
+            global_slot,
+            delegator_index,
+            delegated_stake,
+            total_currency,
+            vrf_output_bytes,
+        }
+    }
+}
+
+/// Checks that the vrf output embedded in a block's consensus state is a
+/// valid truncated output which entitles `delegated_stake` to the slot.
+///
+/// This is not a verification of the vrf evaluation. The block header
+/// doesn't carry the evaluation proof, so that the output was derived from
+/// the producer's key, epoch seed, `global_slot` and `delegator_index` can
+/// only be checked by the block's snark or, with the producer's private
+/// key, by comparing against [`evaluate_vrf`].
+pub fn check_vrf_threshold(input: VrfThresholdCheckInput) -> VrfResult<VrfThresholdCheckOutput> {
+    let VrfThresholdCheckInput {
+        producer_public_key,
+        global_slot,
+        delegator_index,
+        delegated_stake,
+        total_currency,
+        vrf_output_bytes,
+    } = input;
+
+    let vrf_output_repr = ScalarField::from_bytes(&vrf_output_bytes)?.into_repr();
+    if vrf_output_repr.num_bits() > VRF_OUTPUT_NBITS {
+        return Err(VrfError::IvalidWitness);
+    }
+
+    let threshold = Threshold::new(delegated_stake, total_currency);
+
+    Ok(VrfThresholdCheckOutput {
+        producer: producer_public_key.into_address(),
+        global_slot,
+        account_index: delegator_index,
+        vrf_fractional: get_fractional(vrf_output_repr)
+            .to_f64()
+            .ok_or(VrfError::RationalToF64)?,
+        threshold: threshold
+            .threshold_rational
+            .to_f64()
+            .ok_or(VrfError::RationalToF64)?,
+        threshold_met: threshold.threshold_met(vrf_output_repr),
+    })
+}
+
+/// Probability that an account with `delegated_stake` wins a slot, the
+/// threshold its vrf output is compared against.
+pub fn slot_win_probability(delegated_stake: u64, total_currency: u64) -> VrfResult<f64> {
+    Threshold::new(delegated_stake.into(), total_currency.into())
+        .threshold_rational
+        .to_f64()
+        .ok_or(VrfError::RationalToF64)
+}
+
 // TODO(adonagy): unwraps
 pub fn seed_to_basefield(seed: &str) -> BaseField {
     let bytes = bs58::decode(seed).into_vec().unwrap();
@@ -288,7 +382,10 @@ mod test {
     use mina_p2p_messages::bigint::BigInt as MinaBigInt;
     use num::BigInt;
 
-    use crate::{keypair_from_bs58_string, VrfEvaluationInput, VrfEvaluationOutput, VrfWonSlot};
+    use crate::{
+        check_vrf_threshold, keypair_from_bs58_string, VrfEvaluationInput, VrfEvaluationOutput,
+        VrfThresholdCheckInput, VrfWonSlot,
+    };
 
     use super::evaluate_vrf;
 
@@ -350,6 +447,60 @@ mod test {
         assert_eq!(expected, evaluation_result)
     }
 
+    #[test]
+    fn test_check_vrf_threshold() {
+        let producer_key =
+            keypair_from_bs58_string("EKEEpMELfQkMbJDt2fB4cFXKwSf1x4t7YD4twREy5yuJ84HBZtF9");
+        let vrf_input = VrfEvaluationInput {
+            producer_key: producer_key.clone(),
+            epoch_seed: "2va9BGv9JrLTtrzZttiEMDYw1Zj6a6EHzXjmP9evHDTG3oEquURA".to_string(),
+            global_slot: 6,
+            delegator_index: AccountIndex(2),
+            delegated_stake: BigInt::from_str("1000000000000000")
+                .expect("Cannot convert to BigInt"),
+            total_currency: BigInt::from_str("6000000000001000").expect("Cannot convert to BigInt"),
+            account_pub_key: "Placeholder".to_string(),
+        };
+        let VrfEvaluationOutput::SlotWon(won_slot) =
+            evaluate_vrf(vrf_input.clone()).expect("Failed to evaluate vrf")
+        else {
+            panic!("slot expected to be won");
+        };
+
+        let check = |delegated_stake: &str| {
+            check_vrf_threshold(VrfThresholdCheckInput::new(
+                producer_key.public.into_compressed(),
+                vrf_input.global_slot,
+                vrf_input.delegator_index.clone(),
+                BigInt::from_str(delegated_stake).expect("Cannot convert to BigInt"),
+                vrf_input.total_currency.clone(),
+                won_slot.vrf_output_bytes.clone(),
+            ))
+            .expect("Failed to check vrf threshold")
+        };
+
+        let output = check("1000000000000000");
+        assert!(output.threshold_met);
+        assert_eq!(output.vrf_fractional, won_slot.vrf_fractional);
+        assert_eq!(output.producer, won_slot.producer);
+
+        // same output doesn't win the slot with a tiny stake.
+        assert!(!check("1000").threshold_met);
+
+        // outputs are truncated to 253 bits.
+        let mut untruncated = vec![0; 32];
+        untruncated[31] = 0x3f;
+        let result = check_vrf_threshold(VrfThresholdCheckInput::new(
+            producer_key.public.into_compressed(),
+            vrf_input.global_slot,
+            vrf_input.delegator_index.clone(),
+            vrf_input.delegated_stake.clone(),
+            vrf_input.total_currency.clone(),
+            untruncated,
+        ));
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_slot_calculation_time_big_producer() {
         let start = std::time::Instant::now();